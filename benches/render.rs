@@ -0,0 +1,299 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use model_to_image::{MeshData, ModelToImage, ModelToImageBuilder, RenderMode, probe};
+
+fn fish() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/fish.glb")
+}
+
+/// Loads the fish once, so the render benches only measure rendering.
+fn loaded(size: u32, render_mode: RenderMode) -> ModelToImage {
+    ModelToImageBuilder::new(&fish())
+        .with_size((size, size))
+        .with_render_mode(render_mode)
+        .build()
+        .expect("load fish.glb")
+}
+
+fn build(c: &mut Criterion) {
+    // texture decoding runs on several threads with the parallel feature, so label the runs to
+    // compare them side by side
+    let decoding = if cfg!(feature = "parallel") { "parallel" } else { "serial" };
+    let builder = ModelToImageBuilder::new(&fish()).with_size((256, 256));
+
+    c.bench_function(&format!("build/fish/{}", decoding), |b| {
+        b.iter(|| builder.clone().build().expect("load fish.glb"))
+    });
+}
+
+/// Writes a binary glTF of eight quads, each with its own material carrying an embedded 2048x2048
+/// PNG, so that loading it is mostly decoding textures.
+fn write_texture_heavy_glb() -> PathBuf {
+    const TEXTURES: usize = 8;
+    const SIZE: u32 = 2048;
+
+    let mut buffer = Vec::new();
+    let mut views = Vec::new();
+    let mut view = |buffer: &mut Vec<u8>, bytes: Vec<u8>| {
+        views.push(format!(
+            r#"{{ "buffer": 0, "byteOffset": {}, "byteLength": {} }}"#,
+            buffer.len(),
+            bytes.len()
+        ));
+        buffer.extend(bytes);
+        // keep every view four byte aligned
+        buffer.resize(buffer.len().next_multiple_of(4), 0);
+    };
+    let quad: [[f32; 3]; 4] = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+    view(&mut buffer, quad.iter().flatten().flat_map(|c| c.to_le_bytes()).collect());
+    view(&mut buffer, [0_u16, 1, 2, 0, 2, 3].iter().flat_map(|idx| idx.to_le_bytes()).collect());
+    for texture_idx in 0..TEXTURES {
+        // noisy enough that the PNG doesn't compress down to nothing
+        let texture = image::RgbImage::from_fn(SIZE, SIZE, |x, y| {
+            let seed = (texture_idx as u32).wrapping_mul(83_492_791);
+            let noise = (x.wrapping_mul(73_856_093) ^ y.wrapping_mul(19_349_663) ^ seed) as u8;
+            image::Rgb([noise, (x / 8) as u8, (y / 8) as u8])
+        });
+        let mut png = std::io::Cursor::new(Vec::new());
+        texture.write_to(&mut png, image::ImageFormat::Png).expect("encode png");
+        view(&mut buffer, png.into_inner());
+    }
+
+    let list = |item: &dyn Fn(usize) -> String| (0..TEXTURES).map(item).collect::<Vec<_>>().join(", ");
+    let mut json = format!(
+        r#"{{
+  "asset": {{ "version": "2.0" }},
+  "scene": 0,
+  "scenes": [{{ "nodes": [{nodes}] }}],
+  "nodes": [{node_list}],
+  "meshes": [{meshes}],
+  "materials": [{materials}],
+  "textures": [{textures}],
+  "images": [{images}],
+  "buffers": [{{ "byteLength": {total} }}],
+  "bufferViews": [{views}],
+  "accessors": [
+    {{ "bufferView": 0, "componentType": 5126, "count": 4, "type": "VEC3", "min": [0, 0, 0], "max": [1, 1, 0] }},
+    {{ "bufferView": 1, "componentType": 5123, "count": 6, "type": "SCALAR" }}
+  ]
+}}"#,
+        nodes = list(&|idx| idx.to_string()),
+        node_list = list(&|idx| format!(r#"{{ "mesh": {idx}, "translation": [{idx}, 0, 0] }}"#)),
+        meshes = list(&|idx| {
+            format!(r#"{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1, "material": {idx} }}] }}"#)
+        }),
+        materials = list(&|idx| {
+            format!(r#"{{ "pbrMetallicRoughness": {{ "baseColorTexture": {{ "index": {idx} }} }} }}"#)
+        }),
+        textures = list(&|idx| format!(r#"{{ "source": {idx} }}"#)),
+        images = list(&|idx| format!(r#"{{ "bufferView": {}, "mimeType": "image/png" }}"#, idx + 2)),
+        total = buffer.len(),
+        views = views.join(", "),
+    );
+    while json.len() % 4 != 0 {
+        json.push(' ');
+    }
+
+    let mut glb = Vec::new();
+    glb.extend_from_slice(b"glTF");
+    glb.extend_from_slice(&2_u32.to_le_bytes());
+    glb.extend_from_slice(&((12 + 8 + json.len() + 8 + buffer.len()) as u32).to_le_bytes());
+    glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    glb.extend_from_slice(b"JSON");
+    glb.extend_from_slice(json.as_bytes());
+    glb.extend_from_slice(&(buffer.len() as u32).to_le_bytes());
+    glb.extend_from_slice(b"BIN\0");
+    glb.extend_from_slice(&buffer);
+
+    let path = std::env::temp_dir().join("model_to_image_bench_textures.glb");
+    std::fs::write(&path, glb).expect("write glb");
+    path
+}
+
+fn build_texture_heavy(c: &mut Criterion) {
+    // run with and without the parallel feature and compare: decoding the eight textures should
+    // take about as many times less as there are cores, up to eight
+    let decoding = if cfg!(feature = "parallel") { "parallel" } else { "serial" };
+    let builder = ModelToImageBuilder::new(&write_texture_heavy_glb()).with_size((256, 256));
+    let mut group = c.benchmark_group("build/eight_2048_textures");
+    group.sample_size(10);
+    group.bench_function(decoding, |b| b.iter(|| builder.clone().build().expect("load glb")));
+    group.finish();
+}
+
+fn probe_or_build(c: &mut Criterion) {
+    // a probe reads the bounds and counts without decoding textures or building a model
+    let mut group = c.benchmark_group("probe/fish");
+    group.bench_function("probe", |b| b.iter(|| probe(&fish()).expect("probe fish.glb")));
+    let builder = ModelToImageBuilder::new(&fish()).with_size((256, 256));
+    group.bench_function("build", |b| b.iter(|| builder.clone().build().expect("load fish.glb")));
+    group.finish();
+}
+
+fn render_sizes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("render/size");
+    group.sample_size(10);
+    for size in [256, 1024, 4096] {
+        let mut model = loaded(size, RenderMode::Shaded);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| {
+                model.render().expect("render");
+            })
+        });
+    }
+    group.finish();
+}
+
+fn render_textured(c: &mut Criterion) {
+    let mut group = c.benchmark_group("render/texturing");
+    // the material debug view shades the same triangles with a flat colour per material, which
+    // isolates the cost of sampling the textures
+    for (name, render_mode) in [
+        ("textured", RenderMode::Shaded),
+        ("untextured", RenderMode::MaterialDebug { legend: false }),
+    ] {
+        let mut model = loaded(1024, render_mode);
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                model.render().expect("render");
+            })
+        });
+    }
+    group.finish();
+}
+
+/// A `cells` x `cells` grid of squares with a bumpy surface, standing in for a photogrammetry
+/// scan. It's built in memory, since writing out a few million triangles would take longer than
+/// the bench itself.
+fn scan(cells: u32) -> MeshData {
+    let side = cells + 1;
+    let mut mesh = MeshData::default();
+    for y in 0..side {
+        for x in 0..side {
+            let (fx, fy) = (x as f32 / cells as f32 * 2.0 - 1.0, y as f32 / cells as f32 * 2.0 - 1.0);
+            mesh.positions.push([fx, fy, 0.1 * (fx * 7.0).sin() * (fy * 5.0).cos()]);
+        }
+    }
+    for y in 0..cells {
+        for x in 0..cells {
+            let corner = y * side + x;
+            mesh.triangles.push([corner, corner + 1, corner + side + 1]);
+            mesh.triangles.push([corner, corner + side + 1, corner + side]);
+        }
+    }
+    mesh
+}
+
+fn render_simplified(c: &mut Criterion) {
+    // a 2M triangle scan rendered as a 128px thumbnail, as is and simplified to 20k triangles
+    let scan = scan(1000);
+    let mut group = c.benchmark_group("render/scan_thumbnail");
+    group.sample_size(10);
+    for (name, max_triangles) in [("full", None), ("simplified", Some(20_000))] {
+        let mut builder = ModelToImageBuilder::from_meshes(vec![scan.clone()], Vec::new()).with_size((128, 128));
+        if let Some(max_triangles) = max_triangles {
+            builder = builder.with_max_triangles(max_triangles);
+        }
+        let mut model = builder.build().expect("load scan");
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                model.render().expect("render");
+            })
+        });
+    }
+    group.finish();
+}
+
+fn render_high_poly(c: &mut Criterion) {
+    // 200k small triangles at a size where most of them still cover a few pixels, which is
+    // where the rasteriser's inner loop dominates
+    let mut model = ModelToImageBuilder::from_meshes(vec![scan(316)], Vec::new())
+        .with_size((1024, 1024))
+        .build()
+        .expect("load scan");
+    let mut group = c.benchmark_group("render/high_poly");
+    group.sample_size(10);
+    group.bench_function("scan_1024", |b| {
+        b.iter(|| {
+            model.render().expect("render");
+        })
+    });
+    group.finish();
+}
+
+fn render_vertex_order(c: &mut Criterion) {
+    // a 1M triangle scan whose vertices are stored in no particular order, rendered as it was
+    // loaded and with the vertices renumbered in the order the triangles use them
+    let scan = write_scan_scrambled(707);
+    let mut group = c.benchmark_group("render/vertex_order");
+    group.sample_size(10);
+    for (name, optimize) in [("scrambled", false), ("optimized", true)] {
+        let mut model = ModelToImageBuilder::new(&scan)
+            .with_size((1024, 1024))
+            .with_optimize_mesh(optimize)
+            .build()
+            .expect("load scan");
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                model.render().expect("render");
+            })
+        });
+    }
+    group.finish();
+}
+
+/// [`scan`] written out as a PLY file, with the vertices in a scrambled order.
+fn write_scan_scrambled(cells: u32) -> PathBuf {
+    let side = cells + 1;
+    let count = side * side;
+    // multiplying by a number coprime with the count moves every vertex to its own slot
+    let step = (count / 2..count).find(|step| gcd(*step, count) == 1).expect("a coprime step");
+    let slot = |idx: u32| (idx as u64 * step as u64 % count as u64) as u32;
+
+    let mut positions = vec![[0.0_f32; 3]; count as usize];
+    for y in 0..side {
+        for x in 0..side {
+            let (fx, fy) = (x as f32 / cells as f32 * 2.0 - 1.0, y as f32 / cells as f32 * 2.0 - 1.0);
+            positions[slot(y * side + x) as usize] = [fx, fy, 0.1 * (fx * 7.0).sin() * (fy * 5.0).cos()];
+        }
+    }
+    let mut ply = format!(
+        "ply\nformat ascii 1.0\nelement vertex {}\nproperty float x\nproperty float y\nproperty float z\nelement face {}\nproperty list uchar uint vertex_indices\nend_header\n",
+        count,
+        cells * cells * 2
+    );
+    for [x, y, z] in positions {
+        writeln!(ply, "{} {} {}", x, y, z).unwrap();
+    }
+    for y in 0..cells {
+        for x in 0..cells {
+            let corner = y * side + x;
+            let [a, b, c, d] = [corner, corner + 1, corner + side + 1, corner + side].map(slot);
+            writeln!(ply, "3 {} {} {}", a, b, c).unwrap();
+            writeln!(ply, "3 {} {} {}", a, c, d).unwrap();
+        }
+    }
+
+    let path = std::env::temp_dir().join(format!("model_to_image_bench_scrambled_scan_{}.ply", cells));
+    std::fs::write(&path, ply).expect("write scan");
+    path
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+criterion_group!(
+    benches,
+    build,
+    build_texture_heavy,
+    probe_or_build,
+    render_sizes,
+    render_textured,
+    render_simplified,
+    render_high_poly,
+    render_vertex_order
+);
+criterion_main!(benches);
@@ -0,0 +1,78 @@
+#ifndef MODEL_TO_IMAGE_H
+#define MODEL_TO_IMAGE_H
+
+/* Generated by cbindgen from src/capi.rs, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The call succeeded.
+#define MTI_OK 0
+
+// A pointer that must not be null was.
+#define MTI_ERROR_NULL_ARGUMENT 1
+
+// The path or options weren't valid UTF-8.
+#define MTI_ERROR_INVALID_UTF8 2
+
+// The options weren't valid JSON, or had a key or value the API doesn't know.
+#define MTI_ERROR_INVALID_OPTIONS 3
+
+// The model couldn't be loaded.
+#define MTI_ERROR_LOAD_FAILED 4
+
+// Rendering failed, e.g. by running out of time.
+#define MTI_ERROR_RENDER_FAILED 5
+
+// The image couldn't be encoded.
+#define MTI_ERROR_ENCODE_FAILED 6
+
+// The library panicked, which is a bug in it.
+#define MTI_ERROR_PANIC 7
+
+// Renders the model at `path` (UTF-8, nul terminated) to a PNG `width` by `height` pixels.
+//
+// `options_json` may be null, or a JSON object with any of:
+//
+// - `"view"`: `"front"`, `"back"`, `"left"`, `"right"`, `"top"`, `"bottom"`, `"isometric"` or
+//   `"auto"`
+// - `"light_direction"`: `[x, y, z]`
+// - `"light_intensity"`: `1.0` for the default brightness
+// - `"headlight"`: `true` or `false`
+// - `"margin"`: a fraction of the image, like `0.05`
+// - `"samples"`: accumulation samples for anti-aliasing
+// - `"small_image_threshold"`: pixels, images smaller than this both ways are anti-aliased
+// - `"background"`: `[r, g, b]`, 0 to 255
+// - `"outline"`: `{"colour": [r, g, b], "width": pixels}`
+// - `"normalize_scale"`: `true` or `false`
+//
+// On success the PNG is written to `*out_buf` and its length to `*out_len`; free it with
+// [`mti_free`]. On failure they are left alone and the error code says what went wrong, with
+// the details in [`mti_last_error_message`].
+//
+// # Safety
+//
+// `path` and `options_json` (unless null) must point to nul terminated strings, and `out_buf`
+// and `out_len` must be valid for writes.
+int32_t mti_render_file(const char *path,
+                        uint32_t width,
+                        uint32_t height,
+                        const char *options_json,
+                        uint8_t **out_buf,
+                        size_t *out_len);
+
+// The message of the last error on this thread, or null if the last call succeeded. The
+// string belongs to the library and stays valid until the next call on the same thread.
+const char *mti_last_error_message(void);
+
+// Frees a buffer returned by [`mti_render_file`]. Null is ignored.
+//
+// # Safety
+//
+// `ptr` must be null or a buffer from [`mti_render_file`] that hasn't been freed yet.
+void mti_free(uint8_t *ptr);
+
+#endif  /* MODEL_TO_IMAGE_H */
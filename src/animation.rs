@@ -0,0 +1,111 @@
+use std::io::Write;
+#[cfg(feature = "webp")]
+use std::io::{Seek, SeekFrom};
+
+use image::RgbImage;
+
+/// Writes `frames` (there must be exactly `count` of them, all `width` by `height`) as an
+/// animated PNG, encoding each frame as it arrives so only one is held at a time. `loops` of
+/// 0 plays forever.
+pub(crate) fn write_apng(
+    writer: impl Write,
+    (width, height): (u32, u32),
+    count: u32,
+    frames: impl Iterator<Item = anyhow::Result<RgbImage>>,
+    delay_ms: u16,
+    loops: u32,
+) -> anyhow::Result<()> {
+    let mut encoder = png::Encoder::new(writer, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_animated(count, loops)?;
+    encoder.set_frame_delay(delay_ms, 1000)?;
+    let mut png_writer = encoder.write_header()?;
+    for frame in frames {
+        png_writer.write_image_data(frame?.as_raw())?;
+    }
+    png_writer.finish()?;
+    Ok(())
+}
+
+/// Writes `frames` as an animated WebP, each frame losslessly encoded by `image` as it
+/// arrives and copied into an `ANMF` chunk. The sizes in the headers are only known at the
+/// end, so they are filled in afterwards. `loops` of 0 plays forever.
+#[cfg(feature = "webp")]
+pub(crate) fn write_animated_webp(
+    mut writer: impl Write + Seek,
+    (width, height): (u32, u32),
+    frames: impl Iterator<Item = anyhow::Result<RgbImage>>,
+    delay_ms: u32,
+    loops: u16,
+) -> anyhow::Result<()> {
+    let start = writer.stream_position()?;
+    writer.write_all(b"RIFF\0\0\0\0WEBP")?;
+    // VP8X: the animation flag, then the canvas size less one in 24 bits each
+    let mut vp8x = vec![0x02, 0, 0, 0];
+    vp8x.extend_from_slice(&(width - 1).to_le_bytes()[..3]);
+    vp8x.extend_from_slice(&(height - 1).to_le_bytes()[..3]);
+    write_chunk(&mut writer, b"VP8X", &vp8x)?;
+    // ANIM: a white background (as BGRA) under the frames, and the loop count
+    let mut anim = vec![255, 255, 255, 255];
+    anim.extend_from_slice(&loops.to_le_bytes());
+    write_chunk(&mut writer, b"ANIM", &anim)?;
+
+    for frame in frames {
+        let frame = frame?;
+        let mut still = Vec::new();
+        image::codecs::webp::WebPEncoder::new_lossless(&mut still).encode(
+            frame.as_raw(),
+            frame.width(),
+            frame.height(),
+            image::ExtendedColorType::Rgb8,
+        )?;
+        let bitstream = find_chunk(&still, b"VP8L")
+            .ok_or_else(|| anyhow::anyhow!("The WebP encoder didn't write a lossless frame"))?;
+
+        // the frame sits at the top left, lasts `delay_ms` and replaces what was there
+        let mut anmf = vec![0; 6];
+        anmf.extend_from_slice(&(frame.width() - 1).to_le_bytes()[..3]);
+        anmf.extend_from_slice(&(frame.height() - 1).to_le_bytes()[..3]);
+        anmf.extend_from_slice(&delay_ms.to_le_bytes()[..3]);
+        anmf.push(0b10);
+        anmf.extend_from_slice(bitstream);
+        write_chunk(&mut writer, b"ANMF", &anmf)?;
+    }
+
+    let end = writer.stream_position()?;
+    let riff_size = u32::try_from(end - start - 8)
+        .map_err(|_| anyhow::anyhow!("The animation is too large for a WebP file"))?;
+    writer.seek(SeekFrom::Start(start + 4))?;
+    writer.write_all(&riff_size.to_le_bytes())?;
+    writer.seek(SeekFrom::Start(end))?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes a RIFF chunk, padded to an even length.
+#[cfg(feature = "webp")]
+fn write_chunk(writer: &mut impl Write, fourcc: &[u8; 4], payload: &[u8]) -> anyhow::Result<()> {
+    writer.write_all(fourcc)?;
+    writer.write_all(&(payload.len() as u32).to_le_bytes())?;
+    writer.write_all(payload)?;
+    if payload.len() % 2 == 1 {
+        writer.write_all(&[0])?;
+    }
+    Ok(())
+}
+
+/// The whole chunk (header, payload and padding) named `fourcc` in a still WebP file.
+#[cfg(feature = "webp")]
+fn find_chunk<'a>(webp: &'a [u8], fourcc: &[u8; 4]) -> Option<&'a [u8]> {
+    let mut offset = 12;
+    while offset + 8 <= webp.len() {
+        let size = u32::from_le_bytes(webp[offset + 4..offset + 8].try_into().ok()?) as usize;
+        let end = (offset + 8 + size + size % 2).min(webp.len());
+        if &webp[offset..offset + 4] == fourcc {
+            return Some(&webp[offset..end]);
+        }
+        offset = end;
+    }
+    None
+}
@@ -0,0 +1,165 @@
+use nalgebra::Vector3;
+
+use crate::{MeshData, VIEW_DIR, ViewPreset};
+
+/// The width and height in pixels of the image each candidate view is probed at.
+const PROBE_SIZE: usize = 64;
+
+/// The triangles of the scene, gathered once so that probing a view only has to turn them.
+struct ProbeScene {
+    corners: Vec<[Vector3<f32>; 3]>,
+    double_sided: Vec<bool>,
+}
+
+/// Picks the one of [`ViewPreset::CANDIDATES`] that shows the most of `meshes`: the largest
+/// silhouette, with the most variation in shading as a tie breaker, so edge-on views of flat
+/// models lose out.
+///
+/// Each view is probed with a small flat-shaded rasteriser of its own rather than a render, so
+/// the scene isn't touched and a probe costs a few passes over the triangles at a 64x64 size.
+/// Shading only counts how much light a face catches, not its material.
+pub(crate) fn choose(
+    meshes: &[MeshData],
+    lights: &[(Vector3<f32>, f32, [f32; 3])],
+    is_double_sided: impl Fn(usize) -> bool,
+) -> ViewPreset {
+    let scene = ProbeScene::gather(meshes, is_double_sided);
+    let mut turned = Vec::with_capacity(scene.corners.len());
+    let mut depth = vec![f32::NEG_INFINITY; PROBE_SIZE * PROBE_SIZE];
+    let mut shade = vec![0.0; PROBE_SIZE * PROBE_SIZE];
+
+    let mut best = (ViewPreset::Front, f32::NEG_INFINITY);
+    for candidate in ViewPreset::CANDIDATES {
+        let rotation = candidate.rotation();
+        turned.clear();
+        turned.extend(scene.corners.iter().map(|corners| corners.map(|corner| rotation * corner)));
+        depth.fill(f32::NEG_INFINITY);
+        scene.rasterise(&turned, lights, &mut depth, &mut shade);
+
+        let score = score(&depth, &shade);
+        if score > best.1 {
+            best = (candidate, score);
+        }
+    }
+    best.0
+}
+
+impl ProbeScene {
+    fn gather(meshes: &[MeshData], is_double_sided: impl Fn(usize) -> bool) -> Self {
+        let mut scene = Self {
+            corners: Vec::new(),
+            double_sided: Vec::new(),
+        };
+        for mesh in meshes {
+            let double_sided = is_double_sided(mesh.material);
+            for triangle in &mesh.triangles {
+                let [Some(a), Some(b), Some(c)] = triangle.map(|idx| mesh.positions.get(idx as usize)) else {
+                    continue;
+                };
+                let corners = [a, b, c].map(|corner| Vector3::from(*corner));
+                if corners.iter().all(|corner| corner.iter().all(|c| c.is_finite())) {
+                    scene.corners.push(corners);
+                    scene.double_sided.push(double_sided);
+                }
+            }
+        }
+        scene
+    }
+
+    /// Draws the `turned` triangles into `depth` and `shade`, scaled to fill the probe image,
+    /// and culled and lit as the renderer does.
+    fn rasterise(
+        &self,
+        turned: &[[Vector3<f32>; 3]],
+        lights: &[(Vector3<f32>, f32, [f32; 3])],
+        depth: &mut [f32],
+        shade: &mut [f32],
+    ) {
+        let (mut min, mut max) = ([f32::INFINITY; 2], [f32::NEG_INFINITY; 2]);
+        for corner in turned.iter().flatten() {
+            for axis in 0..2 {
+                min[axis] = min[axis].min(corner[axis]);
+                max[axis] = max[axis].max(corner[axis]);
+            }
+        }
+        let extent = (max[0] - min[0]).max(max[1] - min[1]);
+        if !extent.is_finite() || extent <= 0.0 {
+            return;
+        }
+        let scale = (PROBE_SIZE - 1) as f32 / extent;
+        let centre = [(min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0];
+        let to_pixel = |v: &Vector3<f32>| {
+            [
+                (v.x - centre[0]) * scale + PROBE_SIZE as f32 / 2.0,
+                (v.y - centre[1]) * scale + PROBE_SIZE as f32 / 2.0,
+            ]
+        };
+
+        for (corners, &double_sided) in turned.iter().zip(&self.double_sided) {
+            let Some(normal) = (corners[2] - corners[0]).cross(&(corners[1] - corners[0])).try_normalize(f32::EPSILON)
+            else {
+                continue;
+            };
+            let normal = if double_sided && normal.dot(&VIEW_DIR) <= 0.0 { -normal } else { normal };
+            let intensity: f32 = lights.iter().map(|(light, strength, _)| normal.dot(light).max(0.0) * strength).sum();
+            if intensity <= 0.0 {
+                continue;
+            }
+
+            let pts = corners.each_ref().map(to_pixel);
+            let area = edge(pts[0], pts[1], pts[2]);
+            if area.abs() <= f32::EPSILON {
+                continue;
+            }
+            let (x0, x1) = bounds(pts.map(|p| p[0]));
+            let (y0, y1) = bounds(pts.map(|p| p[1]));
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let p = [x as f32 + 0.5, y as f32 + 0.5];
+                    let w = [edge(pts[1], pts[2], p), edge(pts[2], pts[0], p), edge(pts[0], pts[1], p)];
+                    let w = w.map(|w| w / area);
+                    if w.iter().any(|&w| w < 0.0) {
+                        continue;
+                    }
+                    let z = w[0] * corners[0].z + w[1] * corners[1].z + w[2] * corners[2].z;
+                    let idx = y * PROBE_SIZE + x;
+                    if z > depth[idx] {
+                        depth[idx] = z;
+                        shade[idx] = intensity.min(1.0);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Twice the signed area of the triangle `a`, `b`, `p`.
+fn edge(a: [f32; 2], b: [f32; 2], p: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+}
+
+/// The pixels from the first to one past the last that `coords` can reach into.
+fn bounds(coords: [f32; 3]) -> (usize, usize) {
+    let low = coords.iter().copied().fold(f32::INFINITY, f32::min).floor().max(0.0) as usize;
+    let high = coords.iter().copied().fold(f32::NEG_INFINITY, f32::max).ceil().min(PROBE_SIZE as f32) as usize;
+    (low, high.max(low))
+}
+
+/// How well a probe shows the model: the fraction of the image it covers, plus a bonus for
+/// varied shading, which a view straight onto a single face doesn't have.
+fn score(depth: &[f32], shade: &[f32]) -> f32 {
+    let covered: Vec<f32> = depth
+        .iter()
+        .zip(shade)
+        .filter(|(depth, _)| depth.is_finite())
+        .map(|(_, &shade)| shade)
+        .collect();
+    if covered.is_empty() {
+        return 0.0;
+    }
+
+    let area = covered.len() as f32 / depth.len() as f32;
+    let mean = covered.iter().sum::<f32>() / covered.len() as f32;
+    let variance = covered.iter().map(|l| (l - mean).powi(2)).sum::<f32>() / covered.len() as f32;
+    area + variance.sqrt() * 0.5
+}
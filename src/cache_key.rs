@@ -0,0 +1,187 @@
+use std::fs::File;
+use std::io::{BufReader, Read};
+
+use image::DynamicImage;
+
+#[cfg(feature = "scene-cache")]
+use crate::TexturePolicy;
+use crate::{MaterialData, MeshData, ModelToImageBuilder, RenderSettings};
+
+/// Bumped whenever what goes into the key changes, so old keys never match new ones.
+const KEY_FORMAT: &str = "model_to_image cache key 1";
+
+/// 64-bit FNV-1a. Unlike [`std::hash::DefaultHasher`] its output is fixed, so keys stay the
+/// same across runs, platforms and Rust versions.
+struct StableHasher(u64);
+
+impl StableHasher {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    /// Writes `bytes` with their length in front, so neighbouring fields can't run into each
+    /// other.
+    fn write_field(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+
+    fn write_floats(&mut self, values: impl IntoIterator<Item = f32>) {
+        for value in values {
+            self.write(&value.to_bits().to_le_bytes());
+        }
+    }
+}
+
+/// See [`ModelToImageBuilder::cache_key`].
+pub(crate) fn cache_key(builder: &ModelToImageBuilder) -> anyhow::Result<u64> {
+    let mut hasher = StableHasher::new();
+    hasher.write_field(KEY_FORMAT.as_bytes());
+    // a new version of the crate can draw the same model differently
+    hasher.write_field(env!("CARGO_PKG_VERSION").as_bytes());
+
+    match &builder.meshes {
+        Some((meshes, materials)) => hash_meshes(&mut hasher, meshes, materials),
+        None => hash_file(&mut hasher, builder)?,
+    }
+
+    hasher.write_field(canonical_settings(&builder.settings).as_bytes());
+    let images = [
+        builder.settings.environment.as_ref().map(|environment| &environment.image),
+        builder.settings.watermark.as_ref().map(|watermark| &watermark.image),
+        builder.settings.displacement.as_ref().map(|displacement| &displacement.texture),
+    ];
+    for image in images {
+        hash_image(&mut hasher, image);
+    }
+    Ok(hasher.0)
+}
+
+/// The key of the scene imported from the builder's model file, for
+/// [`ModelToImageBuilder::with_scene_cache_dir`]: the file's content and the settings that
+/// change what's imported, but none of the ones that only change how it's drawn.
+#[cfg(feature = "scene-cache")]
+pub(crate) fn scene_key(builder: &ModelToImageBuilder) -> anyhow::Result<u64> {
+    let mut hasher = StableHasher::new();
+    hasher.write_field(b"model_to_image scene key 1");
+    hasher.write_field(env!("CARGO_PKG_VERSION").as_bytes());
+    hash_file(&mut hasher, builder)?;
+
+    let settings = &builder.settings;
+    let import = format!(
+        "{:?} {} {} {} {}",
+        settings.import_properties,
+        settings.max_texture_size,
+        settings.up_axis.is_none() && settings.auto_up_axis,
+        settings.texture_policy == TexturePolicy::Fail,
+        settings.flips_uvs_on_import(),
+    );
+    hasher.write_field(import.as_bytes());
+    Ok(hasher.0)
+}
+
+/// Streams the model file through the hasher without holding all of it in memory.
+fn hash_file(hasher: &mut StableHasher, builder: &ModelToImageBuilder) -> anyhow::Result<()> {
+    let file = File::open(&builder.model_path).map_err(|err| {
+        anyhow::anyhow!("Could not read the model [{}] for its cache key: {}", builder.model_path.display(), err)
+    })?;
+    let mut reader = BufReader::with_capacity(64 * 1024, file);
+    let mut buffer = vec![0; 64 * 1024];
+    let mut length = 0_u64;
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.write(&buffer[..read]);
+        length += read as u64;
+    }
+    hasher.write(&length.to_le_bytes());
+    Ok(())
+}
+
+fn hash_meshes(hasher: &mut StableHasher, meshes: &[MeshData], materials: &[MaterialData]) {
+    hasher.write(&(meshes.len() as u64).to_le_bytes());
+    for mesh in meshes {
+        hasher.write_field(mesh.name.as_bytes());
+        for (count, values) in [
+            (mesh.positions.len(), mesh.positions.as_flattened()),
+            (mesh.normals.len(), mesh.normals.as_flattened()),
+            (mesh.uvs.len(), mesh.uvs.as_flattened()),
+            (mesh.colours.len(), mesh.colours.as_flattened()),
+        ] {
+            hasher.write(&(count as u64).to_le_bytes());
+            hasher.write_floats(values.iter().copied());
+        }
+        for indices in [mesh.triangles.as_flattened(), mesh.lines.as_flattened()] {
+            hasher.write(&(indices.len() as u64).to_le_bytes());
+            for index in indices {
+                hasher.write(&index.to_le_bytes());
+            }
+        }
+        hasher.write(&(mesh.material as u64).to_le_bytes());
+        hasher.write(&(mesh.bones.len() as u64).to_le_bytes());
+        for bone in &mesh.bones {
+            hasher.write_field(bone.name.as_bytes());
+            hasher.write(&(bone.weights.len() as u64).to_le_bytes());
+            for (vertex, weight) in &bone.weights {
+                hasher.write(&vertex.to_le_bytes());
+                hasher.write(&weight.to_bits().to_le_bytes());
+            }
+        }
+    }
+
+    hasher.write(&(materials.len() as u64).to_le_bytes());
+    for material in materials {
+        hasher.write_field(material.name.as_bytes());
+        hasher.write_floats(material.emissive);
+        hasher.write(&[material.double_sided as u8]);
+        hash_image(hasher, material.texture.as_ref());
+    }
+}
+
+/// Hashes an image by its size, pixel layout and pixels.
+fn hash_image(hasher: &mut StableHasher, image: Option<&DynamicImage>) {
+    let Some(image) = image else {
+        hasher.write(&[0]);
+        return;
+    };
+    hasher.write(&[1]);
+    hasher.write(&image.width().to_le_bytes());
+    hasher.write(&image.height().to_le_bytes());
+    hasher.write_field(format!("{:?}", image.color()).as_bytes());
+    hasher.write_field(image.as_bytes());
+}
+
+/// The settings written out in a fixed form, leaving out the images (hashed separately, by
+/// content) and the settings that can never change the rendered image. The derived `Debug`
+/// output is just field names and values, in declaration order, so it only changes when the
+/// settings do, which is covered by the crate version in the key.
+fn canonical_settings(settings: &RenderSettings) -> String {
+    let defaults = RenderSettings::default();
+    let mut settings = settings.clone();
+    // the same environment whichever order it was set up in
+    settings.apply_environment_options();
+    if let Some(environment) = &mut settings.environment {
+        environment.image = DynamicImage::new_rgb8(0, 0);
+    }
+    if let Some(watermark) = &mut settings.watermark {
+        watermark.image = DynamicImage::new_rgb8(0, 0);
+    }
+    if let Some(displacement) = &mut settings.displacement {
+        displacement.texture = DynamicImage::new_rgb8(0, 0);
+    }
+    settings.texture_policy = defaults.texture_policy;
+    settings.time_budget = defaults.time_budget;
+    settings.time_budget_policy = defaults.time_budget_policy;
+    settings.min_coverage = defaults.min_coverage;
+    settings.min_coverage_policy = defaults.min_coverage_policy;
+    format!("{:?}", settings)
+}
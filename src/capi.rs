@@ -0,0 +1,259 @@
+//! The C interface, behind the `capi` feature. The header is `include/model_to_image.h`,
+//! generated from this file by cbindgen with the `cbindgen.toml` at the root of the repo.
+//! Build the library for C with `cargo rustc --profile capi --features capi --crate-type cdylib`
+//! (or `staticlib`).
+//!
+//! Every function catches panics at the boundary and reports them as [`MTI_ERROR_PANIC`].
+//! That only works with panics that unwind, which is what the `capi` profile is for: it is
+//! the release profile, except that the release profile aborts on a panic.
+
+use std::alloc::{self, Layout};
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::ptr;
+
+use serde_json::Value;
+
+use crate::{Colour, ModelToImageBuilder, ViewPreset};
+
+/// The call succeeded.
+pub const MTI_OK: i32 = 0;
+/// A pointer that must not be null was.
+pub const MTI_ERROR_NULL_ARGUMENT: i32 = 1;
+/// The path or options weren't valid UTF-8.
+pub const MTI_ERROR_INVALID_UTF8: i32 = 2;
+/// The options weren't valid JSON, or had a key or value the API doesn't know.
+pub const MTI_ERROR_INVALID_OPTIONS: i32 = 3;
+/// The model couldn't be loaded.
+pub const MTI_ERROR_LOAD_FAILED: i32 = 4;
+/// Rendering failed, e.g. by running out of time.
+pub const MTI_ERROR_RENDER_FAILED: i32 = 5;
+/// The image couldn't be encoded.
+pub const MTI_ERROR_ENCODE_FAILED: i32 = 6;
+/// The library panicked, which is a bug in it.
+pub const MTI_ERROR_PANIC: i32 = 7;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Buffers handed out to C carry their length just in front of them, so [`mti_free`] needs
+/// nothing but the pointer.
+const HEADER: usize = std::mem::size_of::<usize>();
+
+/// Renders the model at `path` (UTF-8, nul terminated) to a PNG `width` by `height` pixels.
+///
+/// `options_json` may be null, or a JSON object with any of:
+///
+/// - `"view"`: `"front"`, `"back"`, `"left"`, `"right"`, `"top"`, `"bottom"`, `"isometric"` or
+///   `"auto"`
+/// - `"light_direction"`: `[x, y, z]`
+/// - `"light_intensity"`: `1.0` for the default brightness
+/// - `"headlight"`: `true` or `false`
+/// - `"margin"`: a fraction of the image, like `0.05`
+/// - `"samples"`: accumulation samples for anti-aliasing
+/// - `"small_image_threshold"`: pixels, images smaller than this both ways are anti-aliased
+/// - `"background"`: `[r, g, b]`, 0 to 255
+/// - `"outline"`: `{"colour": [r, g, b], "width": pixels}`
+/// - `"normalize_scale"`: `true` or `false`
+///
+/// On success the PNG is written to `*out_buf` and its length to `*out_len`; free it with
+/// [`mti_free`]. On failure they are left alone and the error code says what went wrong, with
+/// the details in [`mti_last_error_message`].
+///
+/// # Safety
+///
+/// `path` and `options_json` (unless null) must point to nul terminated strings, and `out_buf`
+/// and `out_len` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mti_render_file(
+    path: *const c_char,
+    width: u32,
+    height: u32,
+    options_json: *const c_char,
+    out_buf: *mut *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        if path.is_null() || out_buf.is_null() || out_len.is_null() {
+            return Err((MTI_ERROR_NULL_ARGUMENT, "path, out_buf and out_len must not be null".to_string()));
+        }
+        // SAFETY: the caller promises nul terminated strings
+        let path = unsafe { CStr::from_ptr(path) }
+            .to_str()
+            .map_err(|err| (MTI_ERROR_INVALID_UTF8, format!("The model path is not UTF-8: {}", err)))?;
+        let options = if options_json.is_null() {
+            None
+        } else {
+            // SAFETY: as above
+            let json = unsafe { CStr::from_ptr(options_json) }
+                .to_str()
+                .map_err(|err| (MTI_ERROR_INVALID_UTF8, format!("The options are not UTF-8: {}", err)))?;
+            Some(json)
+        };
+
+        let png = render_png(path, width, height, options)?;
+        // SAFETY: checked for null above, the caller promises they can be written
+        unsafe {
+            *out_len = png.len();
+            *out_buf = into_c_buffer(&png);
+        }
+        Ok(())
+    }));
+
+    match result {
+        Ok(Ok(())) => {
+            set_last_error(None);
+            MTI_OK
+        }
+        Ok(Err((code, message))) => {
+            set_last_error(Some(message));
+            code
+        }
+        Err(_) => {
+            set_last_error(Some("model_to_image panicked".to_string()));
+            MTI_ERROR_PANIC
+        }
+    }
+}
+
+/// The message of the last error on this thread, or null if the last call succeeded. The
+/// string belongs to the library and stays valid until the next call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn mti_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+/// Frees a buffer returned by [`mti_render_file`]. Null is ignored.
+///
+/// # Safety
+///
+/// `ptr` must be null or a buffer from [`mti_render_file`] that hasn't been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mti_free(ptr: *mut u8) {
+    if ptr.is_null() {
+        return;
+    }
+    let _ = panic::catch_unwind(|| {
+        // SAFETY: the caller promises the buffer came from into_c_buffer, which put the length
+        // in the header in front of it
+        unsafe {
+            let start = ptr.sub(HEADER);
+            let len = start.cast::<usize>().read();
+            alloc::dealloc(start, buffer_layout(len));
+        }
+    });
+}
+
+fn render_png(path: &str, width: u32, height: u32, options: Option<&str>) -> Result<Vec<u8>, (i32, String)> {
+    let mut builder = ModelToImageBuilder::new(&PathBuf::from(path)).with_size((width, height));
+    if let Some(options) = options {
+        builder = apply_options(builder, options).map_err(|err| (MTI_ERROR_INVALID_OPTIONS, err.to_string()))?;
+    }
+    let mut model = builder.build().map_err(|err| (MTI_ERROR_LOAD_FAILED, format!("{:#}", err)))?;
+    model.render().map_err(|err| (MTI_ERROR_RENDER_FAILED, format!("{:#}", err)))?;
+
+    let mut png = Vec::new();
+    model
+        .write_into(&mut png, image::ImageFormat::Png)
+        .map_err(|err| (MTI_ERROR_ENCODE_FAILED, format!("{:#}", err)))?;
+    Ok(png)
+}
+
+/// Maps the JSON options onto the builder, see [`mti_render_file`] for the keys.
+fn apply_options(mut builder: ModelToImageBuilder, json: &str) -> anyhow::Result<ModelToImageBuilder> {
+    let options: Value = serde_json::from_str(json)?;
+    let Value::Object(options) = options else {
+        anyhow::bail!("The options must be a JSON object");
+    };
+
+    for (key, value) in &options {
+        builder = match key.as_str() {
+            "view" => builder.with_view(match value.as_str() {
+                Some("front") => ViewPreset::Front,
+                Some("back") => ViewPreset::Back,
+                Some("left") => ViewPreset::Left,
+                Some("right") => ViewPreset::Right,
+                Some("top") => ViewPreset::Top,
+                Some("bottom") => ViewPreset::Bottom,
+                Some("isometric") => ViewPreset::Isometric,
+                Some("auto") => ViewPreset::Auto,
+                _ => anyhow::bail!("Unknown view {}", value),
+            }),
+            "light_direction" => builder.with_light_direction(floats::<3>(key, value)?),
+            "light_intensity" => builder.with_light_intensity(floats::<1>(key, value)?[0]),
+            "headlight" => builder.with_headlight(boolean(key, value)?),
+            "margin" => builder.with_margin(floats::<1>(key, value)?[0]),
+            "samples" => builder.with_accumulation_samples(integer(key, value)?),
+            "small_image_threshold" => builder.with_small_image_threshold(integer(key, value)?),
+            "background" => builder.with_background(crate::Background::Solid(colour(key, value)?)),
+            "outline" => {
+                let colour = colour("outline.colour", &value["colour"])?;
+                let width = if value.get("width").is_some() { integer("outline.width", &value["width"])? } else { 1 };
+                builder.with_outline(colour, width)
+            }
+            "normalize_scale" => builder.with_normalize_scale(boolean(key, value)?),
+            _ => anyhow::bail!("Unknown option [{}]", key),
+        };
+    }
+    Ok(builder)
+}
+
+fn floats<const N: usize>(key: &str, value: &Value) -> anyhow::Result<[f32; N]> {
+    let numbers: Vec<f32> = match value {
+        Value::Number(number) => number.as_f64().map(|n| vec![n as f32]).unwrap_or_default(),
+        Value::Array(values) => values.iter().filter_map(Value::as_f64).map(|n| n as f32).collect(),
+        _ => Vec::new(),
+    };
+    numbers
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("The option [{}] needs {} number(s), got {}", key, N, value))
+}
+
+fn integer(key: &str, value: &Value) -> anyhow::Result<u32> {
+    value
+        .as_u64()
+        .and_then(|n| u32::try_from(n).ok())
+        .ok_or_else(|| anyhow::anyhow!("The option [{}] needs a whole number, got {}", key, value))
+}
+
+fn boolean(key: &str, value: &Value) -> anyhow::Result<bool> {
+    value.as_bool().ok_or_else(|| anyhow::anyhow!("The option [{}] needs true or false, got {}", key, value))
+}
+
+fn colour(key: &str, value: &Value) -> anyhow::Result<Colour> {
+    let [r, g, b] = floats::<3>(key, value)?;
+    let channel = |c: f32| c.round().clamp(0.0, 255.0) as u8;
+    Ok(Colour::from((channel(r), channel(g), channel(b))))
+}
+
+fn set_last_error(message: Option<String>) {
+    // messages with a nul in them are cut there, C couldn't see past it anyway
+    let message = message.map(|message| {
+        let message = message.split('\0').next().unwrap_or_default().to_string();
+        CString::new(message).unwrap_or_default()
+    });
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+}
+
+fn buffer_layout(len: usize) -> Layout {
+    Layout::from_size_align(HEADER + len, std::mem::align_of::<usize>()).expect("buffer too large")
+}
+
+/// Copies `bytes` into a buffer C can hold on to, with its length in a header in front.
+fn into_c_buffer(bytes: &[u8]) -> *mut u8 {
+    let layout = buffer_layout(bytes.len());
+    // SAFETY: the layout is never zero sized, it always has the header
+    unsafe {
+        let start = alloc::alloc(layout);
+        if start.is_null() {
+            alloc::handle_alloc_error(layout);
+        }
+        start.cast::<usize>().write(bytes.len());
+        let data = start.add(HEADER);
+        ptr::copy_nonoverlapping(bytes.as_ptr(), data, bytes.len());
+        data
+    }
+}
@@ -0,0 +1,179 @@
+use image::RgbImage;
+
+/// How far two renders may differ and still count as the same, see [`assert_images_match`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatchTolerance {
+    /// Largest difference allowed in any one channel of any pixel
+    pub max_channel_difference: u8,
+    /// Fraction of the pixels (0.0 to 1.0) allowed to differ at all
+    pub max_differing_fraction: f32,
+}
+
+impl MatchTolerance {
+    /// Byte for byte identical, which renders on the same platform always are.
+    pub const EXACT: MatchTolerance = MatchTolerance {
+        max_channel_difference: 0,
+        max_differing_fraction: 0.0,
+    };
+
+    /// What renders of the same model with the same settings can differ by between platforms:
+    /// a level of rounding on a handful of pixels, where the platform's `sin`, `cos` or `powf`
+    /// gave a slightly different answer.
+    pub const CROSS_PLATFORM: MatchTolerance = MatchTolerance {
+        max_channel_difference: 1,
+        max_differing_fraction: 0.001,
+    };
+}
+
+impl Default for MatchTolerance {
+    fn default() -> Self {
+        MatchTolerance::CROSS_PLATFORM
+    }
+}
+
+/// Panics unless `actual` matches `expected` within `tolerance`, describing the differences if
+/// it doesn't. This is the comparison to use for golden image tests that run on more than one
+/// platform, see the crate docs on determinism.
+///
+/// ```no_run
+/// # use std::path::PathBuf;
+/// use model_to_image::{MatchTolerance, assert_images_match};
+///
+/// let mut model = model_to_image::ModelToImageBuilder::new(&PathBuf::from("fish.glb")).build()?;
+/// model.render()?;
+/// let expected = image::open("fish_golden.png")?.to_rgb8();
+/// assert_images_match(model.output(), &expected, MatchTolerance::CROSS_PLATFORM);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[track_caller]
+pub fn assert_images_match(actual: &RgbImage, expected: &RgbImage, tolerance: MatchTolerance) {
+    assert_eq!(
+        actual.dimensions(),
+        expected.dimensions(),
+        "the images are different sizes"
+    );
+
+    let mut differing = 0usize;
+    let mut worst: Option<(u32, u32, u8)> = None;
+    for (x, y, pixel) in actual.enumerate_pixels() {
+        let other = expected.get_pixel(x, y);
+        let difference = pixel.0.iter().zip(other.0).map(|(a, b)| a.abs_diff(b)).max().unwrap_or(0);
+        if difference == 0 {
+            continue;
+        }
+        differing += 1;
+        if worst.is_none_or(|(_, _, worst)| difference > worst) {
+            worst = Some((x, y, difference));
+        }
+    }
+
+    let pixel_count = (actual.width() as usize * actual.height() as usize).max(1);
+    let fraction = differing as f32 / pixel_count as f32;
+    if let Some((x, y, difference)) = worst {
+        assert!(
+            difference <= tolerance.max_channel_difference && fraction <= tolerance.max_differing_fraction,
+            "{} of {} pixels differ ({:.3}%), the worst by {} at ({}, {}): {:?} vs {:?}",
+            differing,
+            pixel_count,
+            fraction * 100.0,
+            difference,
+            x,
+            y,
+            actual.get_pixel(x, y).0,
+            expected.get_pixel(x, y).0
+        );
+    }
+}
+
+/// Peak signal to noise ratio between two images of the same size, in decibels, over all three
+/// channels. Higher is closer: identical images give infinity, renders that differ by a level
+/// of rounding here and there score above 50 and anything under about 30 is visibly different.
+///
+/// Fails if the images are different sizes.
+pub fn psnr(a: &RgbImage, b: &RgbImage) -> anyhow::Result<f64> {
+    check_same_size(a, b)?;
+    let squared_error: f64 = a
+        .as_raw()
+        .iter()
+        .zip(b.as_raw())
+        .map(|(&a, &b)| (a as f64 - b as f64).powi(2))
+        .sum();
+    let samples = a.as_raw().len().max(1) as f64;
+    let mse = squared_error / samples;
+    if mse == 0.0 {
+        return Ok(f64::INFINITY);
+    }
+    Ok(10.0 * (255.0 * 255.0 / mse).log10())
+}
+
+/// Structural similarity between two images of the same size, from 1.0 for identical images
+/// down towards 0.0 (or below, for images that are each other's negative). Unlike [`psnr`] it
+/// weighs how the shapes and shading compare rather than the raw differences, so a slightly
+/// moved edge costs less than blotchy noise.
+///
+/// This is the simplified form: the luma of 8x8 windows, stepping 4 pixels, averaged without
+/// the Gaussian weighting of the original paper. Images smaller than a window are compared as
+/// a single window.
+///
+/// Fails if the images are different sizes.
+pub fn ssim(a: &RgbImage, b: &RgbImage) -> anyhow::Result<f64> {
+    const WINDOW: u32 = 8;
+    const STEP: u32 = 4;
+    const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+    const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+
+    check_same_size(a, b)?;
+    let (width, height) = a.dimensions();
+    if width == 0 || height == 0 {
+        return Ok(1.0);
+    }
+    let luma = |img: &RgbImage, x: u32, y: u32| {
+        let [r, g, b] = img.get_pixel(x, y).0;
+        0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64
+    };
+    // the last window is moved back to end on the edge, so every pixel is in one
+    let starts = |length: u32| {
+        let last = length.saturating_sub(WINDOW);
+        let mut starts: Vec<u32> = (0..=last).step_by(STEP as usize).collect();
+        if starts.last() != Some(&last) {
+            starts.push(last);
+        }
+        starts
+    };
+
+    let (mut total, mut windows) = (0.0, 0);
+    for y0 in starts(height) {
+        for x0 in starts(width) {
+            let pixels: Vec<(f64, f64)> = (y0..(y0 + WINDOW).min(height))
+                .flat_map(|y| (x0..(x0 + WINDOW).min(width)).map(move |x| (x, y)))
+                .map(|(x, y)| (luma(a, x, y), luma(b, x, y)))
+                .collect();
+            let n = pixels.len() as f64;
+            let mean_a = pixels.iter().map(|p| p.0).sum::<f64>() / n;
+            let mean_b = pixels.iter().map(|p| p.1).sum::<f64>() / n;
+            let (mut var_a, mut var_b, mut covariance) = (0.0, 0.0, 0.0);
+            for (pa, pb) in &pixels {
+                var_a += (pa - mean_a).powi(2) / n;
+                var_b += (pb - mean_b).powi(2) / n;
+                covariance += (pa - mean_a) * (pb - mean_b) / n;
+            }
+            total += ((2.0 * mean_a * mean_b + C1) * (2.0 * covariance + C2))
+                / ((mean_a * mean_a + mean_b * mean_b + C1) * (var_a + var_b + C2));
+            windows += 1;
+        }
+    }
+    Ok(total / windows as f64)
+}
+
+fn check_same_size(a: &RgbImage, b: &RgbImage) -> anyhow::Result<()> {
+    if a.dimensions() != b.dimensions() {
+        return Err(anyhow::anyhow!(
+            "The images are different sizes, {}x{} and {}x{}",
+            a.width(),
+            a.height(),
+            b.width(),
+            b.height()
+        ));
+    }
+    Ok(())
+}
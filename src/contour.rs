@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+
+/// Traces the outlines of the `true` regions of a `width` by `height` mask with marching
+/// squares, returning one closed polygon per outline (holes included). The last point of each
+/// polygon is not repeated.
+///
+/// Points are in image space with pixel centres at `x + 0.5`, and lie halfway between a
+/// covered pixel and an uncovered one. Pixels only touching diagonally are kept apart.
+pub(crate) fn trace_contours(mask: &[bool], width: u32, height: u32) -> Vec<Vec<(f32, f32)>> {
+    let (width, height) = (width as i64, height as i64);
+    let inside = |x: i64, y: i64| x >= 0 && y >= 0 && x < width && y < height && mask[(x + y * width) as usize];
+
+    // edge midpoints are stored at doubled coordinates so they can be hashed exactly
+    let mut segments: Vec<[(i64, i64); 2]> = Vec::new();
+    for cy in -1..height {
+        for cx in -1..width {
+            let (tl, tr, br, bl) = (inside(cx, cy), inside(cx + 1, cy), inside(cx + 1, cy + 1), inside(cx, cy + 1));
+            let top = (2 * cx + 1, 2 * cy);
+            let right = (2 * cx + 2, 2 * cy + 1);
+            let bottom = (2 * cx + 1, 2 * cy + 2);
+            let left = (2 * cx, 2 * cy + 1);
+
+            match (tl != tr, tr != br, bl != br, tl != bl) {
+                (false, false, false, false) => {}
+                // a saddle: cut off each covered corner on its own
+                (true, true, true, true) if tl => {
+                    segments.push([top, left]);
+                    segments.push([right, bottom]);
+                }
+                (true, true, true, true) => {
+                    segments.push([top, right]);
+                    segments.push([bottom, left]);
+                }
+                (crosses_top, crosses_right, crosses_bottom, crosses_left) => {
+                    let crossings: Vec<(i64, i64)> = [
+                        (crosses_top, top),
+                        (crosses_right, right),
+                        (crosses_bottom, bottom),
+                        (crosses_left, left),
+                    ]
+                    .into_iter()
+                    .filter(|(crosses, _)| *crosses)
+                    .map(|(_, point)| point)
+                    .collect();
+                    if let [a, b] = crossings[..] {
+                        segments.push([a, b]);
+                    }
+                }
+            }
+        }
+    }
+
+    // every midpoint is shared by exactly two segments, so walking from one to the next always
+    // closes a loop
+    let mut by_point: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+    for (idx, segment) in segments.iter().enumerate() {
+        for point in segment {
+            by_point.entry(*point).or_default().push(idx);
+        }
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut contours = Vec::new();
+    for start in 0..segments.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let [first, mut current] = segments[start];
+        let mut points = vec![first];
+
+        while current != first {
+            points.push(current);
+            let next = by_point
+                .get(&current)
+                .and_then(|ids| ids.iter().copied().find(|&idx| !used[idx]));
+            let Some(next) = next else {
+                break;
+            };
+            used[next] = true;
+            let [a, b] = segments[next];
+            current = if a == current { b } else { a };
+        }
+
+        contours.push(
+            points
+                .into_iter()
+                .map(|(x, y)| (x as f32 / 2.0 + 0.5, y as f32 / 2.0 + 0.5))
+                .collect(),
+        );
+    }
+    contours
+}
+
+/// Simplifies a closed polygon with Douglas-Peucker, dropping points that are less than
+/// `tolerance` pixels away from the simplified outline.
+pub(crate) fn simplify_closed(points: &[(f32, f32)], tolerance: f32) -> Vec<(f32, f32)> {
+    if points.len() < 4 || tolerance <= 0.0 {
+        return points.to_vec();
+    }
+
+    // split the loop at the point furthest from the first one, and simplify both halves
+    let distance_sq = |a: (f32, f32), b: (f32, f32)| (a.0 - b.0).powi(2) + (a.1 - b.1).powi(2);
+    let far = (1..points.len())
+        .max_by(|&a, &b| distance_sq(points[0], points[a]).total_cmp(&distance_sq(points[0], points[b])))
+        .unwrap_or(points.len() / 2);
+
+    let mut first_half = simplify_open(&points[..=far], tolerance);
+    let mut second_half: Vec<(f32, f32)> = points[far..].to_vec();
+    second_half.push(points[0]);
+    let second_half = simplify_open(&second_half, tolerance);
+
+    first_half.pop();
+    first_half.extend_from_slice(&second_half[..second_half.len() - 1]);
+    first_half
+}
+
+/// Douglas-Peucker on a polyline, always keeping both ends.
+fn simplify_open(points: &[(f32, f32)], tolerance: f32) -> Vec<(f32, f32)> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    let (start, end) = (points[0], points[points.len() - 1]);
+    let (furthest, distance) = points[1..points.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(idx, &p)| (idx + 1, distance_to_segment(p, start, end)))
+        .fold((0, 0.0_f32), |best, candidate| if candidate.1 > best.1 { candidate } else { best });
+
+    if distance <= tolerance {
+        return vec![start, end];
+    }
+
+    let mut simplified = simplify_open(&points[..=furthest], tolerance);
+    simplified.pop();
+    simplified.extend(simplify_open(&points[furthest..], tolerance));
+    simplified
+}
+
+fn distance_to_segment(p: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_sq = dx * dx + dy * dy;
+    let t = if length_sq > 0.0 {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (x, y) = (a.0 + t * dx, a.1 + t * dy);
+    ((p.0 - x).powi(2) + (p.1 - y).powi(2)).sqrt()
+}
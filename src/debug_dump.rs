@@ -0,0 +1,59 @@
+use std::fs;
+use std::path::Path;
+
+use image::{ImageBuffer, Luma};
+use serde_json::json;
+
+use crate::ModelToImage;
+use crate::gbuffer::NO_MATERIAL;
+
+/// See [`ModelToImage::debug_dump`].
+pub(crate) fn dump(model: &ModelToImage, dir: &Path) -> anyhow::Result<()> {
+    let Some(projection) = &model.projection else {
+        return Err(anyhow::anyhow!("Nothing to dump, the model has not been rendered yet"));
+    };
+    fs::create_dir_all(dir)
+        .map_err(|err| anyhow::anyhow!("Could not create the dump directory [{}]: {}", dir.display(), err))?;
+    let (width, height) = (model.size.width, model.size.height);
+
+    model.img_buf.save(dir.join("colour.png"))?;
+
+    // 0 is left for the pixels the model doesn't cover, so the back of the model still shows
+    let depth: ImageBuffer<Luma<u16>, Vec<u16>> = ImageBuffer::from_fn(width, height, |x, y| {
+        let depth = model.depth[model.size.index(x, y)];
+        Luma([if depth.is_finite() { 1 + (depth.clamp(0.0, 1.0) * 65534.0).round() as u16 } else { 0 }])
+    });
+    depth.save(dir.join("depth.png"))?;
+
+    let coverage: ImageBuffer<Luma<u8>, Vec<u8>> = ImageBuffer::from_fn(width, height, |x, y| {
+        Luma([(model.coverage[model.size.index(x, y)].clamp(0.0, 1.0) * 255.0).round() as u8])
+    });
+    coverage.save(dir.join("coverage.png"))?;
+
+    // material index + 1, so 0 is the background
+    if let Some(gbuffer) = &model.gbuffer {
+        let ids: ImageBuffer<Luma<u16>, Vec<u16>> = ImageBuffer::from_fn(width, height, |x, y| {
+            let material = gbuffer.materials[model.size.index(x, y)];
+            Luma([if material == NO_MATERIAL { 0 } else { (material + 1).min(u16::MAX as u32) as u16 }])
+        });
+        ids.save(dir.join("ids.png"))?;
+    }
+
+    let bounds = model.model_bounds();
+    let framing = json!({
+        "width": width,
+        "height": height,
+        "scale": projection.scale,
+        "content_scale": [projection.content_scale.0, projection.content_scale.1],
+        "centre": [projection.center.0, projection.center.1],
+        "viewport_centre": [projection.viewport_center.0, projection.viewport_center.1],
+        "depth_range": [projection.depth_range.0, projection.depth_range.1],
+        "bounds": {
+            "min": [bounds.min.x, bounds.min.y, bounds.min.z],
+            "max": [bounds.max.x, bounds.max.y, bounds.max.z],
+        },
+        "scale_factor": model.scale_factor,
+    });
+    fs::write(dir.join("framing.json"), serde_json::to_string_pretty(&framing)?)?;
+    Ok(())
+}
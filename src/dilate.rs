@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use image::{DynamicImage, GenericImageView, RgbaImage};
+
+use crate::{MeshData, TextureOrigin};
+
+/// Grows the coloured regions of every texture `pixels` texels into the texels around them,
+/// so sampling just past the edge of a UV island picks up the island's colour instead of the
+/// atlas background. Textures with transparent texels treat those as the background; opaque
+/// ones treat every texel outside the UV triangles of the meshes using them as background.
+/// Only the colour spreads, transparent texels stay transparent.
+///
+/// Textures can be shared with a [`crate::TextureCache`], so dilated ones are copies.
+pub(crate) fn dilate_textures(
+    textures: &mut [Option<Arc<DynamicImage>>],
+    meshes: &[MeshData],
+    pixels: u32,
+    origin: TextureOrigin,
+) {
+    if pixels == 0 {
+        return;
+    }
+    for (material_idx, texture) in textures.iter_mut().enumerate() {
+        let Some(image) = texture.as_deref() else {
+            continue;
+        };
+        let valid = match transparent_background(image) {
+            Some(valid) => valid,
+            None => {
+                let users = meshes.iter().filter(|mesh| mesh.material == material_idx && !mesh.uvs.is_empty());
+                match uv_coverage(image.dimensions(), users, origin) {
+                    Some(valid) => valid,
+                    None => continue,
+                }
+            }
+        };
+        if valid.iter().all(|&valid| valid) || !valid.iter().any(|&valid| valid) {
+            continue;
+        }
+
+        let dilated = dilate(image.to_rgba8(), valid, pixels);
+        *texture = Some(Arc::new(if image.color().has_alpha() {
+            DynamicImage::ImageRgba8(dilated)
+        } else {
+            DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(dilated).to_rgb8())
+        }));
+    }
+}
+
+/// Which texels hold colour, if the texture has any fully transparent ones to treat as its
+/// background.
+fn transparent_background(image: &DynamicImage) -> Option<Vec<bool>> {
+    if !image.color().has_alpha() {
+        return None;
+    }
+    let valid: Vec<bool> = image.to_rgba8().pixels().map(|pixel| pixel.0[3] > 0).collect();
+    valid.contains(&false).then_some(valid)
+}
+
+/// Which texels have their centre inside one of the meshes' UV triangles, indexed
+/// `x + y * width` with rows counted down from the top of the image. `None` if no mesh
+/// samples the texture, or one wraps all the way around it so none of it is background.
+fn uv_coverage<'a>(
+    (width, height): (u32, u32),
+    meshes: impl Iterator<Item = &'a MeshData>,
+    origin: TextureOrigin,
+) -> Option<Vec<bool>> {
+    let mut valid = vec![false; width as usize * height as usize];
+    let mut sampled = false;
+    for mesh in meshes {
+        for triangle in &mesh.triangles {
+            let Some(uvs) = triangle.iter().map(|&idx| mesh.uvs.get(idx as usize)).collect::<Option<Vec<_>>>() else {
+                continue;
+            };
+            let corners = [0, 1, 2].map(|corner| {
+                let [u, v] = *uvs[corner];
+                (u * width as f32, origin.rows_down(v) * height as f32)
+            });
+            let (min_x, max_x) = span(corners.map(|corner| corner.0));
+            let (min_y, max_y) = span(corners.map(|corner| corner.1));
+            // NaN coordinates fail this too, and can't be told apart from covering everything
+            if !(max_x - min_x <= width as f32 && max_y - min_y <= height as f32) {
+                return None;
+            }
+            sampled = true;
+
+            let [a, b, c] = corners;
+            let area = edge(a, b, c);
+            if area == 0.0 {
+                continue;
+            }
+            for y in (min_y - 0.5).ceil() as i64..=(max_y - 0.5).floor() as i64 {
+                for x in (min_x - 0.5).ceil() as i64..=(max_x - 0.5).floor() as i64 {
+                    let centre = (x as f32 + 0.5, y as f32 + 0.5);
+                    let weights = [edge(b, c, centre), edge(c, a, centre), edge(a, b, centre)];
+                    if weights.iter().all(|weight| weight / area >= 0.0) {
+                        // the textures repeat, and so do the islands
+                        let (x, y) = (x.rem_euclid(width as i64) as usize, y.rem_euclid(height as i64) as usize);
+                        valid[x + y * width as usize] = true;
+                    }
+                }
+            }
+        }
+    }
+    sampled.then_some(valid)
+}
+
+/// The smallest and largest of `values`.
+fn span(values: [f32; 3]) -> (f32, f32) {
+    (values.into_iter().fold(f32::INFINITY, f32::min), values.into_iter().fold(f32::NEG_INFINITY, f32::max))
+}
+
+/// Twice the signed area of the triangle `a`, `b`, `p`.
+fn edge(a: (f32, f32), b: (f32, f32), p: (f32, f32)) -> f32 {
+    (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0)
+}
+
+/// Floods the colour of the `valid` texels outwards, one texel per pass for `passes` passes.
+/// Each background texel next to a coloured one takes the average colour of its coloured
+/// neighbours, wrapping around the edges as the texture does.
+fn dilate(mut image: RgbaImage, mut valid: Vec<bool>, passes: u32) -> RgbaImage {
+    let (width, height) = (image.width() as i64, image.height() as i64);
+    for _ in 0..passes {
+        let mut grown = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                if valid[(x + y * width) as usize] {
+                    continue;
+                }
+                let mut sum = [0_u32; 3];
+                let mut count = 0;
+                for (dx, dy) in [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)] {
+                    let (nx, ny) = ((x + dx).rem_euclid(width), (y + dy).rem_euclid(height));
+                    if valid[(nx + ny * width) as usize] {
+                        let pixel = image.get_pixel(nx as u32, ny as u32).0;
+                        for (sum, channel) in sum.iter_mut().zip(pixel) {
+                            *sum += channel as u32;
+                        }
+                        count += 1;
+                    }
+                }
+                if count > 0 {
+                    grown.push((x, y, sum.map(|channel| ((channel + count / 2) / count) as u8)));
+                }
+            }
+        }
+        if grown.is_empty() {
+            break;
+        }
+        for (x, y, [r, g, b]) in grown {
+            let pixel = image.get_pixel_mut(x as u32, y as u32);
+            pixel.0 = [r, g, b, pixel.0[3]];
+            valid[(x + y * width) as usize] = true;
+        }
+    }
+    image
+}
@@ -0,0 +1,60 @@
+use image::{ImageBuffer, Luma};
+
+use crate::simplify::vertex_normals;
+use crate::{Axis, Displacement, MeshData, TextureOrigin, mesh_label};
+
+type Heightmap = ImageBuffer<Luma<u16>, Vec<u16>>;
+
+/// Moves every vertex along the displacement axis by the heightmap's value at the vertex's
+/// texture coordinates, from 0.0 for black to `scale` for white, then recomputes the vertex
+/// normals to match the new shape. Meshes without texture coordinates can't be sampled, so
+/// they are left flat with a warning.
+pub(crate) fn displace_meshes(
+    meshes: &mut [MeshData],
+    displacement: &Displacement,
+    origin: TextureOrigin,
+    warnings: &mut Vec<String>,
+) {
+    // 16-bit, so 8-bit maps lose nothing and 16-bit ones keep their precision
+    let heightmap = displacement.texture.to_luma16();
+    let axis = match displacement.axis {
+        Axis::X => 0,
+        Axis::Y => 1,
+        Axis::Z => 2,
+    };
+
+    let mut flat = Vec::new();
+    for (mesh_idx, mesh) in meshes.iter_mut().enumerate() {
+        if mesh.uvs.is_empty() {
+            flat.push(mesh_label(mesh_idx, &mesh.name));
+            continue;
+        }
+        for (position, uv) in mesh.positions.iter_mut().zip(&mesh.uvs) {
+            position[axis] += sample(&heightmap, origin, *uv) * displacement.scale;
+        }
+        mesh.normals = vertex_normals(&mesh.positions, &mesh.triangles);
+    }
+
+    if !flat.is_empty() {
+        warnings.push(format!("Meshes without texture coordinates can't be displaced: {}", flat.join(", ")));
+    }
+}
+
+/// The height at `uv`, from 0.0 to 1.0, blended between the four nearest texels. The map
+/// repeats like the textures do, with (0, 0) at `origin`.
+fn sample(heightmap: &Heightmap, origin: TextureOrigin, [u, v]: [f32; 2]) -> f32 {
+    let (width, height) = heightmap.dimensions();
+    let x = u.rem_euclid(1.0) * width as f32 - 0.5;
+    let y = origin.rows_down(v).rem_euclid(1.0) * height as f32 - 0.5;
+    let (x0, y0) = (x.floor(), y.floor());
+    let (tx, ty) = (x - x0, y - y0);
+
+    let texel = |x: f32, y: f32| {
+        let x = (x as i64).rem_euclid(width as i64) as u32;
+        let y = (y as i64).rem_euclid(height as i64) as u32;
+        heightmap.get_pixel(x, y).0[0] as f32 / u16::MAX as f32
+    };
+    let top = texel(x0, y0) * (1.0 - tx) + texel(x0 + 1.0, y0) * tx;
+    let bottom = texel(x0, y0 + 1.0) * (1.0 - tx) + texel(x0 + 1.0, y0 + 1.0) * tx;
+    top * (1.0 - ty) + bottom * ty
+}
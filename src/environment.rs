@@ -0,0 +1,73 @@
+use std::f32::consts::PI;
+
+use image::{DynamicImage, GenericImageView, Rgb, RgbImage};
+use nalgebra::Vector3;
+
+use crate::{Environment, utils};
+
+/// Draws the part of the panorama behind a `width` x `height` render, in output orientation
+/// (the top row is the top of the image).
+///
+/// The model is orthographic, so every pixel really looks along the same direction. Sampling
+/// that one direction would give a flat colour, so the backdrop is drawn as if through a lens
+/// with the environment's field of view instead.
+pub(crate) fn render_backdrop(environment: &Environment, width: u32, height: u32) -> RgbImage {
+    let rotation = utils::rotation_about(&Vector3::y_axis(), environment.yaw)
+        * utils::rotation_about(&Vector3::x_axis(), environment.pitch);
+    let half_width = (environment.field_of_view.to_radians() / 2.0).tan();
+    let half_height = half_width * height as f32 / width as f32;
+
+    RgbImage::from_fn(width, height, |x, y| {
+        let sx = (x as f32 + 0.5) / width as f32 * 2.0 - 1.0;
+        let sy = 1.0 - (y as f32 + 0.5) / height as f32 * 2.0;
+        // the viewer looks down -z
+        let direction = rotation * Vector3::new(sx * half_width, sy * half_height, -1.0).normalize();
+        sample(&environment.image, &direction)
+    })
+}
+
+/// The panorama's colour in `direction`, with straight ahead (-z) in the middle of the image
+/// and straight up along its top edge.
+fn sample(image: &DynamicImage, direction: &Vector3<f32>) -> Rgb<u8> {
+    let (width, height) = image.dimensions();
+    let longitude = direction.x.atan2(-direction.z);
+    let latitude = direction.y.clamp(-1.0, 1.0).asin();
+
+    let u = (0.5 + longitude / (2.0 * PI)).rem_euclid(1.0);
+    let v = 0.5 - latitude / PI;
+    let x = ((u * width as f32) as u32).min(width - 1);
+    let y = ((v * height as f32) as u32).min(height - 1);
+
+    let [r, g, b, _] = image.get_pixel(x, y).0;
+    Rgb([r, g, b])
+}
+
+/// The average colour of the panorama, scaled so its brightest channel is 1.0, for tinting
+/// the ambient light. Rows are weighted by how much of the sphere they cover, so the stretched
+/// poles don't count for more than they should.
+pub(crate) fn ambient_tint(image: &DynamicImage) -> [f32; 3] {
+    let rgb = image.to_rgb32f();
+    let (width, height) = rgb.dimensions();
+
+    let mut sum = [0.0_f64; 3];
+    let mut total_weight = 0.0_f64;
+    for y in 0..height {
+        let latitude = PI * (0.5 - (y as f32 + 0.5) / height as f32);
+        let weight = latitude.cos() as f64;
+        for x in 0..width {
+            let pixel = rgb.get_pixel(x, y).0;
+            for (sum, channel) in sum.iter_mut().zip(pixel) {
+                *sum += channel as f64 * weight;
+            }
+            total_weight += weight;
+        }
+    }
+
+    let average = sum.map(|channel| (channel / total_weight.max(f64::EPSILON)) as f32);
+    let brightest = average.iter().copied().fold(0.0, f32::max);
+    if brightest > 0.0 {
+        average.map(|channel| channel / brightest)
+    } else {
+        [1.0; 3]
+    }
+}
@@ -0,0 +1,27 @@
+use nalgebra::Vector3;
+
+use crate::{Aabb, MeshData};
+
+/// Moves every mesh away from the centre of the scene, by `factor` times the distance from the
+/// scene's centre to the mesh's own. Returns where each moved mesh's centre was and where it
+/// is now, for [`crate::Overlay::ExplodeConnectors`]. Meshes without a finite vertex stay put.
+pub(crate) fn explode_meshes(meshes: &mut [MeshData], factor: f32) -> Vec<(Vector3<f32>, Vector3<f32>)> {
+    let scene_centre = Aabb::of_meshes(meshes.iter()).center();
+    if !scene_centre.iter().all(|c| c.is_finite()) {
+        return Vec::new();
+    }
+
+    let mut connectors = Vec::new();
+    for mesh in meshes {
+        let centre = Aabb::of_meshes([&*mesh]).center();
+        if !centre.iter().all(|c| c.is_finite()) {
+            continue;
+        }
+        let offset = (centre - scene_centre) * factor;
+        for position in &mut mesh.positions {
+            *position = (Vector3::from(*position) + offset).into();
+        }
+        connectors.push((centre, centre + offset));
+    }
+    connectors
+}
@@ -0,0 +1,32 @@
+use std::path::Path;
+
+/// File extensions (lowercase, without the dot) of the formats the bundled assimp build can
+/// import.
+///
+/// russimp doesn't expose assimp's importer registry, so this is kept by hand against the
+/// importers enabled in the statically linked build.
+const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "3d", "3ds", "3mf", "ac", "ac3d", "acc", "amf", "ase", "ask", "b3d", "blend", "bvh", "cob", "csm",
+    "dae", "dxf", "enff", "fbx", "glb", "gltf", "hmp", "ifc", "ifczip", "iqm", "irr", "irrmesh", "lwo",
+    "lws", "lxo", "md2", "md3", "md5anim", "md5camera", "md5mesh", "mdc", "mdl", "mesh", "mesh.xml",
+    "mot", "ms3d", "ndo", "nff", "obj", "off", "ogex", "pk3", "ply", "pmx", "prj", "q3o", "q3s", "raw",
+    "scn", "sib", "smd", "stl", "ter", "uc", "vta", "x", "x3d", "x3db", "xgl", "xml", "zae", "zgl",
+];
+
+/// The file extensions (lowercase, without the dot) of the model formats that can be loaded,
+/// e.g. for a file picker filter.
+pub fn supported_extensions() -> &'static [&'static str] {
+    SUPPORTED_EXTENSIONS
+}
+
+/// Whether the extension of `path` is one of [`supported_extensions`], ignoring case.
+pub fn is_supported(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let name = name.to_lowercase();
+    // checked against the whole name so double extensions like `mesh.xml` match too
+    SUPPORTED_EXTENSIONS
+        .iter()
+        .any(|extension| name.strip_suffix(extension).is_some_and(|stem| stem.ends_with('.') && stem.len() > 1))
+}
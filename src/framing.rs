@@ -0,0 +1,35 @@
+use std::path::PathBuf;
+
+use crate::probe;
+
+/// A frame shared by a batch of models, so they all come out at the same scale.
+///
+/// Use [`compute_shared_framing`] to build one from a list of models and pass it to
+/// [`crate::ModelToImageBuilder::with_framing`] for each render.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Framing {
+    /// Width and height in model units that the image is scaled to fit (inside the margin)
+    pub extent: [f32; 2],
+    /// Model space point placed in the middle of the image. When `None`, each model is centred
+    /// on its own bounding box.
+    pub center: Option<[f32; 3]>,
+}
+
+/// Works out a [`Framing`] big enough for the largest of the given models, so a whole
+/// catalogue can be rendered at one consistent scale with each model centred on its own.
+///
+/// Each model is only [`probe`]d, so this is much cheaper than building every model.
+pub fn compute_shared_framing(paths: &[PathBuf]) -> anyhow::Result<Framing> {
+    if paths.is_empty() {
+        return Err(anyhow::anyhow!("Cannot compute a shared framing without any models"));
+    }
+
+    let mut extent = [0.0_f32; 2];
+    for path in paths {
+        let size = probe(path)?.bounds.size();
+        extent[0] = extent[0].max(size[0]);
+        extent[1] = extent[1].max(size[1]);
+    }
+
+    Ok(Framing { extent, center: None })
+}
@@ -0,0 +1,58 @@
+use nalgebra::Vector3;
+
+/// Material index of pixels no opaque surface covers.
+pub(crate) const NO_MATERIAL: u32 = u32::MAX;
+
+/// What the rasteriser found at every pixel, so the image can be shaded again with other lights
+/// or materials without rasterising it again, see [`crate::ModelToImage::render_gbuffer`].
+///
+/// Depth isn't stored here, it's the same as the model's depth buffer.
+#[derive(Debug, Clone)]
+pub(crate) struct GBuffer {
+    width: u32,
+    /// Face normal of the surface at every pixel, in scene space
+    pub normals: Vec<[f32; 3]>,
+    /// Interpolated texture coordinates, NaN where the surface has none
+    pub uvs: Vec<[f32; 2]>,
+    /// Material of the surface, [`NO_MATERIAL`] where there isn't one
+    pub materials: Vec<u32>,
+}
+
+impl GBuffer {
+    pub fn new(width: u32, height: u32) -> Self {
+        let pixel_count = width as usize * height as usize;
+        Self {
+            width,
+            normals: vec![[0.0; 3]; pixel_count],
+            uvs: vec![[f32::NAN; 2]; pixel_count],
+            materials: vec![NO_MATERIAL; pixel_count],
+        }
+    }
+
+    /// Empties every pixel, ready for the next pass.
+    pub fn clear(&mut self) {
+        self.normals.fill([0.0; 3]);
+        self.uvs.fill([f32::NAN; 2]);
+        self.materials.fill(NO_MATERIAL);
+    }
+
+    pub fn write(&mut self, idx: usize, normal: &Vector3<f32>, uv: Option<(f32, f32)>, material_idx: usize) {
+        self.normals[idx] = [normal.x, normal.y, normal.z];
+        self.uvs[idx] = uv.map_or([f32::NAN; 2], |(u, v)| [u, v]);
+        self.materials[idx] = material_idx as u32;
+    }
+
+    /// The texture coordinates at a pixel, if the surface there has any.
+    pub fn uv(&self, idx: usize) -> Option<(f32, f32)> {
+        let [u, v] = self.uvs[idx];
+        (!u.is_nan()).then_some((u, v))
+    }
+
+    /// Flips every layer upside down, to match the output image.
+    pub fn flip_vertical(&mut self) {
+        let width = self.width as usize;
+        self.normals = self.normals.chunks(width).rev().flatten().copied().collect();
+        self.uvs = self.uvs.chunks(width).rev().flatten().copied().collect();
+        self.materials = self.materials.chunks(width).rev().flatten().copied().collect();
+    }
+}
@@ -0,0 +1,138 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+use image::RgbImage;
+
+use crate::stats::RenderStats;
+use crate::{ModelToImage, Size, UpAxis, ViewPreset, orientation_matrix, rotate_scene};
+
+/// One of several renders of the same [`ModelToImage`], see
+/// [`ModelToImage::clone_for_render`]. It shares the model's geometry and textures and has its
+/// own image buffers, size, view and light, so instances can render on different threads at
+/// once without copying the model. Everything a [`ModelToImage`] does works on an instance too.
+#[derive(Debug, Clone)]
+pub struct RenderInstance {
+    model: ModelToImage,
+}
+
+impl RenderInstance {
+    pub(crate) fn new(model: &ModelToImage) -> Self {
+        let size = model.size;
+        let stats = RenderStats {
+            width: size.width,
+            height: size.height,
+            load_time: model.stats.load_time,
+            scene_from_cache: model.stats.scene_from_cache,
+            triangles_before_simplifying: model.stats.triangles_before_simplifying,
+            vertices_welded: model.stats.vertices_welded,
+            faces_skipped: model.stats.faces_skipped,
+            scene_extent: model.stats.scene_extent,
+            ..Default::default()
+        };
+        let model = ModelToImage {
+            model_path: model.model_path.clone(),
+            settings: model.settings.clone(),
+            size,
+            img_buf: RgbImage::new(size.width, size.height),
+            precise: None,
+            hdr: None,
+            img_buf16: None,
+            alpha: None,
+            depth: Vec::new(),
+            coverage: Vec::new(),
+            backdrop: None,
+            ambient_tint: model.ambient_tint,
+            gbuffer: None,
+            deadline: None,
+            timed_out: false,
+            isolated_mesh: None,
+            scissor: None,
+            preview: None,
+            orientation: model.orientation,
+            view: model.view,
+            projection: None,
+            meshes: Arc::clone(&model.meshes),
+            material_names: model.material_names.clone(),
+            emissive: model.emissive.clone(),
+            double_sided: model.double_sided.clone(),
+            nodes: model.nodes.clone(),
+            skeleton: model.skeleton.clone(),
+            connectors: model.connectors.clone(),
+            scale_factor: model.scale_factor,
+            textures: model.textures.clone(),
+            texture_downsampling: model.texture_downsampling.clone(),
+            overwrite: model.overwrite,
+            missing_uvs: model.missing_uvs.clone(),
+            warnings: model.warnings.clone(),
+            stats,
+        };
+        Self { model }
+    }
+
+    /// Renders at `size` instead of the model's size. Fails for sizes
+    /// [`crate::ModelToImageBuilder::build`] would reject.
+    pub fn with_size(mut self, size: impl Into<Size>) -> anyhow::Result<Self> {
+        let size = size.into();
+        self.model.settings.size = size.into();
+        self.model.settings.validate()?;
+        self.model.size = size;
+        self.model.img_buf = RgbImage::new(size.width, size.height);
+        self.model.stats.width = size.width;
+        self.model.stats.height = size.height;
+        Ok(self)
+    }
+
+    /// Shows the model from `view` instead of the model's view, picking one again for
+    /// [`ViewPreset::Auto`]. The geometry is turned to match, so unless the view stays the
+    /// same this instance gets a copy of it.
+    pub fn with_view(mut self, view: ViewPreset) -> Self {
+        let model = &mut self.model;
+        model.settings.view = view;
+        if view == model.view {
+            return self;
+        }
+
+        // back to the front view first, which is where picking a view starts from
+        let roll = model.settings.camera_roll;
+        let target = match view {
+            ViewPreset::Auto => orientation_matrix(UpAxis::Y, ViewPreset::Front, 0.0),
+            _ => orientation_matrix(UpAxis::Y, view, roll),
+        };
+        let turn = target * orientation_matrix(UpAxis::Y, model.view, roll).transpose();
+        rotate_scene(Arc::make_mut(&mut model.meshes), &turn);
+        model.orientation = turn * model.orientation;
+        model.view = view;
+        if view == ViewPreset::Auto {
+            model.choose_best_view();
+        }
+        self
+    }
+
+    /// Lights the model from `light_dir` instead of the model's light, see
+    /// [`crate::ModelToImageBuilder::with_light_direction`]. Fails for a direction the builder
+    /// would reject.
+    pub fn with_light_direction<T: Into<[f32; 3]>>(mut self, light_dir: T) -> anyhow::Result<Self> {
+        self.model.settings.light_dir = light_dir.into();
+        self.model.settings.validate()?;
+        Ok(self)
+    }
+
+    /// The instance as a model of its own, for code that takes a [`ModelToImage`].
+    pub fn into_model(self) -> ModelToImage {
+        self.model
+    }
+}
+
+impl Deref for RenderInstance {
+    type Target = ModelToImage;
+
+    fn deref(&self) -> &ModelToImage {
+        &self.model
+    }
+}
+
+impl DerefMut for RenderInstance {
+    fn deref_mut(&mut self) -> &mut ModelToImage {
+        &mut self.model
+    }
+}
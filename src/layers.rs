@@ -0,0 +1,65 @@
+use image::{ImageBuffer, Luma, Rgb, RgbImage, Rgba, RgbaImage};
+
+use crate::Size;
+
+/// The model rendered on its own, see [`crate::ModelToImage::render_layers`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderLayers {
+    /// The model's colour with straight (not premultiplied) alpha, where alpha is how much of
+    /// each pixel the model covers
+    pub colour: RgbaImage,
+    /// Depth of the model at every pixel, from 0.0 at the back of the model to 1.0 at its front
+    /// (see [`crate::ModelToImage::depth_range`] for real units). Pixels the model doesn't cover
+    /// are negative infinity.
+    pub depth: ImageBuffer<Luma<f32>, Vec<f32>>,
+}
+
+impl RenderLayers {
+    /// Separates a render over `background` (the same size) back into the model colour, using
+    /// the coverage of every pixel.
+    pub(crate) fn from_render(img: &RgbImage, coverage: &[f32], depth: &[f32], background: &RgbImage) -> Self {
+        let (width, height) = img.dimensions();
+        let size = Size::new(width, height);
+
+        let colour = RgbaImage::from_fn(width, height, |x, y| {
+            let alpha = coverage[size.index(x, y)];
+            if alpha <= 0.0 {
+                return Rgba([0, 0, 0, 0]);
+            }
+            // edge pixels were averaged with the background, so take it back out
+            let rgb = img.get_pixel(x, y).0;
+            let below = background.get_pixel(x, y).0;
+            let channel = |i: usize| {
+                ((rgb[i] as f32 - below[i] as f32 * (1.0 - alpha)) / alpha).round().clamp(0.0, 255.0) as u8
+            };
+            Rgba([channel(0), channel(1), channel(2), (alpha * 255.0).round() as u8])
+        });
+        let depth = ImageBuffer::from_fn(width, height, |x, y| Luma([depth[size.index(x, y)]]));
+
+        Self { colour, depth }
+    }
+
+    /// The colour layer with every channel multiplied by alpha.
+    pub fn premultiplied(&self) -> RgbaImage {
+        let mut premultiplied = self.colour.clone();
+        for pixel in premultiplied.pixels_mut() {
+            let alpha = pixel.0[3] as f32 / 255.0;
+            for channel in &mut pixel.0[..3] {
+                *channel = (*channel as f32 * alpha).round() as u8;
+            }
+        }
+        premultiplied
+    }
+
+    /// Composites the model over `background`, which should be the same size as the render.
+    /// Any part of the render the background doesn't reach is composited over black.
+    pub fn composite_over(&self, background: &RgbImage) -> RgbImage {
+        RgbImage::from_fn(self.colour.width(), self.colour.height(), |x, y| {
+            let [r, g, b, a] = self.colour.get_pixel(x, y).0;
+            let below = background.get_pixel_checked(x, y).map_or([0, 0, 0], |pixel| pixel.0);
+            let alpha = a as f32 / 255.0;
+            let blend = |src: u8, dst: u8| (src as f32 * alpha + dst as f32 * (1.0 - alpha)).round() as u8;
+            Rgb([blend(r, below[0]), blend(g, below[1]), blend(b, below[2])])
+        })
+    }
+}
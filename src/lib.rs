@@ -25,6 +25,20 @@
 //! This was tested under the release profile using the command `cargo run --release` and the
 //! `time` command on Git Bash. 
 //! 
+//! ## Determinism
+//!
+//! Rendering is deterministic: the same model, settings and crate version give byte for byte
+//! the same image on every run on the same platform, whatever the thread count or the
+//! `parallel` feature. Nothing depends on hash map iteration order, textures decoded in
+//! parallel are gathered back in material order, translucent meshes are sorted stably, the
+//! anti-aliasing jitter comes from a seeded sequence, and every colour is rounded exactly
+//! once, explicitly.
+//!
+//! Between platforms, the only differences come from the maths library behind `sin`, `cos`
+//! and `powf` (camera roll, the isometric view, rim lights and environments), which can move
+//! an edge or a shade by one level on a few pixels. Compare renders from different machines
+//! with [`assert_images_match`] and [`MatchTolerance::CROSS_PLATFORM`] rather than exactly.
+//!
 //! ## Example
 //! 
 //! ```rust
@@ -46,22 +60,545 @@
 //! }
 //! ```
 
+pub(crate) mod animation;
+pub(crate) mod best_view;
+pub(crate) mod cache_key;
+#[cfg(feature = "capi")]
+pub mod capi;
+pub(crate) mod compare;
+pub(crate) mod contour;
+#[cfg(feature = "debug-dump")]
+pub(crate) mod debug_dump;
+pub(crate) mod dilate;
+pub(crate) mod displace;
+pub(crate) mod environment;
+pub(crate) mod explode;
+pub(crate) mod formats;
+pub(crate) mod framing;
+pub(crate) mod gbuffer;
+pub(crate) mod instance;
+pub(crate) mod layers;
+pub(crate) mod light_rig;
+pub(crate) mod lut;
+pub(crate) mod mesh_data;
+pub(crate) mod mesh_transform;
+pub(crate) mod orient;
+pub(crate) mod output_name;
+pub(crate) mod overlay;
+pub(crate) mod palette;
+pub(crate) mod passes;
+pub(crate) mod paths;
+pub(crate) mod pipeline;
+pub(crate) mod post;
+pub(crate) mod probe;
+pub(crate) mod ramp;
+#[cfg(feature = "scene-cache")]
+pub(crate) mod scene_cache;
+pub(crate) mod scene_graph;
+pub(crate) mod sink;
+pub(crate) mod simplify;
+pub(crate) mod size;
+pub(crate) mod stats;
+pub(crate) mod stereo;
+pub(crate) mod texture;
+pub(crate) mod theme;
+pub(crate) mod turntable;
 pub(crate) mod utils;
+pub(crate) mod vertex_order;
+pub(crate) mod weld;
 
-use std::path::PathBuf;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use image::{DynamicImage, GenericImageView, Rgb, RgbImage};
-use nalgebra::Vector3;
+use image::{DynamicImage, GenericImageView, GrayImage, ImageBuffer, Rgb, RgbImage, Rgba, RgbaImage};
+use nalgebra::{Matrix3, Matrix4, Rotation3, Unit, Vector3};
+use russimp_ng::metadata::MetadataType;
+use russimp_ng::property::PropertyStore;
 use russimp_ng::scene::{PostProcess, Scene};
 
-use crate::utils::Colour;
+use crate::gbuffer::{GBuffer, NO_MATERIAL};
+use crate::mesh_data::SceneData;
+use crate::passes::{Pass, PassPixels};
+use crate::scene_graph::Joint;
 
-#[derive(Debug, Clone)]
-pub struct ModelToImageBuilder {
-    pub model_path: PathBuf,
+pub use crate::compare::{MatchTolerance, assert_images_match, psnr, ssim};
+pub use crate::formats::{is_supported, supported_extensions};
+pub use crate::framing::{Framing, compute_shared_framing};
+pub use crate::instance::RenderInstance;
+pub use crate::layers::RenderLayers;
+pub use crate::light_rig::{Light, LightRig};
+pub use crate::lut::Lut;
+pub use crate::mesh_data::{BoneWeights, MaterialData, MeshData};
+pub use crate::mesh_transform::Transform;
+pub use crate::output_name::{
+    NameCollision, OUTPUT_NAME_PLACEHOLDERS, OutputNameContext, OutputNameTemplate, TemplateError,
+};
+pub use crate::palette::IndexedPng;
+pub use crate::passes::PassContainer;
+pub use crate::pipeline::{Framebuffer, RenderPipeline, Stage, StageOrderError};
+pub use crate::probe::{Bounds, ModelProbe, probe};
+pub use crate::ramp::ColourRamp;
+pub use crate::sink::{ImageSink, SeekWriter};
+pub use crate::size::{ImageSize, ImageTooLarge, PixelRect, Size};
+pub use crate::stats::{CoverageStats, MeshUvStats, RenderStats};
+pub use crate::stereo::StereoMode;
+pub use crate::texture::{CacheKey, TextureCache};
+pub use crate::theme::Theme;
+pub use crate::utils::text::{GLYPH_HEIGHT, GLYPH_WIDTH, draw_text, measure_text};
+pub use crate::utils::{Colour, DefinedColours, material_colour};
+
+/// Everything about how a model gets rendered, separate from which model it is.
+///
+/// This implements [`Default`], so a set of settings can be built up once, tweaked with struct
+/// update syntax and shared between builders through [`ModelToImageBuilder::with_settings`]:
+///
+/// ```no_run
+/// use std::path::PathBuf;
+/// use model_to_image::{ModelToImageBuilder, RenderSettings};
+///
+/// let settings = RenderSettings {
+///     size: (512, 512),
+///     margin: 0.05,
+///     ..Default::default()
+/// };
+///
+/// let mut model = ModelToImageBuilder::new(&PathBuf::from("fish.glb"))
+///     .with_settings(settings.clone())
+///     .build()?;
+/// model.render()?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// Each field is documented on its matching `with_*` method of [`ModelToImageBuilder`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderSettings {
     pub size: (u32, u32),
+    pub max_pixels: u64,
     pub light_dir: [f32; 3],
+    pub light_intensity: f32,
+    pub headlight: bool,
+    /// Degrees of yaw and pitch, see [`ModelToImageBuilder::with_headlight_offset`]
+    pub headlight_offset: (f32, f32),
+    pub light_rig: Option<LightRig>,
+    pub rim_light: Option<RimLight>,
+    pub depth_of_field: Option<DepthOfField>,
+    pub bloom: Option<Bloom>,
+    pub displacement: Option<Displacement>,
+    pub explode: f32,
+    pub weld: Option<f32>,
+    pub smooth_shading: bool,
+    pub import_properties: Vec<(String, PropertyValue)>,
+    pub depth_precision: DepthPrecision,
+    pub depth_test: DepthTest,
+    pub double_sided: bool,
+    pub handedness: Handedness,
+    pub texture_origin: TextureOrigin,
+    pub flip_uvs_on_import: bool,
+    pub seam_dilation: u32,
+    pub background: Background,
+    pub outline: Option<Outline>,
+    pub adaptive_background: bool,
+    pub drop_shadow: Option<DropShadow>,
+    pub min_intensity: f32,
+    pub tonemap: bool,
+    pub lut: Option<Lut>,
     pub margin: f32,
+    /// Horizontal and vertical, see [`ModelToImageBuilder::with_content_scale`]
+    pub content_scale: (f32, f32),
+    pub accumulation_samples: u32,
+    pub small_image_threshold: u32,
+    pub seed: u64,
+    pub mesh_opacity: Vec<(MeshSelector, f32)>,
+    pub mesh_transforms: Vec<(MeshSelector, Transform)>,
+    pub transparent_sort: SortMode,
+    pub render_mode: RenderMode,
+    pub colour_ramp: Option<ColourRamp>,
+    pub clip_planes: Vec<ClipPlane>,
+    pub clip_cap_colour: Option<Colour>,
+    pub overlays: Vec<Overlay>,
+    pub overlay_depth_test: bool,
+    pub dimension_labels: Option<DimensionLabels>,
+    pub watermark: Option<Watermark>,
+    pub debug_hud: bool,
+    pub environment: Option<Environment>,
+    /// The yaw and pitch from [`ModelToImageBuilder::with_environment_rotation`], moved into
+    /// `environment` when the model is built, so it can be set before the environment is
+    pub environment_rotation: Option<(f32, f32)>,
+    /// Like `environment_rotation`, from [`ModelToImageBuilder::with_environment_ambient`]
+    pub environment_ambient: Option<bool>,
+    pub mask: Option<Mask>,
+    pub world_scale: Option<f32>,
+    pub framing: Option<Framing>,
+    pub line_rendering: bool,
+    pub line_colour: Colour,
+    pub line_width: u32,
+    pub degenerate_epsilon: f32,
+    pub max_texture_size: u32,
+    pub texture_policy: TexturePolicy,
+    pub max_triangles: Option<usize>,
+    pub optimize_mesh: bool,
+    pub min_texels: f32,
+    pub normalize_scale: bool,
+    pub up_axis: Option<UpAxis>,
+    pub auto_up_axis: bool,
+    pub auto_orient: bool,
+    /// Degrees
+    pub camera_roll: f32,
+    pub view: ViewPreset,
+    pub output_pixels: OutputPixels,
+    pub time_budget: Option<Duration>,
+    pub time_budget_policy: TimeBudgetPolicy,
+    /// Fraction of the image
+    pub min_coverage: Option<f32>,
+    pub min_coverage_policy: CoveragePolicy,
+    /// 0 loops forever, see [`ModelToImageBuilder::with_animation_loops`]
+    pub animation_loops: u32,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            size: (256, 256),
+            max_pixels: size::DEFAULT_MAX_PIXELS,
+            light_dir: Vector3::new(0.0, 0.0 ,-1.0).into(),
+            light_intensity: 1.0,
+            headlight: false,
+            headlight_offset: (0.0, 0.0),
+            light_rig: None,
+            rim_light: None,
+            depth_of_field: None,
+            bloom: None,
+            displacement: None,
+            explode: 0.0,
+            weld: None,
+            smooth_shading: false,
+            import_properties: Vec::new(),
+            depth_precision: DepthPrecision::default(),
+            depth_test: DepthTest::default(),
+            double_sided: false,
+            handedness: Handedness::default(),
+            texture_origin: TextureOrigin::default(),
+            flip_uvs_on_import: false,
+            seam_dilation: 0,
+            background: Background::default(),
+            outline: None,
+            adaptive_background: false,
+            drop_shadow: None,
+            min_intensity: 0.0,
+            tonemap: false,
+            lut: None,
+            margin: 0.1,
+            content_scale: (1.0, 1.0),
+            accumulation_samples: 1,
+            small_image_threshold: 0,
+            seed: 0,
+            mesh_opacity: Vec::new(),
+            mesh_transforms: Vec::new(),
+            transparent_sort: SortMode::default(),
+            render_mode: RenderMode::default(),
+            colour_ramp: None,
+            clip_planes: Vec::new(),
+            clip_cap_colour: None,
+            overlays: Vec::new(),
+            overlay_depth_test: false,
+            dimension_labels: None,
+            watermark: None,
+            debug_hud: false,
+            environment: None,
+            environment_rotation: None,
+            environment_ambient: None,
+            mask: None,
+            world_scale: None,
+            framing: None,
+            line_rendering: true,
+            line_colour: Colour::from((40, 40, 40)),
+            line_width: 1,
+            degenerate_epsilon: 1e-6,
+            max_texture_size: 4096,
+            texture_policy: TexturePolicy::Warn,
+            max_triangles: None,
+            optimize_mesh: false,
+            min_texels: 0.0,
+            normalize_scale: false,
+            up_axis: None,
+            auto_up_axis: false,
+            auto_orient: false,
+            camera_roll: 0.0,
+            view: ViewPreset::Front,
+            output_pixels: OutputPixels::Srgb8,
+            time_budget: None,
+            time_budget_policy: TimeBudgetPolicy::Abort,
+            min_coverage: None,
+            min_coverage_policy: CoveragePolicy::Warn,
+            animation_loops: 0,
+        }
+    }
+}
+
+impl RenderSettings {
+    /// The direction the main light shines in, as a unit vector in the space the scene is
+    /// drawn in, where the camera looks down -Z.
+    pub(crate) fn primary_light(&self) -> Vector3<f32> {
+        if !self.headlight {
+            return Vector3::from(self.light_dir).normalize();
+        }
+        let (yaw, pitch) = self.headlight_offset;
+        // a light to the right and above the camera shines towards -X and -Y
+        let yaw = utils::rotation_about(&Vector3::y_axis(), yaw);
+        let pitch = utils::rotation_about(&Vector3::x_axis(), -pitch);
+        (yaw * pitch * -Vector3::z()).normalize()
+    }
+
+    /// Moves [`Self::environment_rotation`] and [`Self::environment_ambient`] into the
+    /// environment, whichever order they were set in. Without an environment they're dropped.
+    pub(crate) fn apply_environment_options(&mut self) {
+        let (rotation, ambient) = (self.environment_rotation.take(), self.environment_ambient.take());
+        if let Some(environment) = &mut self.environment {
+            if let Some((yaw, pitch)) = rotation {
+                (environment.yaw, environment.pitch) = (yaw, pitch);
+            }
+            if let Some(tint_ambient) = ambient {
+                environment.tint_ambient = tint_ambient;
+            }
+        }
+    }
+
+    /// Every light shining on the model, as a unit direction (in the same space as
+    /// [`Self::primary_light`]), an intensity and a colour from 0.0 to 1.0 per channel: the
+    /// light rig if there is one, otherwise the white primary light.
+    pub(crate) fn lights(&self) -> Vec<(Vector3<f32>, f32, [f32; 3])> {
+        match &self.light_rig {
+            Some(rig) => rig
+                .lights
+                .iter()
+                .map(|light| {
+                    let colour: [f32; 4] = light.colour.into();
+                    (Vector3::from(light.direction).normalize(), light.intensity, [colour[0], colour[1], colour[2]])
+                })
+                .collect(),
+            None => vec![(self.primary_light(), self.primary_light_intensity(), [1.0; 3])],
+        }
+    }
+
+    /// How bright the primary light is: [`Self::light_intensity`], times the length of
+    /// [`Self::light_dir`] unless the headlight replaces it, up to [`MAX_LIGHT_INTENSITY`].
+    pub(crate) fn primary_light_intensity(&self) -> f32 {
+        self.unclamped_light_intensity().min(MAX_LIGHT_INTENSITY)
+    }
+
+    fn unclamped_light_intensity(&self) -> f32 {
+        if self.headlight {
+            self.light_intensity
+        } else {
+            self.light_intensity * Vector3::from(self.light_dir).norm()
+        }
+    }
+
+    /// Whether the texture coordinates are flipped as the model loads, see
+    /// [`ModelToImageBuilder::with_flip_uvs_on_import`].
+    pub(crate) fn flips_uvs_on_import(&self) -> bool {
+        self.flip_uvs_on_import && self.texture_origin == TextureOrigin::TopLeft
+    }
+
+    /// The origin the textures are sampled with, once any flip on import is accounted for.
+    pub(crate) fn sampled_texture_origin(&self) -> TextureOrigin {
+        if self.flips_uvs_on_import() { TextureOrigin::BottomLeft } else { self.texture_origin }
+    }
+
+    /// What [`ModelToImage::warnings`] says about a margin that leaves no room for the model,
+    /// which is drawn a pixel across instead.
+    pub(crate) fn margin_warnings(&self) -> Vec<String> {
+        let (width, height) = self.size;
+        let drawable = width.min(height) as f32 * (1.0 - 2.0 * self.margin);
+        if self.world_scale.is_some() || drawable >= 1.0 {
+            return Vec::new();
+        }
+        vec![format!(
+            "A margin of {} leaves less than a pixel of the {}x{} image for the model, so it is drawn a pixel \
+             across; lower with_margin",
+            self.margin, width, height
+        )]
+    }
+
+    /// What [`ModelToImage::warnings`] says about the primary light's intensity.
+    pub(crate) fn light_warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.light_rig.is_some() {
+            return warnings;
+        }
+        let length = Vector3::from(self.light_dir).norm();
+        if !self.headlight && (length - 1.0).abs() > 1.0e-3 {
+            warnings.push(format!(
+                "The light direction {:?} is {} long, so the light is {} times as bright; use a unit \
+                 direction and with_light_intensity instead",
+                self.light_dir, length, length
+            ));
+        }
+        let intensity = self.unclamped_light_intensity();
+        if intensity > MAX_LIGHT_INTENSITY {
+            warnings.push(format!("The light intensity {} is clamped to {}", intensity, MAX_LIGHT_INTENSITY));
+        }
+        warnings
+    }
+
+    /// Checks the settings for values that can't be rendered.
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        if Size::from(self.size).is_empty() {
+            return Err(anyhow::anyhow!(
+                "The image needs to be at least one pixel wide and high, got [{}x{}]",
+                self.size.0,
+                self.size.1
+            ));
+        }
+        ImageTooLarge::check(self.size.0 as u64, self.size.1 as u64, self.max_pixels)?;
+        let light_dir = Vector3::from(self.light_dir);
+        if !self.headlight && (!light_dir.iter().all(|c| c.is_finite()) || light_dir.norm() == 0.0) {
+            return Err(anyhow::anyhow!("The light needs a direction, got [{:?}]", self.light_dir));
+        }
+        if !self.light_intensity.is_finite() || self.light_intensity < 0.0 {
+            return Err(anyhow::anyhow!(
+                "The light intensity must be a non-negative number, got [{}]",
+                self.light_intensity
+            ));
+        }
+        if let Some(ramp) = &self.colour_ramp {
+            ramp.validate()?;
+        }
+        if let Some(lut) = &self.lut {
+            lut.validate()?;
+        }
+        if let Some(labels) = &self.dimension_labels {
+            if !labels.unit_scale.is_finite() || labels.unit_scale <= 0.0 {
+                return Err(anyhow::anyhow!(
+                    "The dimension label unit scale must be a positive number, got [{}]",
+                    labels.unit_scale
+                ));
+            }
+        }
+        if let Some(scale) = self.world_scale {
+            if !scale.is_finite() || scale <= 0.0 {
+                return Err(anyhow::anyhow!("The world scale must be a positive number, got [{}]", scale));
+            }
+        }
+        if let Some(environment) = &self.environment {
+            if !(environment.field_of_view > 0.0 && environment.field_of_view < 180.0) {
+                return Err(anyhow::anyhow!(
+                    "The environment field of view must be between 0 and 180 degrees, got [{}]",
+                    environment.field_of_view
+                ));
+            }
+            if environment.image.width() == 0 || environment.image.height() == 0 {
+                return Err(anyhow::anyhow!("The environment panorama is empty"));
+            }
+        }
+        if let Some(rig) = &self.light_rig {
+            rig.validate()?;
+        }
+        if let Some(rim) = &self.rim_light {
+            if !rim.strength.is_finite() || rim.strength < 0.0 || !rim.power.is_finite() || rim.power <= 0.0 {
+                return Err(anyhow::anyhow!(
+                    "The rim light needs a non-negative strength and a positive power, got [{}] and [{}]",
+                    rim.strength,
+                    rim.power
+                ));
+            }
+        }
+        if let Some(min_coverage) = self.min_coverage {
+            if !(0.0..=1.0).contains(&min_coverage) {
+                return Err(anyhow::anyhow!("The minimum coverage must be between 0 and 1, got [{}]", min_coverage));
+            }
+        }
+        if let Some(dof) = &self.depth_of_field {
+            if !(dof.range.is_finite() && dof.range > 0.0) || !(dof.max_blur_px.is_finite() && dof.max_blur_px >= 0.0) {
+                return Err(anyhow::anyhow!(
+                    "Depth of field needs a positive range and a non-negative blur, got [{}] and [{}]",
+                    dof.range,
+                    dof.max_blur_px
+                ));
+            }
+        }
+        if let Some(shadow) = &self.drop_shadow {
+            if !(0.0..=1.0).contains(&shadow.opacity) || !(shadow.blur_px.is_finite() && shadow.blur_px >= 0.0) {
+                return Err(anyhow::anyhow!(
+                    "The drop shadow needs an opacity from 0.0 to 1.0 and a non-negative blur, got [{}] and [{}]",
+                    shadow.opacity,
+                    shadow.blur_px
+                ));
+            }
+        }
+        if !self.explode.is_finite() {
+            return Err(anyhow::anyhow!("The explode factor must be finite, got [{}]", self.explode));
+        }
+        let (sx, sy) = self.content_scale;
+        if !(sx.is_finite() && sx > 0.0 && sy.is_finite() && sy > 0.0) {
+            return Err(anyhow::anyhow!("The content scale must be positive on both axes, got [{}, {}]", sx, sy));
+        }
+        for (_, transform) in &self.mesh_transforms {
+            transform.validate()?;
+        }
+        if let Some(tolerance) = self.weld {
+            if !tolerance.is_finite() || tolerance < 0.0 {
+                return Err(anyhow::anyhow!("The weld tolerance must be a non-negative number, got [{}]", tolerance));
+            }
+        }
+        if let Some(displacement) = &self.displacement {
+            if !displacement.scale.is_finite() {
+                return Err(anyhow::anyhow!("The displacement scale must be finite, got [{}]", displacement.scale));
+            }
+            if displacement.texture.width() == 0 || displacement.texture.height() == 0 {
+                return Err(anyhow::anyhow!("The displacement heightmap is empty"));
+            }
+        }
+        if let Some(bloom) = &self.bloom {
+            let valid = |value: f32| value.is_finite() && value >= 0.0;
+            if !valid(bloom.threshold) || !valid(bloom.strength) || !(valid(bloom.radius_px) && bloom.radius_px > 0.0) {
+                return Err(anyhow::anyhow!(
+                    "Bloom needs a non-negative threshold and strength and a positive radius, got [{}], [{}] and [{}]",
+                    bloom.threshold,
+                    bloom.strength,
+                    bloom.radius_px
+                ));
+            }
+        }
+        if self.clip_planes.iter().any(|plane| plane.normal == [0.0; 3]) {
+            return Err(anyhow::anyhow!("A clip plane was given a zero length normal"));
+        }
+        Ok(())
+    }
+
+    /// Opacity of a mesh after applying all of the [`ModelToImageBuilder::with_mesh_opacity`] calls.
+    pub(crate) fn opacity_for(&self, mesh_idx: usize, mesh_name: &str) -> f32 {
+        self.mesh_opacity
+            .iter()
+            .rev()
+            .find(|(selector, _)| selector.matches(mesh_idx, mesh_name))
+            .map(|(_, alpha)| *alpha)
+            .unwrap_or(1.0)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ModelToImageBuilder {
+    pub model_path: PathBuf,
+    pub settings: RenderSettings,
+    pub texture_cache: Option<TextureCache>,
+    /// Whether writing may replace existing files, see [`Self::with_overwrite`]. Kept out of
+    /// `settings`, as it never changes what is rendered.
+    pub overwrite: bool,
+    /// Meshes to render instead of loading `model_path`, see [`Self::from_meshes`]
+    pub meshes: Option<(Vec<MeshData>, Vec<MaterialData>)>,
+    /// Where imported scenes are kept between builds, see [`Self::with_scene_cache_dir`]
+    #[cfg(feature = "scene-cache")]
+    pub scene_cache_dir: Option<PathBuf>,
+}
+
+impl Default for ModelToImageBuilder {
+    fn default() -> Self {
+        Self::new(&PathBuf::new())
+    }
 }
 
 impl ModelToImageBuilder {
@@ -72,25 +609,266 @@ impl ModelToImageBuilder {
     pub fn new(model_path: &PathBuf) -> Self {
         Self {
             model_path: model_path.clone(),
-            size: (256, 256),
-            light_dir: Vector3::new(0.0, 0.0 ,-1.0).into(),
-            margin: 0.1,
+            settings: RenderSettings::default(),
+            texture_cache: None,
+            overwrite: true,
+            meshes: None,
+            #[cfg(feature = "scene-cache")]
+            scene_cache_dir: None,
+        }
+    }
+
+    /// Renders meshes given as raw arrays instead of loading a model file, for geometry built
+    /// in code or read by a loader of your own. Each mesh's `material` indexes into
+    /// `materials`; meshes pointing past the end are drawn untextured.
+    ///
+    /// Everything else works as it does for files, except for what only a file can provide:
+    /// there is no node hierarchy for [`ModelToImage::node_world_transform`] and no metadata
+    /// for [`Self::with_auto_up_axis`], and [`ModelToImage::model_path`] is empty.
+    pub fn from_meshes(meshes: Vec<MeshData>, materials: Vec<MaterialData>) -> Self {
+        Self {
+            model_path: PathBuf::new(),
+            settings: RenderSettings::default(),
+            texture_cache: None,
+            overwrite: true,
+            meshes: Some((meshes, materials)),
+            #[cfg(feature = "scene-cache")]
+            scene_cache_dir: None,
         }
     }
 
-    /// Provides an size for the image. 
+    /// Provides an size for the image, as a [`Size`] or a `(width, height)` tuple. Any size of
+    /// at least one pixel each way is rendered as asked, up to [`Self::with_max_pixels`];
+    /// building fails for an empty one.
     ///
     /// Default: (256, 256) if function not used
-    pub fn with_size(mut self, size: (u32, u32)) -> Self {
-        self.size = (size.0.max(10), size.1.max(10));
+    pub fn with_size(mut self, size: impl Into<Size>) -> Self {
+        self.settings.size = size.into().into();
+        self
+    }
+
+    /// The most pixels an image may have, which bounds the memory a render takes (about 40
+    /// bytes a pixel, more with accumulation, bloom or 16-bit output). Building a larger image,
+    /// or rendering a light grid larger than this, fails with [`ImageTooLarge`] instead of
+    /// running out of memory.
+    ///
+    /// Default: 8192 x 8192, 64 megapixels
+    pub fn with_max_pixels(mut self, max_pixels: u64) -> Self {
+        self.settings.max_pixels = max_pixels;
         self
     }
 
     /// Provides a light direction to be shining onto the model. 
+    ///
+    /// Only the direction should matter, so pass a unit vector and set the brightness with
+    /// [`Self::with_light_intensity`]. A longer or shorter vector still works as it used to be
+    /// read: its length multiplies the intensity, and [`ModelToImage::warnings`] says so.
+    /// [`Self::build`] fails for a zero or non-finite vector.
     /// 
     /// Default: (0.0, 0.0, -1.0) if function not used
     pub fn with_light_direction<T: Into<[f32; 3]>>(mut self, light_dir: T) -> Self {
-        self.light_dir = light_dir.into();
+        self.settings.light_dir = light_dir.into();
+        self
+    }
+
+    /// How bright the light of [`Self::with_light_direction`] or [`Self::with_headlight`] is.
+    /// A face square on to a light of intensity 1.0 is drawn in its full colour, and the
+    /// brightness goes up and down linearly with the intensity and the cosine of the angle
+    /// between the face and the light. Anything past full colour is clipped, unless
+    /// [`Self::with_tonemap`] is on.
+    ///
+    /// [`Self::build`] fails for negative or non-finite intensities, and intensities above
+    /// [`MAX_LIGHT_INTENSITY`] are clamped to it with a warning. A [`LightRig`] has an intensity
+    /// per light instead.
+    ///
+    /// Default: 1.0
+    pub fn with_light_intensity(mut self, intensity: f32) -> Self {
+        self.settings.light_intensity = intensity;
+        self
+    }
+
+    /// Shines the light from the camera, whichever way it looks at the model, in place of
+    /// [`Self::with_light_direction`]. Every view comes out about as bright, and
+    /// [`Self::with_headlight_offset`] moves the light off to the side of the camera.
+    ///
+    /// Default: false
+    pub fn with_headlight(mut self, headlight: bool) -> Self {
+        self.settings.headlight = headlight;
+        self
+    }
+
+    /// Turns the headlight `yaw` degrees to the right and `pitch` degrees up from the camera,
+    /// so the light rakes across the model instead of hitting it head on. Only used with
+    /// [`Self::with_headlight`].
+    ///
+    /// Default: (0.0, 0.0)
+    pub fn with_headlight_offset(mut self, yaw: f32, pitch: f32) -> Self {
+        self.settings.headlight_offset = (yaw, pitch);
+        self
+    }
+
+    /// Lights the model with a [`LightRig`] of several lights in place of the single light of
+    /// [`Self::with_light_direction`] and [`Self::with_headlight`]. The rig's lights are placed
+    /// relative to the camera, so they light every view and every turntable frame from the same
+    /// sides. The light each face gets from them is added up.
+    ///
+    /// ```no_run
+    /// # use std::path::PathBuf;
+    /// use model_to_image::{LightRig, ModelToImageBuilder};
+    /// let builder = ModelToImageBuilder::new(&PathBuf::from("fish.glb")).with_light_rig(LightRig::three_point());
+    /// # let _ = builder;
+    /// ```
+    ///
+    /// Default: no rig, a single light
+    pub fn with_light_rig(mut self, rig: LightRig) -> Self {
+        self.settings.light_rig = Some(rig);
+        self
+    }
+
+    /// Lights the model with `lights`, the same as [`Self::with_light_rig`] with
+    /// [`LightRig::new`]. Coloured lights, like those of [`Light::directional_kelvin`], tint
+    /// what they shine on.
+    ///
+    /// ```no_run
+    /// # use std::path::PathBuf;
+    /// use model_to_image::{Light, ModelToImageBuilder};
+    /// let builder = ModelToImageBuilder::new(&PathBuf::from("fish.glb")).with_lights(vec![
+    ///     Light::directional_kelvin([-1.0, -1.0, -1.0], 3200.0, 0.8),
+    ///     Light::directional_kelvin([1.0, -0.3, -1.0], 9000.0, 0.4),
+    /// ]);
+    /// # let _ = builder;
+    /// ```
+    ///
+    /// Default: no rig, a single light
+    pub fn with_lights(self, lights: Vec<Light>) -> Self {
+        self.with_light_rig(LightRig::new(lights))
+    }
+
+    /// Replaces one light of the rig set by [`Self::with_light_rig`], by its index in the
+    /// preset's order (see [`LightRig::with_light`]), e.g. to dim the fill of
+    /// [`LightRig::three_point`]. Without a rig, starts one with just this light.
+    pub fn with_rig_light(mut self, index: usize, light: Light) -> Self {
+        let rig = self.settings.light_rig.take().unwrap_or_else(|| LightRig::new(Vec::new()));
+        self.settings.light_rig = Some(rig.with_light(index, light));
+        self
+    }
+
+    /// Adds a rim light, which brightens the edges of the model's silhouette so dark models stand
+    /// out against the background. Each face gets `colour` added on top of its diffuse shading,
+    /// weighted by `strength * (1 - |normal · view|)^power`: faces seen edge on get the most,
+    /// and a higher `power` keeps the rim thinner.
+    ///
+    /// Default: no rim light
+    pub fn with_rim_light(mut self, colour: Colour, strength: f32, power: f32) -> Self {
+        self.settings.rim_light = Some(RimLight { colour, strength, power });
+        self
+    }
+
+    /// Applies a [`Theme`]: its background, outline, drop shadow and rim light all at once.
+    /// Calls after this one change single parts of it, e.g. `.with_theme(Theme::dark())
+    /// .with_outline(colour, 2)` keeps the rest of the dark theme.
+    ///
+    /// Default: [`Theme::default`], the flat grey background with none of the rest
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.settings.background = theme.background;
+        self.settings.outline = theme.outline;
+        self.settings.drop_shadow = theme.drop_shadow;
+        self.settings.rim_light = theme.rim_light;
+        self
+    }
+
+    /// What is drawn behind the model, unless [`Self::with_environment`] puts a panorama
+    /// there.
+    ///
+    /// Default: [`Background::Solid`] light grey, (211, 211, 211)
+    pub fn with_background(mut self, background: Background) -> Self {
+        self.settings.background = background;
+        self
+    }
+
+    /// Swaps the background for a much darker or lighter shade of it when the model would
+    /// hardly stand out against it, e.g. a grey model on the default grey. The model's average
+    /// colour is compared with the background's, and anything under a 3:1 contrast ratio is
+    /// swapped, with a warning saying so. Has no effect with [`Self::with_environment`].
+    ///
+    /// Default: false
+    pub fn with_adaptive_background(mut self, adaptive: bool) -> Self {
+        self.settings.adaptive_background = adaptive;
+        self
+    }
+
+    /// Draws a line `width_px` wide around the outside of the model's silhouette.
+    ///
+    /// Default: no outline
+    pub fn with_outline(mut self, colour: Colour, width_px: u32) -> Self {
+        self.settings.outline = Some(Outline { colour, width_px });
+        self
+    }
+
+    /// Darkens the background under a copy of the model's silhouette shifted by `offset_px`
+    /// (right and down) and blurred by `blur_px`, as if the model floated above it. `opacity`
+    /// is how dark the middle of the shadow gets, from 0.0 to 1.0.
+    ///
+    /// Default: no shadow
+    pub fn with_drop_shadow(mut self, opacity: f32, offset_px: (i32, i32), blur_px: f32) -> Self {
+        self.settings.drop_shadow = Some(DropShadow { opacity, offset_px, blur_px });
+        self
+    }
+
+    /// Makes bright parts of the model glow, like emissive panels (see [`MaterialData::emissive`]
+    /// or the emissive colour of a model's materials). Pixels of the model brighter than
+    /// `threshold`, in luminance where 1.0 is white, are blurred with a Gaussian `radius_px`
+    /// wide and added back on top, scaled by `strength`. Only the model glows, though the glow
+    /// spreads onto the background around it.
+    ///
+    /// The brightness is taken before it's clipped to 8 bits, so with a threshold of 1.0 or
+    /// more only lights brighter than white glow.
+    ///
+    /// Default: no bloom
+    pub fn with_bloom(mut self, threshold: f32, radius_px: f32, strength: f32) -> Self {
+        self.settings.bloom = Some(Bloom {
+            threshold,
+            radius_px,
+            strength,
+        });
+        self
+    }
+
+    /// The lowest diffuse light intensity a face facing the viewer can get, from `0.0` to
+    /// `1.0`, so parts of the model facing away from the light don't go fully black.
+    ///
+    /// Default: 0.0
+    pub fn with_min_intensity(mut self, min_intensity: f32) -> Self {
+        self.settings.min_intensity = min_intensity.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Compresses lighting that goes past full brightness (e.g. from a strong rim light) with a
+    /// Reinhard tonemap instead of clipping it. The tonemap works on brightness, so colours keep
+    /// their hue instead of washing out towards white, at the cost of slightly darker midtones.
+    /// Without it, each channel is clipped once after all the lighting has been added up.
+    ///
+    /// Default: false
+    pub fn with_tonemap(mut self, tonemap: bool) -> Self {
+        self.settings.tonemap = tonemap;
+        self
+    }
+
+    /// Runs every pixel of the finished image, background, labels and watermark included,
+    /// through a colour lookup table, e.g. a grade or tone curve from a colour pipeline. This
+    /// is the last change to the colours: it works on the sRGB values, before they are rounded
+    /// to 8 bits or converted for [`Self::with_output_format`].
+    ///
+    /// ```rust,ignore
+    /// builder.with_lut(Lut::from_cube_file(Path::new("grade.cube"))?)
+    /// ```
+    ///
+    /// [`Self::build`] fails if a curve has fewer than 2 entries or a cube's table isn't
+    /// `size³` long.
+    ///
+    /// Default: no LUT
+    pub fn with_lut(mut self, lut: Lut) -> Self {
+        self.settings.lut = Some(lut);
         self
     }
 
@@ -98,245 +876,3287 @@ impl ModelToImageBuilder {
     /// 
     /// Default: 0.1_f32
     pub fn with_margin(mut self, margin: f32) -> Self {
-        self.margin = margin;
+        self.settings.margin = margin;
         self
     }
 
-    pub fn build(self) -> anyhow::Result<ModelToImage> {
-        if !self.model_path.exists() {
-            return Err(anyhow::anyhow!(format!(
-                "The model path [{}] does not exist on disk. Please ensure it exists or the path provided is correct.",
-                self.model_path.to_str().unwrap()
-            )));
-        }
-        let scene = Scene::from_file(
-            self.model_path.to_str().unwrap(),
-            vec![
-                PostProcess::CalculateTangentSpace,
-                PostProcess::Triangulate,
-                PostProcess::JoinIdenticalVertices,
-                PostProcess::SortByPrimitiveType,
-            ],
-        )?;
-        ModelToImage::new(self, scene)
+    /// Stretches the model `sx` times horizontally and `sy` times vertically on the canvas,
+    /// e.g. `(1.0, 0.85)` for a slightly squashed card header. Applied after the model is fitted
+    /// inside the margin (or framed with [`Self::with_framing`] or [`Self::with_world_scale`]),
+    /// around the middle of the image, so a factor below 1.0 leaves more room on that axis and
+    /// one above it can push the model past the margin. The image size doesn't change.
+    ///
+    /// Default: (1.0, 1.0)
+    pub fn with_content_scale(mut self, sx: f32, sy: f32) -> Self {
+        self.settings.content_scale = (sx, sy);
+        self
     }
-}
 
+    /// Renders the scene `samples` times, each with a small sub-pixel jitter, and averages the
+    /// results to smooth out jagged edges. Unlike supersampling, the memory cost does not grow
+    /// with the sample count; only the render time does.
+    ///
+    /// The jitter offsets follow a fixed low-discrepancy sequence, so the same settings always
+    /// produce the exact same image.
+    ///
+    /// Default: 1 (no anti-aliasing)
+    pub fn with_accumulation_samples(mut self, samples: u32) -> Self {
+        self.settings.accumulation_samples = samples.max(1);
+        self
+    }
 
-#[derive(Debug)]
-pub struct ModelToImage {
-    #[allow(dead_code)]
-    config: ModelToImageBuilder,
-    size: Size,
-    margin: f32,
-    img_buf: RgbImage,
-    scene: Scene,
-    light_dir: [f32; 3],
-    textures: Vec<Option<DynamicImage>>,
-}
+    /// Renders images whose width and height are both under `pixels` with
+    /// [`SMALL_IMAGE_SAMPLES`] accumulation samples, the same as drawing them four times as
+    /// large each way and scaling them down, so favicon sized renders come out as smooth shapes
+    /// instead of aliased specks. A higher [`Self::with_accumulation_samples`] is kept.
+    ///
+    /// Default: 0, off, so small images stay exactly as sharp (and as quick) as before
+    pub fn with_small_image_threshold(mut self, pixels: u32) -> Self {
+        self.settings.small_image_threshold = pixels;
+        self
+    }
 
-#[derive(Debug, Clone, Copy)]
-pub struct Size {
-    width: u32,
-    height: u32,
-}
+    /// Sets the seed used to offset the jitter pattern of [`Self::with_accumulation_samples`].
+    /// Two renders with the same seed are identical, byte for byte.
+    ///
+    /// Default: 0
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.settings.seed = seed;
+        self
+    }
 
-impl ModelToImage {
-    pub(crate) fn new(builder: ModelToImageBuilder, scene: Scene) -> anyhow::Result<Self> {
-        let size = builder.size;
-        let size = Size {
-            width: size.0,
-            height: size.1,
-        };
-        let light_dir = builder.light_dir;
-
-        let mut textures = Vec::new();
-        for material in &scene.materials {
-            if let Some(texture_path) = material.textures.get(&russimp::material::TextureType::Diffuse) {
-                match &texture_path.borrow().data {
-                    russimp::material::DataContent::Bytes(data) => {
-                        match image::load_from_memory(&data) {
-                            Ok(img) => textures.push(Some(img)),
-                            Err(e) => {
-                                eprintln!("Failed to load embedded texture: {}", e);
-                                textures.push(None);
-                            }
-                        }
-                    },
-                    _ => textures.push(None)
-                }
-            } else {
-                textures.push(None)
-            }
-        }
+    /// Renders the selected meshes as translucent, e.g. a glass case around a product. `alpha`
+    /// ranges from 0.0 (invisible) to 1.0 (fully opaque) and can be called multiple times; the
+    /// last matching call wins.
+    ///
+    /// Translucent meshes are drawn after all of the opaque ones, in the order picked with
+    /// [`Self::with_transparent_sort`]. This is a painter's algorithm, so translucent triangles
+    /// that intersect each other will not blend perfectly.
+    ///
+    /// Default: every mesh is opaque
+    pub fn with_mesh_opacity<S: Into<MeshSelector>>(mut self, mesh_selector: S, alpha: f32) -> Self {
+        self.settings.mesh_opacity.push((mesh_selector.into(), alpha.clamp(0.0, 1.0)));
+        self
+    }
 
-        let margin = builder.margin;
-        Ok(Self {
-            config: builder,
-            size,
-            img_buf: RgbImage::new(size.width, size.height),
-            scene,
-            light_dir,
-            textures,
-            margin,
-        })
+    /// Moves, turns or resizes the selected meshes on top of where the model's node hierarchy
+    /// puts them, e.g. to drop a prop floating above a table back onto it without editing the
+    /// file. Can be called multiple times; the last matching call wins. The model is framed
+    /// around where the meshes end up. Selectors that match no mesh are recorded as warnings.
+    ///
+    /// ```no_run
+    /// # use std::path::PathBuf;
+    /// use model_to_image::{ModelToImageBuilder, Transform};
+    /// let builder = ModelToImageBuilder::new(&PathBuf::from("scene.glb"))
+    ///     .with_mesh_transform("vase", Transform::from_translation([0.0, -1.0, 0.0]));
+    /// # let _ = builder;
+    /// ```
+    ///
+    /// Default: every mesh stays where the model puts it
+    pub fn with_mesh_transform<S: Into<MeshSelector>>(mut self, mesh_selector: S, transform: Transform) -> Self {
+        self.settings.mesh_transforms.push((mesh_selector.into(), transform));
+        self
+    }
+
+    /// The order translucent meshes, and the triangles within each of them, are blended in.
+    /// Every mode breaks ties by the original mesh and face order, so the same scene always
+    /// blends the same way, and the frames of a turntable don't pop when two parts swap places.
+    ///
+    /// Default: [`SortMode::CentroidDepth`]
+    pub fn with_transparent_sort(mut self, sort: SortMode) -> Self {
+        self.settings.transparent_sort = sort;
+        self
+    }
+
+    /// Picks how the model is coloured, see [`RenderMode`] for the debug views on offer.
+    ///
+    /// Default: [`RenderMode::Shaded`]
+    pub fn with_render_mode(mut self, render_mode: RenderMode) -> Self {
+        self.settings.render_mode = render_mode;
+        self
+    }
+
+    /// Colours the model with a gradient along `axis` instead of its textures, which is then
+    /// lit as usual. Each stop is a position from 0.0 (the lowest point of the model along the
+    /// axis) to 1.0 (the highest) and the colour at that position; colours are linearly blended
+    /// between stops. The stops do not need to be given in order, but [`Self::build`] fails if
+    /// there are none or any position is outside of 0.0 to 1.0.
+    ///
+    /// ```rust,ignore
+    /// builder.with_colour_ramp(Axis::Y, vec![
+    ///     (0.0, Colour::from((30, 60, 200))),
+    ///     (0.3, Colour::from((60, 160, 60))),
+    ///     (0.7, Colour::from((120, 90, 50))),
+    ///     (1.0, Colour::from((255, 255, 255))),
+    /// ])
+    /// ```
+    ///
+    /// Default: no ramp
+    pub fn with_colour_ramp(mut self, axis: Axis, mut stops: Vec<(f32, Colour)>) -> Self {
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self.settings.colour_ramp = Some(ColourRamp { axis, stops });
+        self
+    }
+
+    /// Cuts the model with a plane for a cross-section view: everything on the side that `normal`
+    /// points to, past `offset` units from the origin, is not drawn. Can be called multiple times
+    /// to cut with several planes. See [`ClipPlane`] for the exact maths.
+    ///
+    /// The framing of the image is still based on the whole model.
+    ///
+    /// Default: no clip planes
+    pub fn with_clip_plane<T: Into<[f32; 3]>>(mut self, normal: T, offset: f32) -> Self {
+        let normal = Vector3::from(normal.into());
+        let length = normal.norm();
+        // a zero normal is kept as-is and rejected by build()
+        let (normal, offset) = if length > 0.0 { (normal / length, offset / length) } else { (normal, offset) };
+        self.settings.clip_planes.push(ClipPlane { normal: normal.into(), offset });
+        self
+    }
+
+    /// Fills the surface exposed by the clip planes with a flat colour, so a cut solid looks
+    /// solid instead of hollow. This works by drawing the inside (back faces) of the model in
+    /// `colour`, so it only looks right on closed meshes.
+    ///
+    /// Default: no cap, the inside of the model is left out
+    pub fn with_clip_cap(mut self, colour: Colour) -> Self {
+        self.settings.clip_cap_colour = Some(colour);
+        self
+    }
+
+    /// Adds an [`Overlay`] drawn over the model once it has been rendered. Can be called multiple
+    /// times, the overlays are drawn in the order they were added.
+    ///
+    /// Default: no overlays
+    pub fn with_overlay(mut self, overlay: Overlay) -> Self {
+        self.settings.overlays.push(overlay);
+        self
+    }
+
+    /// Whether overlays are hidden behind the model where it covers them. When off, overlays
+    /// are always drawn on top.
+    ///
+    /// Default: false
+    pub fn with_overlay_depth_test(mut self, depth_test: bool) -> Self {
+        self.settings.overlay_depth_test = depth_test;
+        self
+    }
+
+    /// Writes the width, height and depth of the model along the bottom of the image, e.g.
+    /// `W 12.3 × H 4.5 × D 7.8 cm`. The sizes come from the model's bounding box, multiplied by
+    /// `unit_scale` to convert the model's own units into `units`. With `scale_bar` set, a bar
+    /// of a round length (1, 2 or 5 times a power of ten) is drawn in the bottom right corner.
+    ///
+    /// Default: no labels
+    pub fn with_dimension_labels(mut self, units: Units, unit_scale: f32, scale_bar: bool) -> Self {
+        self.settings.dimension_labels = Some(DimensionLabels { units, unit_scale, scale_bar });
+        self
+    }
+
+    /// Stamps `image` (e.g. a logo) into a corner of the final render, `margin_px` pixels away
+    /// from the edges. The watermark's own alpha channel is respected and further multiplied by
+    /// `opacity`. If it is bigger than a quarter of the output in either direction, it is
+    /// scaled down to fit.
+    ///
+    /// Default: no watermark
+    pub fn with_watermark(mut self, image: DynamicImage, corner: Corner, opacity: f32, margin_px: u32) -> Self {
+        self.settings.watermark = Some(Watermark { image, corner, opacity, margin_px });
+        self
+    }
+
+    /// Writes the output size, the triangles drawn out of the model's total and the time the
+    /// frame took into the top left corner of every render, with the built-in font (see
+    /// [`draw_text`]). Meant for checking renders at a glance, not for output anyone else sees.
+    ///
+    /// Default: false
+    pub fn with_debug_hud(mut self, debug_hud: bool) -> Self {
+        self.settings.debug_hud = debug_hud;
+        self
+    }
+
+    /// Raises the surface of the model by a heightmap, e.g. for terrain tiles that are flat
+    /// grids with the elevation in a texture. Every vertex moves along `axis` (of the model as
+    /// it's loaded, before [`Self::with_up_axis`] turns it) by the brightness of `texture` at
+    /// its texture coordinates, from nothing for black to `scale` model units for white.
+    /// Lighting and framing follow the displaced shape.
+    ///
+    /// Only vertices are moved, so the grid needs to be fine enough for the detail in the map.
+    /// Meshes without texture coordinates stay as they are.
+    ///
+    /// Default: no displacement
+    pub fn with_displacement(mut self, texture: DynamicImage, scale: f32, axis: Axis) -> Self {
+        self.settings.displacement = Some(Displacement { texture, scale, axis });
+        self
+    }
+
+    /// Renders an exploded view: every mesh moves away from the centre of the scene, by
+    /// `factor` times how far its own centre is from it, so 1.0 doubles the distance. Parts
+    /// are moved once the model's node transforms are applied and the framing fits around
+    /// the exploded scene. Add [`Overlay::ExplodeConnectors`] to show where each part came
+    /// from.
+    ///
+    /// Parts are whole meshes, so a model made of one mesh doesn't come apart.
+    ///
+    /// Default: 0.0, nothing moves
+    pub fn with_explode(mut self, factor: f32) -> Self {
+        self.settings.explode = factor;
+        self
+    }
+
+    /// Merges vertices of a mesh that lie within `tolerance` (in the model's units) of each
+    /// other as it's loaded, averaging their normals. Some exporters write every face with
+    /// vertices of its own, which `JoinIdenticalVertices` can't join when their normals differ
+    /// slightly; welding joins the faces back up. Vertices whose texture coordinates or colours
+    /// differ are kept apart, so UV seams stay. Meshes with bones or morph targets aren't
+    /// welded. [`RenderStats::vertices_welded`] tells how many vertices were merged.
+    ///
+    /// Default: none, vertices are kept as loaded
+    pub fn with_weld(mut self, tolerance: f32) -> Self {
+        self.settings.weld = Some(tolerance);
+        self
+    }
+
+    /// Lights each triangle from the normals at its corners, blending the light across it,
+    /// instead of from the flat normal of the face, so curved surfaces look round rather than
+    /// faceted. Meshes without vertex normals stay flat. A corner only shares its normal with
+    /// the faces around it if they share the vertex, so a model split into separate faces
+    /// still looks faceted until [`Self::with_weld`] joins it up.
+    ///
+    /// Default: false, every face is lit flat
+    pub fn with_smooth_shading(mut self, smooth_shading: bool) -> Self {
+        self.settings.smooth_shading = smooth_shading;
+        self
+    }
+
+    /// Sets an assimp import property, which changes how the model file is read: e.g.
+    /// `"PP_FD_REMOVE"` set to true drops degenerate faces, `"PP_RVC_FLAGS"` picks the
+    /// components to strip and `"IMPORT_FBX_PRESERVE_PIVOTS"` keeps FBX pivots as extra
+    /// nodes. `key` is the string value of assimp's `AI_CONFIG_*` constant. Keys assimp doesn't
+    /// know are passed on anyway and ignored by it.
+    ///
+    /// Properties read by post processing steps the crate doesn't run by default (those
+    /// starting with `PP_FD_`, `PP_RVC_`, `PP_PTV_` and `PP_SLM_`) turn their step on. Setting a
+    /// key again replaces its value.
+    ///
+    /// Default: no properties
+    pub fn with_import_property(mut self, key: &str, value: PropertyValue) -> Self {
+        self.settings.import_properties.retain(|(existing, _)| existing != key);
+        self.settings.import_properties.push((key.to_string(), value));
+        self
+    }
+
+    /// Replaces the flat grey background with an equirectangular panorama (2:1, straight ahead
+    /// in the middle, the sky along the top), e.g. a sunset or a studio HDRI converted to LDR.
+    ///
+    /// The camera is orthographic, so the panorama is drawn as if seen through a lens with the
+    /// [`Environment::field_of_view`]; see [`Self::with_environment_rotation`] to turn it and
+    /// [`Self::with_environment_ambient`] to tint the lighting with it.
+    ///
+    /// Default: no environment
+    pub fn with_environment(mut self, image: DynamicImage) -> Self {
+        self.settings.environment = Some(Environment {
+            image,
+            yaw: 0.0,
+            pitch: 0.0,
+            field_of_view: 60.0,
+            tint_ambient: false,
+        });
+        self
+    }
+
+    /// Turns the panorama from [`Self::with_environment`] by `yaw` degrees to the left and
+    /// `pitch` degrees up, so a different part of it sits behind the model. Can be called
+    /// before or after [`Self::with_environment`], and does nothing without one.
+    ///
+    /// Default: 0.0, 0.0
+    pub fn with_environment_rotation(mut self, yaw: f32, pitch: f32) -> Self {
+        self.settings.environment_rotation = Some((yaw, pitch));
+        self
+    }
+
+    /// Blurs the parts of the model away from `focus_depth`, like a camera focused on it. Depths
+    /// are the same as in [`RenderLayers::depth`], from 0.0 at the furthest point of the model
+    /// to 1.0 at the nearest; pixels more than `range` away from the focus get the full
+    /// `max_blur_px` radius, and those in between a share of it. The background is left sharp,
+    /// see [`Self::with_depth_of_field_background`].
+    ///
+    /// Default: no blur
+    pub fn with_depth_of_field(mut self, focus_depth: f32, range: f32, max_blur_px: f32) -> Self {
+        self.settings.depth_of_field = Some(DepthOfField {
+            focus_depth,
+            range,
+            max_blur_px,
+            blur_background: false,
+        });
+        self
+    }
+
+    /// Whether the background gets the full blur of [`Self::with_depth_of_field`], as if it was
+    /// far behind the model, which suits a backdrop from [`Self::with_environment`]. Does
+    /// nothing without depth of field, so call it after [`Self::with_depth_of_field`].
+    ///
+    /// Default: false
+    pub fn with_depth_of_field_background(mut self, blur_background: bool) -> Self {
+        if let Some(dof) = &mut self.settings.depth_of_field {
+            dof.blur_background = blur_background;
+        }
+        self
+    }
+
+    /// Tints the ambient light (see [`Self::with_min_intensity`]) by the average colour of the
+    /// panorama from [`Self::with_environment`], so shadowed sides pick up the colour of the
+    /// surroundings. Can be called before or after [`Self::with_environment`], and does
+    /// nothing without one.
+    ///
+    /// Default: false
+    pub fn with_environment_ambient(mut self, tint_ambient: bool) -> Self {
+        self.settings.environment_ambient = Some(tint_ambient);
+        self
+    }
+
+    /// Cuts the output down to a [`Mask`] shape, e.g. a circle for avatar style previews.
+    /// Everything outside of the shape becomes transparent and the edge is anti-aliased. The
+    /// colour under the transparent parts is left as the background.
+    ///
+    /// The alpha channel is only available through [`ModelToImage::output_rgba`] and
+    /// [`ModelToImage::write_to`] (for formats that support it).
+    ///
+    /// Default: no mask
+    pub fn with_mask(mut self, mask: Mask) -> Self {
+        self.settings.mask = Some(mask);
+        self
+    }
+
+    /// Renders at a fixed scale of `pixels_per_unit` image pixels per model unit, instead of
+    /// scaling the model to fill the image. This keeps the size of different models comparable,
+    /// e.g. a 10cm part comes out half as big as a 20cm part. The model is still centred, and
+    /// anything that does not fit in the image is cropped.
+    ///
+    /// Default: none, the model is scaled to fit inside the margin
+    pub fn with_world_scale(mut self, pixels_per_unit: f32) -> Self {
+        self.settings.world_scale = Some(pixels_per_unit);
+        self
+    }
+
+    /// Frames the model with a [`Framing`] shared by a batch of models (see
+    /// [`compute_shared_framing`]) instead of fitting the image to this model alone, so every
+    /// model in the batch is drawn at the same scale. [`Self::with_world_scale`] takes priority
+    /// over this if both are set.
+    ///
+    /// Default: none, the model is scaled to fit inside the margin
+    pub fn with_framing(mut self, framing: Framing) -> Self {
+        self.settings.framing = Some(framing);
+        self
+    }
+
+    /// Shares decoded textures with other models built with the same [`TextureCache`], so
+    /// textures used by many models in a batch are only decoded once.
+    ///
+    /// Default: no cache, every build decodes its own textures
+    pub fn with_texture_cache(mut self, cache: TextureCache) -> Self {
+        self.texture_cache = Some(cache);
+        self
+    }
+
+    /// Keeps the imported geometry, textures and materials of the model in `dir`, keyed by the
+    /// content of the model file, so building the same model again (at another size or from
+    /// another view) skips assimp entirely. [`RenderStats::scene_from_cache`] tells whether it
+    /// did. Entries that are corrupt or were written by another version are imported again and
+    /// replaced. Textures are keyed by the model file only, so clear the cache if they change.
+    ///
+    /// Needs the `scene-cache` feature. Has no effect on [`Self::from_meshes`].
+    ///
+    /// Default: no cache, every build imports the model
+    #[cfg(feature = "scene-cache")]
+    pub fn with_scene_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.scene_cache_dir = Some(dir.into());
+        self
+    }
+
+    /// Whether line primitives in the model (wireframes, construction lines and polylines from
+    /// CAD exports) are drawn. They are depth tested against the solid parts of the model, but
+    /// are not cut by clip planes.
+    ///
+    /// Default: true
+    pub fn with_line_rendering(mut self, line_rendering: bool) -> Self {
+        self.settings.line_rendering = line_rendering;
+        self
+    }
+
+    /// Colour and thickness in pixels of the model's line primitives, see
+    /// [`Self::with_line_rendering`].
+    ///
+    /// Default: dark grey (40, 40, 40), 1 pixel wide
+    pub fn with_line_style(mut self, colour: Colour, width_px: u32) -> Self {
+        self.settings.line_colour = colour;
+        self.settings.line_width = width_px.max(1);
+        self
+    }
+
+    /// The largest width or height a texture is kept at. Bigger textures are downsampled as
+    /// soon as they are decoded (with a warning, and by the factor in
+    /// [`ModelToImage::texture_downsampling`]), so a model with a huge embedded texture
+    /// doesn't balloon memory. Textures too big to decode at all (over 512 MiB) are skipped.
+    ///
+    /// Default: 4096
+    pub fn with_max_texture_size(mut self, max_size: u32) -> Self {
+        self.settings.max_texture_size = max_size.max(1);
+        self
+    }
+
+    /// What happens when a texture can't be loaded: its file is missing, it's in a format that
+    /// can't be decoded (like KTX2 or Basis, or TGA and TIFF without the `tga` and `tiff`
+    /// features), or its data is broken. Either the model renders without it, with a warning
+    /// naming the material and the format, or building fails.
+    ///
+    /// Default: [`TexturePolicy::Warn`]
+    pub fn with_texture_policy(mut self, policy: TexturePolicy) -> Self {
+        self.settings.texture_policy = policy;
+        self
+    }
+
+    /// Simplifies models with more than `max_triangles` triangles down to about that many when
+    /// they are loaded, by merging nearby vertices on a grid. Rasterising a million triangle
+    /// scan into a 128 pixel thumbnail is mostly wasted work, and at that size the simplified
+    /// model looks the same. [`RenderStats::triangles_before_simplifying`] tells whether it
+    /// kicked in.
+    ///
+    /// Default: no limit
+    pub fn with_max_triangles(mut self, max_triangles: usize) -> Self {
+        self.settings.max_triangles = Some(max_triangles.max(1));
+        self
+    }
+
+    /// Renumbers each mesh's vertices in the order its triangles first use them when it's
+    /// loaded, so the rasteriser reads the vertex data front to back instead of jumping around
+    /// it. Scans and exported meshes often have their vertices in an order unrelated to their
+    /// triangles, and with a million or so of them the jumping is what the rasteriser spends
+    /// its time on.
+    ///
+    /// Only the vertices move: the triangles stay in the order they were given, as that
+    /// order decides which of two surfaces at the same depth ends up on top, so the image is
+    /// exactly the same either way. Meshes with bones or morph targets are left as they are.
+    ///
+    /// Default: false
+    pub fn with_optimize_mesh(mut self, optimize_mesh: bool) -> Self {
+        self.settings.optimize_mesh = optimize_mesh;
+        self
+    }
+
+    /// Warns about textured meshes whose faces cover fewer than `min_texels` texels of their
+    /// texture, added up over every face, which usually means the texture's resolution is too
+    /// low for the mesh or its UVs are squashed into a corner of an atlas. See
+    /// [`ModelToImage::uv_stats`] for the numbers behind the warning.
+    ///
+    /// Default: 0.0, no warning
+    pub fn with_min_texels(mut self, min_texels: f32) -> Self {
+        self.settings.min_texels = min_texels;
+        self
+    }
+
+    /// How precisely depth is compared between overlapping surfaces. Depth runs across the
+    /// whole depth of the model, so in scenes kilometres deep, surfaces a few millimetres
+    /// apart (a railing against a wall) can end up at the same single precision depth and show
+    /// through each other in speckles. [`DepthPrecision::Double`] fixes that, at the cost of a
+    /// z-buffer twice the size. [`RenderStats::scene_extent`] tells how big the scene is.
+    ///
+    /// Default: [`DepthPrecision::Single`]
+    pub fn with_depth_precision(mut self, precision: DepthPrecision) -> Self {
+        self.settings.depth_precision = precision;
+        self
+    }
+
+    /// Which surface wins where several cover a pixel. [`DepthTest::LessWins`] keeps the one
+    /// with the smallest Z, for models exported facing +Z that otherwise show their far side
+    /// where parts overlap. Faces are still lit and culled by their winding.
+    ///
+    /// Default: [`DepthTest::GreaterWins`]
+    pub fn with_depth_test(mut self, depth_test: DepthTest) -> Self {
+        self.settings.depth_test = depth_test;
+        self
+    }
+
+    /// Draws the back of every face as well as its front, for all materials instead of only
+    /// those marked double-sided (glTF's `doubleSided`, or [`MaterialData::double_sided`]).
+    /// A face seen from behind is lit as if it faced the viewer, where otherwise it's skipped
+    /// unless the light reaches its back. Leave it off for closed models, which never show
+    /// their back faces and draw faster when those are skipped.
+    ///
+    /// Default: false, only materials marked double-sided
+    pub fn with_double_sided(mut self, double_sided: bool) -> Self {
+        self.settings.double_sided = double_sided;
+        self
+    }
+
+    /// The handedness of the model's coordinates. [`Handedness::Left`] mirrors the model's Z
+    /// (positions and normals, but not the winding) as it's loaded, which turns data from
+    /// left-handed tools the right way out.
+    ///
+    /// Default: [`Handedness::Right`], as assimp and glTF use
+    pub fn with_handedness(mut self, handedness: Handedness) -> Self {
+        self.settings.handedness = handedness;
+        self
+    }
+
+    /// Where the texture coordinates' origin is on the textures. [`TextureOrigin::TopLeft`]
+    /// suits models exported for APIs that put (0, 0) at the image's first row, whose textures
+    /// otherwise come out upside down.
+    ///
+    /// Default: [`TextureOrigin::BottomLeft`], as assimp, glTF importers and OpenGL use
+    pub fn with_texture_origin(mut self, origin: TextureOrigin) -> Self {
+        self.settings.texture_origin = origin;
+        self
+    }
+
+    /// Flips the texture coordinates as the model is loaded (with assimp's `FlipUVs` step, or
+    /// directly for [`Self::from_meshes`]) instead of while sampling the textures, when the
+    /// origin is [`TextureOrigin::TopLeft`]. The image is the same either way; this only
+    /// matters to what reads the meshes afterwards, e.g. [`ModelToImage::uv_stats`].
+    ///
+    /// Default: false
+    pub fn with_flip_uvs_on_import(mut self, flip: bool) -> Self {
+        self.settings.flip_uvs_on_import = flip;
+        self
+    }
+
+    /// Spreads the colour of every texture's UV islands `pixels` texels out into the atlas
+    /// background around them, once as the model is built. Small renders sample the textures
+    /// coarsely, and without this the texels just past an island's edge show as dark seams
+    /// along the model's UV seams.
+    ///
+    /// The background is a texture's fully transparent texels if it has any, otherwise every
+    /// texel outside the UV triangles of the meshes using it. Textures whose UVs wrap around
+    /// them more than once are left as they are, as no part of them is background.
+    ///
+    /// Default: 0, textures are sampled as they are
+    pub fn with_seam_dilation(mut self, pixels: u32) -> Self {
+        self.settings.seam_dilation = pixels;
+        self
+    }
+
+    /// How small a triangle's area on screen (in square pixels, doubled) can get before it is
+    /// treated as having none and skipped. Raise it if near zero area triangles produce
+    /// speckles, lower it if long thin triangles leave gaps.
+    ///
+    /// Default: 1e-6
+    pub fn with_degenerate_epsilon(mut self, epsilon: f32) -> Self {
+        self.settings.degenerate_epsilon = epsilon.max(0.0);
+        self
+    }
+
+    /// Rescales the model after loading so its largest dimension is 1.0, which keeps the maths
+    /// well behaved for models in tiny or huge units (e.g. a building exported in millimetres).
+    /// The original scale is remembered (see [`ModelToImage::scale_factor`]), so settings given
+    /// in model units, like [`Self::with_world_scale`], [`Self::with_framing`],
+    /// [`Self::with_clip_plane`] and [`Self::with_dimension_labels`], still use the model's
+    /// original units.
+    ///
+    /// Independent of this, FBX files are always converted into metres on import, by the
+    /// `UnitScaleFactor` they declare.
+    ///
+    /// Default: false
+    pub fn with_normalize_scale(mut self, normalize_scale: bool) -> Self {
+        self.settings.normalize_scale = normalize_scale;
+        self
+    }
+
+    /// Declares which axis of the model points up, so models from exporters that use Z as up
+    /// (3ds Max and many CAD tools) don't render lying on their side. The model is rotated
+    /// after loading so that this axis points up in the image, and settings given in model
+    /// space (like [`Self::with_clip_plane`]) apply to the rotated model.
+    ///
+    /// This always wins over [`Self::with_auto_up_axis`].
+    ///
+    /// Default: None, which keeps the model as it is (Y up)
+    pub fn with_up_axis(mut self, up_axis: UpAxis) -> Self {
+        self.settings.up_axis = Some(up_axis);
+        self
+    }
+
+    /// Reads the up axis from the model's metadata (the `UpAxis`/`OriginalUpAxis` keys assimp
+    /// fills in for formats like FBX) when [`Self::with_up_axis`] hasn't set one. Formats that
+    /// don't record an up axis, like OBJ, are left as they are.
+    ///
+    /// Default: false
+    pub fn with_auto_up_axis(mut self, auto_up_axis: bool) -> Self {
+        self.settings.auto_up_axis = auto_up_axis;
+        self
+    }
+
+    /// Turns the model by its shape, for scans that come in lying on their side: its longest
+    /// dimension goes across the image, the next longest up and down, and its thinnest towards
+    /// the camera. The dimensions are the principal axes of the vertices, so this is a guess
+    /// that suits elongated models and can pick any side of a cube.
+    ///
+    /// Takes the place of [`Self::with_auto_up_axis`], and [`Self::with_up_axis`] turns it off
+    /// as an explicit choice. [`Self::with_view`] and [`Self::with_camera_roll`] turn the
+    /// model further from where this leaves it.
+    ///
+    /// Default: false
+    pub fn with_auto_orient(mut self, auto_orient: bool) -> Self {
+        self.settings.auto_orient = auto_orient;
+        self
+    }
+
+    /// Rotates the model clockwise around the viewing direction by `degrees`, after the up axis
+    /// has been applied, for fine adjustment.
+    ///
+    /// Default: 0.0
+    pub fn with_camera_roll(mut self, degrees: f32) -> Self {
+        self.settings.camera_roll = degrees;
+        self
+    }
+
+    /// Which side of the model is shown. [`ViewPreset::Auto`] tries the candidate views at a
+    /// small size and picks the one showing the most of the model, which suits unattended
+    /// thumbnail generation; [`ModelToImage::view`] tells which one was picked.
+    ///
+    /// The view is chosen after [`Self::with_up_axis`] stands the model up, and
+    /// [`Self::with_camera_roll`] rolls the image of it.
+    ///
+    /// Default: [`ViewPreset::Front`]
+    pub fn with_view(mut self, view: ViewPreset) -> Self {
+        self.settings.view = view;
+        self
+    }
+
+    /// The colour encoding and bit depth of the output. The linear formats undo the sRGB curve
+    /// so the values are proportional to light, which is what compositors want for grading;
+    /// [`OutputPixels::Linear16`] also keeps the full precision of the shading, so gradients
+    /// don't band.
+    ///
+    /// [`ModelToImage::output`] is always 8 bits per channel (linear for both linear formats),
+    /// use [`ModelToImage::output_any`] for the 16-bit image. [`ModelToImage::write_to`] saves
+    /// the 16-bit image to PNG and TIFF files.
+    ///
+    /// Default: [`OutputPixels::Srgb8`]
+    pub fn with_output_format(mut self, output_pixels: OutputPixels) -> Self {
+        self.settings.output_pixels = output_pixels;
+        self
+    }
+
+    /// Bounds how long rasterising may take in each call to [`ModelToImage::render`] (and its
+    /// siblings), for services that can't let one model hold up the rest. The time is checked
+    /// between triangles, so a render stops within a few hundred triangles of running out.
+    /// What happens then is up to `policy`: fail with [`TimeBudgetExceeded`], or keep the
+    /// partial image and report it in [`RenderStats::time_budget_exceeded`].
+    ///
+    /// Default: no limit
+    pub fn with_time_budget(mut self, budget: Duration, policy: TimeBudgetPolicy) -> Self {
+        self.settings.time_budget = Some(budget);
+        self.settings.time_budget_policy = policy;
+        self
+    }
+
+    /// Checks every [`ModelToImage::render`] for a model that fills less than `min_fraction` of
+    /// the image (see [`ModelToImage::coverage`]), which usually means broken geometry or a bad
+    /// fit. What happens then is up to `policy`: a warning, or failing with
+    /// [`InsufficientCoverage`].
+    ///
+    /// Default: no check
+    pub fn with_min_coverage(mut self, min_fraction: f32, policy: CoveragePolicy) -> Self {
+        self.settings.min_coverage = Some(min_fraction);
+        self.settings.min_coverage_policy = policy;
+        self
+    }
+
+    /// Whether [`ModelToImage::write_to`] (and the other `write_*` methods) may replace a file
+    /// that already exists. When false, writing to an existing path is an error instead. Can
+    /// be changed later with [`ModelToImage::set_overwrite`].
+    ///
+    /// Default: true
+    pub fn with_overwrite(mut self, overwrite: bool) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// How many times animations written by [`ModelToImage::write_apng_to`] (and
+    /// `write_animated_webp_to`) play before stopping, 0 to loop forever.
+    ///
+    /// Default: 0
+    pub fn with_animation_loops(mut self, loops: u32) -> Self {
+        self.settings.animation_loops = loops;
+        self
+    }
+
+    /// Replaces every render setting at once, e.g. with a shared set of defaults. Any `with_*`
+    /// calls after this one are applied on top.
+    pub fn with_settings(mut self, settings: RenderSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// The output size, which used to be the public `size` field.
+    #[deprecated(note = "use `settings.size`, or `with_size` to set it")]
+    pub fn size(&self) -> (u32, u32) {
+        self.settings.size
+    }
+
+    /// The output size, for code that used to assign the public `size` field.
+    #[deprecated(note = "use `settings.size`, or `with_size` to set it")]
+    pub fn size_mut(&mut self) -> &mut (u32, u32) {
+        &mut self.settings.size
+    }
+
+    /// The light direction, which used to be the public `light_dir` field.
+    #[deprecated(note = "use `settings.light_dir`, or `with_light_direction` to set it")]
+    pub fn light_dir(&self) -> [f32; 3] {
+        self.settings.light_dir
+    }
+
+    /// The light direction, for code that used to assign the public `light_dir` field.
+    #[deprecated(note = "use `settings.light_dir`, or `with_light_direction` to set it")]
+    pub fn light_dir_mut(&mut self) -> &mut [f32; 3] {
+        &mut self.settings.light_dir
+    }
+
+    /// The margin, which used to be the public `margin` field.
+    #[deprecated(note = "use `settings.margin`, or `with_margin` to set it")]
+    pub fn margin(&self) -> f32 {
+        self.settings.margin
+    }
+
+    /// The margin, for code that used to assign the public `margin` field.
+    #[deprecated(note = "use `settings.margin`, or `with_margin` to set it")]
+    pub fn margin_mut(&mut self) -> &mut f32 {
+        &mut self.settings.margin
+    }
+
+    /// A key for caching what this builder renders: the same model and settings always give
+    /// the same key, on any platform and in any run, and changing a single byte of the model or
+    /// any setting that affects the image gives a different one. Settings that can't change the
+    /// image, like [`Self::with_time_budget`] or [`Self::with_overwrite`], are left out.
+    ///
+    /// The model file is streamed through the hash rather than loaded, but textures it refers
+    /// to by file name are not part of the key. Keys also change between versions of this
+    /// crate, since those can render the same model differently.
+    ///
+    /// ```no_run
+    /// # use std::path::PathBuf;
+    /// let builder = model_to_image::ModelToImageBuilder::new(&PathBuf::from("fish.glb")).with_size((128, 128));
+    /// let thumbnail = PathBuf::from(format!("thumbnails/{:016x}.png", builder.cache_key()?));
+    /// if !thumbnail.exists() {
+    ///     builder.build()?.render()?.write_to(Some(&thumbnail))?;
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn cache_key(&self) -> anyhow::Result<u64> {
+        cache_key::cache_key(self)
+    }
+
+    /// Loads the model and gets it ready to render.
+    ///
+    /// Fails if the model path does not exist, the model can't be imported, or the settings
+    /// contain values that can't be rendered.
+    pub fn build(mut self) -> anyhow::Result<ModelToImage> {
+        self.settings.validate()?;
+        self.settings.apply_environment_options();
+        let started = Instant::now();
+        let (scene, from_cache) = match self.meshes.take() {
+            Some((mut meshes, materials)) => {
+                if self.settings.flips_uvs_on_import() {
+                    for uv in meshes.iter_mut().flat_map(|mesh| mesh.uvs.iter_mut()) {
+                        uv[1] = 1.0 - uv[1];
+                    }
+                }
+                (SceneData::from_meshes(meshes, materials, self.settings.max_texture_size), false)
+            }
+            None => self.import()?,
+        };
+        let mut model = ModelToImage::new(self, scene)?;
+        model.stats.load_time = started.elapsed();
+        model.stats.scene_from_cache = from_cache;
+        Ok(model)
+    }
+
+    /// Imports the model file, or reads it from the scene cache if there is one. The flag is
+    /// whether it came from the cache.
+    fn import(&self) -> anyhow::Result<(SceneData, bool)> {
+        let import = || {
+            let mut post_process = vec![
+                PostProcess::CalculateTangentSpace,
+                PostProcess::Triangulate,
+                PostProcess::JoinIdenticalVertices,
+                PostProcess::SortByPrimitiveType,
+            ];
+            post_process.extend(steps_for_properties(&self.settings.import_properties));
+            if self.settings.flips_uvs_on_import() {
+                post_process.push(PostProcess::FlipUVs);
+            }
+            let scene = load_scene(&self.model_path, post_process, &self.settings.import_properties)?;
+            SceneData::from_scene(scene, self)
+        };
+        #[cfg(feature = "scene-cache")]
+        if let Some(dir) = &self.scene_cache_dir {
+            return scene_cache::load_or_import(dir, self, import);
+        }
+        Ok((import()?, false))
+    }
+}
+
+/// The post processing steps that read the given import properties, which assimp would
+/// otherwise never look at: e.g. `PP_FD_REMOVE` only does anything in the step finding
+/// degenerate faces, which isn't run by default.
+fn steps_for_properties(properties: &[(String, PropertyValue)]) -> Vec<PostProcess> {
+    const STEPS: [(&str, PostProcess); 4] = [
+        ("PP_FD_", PostProcess::FindDegenerates),
+        ("PP_RVC_", PostProcess::RemoveComponent),
+        ("PP_PTV_", PostProcess::PreTransformVertices),
+        ("PP_SLM_", PostProcess::SplitLargeMeshes),
+    ];
+    let mut steps = Vec::new();
+    for (prefix, step) in STEPS {
+        if properties.iter().any(|(key, _)| key.starts_with(prefix)) {
+            steps.push(step);
+        }
+    }
+    steps
+}
+
+/// Imports the model at `path` with the given assimp post processing steps and import
+/// properties.
+pub(crate) fn load_scene(
+    path: &Path,
+    post_process: Vec<PostProcess>,
+    properties: &[(String, PropertyValue)],
+) -> anyhow::Result<Scene> {
+    let path = paths::loader_path(path)?;
+    let path_str = path.as_str();
+    if properties.is_empty() {
+        return Ok(Scene::from_file(path_str, post_process)?);
+    }
+
+    // assimp reads the keys as C strings
+    let keys: Vec<Vec<u8>> = properties.iter().map(|(key, _)| [key.as_bytes(), b"\0"].concat()).collect();
+    let mut store = PropertyStore::default();
+    for ((_, value), key) in properties.iter().zip(&keys) {
+        match value {
+            PropertyValue::String(value) => store.set_string(key, value),
+            PropertyValue::Int(value) => store.set_integer(key, *value),
+            PropertyValue::Float(value) => store.set_float(key, *value),
+            PropertyValue::Bool(value) => store.set_integer(key, *value as i32),
+        }
+    }
+    Ok(Scene::from_file_with_props(path_str, post_process, &store)?)
+}
+
+/// A model ready to render, made by [`ModelToImageBuilder::build`].
+///
+/// Everything the renderer needs (geometry, decoded textures, material colours) is copied out
+/// of assimp's scene while building, and the scene is dropped, so this only owns plain data
+/// and is `Send` and `Sync`: build it on one thread and render it on another, or share it
+/// behind a `Mutex`. Rendering takes `&mut self` for the framebuffers, so to render from
+/// several threads at once give each its own [`Self::clone_for_render`], which shares the
+/// geometry and textures. A plain clone shares them too, but also copies the image buffers.
+#[derive(Debug, Clone)]
+pub struct ModelToImage {
+    model_path: PathBuf,
+    settings: RenderSettings,
+    size: Size,
+    img_buf: RgbImage,
+    /// The colour of every pixel before it was rounded to 8 bits, in the same layout as
+    /// `img_buf` (and `0.0..=255.0`). Only kept for [`OutputPixels::Linear16`].
+    precise: Option<Vec<[f32; 3]>>,
+    /// The colour of every pixel before it was clipped to 8 bits, allowed to go over 255. Only
+    /// kept for [`ModelToImageBuilder::with_bloom`], which needs to know how bright lights are.
+    hdr: Option<Vec<[f32; 3]>>,
+    /// The output for [`OutputPixels::Linear16`]
+    img_buf16: Option<Rgb16Image>,
+    /// Alpha channel of the output, only present when something made parts of it transparent
+    alpha: Option<GrayImage>,
+    /// Depth of the model at every pixel of the output (in output orientation), negative
+    /// infinity where the model doesn't cover it. Empty until rendered.
+    depth: Vec<f32>,
+    /// Fraction of the render passes in which the model covered each pixel, in the same layout
+    /// as `depth`
+    coverage: Vec<f32>,
+    /// The environment behind the model at the current size, in output orientation. Drawn on
+    /// the first render that needs it.
+    backdrop: Option<RgbImage>,
+    /// Colour the ambient light is multiplied by, see
+    /// [`ModelToImageBuilder::with_environment_ambient`]
+    ambient_tint: Option<[f32; 3]>,
+    /// Surface attributes of every pixel, in the same layout as `depth`. Only kept up to date
+    /// once [`ModelToImage::render_gbuffer`] has been called.
+    gbuffer: Option<GBuffer>,
+    /// When the current render runs out of time, see [`ModelToImageBuilder::with_time_budget`]
+    deadline: Option<Instant>,
+    /// Set once the current render has gone past `deadline`, after which nothing more is drawn
+    timed_out: bool,
+    /// Only this mesh is rendered when set, see [`ModelToImage::render_per_mesh`]
+    isolated_mesh: Option<usize>,
+    /// Only these pixels (of the image still upside down) are rasterised when set, see
+    /// [`ModelToImage::render_dirty_rect`]
+    scissor: Option<PixelRect>,
+    /// The full size the model is framed for and how many times smaller the image is, while
+    /// [`ModelToImage::render_preview`] runs
+    preview: Option<(Size, u32)>,
+    /// Rotation baked into the scene after loading, see [`ModelToImageBuilder::with_up_axis`]
+    /// and [`ModelToImageBuilder::with_view`]
+    orientation: Matrix3<f32>,
+    /// The view the model is rendered from, never [`ViewPreset::Auto`]
+    view: ViewPreset,
+    /// The (unjittered) projection of the last render
+    projection: Option<Projection>,
+    /// Shared with every [`RenderInstance`] made from this model, and copied on the first
+    /// change while it is
+    meshes: Arc<Vec<MeshData>>,
+    material_names: Vec<String>,
+    /// Light given off by every material, indexed like `material_names`, in `0.0..=255.0` per
+    /// channel but allowed to go over
+    emissive: Vec<[f32; 3]>,
+    /// Whether every material is marked double-sided, indexed like `material_names`
+    double_sided: Vec<bool>,
+    /// The name and world transform of every node, see [`ModelToImage::node_world_transform`]
+    nodes: Vec<(String, Matrix4<f32>)>,
+    /// The joints of the model's bones, in world space like `nodes`, see [`Overlay::Skeleton`]
+    skeleton: Vec<Joint>,
+    /// The centre of every part of an exploded view before and after it moved, in world space
+    /// like `nodes`, see [`Overlay::ExplodeConnectors`]
+    connectors: Vec<(Vector3<f32>, Vector3<f32>)>,
+    /// How many of the model's original units one unit of `meshes` is, see
+    /// [`ModelToImageBuilder::with_normalize_scale`]
+    scale_factor: f32,
+    textures: Vec<Option<Arc<DynamicImage>>>,
+    /// See [`ModelToImage::texture_downsampling`]
+    texture_downsampling: Vec<f32>,
+    /// See [`ModelToImage::set_overwrite`]
+    overwrite: bool,
+    /// See [`ModelToImage::missing_uvs`]
+    missing_uvs: Vec<MissingUvs>,
+    warnings: Vec<String>,
+    stats: RenderStats,
+}
+
+/// Picks out meshes of the loaded scene, for options that only apply to some of them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeshSelector {
+    /// The mesh at this index in the scene's mesh list
+    Index(usize),
+    /// Every mesh with exactly this name
+    Name(String),
+}
+
+impl MeshSelector {
+    pub(crate) fn matches(&self, mesh_idx: usize, mesh_name: &str) -> bool {
+        match self {
+            MeshSelector::Index(idx) => *idx == mesh_idx,
+            MeshSelector::Name(name) => name == mesh_name,
+        }
+    }
+}
+
+/// Picks out a bone of the loaded model, see [`RenderMode::BoneWeights`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoneSelector {
+    /// The bone at this index, counting every differently named bone in the order the meshes
+    /// first use them
+    Index(usize),
+    /// The bone with exactly this name, which is also the name of its node
+    Name(String),
+}
+
+impl From<usize> for BoneSelector {
+    fn from(value: usize) -> Self {
+        BoneSelector::Index(value)
+    }
+}
+
+impl From<&str> for BoneSelector {
+    fn from(value: &str) -> Self {
+        BoneSelector::Name(value.to_string())
+    }
+}
+
+impl From<String> for BoneSelector {
+    fn from(value: String) -> Self {
+        BoneSelector::Name(value)
+    }
+}
+
+impl From<usize> for MeshSelector {
+    fn from(value: usize) -> Self {
+        MeshSelector::Index(value)
+    }
+}
+
+impl From<&str> for MeshSelector {
+    fn from(value: &str) -> Self {
+        MeshSelector::Name(value.to_string())
+    }
+}
+
+impl From<String> for MeshSelector {
+    fn from(value: String) -> Self {
+        MeshSelector::Name(value)
+    }
+}
+
+/// One of the three axes of the model's coordinate space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    /// The component of `v` along this axis.
+    pub(crate) fn component(&self, v: &Vector3<f32>) -> f32 {
+        match self {
+            Axis::X => v.x,
+            Axis::Y => v.y,
+            Axis::Z => v.z,
+        }
+    }
+}
+
+/// A plane that cuts the model, discarding everything on the side its normal points to.
+///
+/// A point `p` is kept when `normal · p <= offset`, so `offset` is the signed distance of the
+/// plane from the origin along the (normalised) normal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClipPlane {
+    pub normal: [f32; 3],
+    pub offset: f32,
+}
+
+impl ClipPlane {
+    /// Whether the model space position `p` is on the discarded side of the plane.
+    pub(crate) fn clips(&self, p: &Vector3<f32>) -> bool {
+        Vector3::from(self.normal).dot(p) > self.offset
+    }
+}
+
+/// Annotations drawn on top of the rendered model, using the same projection as the model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Overlay {
+    /// The 12 edges of the model's axis aligned bounding box
+    BoundingBox { colour: Colour, width_px: u32 },
+    /// Lines from the centre of the model along +X (red), +Y (green) and +Z (blue), each
+    /// `length_fraction` of the model's largest dimension long
+    Axes { length_fraction: f32 },
+    /// The bones of the model's skeleton, as lines from each joint to the next with a dot on
+    /// every joint. Always drawn on top, as the bones are inside the model.
+    Skeleton { colour: Colour, width_px: u32 },
+    /// Lines from where the centre of each part was to where it is in an exploded view, see
+    /// [`ModelToImageBuilder::with_explode`]
+    ExplodeConnectors { colour: Colour, width_px: u32 },
+}
+
+/// Light added to the silhouette edges of the model, see
+/// [`ModelToImageBuilder::with_rim_light`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RimLight {
+    pub colour: Colour,
+    pub strength: f32,
+    pub power: f32,
+}
+
+/// What is drawn behind the model, see [`ModelToImageBuilder::with_background`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Background {
+    Solid(Colour),
+    /// Blends from `top` at the top of the image to `bottom` at the bottom
+    Gradient { top: Colour, bottom: Colour },
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Background::Solid(Colour::from(BACKGROUND))
+    }
+}
+
+impl Background {
+    /// The background as an image, the right way up.
+    pub(crate) fn render(&self, width: u32, height: u32) -> RgbImage {
+        match *self {
+            Background::Solid(colour) => RgbImage::from_pixel(width, height, colour.into()),
+            Background::Gradient { top, bottom } => {
+                let (top, bottom): ([u8; 3], [u8; 3]) = (top.into(), bottom.into());
+                let span = height.saturating_sub(1).max(1) as f32;
+                RgbImage::from_fn(width, height, |_, y| {
+                    let t = y as f32 / span;
+                    Rgb([0, 1, 2].map(|c| (top[c] as f32 * (1.0 - t) + bottom[c] as f32 * t).round() as u8))
+                })
+            }
+        }
+    }
+}
+
+/// A line around the model's silhouette, see [`ModelToImageBuilder::with_outline`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Outline {
+    pub colour: Colour,
+    pub width_px: u32,
+}
+
+/// A shadow cast by the model onto the background, see
+/// [`ModelToImageBuilder::with_drop_shadow`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DropShadow {
+    pub opacity: f32,
+    pub offset_px: (i32, i32),
+    pub blur_px: f32,
+}
+
+/// A glow around the brightest parts of the model, see [`ModelToImageBuilder::with_bloom`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bloom {
+    pub threshold: f32,
+    pub radius_px: f32,
+    pub strength: f32,
+}
+
+/// A blur that grows with the distance from a focal depth, see
+/// [`ModelToImageBuilder::with_depth_of_field`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepthOfField {
+    pub focus_depth: f32,
+    pub range: f32,
+    pub max_blur_px: f32,
+    pub blur_background: bool,
+}
+
+/// The side of the model facing the viewer, see [`ModelToImageBuilder::with_view`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ViewPreset {
+    /// Looking at the model's +Z side, with +Y up
+    #[default]
+    Front,
+    Back,
+    /// Looking at the model's -X side
+    Left,
+    /// Looking at the model's +X side
+    Right,
+    /// Looking down at the model's +Y side, with its back at the top
+    Top,
+    Bottom,
+    /// From the front, right and above, at equal angles to all three
+    Isometric,
+    /// Whichever of the other views shows the most of the model
+    Auto,
+}
+
+impl ViewPreset {
+    /// The views [`ViewPreset::Auto`] picks from.
+    pub const CANDIDATES: [ViewPreset; 7] = [
+        ViewPreset::Front,
+        ViewPreset::Isometric,
+        ViewPreset::Right,
+        ViewPreset::Left,
+        ViewPreset::Top,
+        ViewPreset::Back,
+        ViewPreset::Bottom,
+    ];
+
+    /// The lowercase name of the view, e.g. `isometric`, as the C API and output name templates
+    /// spell it.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ViewPreset::Front => "front",
+            ViewPreset::Back => "back",
+            ViewPreset::Left => "left",
+            ViewPreset::Right => "right",
+            ViewPreset::Top => "top",
+            ViewPreset::Bottom => "bottom",
+            ViewPreset::Isometric => "isometric",
+            ViewPreset::Auto => "auto",
+        }
+    }
+
+    /// Rotates the model so this side faces the viewer (who looks down -Z).
+    pub(crate) fn rotation(&self) -> Matrix3<f32> {
+        let about = |axis: Unit<Vector3<f32>>, degrees: f32| utils::rotation_about(&axis, degrees);
+        let rotation = match self {
+            ViewPreset::Front | ViewPreset::Auto => Rotation3::identity(),
+            ViewPreset::Back => about(Vector3::y_axis(), 180.0),
+            ViewPreset::Left => about(Vector3::y_axis(), 90.0),
+            ViewPreset::Right => about(Vector3::y_axis(), -90.0),
+            ViewPreset::Top => about(Vector3::x_axis(), 90.0),
+            ViewPreset::Bottom => about(Vector3::x_axis(), -90.0),
+            // tilting by atan(1 / sqrt(2)) after turning 45 degrees puts all three axes at the
+            // same angle to the screen
+            ViewPreset::Isometric => {
+                about(Vector3::x_axis(), (1.0 / 2.0_f32.sqrt()).atan().to_degrees()) * about(Vector3::y_axis(), -45.0)
+            }
+        };
+        rotation.into_inner()
+    }
+}
+
+/// The axis of a model that points up, see [`ModelToImageBuilder::with_up_axis`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UpAxis {
+    #[default]
+    Y,
+    Z,
+    NegY,
+    NegZ,
+}
+
+impl UpAxis {
+    /// Rotates `v` so that this axis ends up pointing along +Y.
+    pub(crate) fn to_y_up(&self, v: Vector3<f32>) -> Vector3<f32> {
+        match self {
+            UpAxis::Y => v,
+            UpAxis::Z => Vector3::new(v.x, v.z, -v.y),
+            UpAxis::NegY => Vector3::new(v.x, -v.y, -v.z),
+            UpAxis::NegZ => Vector3::new(v.x, -v.z, v.y),
+        }
+    }
+}
+
+/// Real world units the model's dimensions are reported in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Units {
+    Millimetres,
+    Centimetres,
+    Metres,
+    Inches,
+    Feet,
+}
+
+impl Units {
+    /// The short suffix written after a measurement, e.g. `cm`.
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            Units::Millimetres => "mm",
+            Units::Centimetres => "cm",
+            Units::Metres => "m",
+            Units::Inches => "in",
+            Units::Feet => "ft",
+        }
+    }
+}
+
+/// Settings for the dimension annotation, see [`ModelToImageBuilder::with_dimension_labels`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DimensionLabels {
+    pub units: Units,
+    /// How many `units` one unit of the model is
+    pub unit_scale: f32,
+    pub scale_bar: bool,
+}
+
+/// A corner of the output image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// A logo or other image stamped onto every render, see [`ModelToImageBuilder::with_watermark`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Watermark {
+    pub image: DynamicImage,
+    pub corner: Corner,
+    pub opacity: f32,
+    pub margin_px: u32,
+}
+
+/// What a render does when it runs out of time, see [`ModelToImageBuilder::with_time_budget`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeBudgetPolicy {
+    /// Fail with [`TimeBudgetExceeded`]
+    #[default]
+    Abort,
+    /// Stop drawing and keep whatever was drawn so far, noted in
+    /// [`RenderStats::time_budget_exceeded`]
+    Partial,
+}
+
+/// The error a render fails with when it runs out of time under [`TimeBudgetPolicy::Abort`].
+/// Render errors are [`anyhow::Error`]s, so check for it with `downcast_ref`:
+///
+/// ```no_run
+/// # use std::path::PathBuf;
+/// # use std::time::Duration;
+/// use model_to_image::{TimeBudgetExceeded, TimeBudgetPolicy};
+///
+/// let mut model = model_to_image::ModelToImageBuilder::new(&PathBuf::from("scan.ply"))
+///     .with_time_budget(Duration::from_secs(10), TimeBudgetPolicy::Abort)
+///     .build()?;
+/// if let Err(err) = model.render() {
+///     if let Some(exceeded) = err.downcast_ref::<TimeBudgetExceeded>() {
+///         eprintln!("gave up after {:?}", exceeded.elapsed);
+///     }
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeBudgetExceeded {
+    pub budget: Duration,
+    pub elapsed: Duration,
+}
+
+impl std::fmt::Display for TimeBudgetExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Rendering took longer than its time budget of {:?} (stopped after {:?})", self.budget, self.elapsed)
+    }
+}
+
+impl std::error::Error for TimeBudgetExceeded {}
+
+/// A model path that can't be handed to the importer as it is, e.g. one that isn't valid
+/// unicode. Returned by [`ModelToImageBuilder::build`] and [`probe`], so callers can
+/// `downcast_ref` it from the error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidPath {
+    /// The path as it was resolved, made absolute
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+impl std::fmt::Display for InvalidPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The model path [{}] can't be loaded, as {}", self.path.display(), self.reason)
+    }
+}
+
+impl std::error::Error for InvalidPath {}
+
+/// How depth is compared while rasterising, see [`ModelToImageBuilder::with_depth_precision`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DepthPrecision {
+    /// Depth worked out and compared in `f32`, enough unless the model is very deep and
+    /// detailed at once
+    #[default]
+    Single,
+    /// Depth worked out and compared in `f64`, interpolated from the corner of each triangle so
+    /// flat surfaces keep exactly the depth of their vertices
+    Double,
+}
+
+/// Which of the surfaces covering a pixel is drawn, see [`ModelToImageBuilder::with_depth_test`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DepthTest {
+    /// The surface with the largest Z, nearest the camera looking down -Z
+    #[default]
+    GreaterWins,
+    /// The surface with the smallest Z
+    LessWins,
+}
+
+/// The handedness of a model's coordinate system, see
+/// [`ModelToImageBuilder::with_handedness`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Handedness {
+    #[default]
+    Right,
+    /// Mirrored in Z compared to the right-handed coordinates the renderer uses
+    Left,
+}
+
+/// Where (0, 0) in texture coordinates is on a texture, see
+/// [`ModelToImageBuilder::with_texture_origin`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextureOrigin {
+    /// V runs up from the bottom row of the image
+    #[default]
+    BottomLeft,
+    /// V runs down from the top row of the image, as in Direct3D, Metal and Vulkan
+    TopLeft,
+}
+
+impl TextureOrigin {
+    /// How far down the image, from 0.0 at the top row to 1.0 past the bottom one, `v` is.
+    pub(crate) fn rows_down(self, v: f32) -> f32 {
+        match self {
+            Self::BottomLeft => 1.0 - v,
+            Self::TopLeft => v,
+        }
+    }
+}
+
+/// The order translucent geometry is blended in, see
+/// [`ModelToImageBuilder::with_transparent_sort`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortMode {
+    /// Back to front by the average depth of each mesh, and of each triangle within it
+    #[default]
+    CentroidDepth,
+    /// Grouped by material, in material order, then back to front within each material
+    MaterialThenDepth,
+    /// The order of the meshes and triangles in the model, so the caller decides
+    Stable,
+}
+
+/// What building a model does about textures that can't be loaded, see
+/// [`ModelToImageBuilder::with_texture_policy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TexturePolicy {
+    /// Render the materials without their textures, with a warning for each
+    #[default]
+    Warn,
+    /// Fail to build, listing every texture that couldn't be loaded
+    Fail,
+}
+
+/// What a render does when the model covers too little of the image, see
+/// [`ModelToImageBuilder::with_min_coverage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CoveragePolicy {
+    /// Keep the image and add a warning
+    #[default]
+    Warn,
+    /// Fail with [`InsufficientCoverage`]
+    Fail,
+}
+
+/// The error a render fails with when the model covers less of the image than
+/// [`ModelToImageBuilder::with_min_coverage`] asks for, under [`CoveragePolicy::Fail`]. The
+/// image is still rendered, so it can be looked at to see what went wrong.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InsufficientCoverage {
+    pub coverage: CoverageStats,
+    pub min_fraction: f32,
+}
+
+impl std::fmt::Display for InsufficientCoverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The model covers {:.1}% of the image, less than the minimum of {:.1}%",
+            self.coverage.fraction * 100.0,
+            self.min_fraction * 100.0
+        )
+    }
+}
+
+impl std::error::Error for InsufficientCoverage {}
+
+/// A mesh whose material has a texture, but which has no texture coordinates to sample it
+/// with, so it's shaded plain grey instead. Listed by [`ModelToImage::missing_uvs`], and as
+/// text in [`ModelToImage::warnings`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingUvs {
+    pub mesh_idx: usize,
+    /// The mesh's name, may be empty
+    pub name: String,
+}
+
+impl std::fmt::Display for MissingUvs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Mesh {}: missing UVs, its texture is skipped and it is shaded plain grey",
+            mesh_label(self.mesh_idx, &self.name)
+        )
+    }
+}
+
+/// The colour encoding and bit depth of the output, see
+/// [`ModelToImageBuilder::with_output_format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputPixels {
+    /// 8 bits per channel with the sRGB curve, ready for display
+    #[default]
+    Srgb8,
+    /// 8 bits per channel, linear light
+    Linear8,
+    /// 16 bits per channel, linear light
+    Linear16,
+}
+
+/// An RGB image with 16 bits per channel.
+pub type Rgb16Image = ImageBuffer<Rgb<u16>, Vec<u16>>;
+
+/// The output at whichever bit depth [`ModelToImageBuilder::with_output_format`] asked for,
+/// see [`ModelToImage::output_any`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputImage<'a> {
+    Rgb8(&'a RgbImage),
+    Rgb16(&'a Rgb16Image),
+}
+
+/// The value of an assimp import property, see [`ModelToImageBuilder::with_import_property`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue {
+    String(String),
+    Int(i32),
+    Float(f32),
+    /// Stored as an integer, 1 for true, like assimp's own boolean properties
+    Bool(bool),
+}
+
+/// A heightmap moving the model's vertices, see [`ModelToImageBuilder::with_displacement`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Displacement {
+    /// Read as greyscale, black for no displacement and white for `scale`
+    pub texture: DynamicImage,
+    pub scale: f32,
+    pub axis: Axis,
+}
+
+/// A panorama drawn behind the model, see [`ModelToImageBuilder::with_environment`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Environment {
+    /// Equirectangular panorama
+    pub image: DynamicImage,
+    /// Degrees to the left
+    pub yaw: f32,
+    /// Degrees up
+    pub pitch: f32,
+    /// Horizontal field of view the backdrop is drawn with, in degrees
+    pub field_of_view: f32,
+    pub tint_ambient: bool,
+}
+
+/// A shape the output is cut down to, with everything outside of it made transparent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mask {
+    /// The largest circle that fits in the centre of the image
+    Circle,
+    /// The whole image with its corners rounded off by `radius_px`
+    RoundedRect { radius_px: u32 },
+}
+
+/// Controls how each pixel of the model gets its colour.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum RenderMode {
+    /// The regular render: textures (or plain grey) multiplied by the light intensity
+    #[default]
+    Shaded,
+    /// Writes the interpolated texture coordinates straight into the image, U as red and V as
+    /// green. Meshes without texture coordinates come out black.
+    UvDebug,
+    /// Replaces every texture with a procedural checkerboard of `cells` by `cells` squares
+    /// across the UV space. Useful for spotting stretched or flipped UVs.
+    Checker { cells: u32 },
+    /// Ignores lighting and colours every triangle facing the viewer blue and every triangle
+    /// facing away red, which makes inverted normals and flipped winding easy to spot. With
+    /// `normal_ticks` set, a short yellow line is also drawn out of the centre of each visible
+    /// face in the direction it points.
+    FacingDebug { normal_ticks: bool },
+    /// Colours each material with its own flat colour, lit like [`RenderMode::Shaded`], to
+    /// check which parts of the model use which material. With `legend` set, a strip along the
+    /// bottom of the image lists the colour and name of every material in use.
+    MaterialDebug { legend: bool },
+    /// Colours the model by how strongly the selected bone pulls on it, lit like
+    /// [`RenderMode::Shaded`]: from blue where it has no weight to red where it has all of it,
+    /// interpolated between the vertices. Combine with [`Overlay::Skeleton`] to see the bones.
+    BoneWeights { bone: BoneSelector },
+    /// Ignores the materials and colours the model by depth instead, lit like
+    /// [`RenderMode::Shaded`]: `near` at the front of the model blending into `far` at its
+    /// back. Shows the shape at a glance, e.g. in documentation.
+    DepthTint { near: Colour, far: Colour },
+}
+
+impl RenderMode {
+    /// Whether this mode shades with the lights, rather than showing a fixed debug colour.
+    pub(crate) fn is_lit(&self) -> bool {
+        matches!(
+            self,
+            RenderMode::Shaded
+                | RenderMode::Checker { .. }
+                | RenderMode::MaterialDebug { .. }
+                | RenderMode::BoneWeights { .. }
+                | RenderMode::DepthTint { .. }
+        )
+    }
+
+    /// Whether this mode only makes sense with texture coordinates.
+    pub(crate) fn samples_uvs(&self) -> bool {
+        matches!(self, RenderMode::UvDebug | RenderMode::Checker { .. })
+    }
+}
+
+/// How far behind the visible surface (in normalised depth) lines and ticks drawn on top of the
+/// model can be and still show, so ones lying right on the surface don't flicker behind it.
+const DEPTH_EPSILON: f32 = 0.01;
+
+/// Below this average normalised depth of the visible surfaces a render is taken to be showing
+/// the far side of the model. A correctly facing closed mesh averages above a half.
+const INVERTED_DEPTH_THRESHOLD: f64 = 0.35;
+
+/// The brightest the single light can be, see [`ModelToImageBuilder::with_light_intensity`].
+/// Even a face at a grazing angle to it is fully lit well before this.
+pub const MAX_LIGHT_INTENSITY: f32 = 16.0;
+
+/// The accumulation samples small images get, see
+/// [`ModelToImageBuilder::with_small_image_threshold`]: a 4×4 grid's worth for every pixel.
+pub const SMALL_IMAGE_SAMPLES: u32 = 16;
+
+/// The colour behind the model.
+const BACKGROUND: (u8, u8, u8) = (211, 211, 211);
+
+/// The direction the model is viewed from. With the winding used by [`ModelToImage::draw_mesh`],
+/// a face pointing at the viewer has its normal along this direction.
+const VIEW_DIR: Vector3<f32> = Vector3::new(0.0, 0.0, -1.0);
+
+/// Axis aligned bounding box of the model, in model space.
+#[derive(Debug, Clone, Copy)]
+struct Aabb {
+    min: Vector3<f32>,
+    max: Vector3<f32>,
+}
+
+impl Aabb {
+    fn of_meshes<'a>(meshes: impl IntoIterator<Item = &'a MeshData>) -> Self {
+        Self::of_points(meshes.into_iter().flat_map(|mesh| mesh.positions.iter().copied()))
+    }
+
+    fn of_points(points: impl IntoIterator<Item = [f32; 3]>) -> Self {
+        let mut min = Vector3::repeat(f32::INFINITY);
+        let mut max = Vector3::repeat(f32::NEG_INFINITY);
+
+        for point in points {
+            let v = Vector3::from(point);
+            // one broken vertex should not throw off the framing of the whole model
+            if !v.iter().all(|c| c.is_finite()) {
+                continue;
+            }
+            min = min.inf(&v);
+            max = max.sup(&v);
+        }
+
+        Self { min, max }
+    }
+
+    fn center(&self) -> Vector3<f32> {
+        (self.min + self.max) / 2.0
+    }
+
+    fn extent(&self) -> Vector3<f32> {
+        self.max - self.min
+    }
+
+    fn corners(&self) -> [Vector3<f32>; 8] {
+        let (lo, hi) = (self.min, self.max);
+        [
+            Vector3::new(lo.x, lo.y, lo.z),
+            Vector3::new(hi.x, lo.y, lo.z),
+            Vector3::new(hi.x, hi.y, lo.z),
+            Vector3::new(lo.x, hi.y, lo.z),
+            Vector3::new(lo.x, lo.y, hi.z),
+            Vector3::new(hi.x, lo.y, hi.z),
+            Vector3::new(hi.x, hi.y, hi.z),
+            Vector3::new(lo.x, hi.y, hi.z),
+        ]
+    }
+}
+
+/// Maps model space onto the image: an orthographic view down the z axis, scaled so the model
+/// fits inside the margin and centred in the viewport.
+#[derive(Debug, Clone, Copy)]
+struct Projection {
+    center: (f32, f32),
+    scale: f32,
+    /// Extra horizontal and vertical stretch on top of `scale`, from
+    /// [`ModelToImageBuilder::with_content_scale`]
+    content_scale: (f32, f32),
+    viewport_center: (f32, f32),
+    /// Nearest and furthest model space z, mapped to depths 1.0 and 0.0
+    depth_range: (f32, f32),
+    /// [`DepthTest::LessWins`], which maps the smallest z to 1.0 instead, so the z-buffer can
+    /// always keep the larger depth
+    less_wins: bool,
+}
+
+impl Projection {
+    /// `scale_factor` is how many of the model's original units one unit of `bounds` is, so
+    /// settings given in original units can be converted.
+    fn fit(bounds: &Aabb, size: Size, settings: &RenderSettings, scale_factor: f32, jitter: (f32, f32)) -> Self {
+        let scale = settings.world_scale.map(|scale| scale * scale_factor).unwrap_or_else(|| {
+            let extent = match settings.framing {
+                Some(framing) => Vector3::new(framing.extent[0], framing.extent[1], 0.0) / scale_factor,
+                None => bounds.extent(),
+            };
+            // at least a pixel to fit the model in, however small the image or wide the margin
+            let drawable = |pixels: u32| (pixels as f32 * (1.0 - 2.0 * settings.margin)).max(1.0);
+            let scale_x = drawable(size.width) / extent.x;
+            let scale_y = drawable(size.height) / extent.y;
+            // a model with no extent either way has nothing to fit
+            let scale = scale_x.min(scale_y);
+            if scale.is_finite() { scale } else { 1.0 }
+        });
+        let center = match settings.framing.and_then(|framing| framing.center) {
+            Some(center) => Vector3::from(center) / scale_factor,
+            None => bounds.center(),
+        };
+
+        Self {
+            center: (center.x, center.y),
+            scale,
+            content_scale: settings.content_scale,
+            viewport_center: (size.width as f32 / 2.0 + jitter.0, size.height as f32 / 2.0 + jitter.1),
+            depth_range: (bounds.min.z, bounds.max.z),
+            less_wins: settings.depth_test == DepthTest::LessWins,
+        }
+    }
+
+    /// The same framing for an image `divisor` times smaller, see
+    /// [`ModelToImage::render_preview`]. Each preview pixel samples the middle of the block of
+    /// full size pixels it stands in for, so it matches the full image scaled down.
+    fn downscaled(self, divisor: u32, jitter: (f32, f32)) -> Self {
+        let divisor = divisor as f32;
+        let offset = (divisor - 1.0) / 2.0;
+        Self {
+            scale: self.scale / divisor,
+            viewport_center: (
+                (self.viewport_center.0 - offset) / divisor + jitter.0,
+                (self.viewport_center.1 - offset) / divisor + jitter.1,
+            ),
+            ..self
+        }
+    }
+
+    /// Maps a model space z onto `0.0..=1.0` across the depth of the model, so depth behaves
+    /// the same whatever the model's size. Larger is still nearer the viewer.
+    fn depth(&self, z: f32) -> f32 {
+        let (min, max) = self.depth_range;
+        let depth = if max > min { (z - min) / (max - min) } else { 0.5 };
+        if self.less_wins { 1.0 - depth } else { depth }
+    }
+
+    /// [`Self::depth`] in double precision, for [`DepthPrecision::Double`].
+    fn depth_f64(&self, z: f32) -> f64 {
+        let (min, max) = (self.depth_range.0 as f64, self.depth_range.1 as f64);
+        let depth = if max > min { (z as f64 - min) / (max - min) } else { 0.5 };
+        if self.less_wins { 1.0 - depth } else { depth }
+    }
+
+    fn project(&self, v: &Vector3<f32>) -> (f32, f32) {
+        (
+            (v.x - self.center.0) * self.scale * self.content_scale.0 + self.viewport_center.0,
+            (v.y - self.center.1) * self.scale * self.content_scale.1 + self.viewport_center.1,
+        )
+    }
+}
+
+/// A mesh that has been projected onto the screen and is ready to rasterise. Only one mesh is
+/// prepared at a time, and the buffers are reused for the next one, so even scans with millions
+/// of triangles only ever need them for their largest mesh.
+#[derive(Default)]
+struct MeshDrawData {
+    projected: Vec<(f32, f32)>,
+    faces: Vec<[usize; 3]>,
+    /// Line primitives, as pairs of vertex indices
+    lines: Vec<[usize; 2]>,
+    world_coords: Vec<Vector3<f32>>,
+    /// Normalised depth of every vertex, see [`Projection::depth`]
+    depths: Vec<f32>,
+    /// `depths` in double precision, only filled in for [`DepthPrecision::Double`]
+    depths_f64: Vec<f64>,
+    /// Texture coordinates of every face's corners, empty when the mesh has no UVs
+    texture_coords: Vec<[(f32, f32); 3]>,
+    has_uvs: bool,
+    /// Position of each vertex along the colour ramp, empty when there is no ramp
+    ramp_coords: Vec<f32>,
+    /// Weight of the bone picked by [`RenderMode::BoneWeights`] on each vertex, empty in
+    /// every other mode
+    bone_weights: Vec<f32>,
+    /// Normal of every vertex, empty unless smooth shading is on and the mesh has them
+    normals: Vec<Vector3<f32>>,
+    material_idx: usize,
+    opacity: f32,
+}
+
+/// The lines and normal ticks of the meshes, gathered while their triangles are drawn. They
+/// are tested against the finished z-buffer, so they can only be drawn after every mesh, and
+/// keeping them in screen space saves preparing those meshes a second time.
+#[derive(Default)]
+struct DeferredLines {
+    /// Line primitives in screen space, with the depth of either end
+    lines: Vec<[(f32, f32, f32); 2]>,
+    ticks: Vec<overlay::NormalTick>,
+}
+
+/// A value in the z-buffer, `f32` for [`DepthPrecision::Single`] and `f64` for
+/// [`DepthPrecision::Double`], so the default keeps a z-buffer half the size.
+trait Depth: Copy + PartialOrd {
+    /// Further away than anything that can be drawn.
+    const FARTHEST: Self;
+
+    /// The depth of every vertex of `mesh` at this precision.
+    fn of_vertices(mesh: &MeshDrawData) -> &[Self];
+
+    /// The depth at the barycentric weights `w` of a triangle with its corners at `corners`.
+    fn interpolate(corners: [Self; 3], w: [f32; 3]) -> Self;
+
+    fn to_f32(self) -> f32;
+}
+
+impl Depth for f32 {
+    const FARTHEST: Self = f32::NEG_INFINITY;
+
+    fn of_vertices(mesh: &MeshDrawData) -> &[Self] {
+        &mesh.depths
+    }
+
+    fn interpolate(corners: [Self; 3], w: [f32; 3]) -> Self {
+        corners[0] * w[0] + corners[1] * w[1] + corners[2] * w[2]
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+}
+
+impl Depth for f64 {
+    const FARTHEST: Self = f64::NEG_INFINITY;
+
+    fn of_vertices(mesh: &MeshDrawData) -> &[Self] {
+        &mesh.depths_f64
+    }
+
+    /// The weights only add up to one give or take rounding, so this starts from a corner and
+    /// adds the differences, keeping flat triangles exact.
+    fn interpolate(corners: [Self; 3], w: [f32; 3]) -> Self {
+        corners[0] + (corners[1] - corners[0]) * w[1] as f64 + (corners[2] - corners[0]) * w[2] as f64
+    }
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+}
+
+/// Everything the colour of one pixel depends on, whether it comes from a triangle being
+/// rasterised or from the G-buffer.
+struct Fragment<'a> {
+    texture: Option<&'a DynamicImage>,
+    uv: Option<(f32, f32)>,
+    /// Position along the colour ramp
+    ramp_t: Option<f32>,
+    /// Weight of the bone picked by [`RenderMode::BoneWeights`]
+    bone_weight: Option<f32>,
+    /// Normalised depth, 1.0 at the front of the model
+    depth: f32,
+    light_intensity: f32,
+    /// How much of `light_intensity` is ambient light, from the floor of
+    /// [`ModelToImageBuilder::with_min_intensity`]
+    ambient: f32,
+    /// See [`TriangleShading::light_tint`]
+    light_tint: Option<[f32; 3]>,
+    front_facing: bool,
+    material_colour: Option<Colour>,
+    /// Replaces the texture or white of [`RenderMode::Shaded`], see [`ModelToImage::shade`]
+    override_colour: Option<Colour>,
+    rim: Option<[f32; 3]>,
+    /// Light given off by the material, in `0.0..=255.0` per channel but allowed to go over
+    emissive: [f32; 3],
+    cap: bool,
+}
+
+/// What became of a triangle handed to the rasteriser.
+enum Rasterised {
+    Drawn,
+    /// Skipped for having no area on screen
+    NoArea,
+    /// Skipped for lying entirely outside the image
+    OffScreen,
+}
+
+/// Everything needed to colour the pixels of a single triangle.
+#[derive(Clone, Copy)]
+struct TriangleShading<'a> {
+    texture: Option<&'a DynamicImage>,
+    tex_coords: Option<[(f32, f32); 3]>,
+    ramp_coords: Option<[f32; 3]>,
+    /// See [`MeshDrawData::bone_weights`]
+    bone_weights: Option<[f32; 3]>,
+    /// Model space positions of the corners, for clipping
+    world: [Vector3<f32>; 3],
+    normal: Vector3<f32>,
+    /// Normals and light intensities of the corners, blended across the triangle in place of
+    /// `normal` and `light_intensity` with smooth shading
+    smooth: Option<([Vector3<f32>; 3], [f32; 3])>,
+    material_idx: usize,
+    light_intensity: f32,
+    /// See [`Fragment::ambient`]
+    ambient: f32,
+    /// Colour of the direct share of the light, the lights' colours weighted by how much each
+    /// one lights the triangle, or `None` when every light is white
+    light_tint: Option<[f32; 3]>,
+    opacity: f32,
+    front_facing: bool,
+    /// Flat colour of the triangle's material, only set in [`RenderMode::MaterialDebug`]
+    material_colour: Option<Colour>,
+    /// Rim light added on top of the shaded colour, in `0.0..=255.0` per channel
+    rim: Option<[f32; 3]>,
+    /// Draw the triangle in the clip cap colour
+    cap: bool,
+}
+
+/// Average depth of a mesh, used to sort translucent meshes.
+fn centroid_z(mesh: &MeshData) -> f32 {
+    if mesh.positions.is_empty() {
+        return 0.0;
+    }
+    mesh.positions.iter().map(|v| v[2]).sum::<f32>() / mesh.positions.len() as f32
+}
+
+impl ModelToImage {
+    pub(crate) fn new(builder: ModelToImageBuilder, scene: SceneData) -> anyhow::Result<Self> {
+        let SceneData {
+            mut meshes,
+            material_names,
+            emissive,
+            double_sided,
+            mut textures,
+            texture_downsampling,
+            deforming,
+            nodes,
+            skeleton,
+            up_axis,
+            mut warnings,
+        } = scene;
+
+        let vertices_welded = builder
+            .settings
+            .weld
+            .map_or(0, |tolerance| weld::weld_meshes(&mut meshes, &deforming, tolerance));
+        for selector in mesh_transform::apply_overrides(&mut meshes, &builder.settings.mesh_transforms) {
+            warnings.push(format!("The mesh transform for {:?} matches no mesh", selector));
+        }
+        warnings.extend(builder.settings.light_warnings());
+        warnings.extend(builder.settings.margin_warnings());
+        if builder.settings.handedness == Handedness::Left {
+            for mesh in &mut meshes {
+                for vertex in mesh.positions.iter_mut().chain(&mut mesh.normals) {
+                    vertex[2] = -vertex[2];
+                }
+            }
+        }
+        if let Some(displacement) = &builder.settings.displacement {
+            let origin = builder.settings.sampled_texture_origin();
+            displace::displace_meshes(&mut meshes, displacement, origin, &mut warnings);
+        }
+        let connectors = if builder.settings.explode != 0.0 {
+            explode::explode_meshes(&mut meshes, builder.settings.explode)
+        } else {
+            Vec::new()
+        };
+
+        // the shape picks the axes, in place of the up axis, unless one was asked for
+        let shape_orientation = (builder.settings.auto_orient && builder.settings.up_axis.is_none())
+            .then(|| orient::principal_axes_rotation(&meshes))
+            .flatten();
+        let up_axis = match shape_orientation {
+            Some(_) => UpAxis::Y,
+            None => builder.settings.up_axis.or(up_axis).unwrap_or(UpAxis::Y),
+        };
+        // Auto only stands the model up for now, the view and roll follow once it's picked
+        let auto_view = builder.settings.view == ViewPreset::Auto;
+        let orientation = if auto_view {
+            orientation_matrix(up_axis, ViewPreset::Front, 0.0)
+        } else {
+            orientation_matrix(up_axis, builder.settings.view, builder.settings.camera_roll)
+        };
+        let orientation = orientation * shape_orientation.unwrap_or_else(Matrix3::identity);
+        let scene_extent = Aabb::of_meshes(meshes.iter()).extent().max();
+        let scene_extent = if scene_extent.is_finite() { scene_extent } else { 0.0 };
+        rotate_scene(&mut meshes, &orientation);
+
+        let scale_factor = if builder.settings.normalize_scale {
+            normalize_scene(&mut meshes)
+        } else {
+            1.0
+        };
+
+        let size = builder.settings.size;
+        let size = Size {
+            width: size.0,
+            height: size.1,
+        };
+
+        let faces_skipped = sanitize_meshes(&mut meshes, &mut warnings);
+        let origin = builder.settings.sampled_texture_origin();
+        dilate::dilate_textures(&mut textures, &meshes, builder.settings.seam_dilation, origin);
+        let triangles_before_simplifying = builder
+            .settings
+            .max_triangles
+            .and_then(|max_triangles| simplify::simplify_scene(&mut meshes, &deforming, max_triangles));
+        if builder.settings.optimize_mesh {
+            vertex_order::optimize_vertex_order(&mut meshes, &deforming);
+        }
+
+        if let RenderMode::BoneWeights { bone } = &builder.settings.render_mode {
+            if selected_bone(&meshes, bone).is_none() {
+                warnings.push(format!("The model has no bone {:?}, so nothing has any weight", bone));
+            }
+        }
+        let skeleton_overlay = builder.settings.overlays.iter().any(|overlay| matches!(overlay, Overlay::Skeleton { .. }));
+        if skeleton_overlay && skeleton.is_empty() {
+            warnings.push("The model has no skeleton to draw".to_string());
+        }
+        let connector_overlay =
+            builder.settings.overlays.iter().any(|overlay| matches!(overlay, Overlay::ExplodeConnectors { .. }));
+        if connector_overlay && builder.settings.explode == 0.0 {
+            warnings.push("The explode connectors are drawn only in an exploded view, see with_explode".to_string());
+        }
+
+        if builder.settings.render_mode.samples_uvs() {
+            let missing: Vec<String> = meshes
+                .iter()
+                .enumerate()
+                .filter(|(_, mesh)| mesh.uvs.is_empty())
+                .map(|(idx, mesh)| mesh_label(idx, &mesh.name))
+                .collect();
+            if !missing.is_empty() {
+                warnings.push(format!("Meshes without texture coordinates: {}", missing.join(", ")));
+            }
+        }
+        let missing_uvs: Vec<MissingUvs> = if builder.settings.render_mode.samples_uvs() {
+            Vec::new()
+        } else {
+            meshes
+                .iter()
+                .enumerate()
+                .filter(|(_, mesh)| mesh.uvs.is_empty() && matches!(textures.get(mesh.material), Some(Some(_))))
+                .map(|(mesh_idx, mesh)| MissingUvs { mesh_idx, name: mesh.name.clone() })
+                .collect()
+        };
+        warnings.extend(missing_uvs.iter().map(MissingUvs::to_string));
+        if builder.settings.min_texels > 0.0 {
+            for (mesh_idx, mesh) in meshes.iter().enumerate() {
+                let Some(texture) = textures.get(mesh.material).and_then(|texture| texture.as_deref()) else {
+                    continue;
+                };
+                let uv_stats = MeshUvStats::of(mesh, Some(texture));
+                if !mesh.uvs.is_empty() && uv_stats.texels < builder.settings.min_texels {
+                    warnings.push(format!(
+                        "Mesh {}: texture resolution too low, its faces cover only {:.0} texels of its {}x{} texture",
+                        mesh_label(mesh_idx, &mesh.name),
+                        uv_stats.texels,
+                        texture.width(),
+                        texture.height()
+                    ));
+                }
+            }
+        }
+
+        let ambient_tint = builder
+            .settings
+            .environment
+            .as_ref()
+            .filter(|environment| environment.tint_ambient)
+            .map(|environment| environment::ambient_tint(&environment.image));
+
+        let view = if auto_view { ViewPreset::Front } else { builder.settings.view };
+        let mut model = Self {
+            model_path: builder.model_path,
+            settings: builder.settings,
+            size,
+            img_buf: RgbImage::new(size.width, size.height),
+            precise: None,
+            hdr: None,
+            img_buf16: None,
+            alpha: None,
+            depth: Vec::new(),
+            coverage: Vec::new(),
+            backdrop: None,
+            ambient_tint,
+            gbuffer: None,
+            deadline: None,
+            timed_out: false,
+            isolated_mesh: None,
+            scissor: None,
+            preview: None,
+            orientation,
+            view,
+            projection: None,
+            meshes: Arc::new(meshes),
+            material_names,
+            skeleton,
+            connectors,
+            emissive: emissive.iter().map(|colour| colour.map(|channel| channel * 255.0)).collect(),
+            double_sided,
+            nodes,
+            scale_factor,
+            textures,
+            texture_downsampling,
+            overwrite: builder.overwrite,
+            missing_uvs,
+            warnings,
+            stats: RenderStats {
+                width: size.width,
+                height: size.height,
+                faces_skipped,
+                vertices_welded,
+                triangles_before_simplifying,
+                scene_extent,
+                ..Default::default()
+            },
+        };
+
+        if auto_view {
+            model.choose_best_view();
+        }
+        Ok(model)
+    }
+
+    /// Turns the model to whichever of [`ViewPreset::CANDIDATES`] shows the most of it, see
+    /// [`best_view::choose`], followed by the camera roll.
+    fn choose_best_view(&mut self) {
+        let lights = self.settings.lights();
+        let best = best_view::choose(&self.meshes, &lights, |material_idx| self.is_double_sided(material_idx));
+
+        let view_and_roll = orientation_matrix(UpAxis::Y, best, self.settings.camera_roll);
+        rotate_scene(Arc::make_mut(&mut self.meshes), &view_and_roll);
+        self.orientation = view_and_roll * self.orientation;
+        self.view = best;
+    }
+
+    /// Starts the rendering, and provides a populated image buffer in the [`ModelToImage`] struct.
+    /// Use a [`RenderPipeline`] to run the same stages one at a time.
+    pub fn render(&mut self) -> anyhow::Result<&mut Self> {
+        RenderPipeline::new(self).prepare()?.rasterize()?.post_process()?.finish()?;
+        Ok(self)
+    }
+
+    /// Draws just the pixels in `rect` again, e.g. after changing a material in an editor where
+    /// the rest of a large image is known not to change, leaving the others as the last
+    /// render left them. Only that part of the image and depth buffer is cleared and filled
+    /// again, from the triangles that reach into it, so the result matches a full
+    /// [`Self::render`] pixel for pixel as long as nothing outside `rect` has changed.
+    ///
+    /// Needs a full render first, for the rest of the image. Effects that spread pixels into
+    /// their neighbours or draw across the whole image (glow, blur, outlines, shadows, an
+    /// adaptive background, lines, overlays, labels and watermarks), as well as a G-buffer, a
+    /// LUT and output formats other than 8-bit sRGB, can't be redrawn in part, so with any of
+    /// them this is a full [`Self::render`]. [`Self::stats`] then describes the partial
+    /// redraw.
+    pub fn render_dirty_rect(&mut self, rect: PixelRect) -> anyhow::Result<&mut Self> {
+        if self.projection.is_none() || self.depth.len() != self.size.pixel_count() as usize {
+            return Err(anyhow::anyhow!("render_dirty_rect redraws part of the last render, so call render first"));
+        }
+        let Some(rect) = rect.clipped_to(self.size) else {
+            return Ok(self);
+        };
+        if !self.can_redraw_in_part() {
+            return self.render();
+        }
+
+        let (mut img_buf, mut depth, mut coverage) = (
+            std::mem::replace(&mut self.img_buf, RgbImage::new(self.size.width, self.size.height)),
+            std::mem::take(&mut self.depth),
+            std::mem::take(&mut self.coverage),
+        );
+        self.scissor = Some(rect.flipped(self.size));
+        let started = self.prepare_frame();
+        let result = self.draw_frame(started);
+        self.scissor = None;
+
+        for y in rect.y..rect.y + rect.height {
+            for x in rect.x..rect.x + rect.width {
+                let idx = self.size.index(x, y);
+                img_buf.put_pixel(x, y, *self.img_buf.get_pixel(x, y));
+                depth[idx] = self.depth[idx];
+                coverage[idx] = self.coverage[idx];
+            }
+        }
+        (self.img_buf, self.depth, self.coverage) = (img_buf, depth, coverage);
+        self.stats.render_time = started.elapsed();
+        result.map(|_| self)
+    }
+
+    /// Whether every pixel of a render only depends on what's drawn at that pixel, see
+    /// [`Self::render_dirty_rect`].
+    fn can_redraw_in_part(&self) -> bool {
+        let settings = &self.settings;
+        let has_lines = settings.line_rendering && self.meshes.iter().any(|mesh| !mesh.lines.is_empty());
+        let spreads = settings.bloom.is_some()
+            || settings.depth_of_field.is_some()
+            || settings.drop_shadow.is_some()
+            || settings.outline.is_some()
+            || settings.adaptive_background;
+        let draws_over = has_lines
+            || !settings.overlays.is_empty()
+            || settings.dimension_labels.is_some()
+            || settings.watermark.is_some()
+            || settings.debug_hud
+            || matches!(
+                settings.render_mode,
+                RenderMode::MaterialDebug { legend: true } | RenderMode::FacingDebug { normal_ticks: true }
+            );
+        let encodes = settings.lut.is_some() || settings.output_pixels != OutputPixels::Srgb8;
+        !spreads && !draws_over && !encodes && self.gbuffer.is_none()
+    }
+
+    /// Everything [`Self::render`] does to the rasterised image: the background, glow, blur,
+    /// outline and shadow, then the legend, labels and watermark.
+    pub(crate) fn post_process(&mut self) {
+        // a custom stage may have drawn straight into the image
+        self.sync_precise();
+        if self.settings.adaptive_background && self.settings.environment.is_none() {
+            self.adapt_background();
+        }
+        if let (Some(bloom), Some(hdr)) = (&self.settings.bloom, self.hdr.take()) {
+            post::bloom(&mut self.img_buf, &hdr, &self.depth, bloom);
+            self.sync_precise();
+        }
+        if let Some(dof) = &self.settings.depth_of_field {
+            post::depth_of_field(&mut self.img_buf, &self.depth, dof);
+            self.sync_precise();
+        }
+        if let Some(shadow) = &self.settings.drop_shadow {
+            post::drop_shadow(&mut self.img_buf, &self.depth, shadow);
+        }
+        if let Some(outline) = &self.settings.outline {
+            post::outline(&mut self.img_buf, &self.depth, outline);
+        }
+        self.sync_precise();
+
+        // text has to go on after the flip, otherwise it would be upside down
+        if let RenderMode::MaterialDebug { legend: true } = self.settings.render_mode {
+            overlay::draw_material_legend(&mut self.img_buf, &self.material_legend());
+        }
+
+        if let Some(labels) = self.settings.dimension_labels {
+            let bounds = self.model_bounds();
+            let projection = Projection::fit(&bounds, self.size, &self.settings, self.scale_factor, (0.0, 0.0));
+            overlay::draw_dimension_labels(&mut self.img_buf, &labels, &bounds, &projection, self.scale_factor);
+        }
+
+        if let Some(watermark) = &self.settings.watermark {
+            post::apply_watermark(&mut self.img_buf, watermark);
+        }
+    }
+
+    /// Encodes the output and checks the coverage of the frame started at `started`.
+    pub(crate) fn finish_frame(&mut self, started: Instant) -> anyhow::Result<()> {
+        self.alpha = self.settings.mask.map(|mask| post::mask_alpha(self.size.width, self.size.height, mask));
+        // last, so the time covers everything else that went into the frame
+        if self.settings.debug_hud {
+            overlay::draw_debug_hud(&mut self.img_buf, &self.stats, started.elapsed());
+        }
+        self.encode_output();
+
+        self.stats.render_time = started.elapsed();
+        // a partial render can't tell what wasn't drawn from what was never reached
+        if !self.timed_out && self.scissor.is_none() {
+            if let Some(warning) = self.stats.nothing_drawn_warning() {
+                self.warn_once(warning);
+            }
+        }
+        if let Some(min_fraction) = self.settings.min_coverage {
+            let coverage = self.coverage();
+            if coverage.fraction < min_fraction {
+                let insufficient = InsufficientCoverage { coverage, min_fraction };
+                match self.settings.min_coverage_policy {
+                    CoveragePolicy::Warn => self.warn_once(insufficient.to_string()),
+                    CoveragePolicy::Fail => return Err(insufficient.into()),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders the model turning a full circle about its vertical axis, one frame per call to
+    /// `next`, so an animation encoder can take each frame and drop it before the next is
+    /// drawn instead of holding all of them. The framing is fixed around the circle the model
+    /// sweeps (unless set with [`ModelToImageBuilder::with_framing`] or a world scale), so
+    /// the model doesn't zoom in and out as it turns.
+    ///
+    /// The model turns in the view, so the light stays where it is relative to the camera.
+    /// Dropping the iterator, after any number of frames, leaves the model as it was.
+    ///
+    /// ```no_run
+    /// # use std::path::PathBuf;
+    /// let mut model = model_to_image::ModelToImageBuilder::new(&PathBuf::from("fish.glb")).build()?;
+    /// for (idx, frame) in model.turntable_frames(36).enumerate() {
+    ///     frame?.save(format!("fish_{:02}.png", idx))?;
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn turntable_frames(&mut self, frames: u32) -> impl Iterator<Item = anyhow::Result<RgbImage>> + '_ {
+        let angles = (0..frames).map(|frame| 360.0 * frame as f32 / frames as f32).collect();
+        turntable::Turntable::new(self, angles)
+    }
+
+    /// Every frame of [`Self::turntable_frames`] at once.
+    pub fn render_turntable(&mut self, frames: u32) -> anyhow::Result<Vec<RgbImage>> {
+        self.turntable_frames(frames).collect()
+    }
+
+    /// Renders the model as seen by a left and a right eye `eye_separation` degrees apart and
+    /// puts the two views together as `mode` says, for quick 3D previews. The camera is
+    /// orthographic, so moving it sideways wouldn't show the model from a different side;
+    /// instead each eye's view turns the model half of `eye_separation` about its vertical
+    /// axis, as in [`Self::turntable_frames`]. A few degrees (around 4) is comfortable to
+    /// look at.
+    ///
+    /// Both eyes share one framing, around the circle the model sweeps, so the views line up
+    /// and fuse. Afterwards [`Self::output`] holds the right eye's view.
+    pub fn render_stereo(&mut self, eye_separation: f32, mode: StereoMode) -> anyhow::Result<RgbImage> {
+        if !eye_separation.is_finite() || !(0.0..=90.0).contains(&eye_separation) {
+            return Err(anyhow::anyhow!(
+                "The eye separation must be between 0 and 90 degrees, got [{}]",
+                eye_separation
+            ));
+        }
+        if mode == StereoMode::SideBySide {
+            ImageTooLarge::check(self.size.width as u64 * 2, self.size.height as u64, self.settings.max_pixels)?;
+        }
+        // turning the model's left side towards the camera shows it as the left eye sees it
+        let angles = vec![eye_separation / 2.0, -eye_separation / 2.0];
+        let views = turntable::Turntable::new(self, angles).collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(mode.combine(&views[0], &views[1]))
+    }
+
+    /// Renders the model turning a full circle, as `width_slices` images `slice_px` wide and
+    /// `height` tall laid side by side from left to right, for 360° product viewers that take
+    /// one strip and show a window of it as the user drags. Slice `i` shows the model turned
+    /// `360 * i / width_slices` degrees, framed as in [`Self::turntable_frames`], so the model
+    /// stays the same size and in the same place in every slice and the strip wraps around
+    /// without a seam.
+    ///
+    /// The model is only loaded once and turned for every slice. Afterwards [`Self::output`]
+    /// is cleared, as it is after [`ModelToImageBuilder::with_auto_orient`]; render again to
+    /// get the usual image.
+    pub fn render_strip(&mut self, width_slices: u32, slice_px: u32, height: u32) -> anyhow::Result<RgbImage> {
+        if width_slices == 0 || slice_px == 0 || height == 0 {
+            return Err(anyhow::anyhow!(
+                "A strip needs at least one slice and a non-zero slice size, got {} slices of {}x{}",
+                width_slices,
+                slice_px,
+                height
+            ));
+        }
+        let strip_size = ImageTooLarge::check(
+            slice_px as u64 * width_slices as u64,
+            height as u64,
+            self.settings.max_pixels,
+        )?;
+        let mut strip = RgbImage::new(strip_size.width, strip_size.height);
+
+        let size = self.size;
+        self.size = Size::new(slice_px, height);
+        self.img_buf = RgbImage::new(slice_px, height);
+        let mut result = Ok(());
+        for (idx, slice) in self.turntable_frames(width_slices).enumerate() {
+            match slice {
+                Ok(slice) => image::imageops::replace(&mut strip, &slice, idx as i64 * slice_px as i64, 0),
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            }
+        }
+
+        self.size = size;
+        // everything left over from the last slice is the wrong size for the model's own image
+        self.img_buf = RgbImage::new(size.width, size.height);
+        self.depth.clear();
+        self.coverage.clear();
+        self.projection = None;
+        self.precise = None;
+        self.img_buf16 = None;
+        self.gbuffer = None;
+        self.alpha = None;
+        result.map(|_| strip)
+    }
+
+    /// Renders a quick preview `scale_divisor` times smaller than the output, e.g. to show while
+    /// the full render runs in the background. The model is framed exactly as it is in the full
+    /// size image and the coordinates are then scaled down, rather than fitted again to the
+    /// smaller image, so the preview matches [`Self::render`] scaled down and swapping one for
+    /// the other doesn't jump.
+    ///
+    /// Only the model is drawn, without labels, legends or a watermark. The last full render
+    /// (its output, depth and stats) is kept as it was.
+    pub fn render_preview(&mut self, scale_divisor: u32) -> anyhow::Result<RgbImage> {
+        let divisor = scale_divisor.max(1);
+        let full_size = self.size;
+        let saved = (
+            std::mem::take(&mut self.img_buf),
+            std::mem::take(&mut self.depth),
+            std::mem::take(&mut self.coverage),
+            self.projection.take(),
+            self.precise.take(),
+            self.img_buf16.take(),
+            self.gbuffer.take(),
+            self.stats,
+        );
+
+        self.size = Size {
+            width: (full_size.width / divisor).max(1),
+            height: (full_size.height / divisor).max(1),
+        };
+        self.img_buf = RgbImage::new(self.size.width, self.size.height);
+        self.preview = Some((full_size, divisor));
+        let result = self.rasterise();
+        let preview = std::mem::take(&mut self.img_buf);
+
+        self.preview = None;
+        self.size = full_size;
+        (
+            self.img_buf,
+            self.depth,
+            self.coverage,
+            self.projection,
+            self.precise,
+            self.img_buf16,
+            self.gbuffer,
+            self.stats,
+        ) = saved;
+        result.map(|_| preview)
+    }
+
+    /// Renders the model lit from each of `candidates` in turn and tiles the images into one,
+    /// `columns` wide, left to right and then top to bottom, each with its index in its top
+    /// left corner. Handy for picking a [`ModelToImageBuilder::with_light_direction`] by eye:
+    /// the index of the best tile is the index of its direction.
+    ///
+    /// The model is only rasterised once and then lit again for every tile, see
+    /// [`Self::shade`], so the same limits apply: translucent meshes, lines and overlays are
+    /// left out.
+    pub fn render_light_grid(&mut self, candidates: &[[f32; 3]], columns: u32) -> anyhow::Result<RgbImage> {
+        let columns = columns.clamp(1, candidates.len().max(1) as u32);
+        let rows = (candidates.len() as u32).div_ceil(columns);
+        let (width, height) = (self.size.width, self.size.height);
+        let grid_size = ImageTooLarge::check(
+            width as u64 * columns as u64,
+            height as u64 * rows as u64,
+            self.settings.max_pixels,
+        )?;
+        let mut grid = self.settings.background.render(grid_size.width, grid_size.height);
+
+        let font_scale = (width.min(height) / 256).max(1);
+        let padding = 2 * font_scale;
+        let keep_gbuffer = self.gbuffer.is_some();
+        self.render_gbuffer()?;
+        for (idx, light) in candidates.iter().enumerate() {
+            let tile = self.shade(&[*light], &HashMap::new())?;
+            let (x, y) = ((idx as u32 % columns) * width, (idx as u32 / columns) * height);
+            image::imageops::replace(&mut grid, tile, x as i64, y as i64);
+
+            let label = idx.to_string();
+            let (label_width, label_height) = utils::text::measure_text(&label, font_scale);
+            for ly in y..(y + label_height + padding * 2).min(y + height) {
+                for lx in x..(x + label_width + padding * 2).min(x + width) {
+                    grid.put_pixel(lx, ly, Rgb([245, 245, 245]));
+                }
+            }
+            utils::text::draw_text(
+                &mut grid,
+                (x + padding) as i64,
+                (y + padding) as i64,
+                &label,
+                Rgb([40, 40, 40]),
+                font_scale,
+            );
+        }
+
+        if !keep_gbuffer {
+            self.gbuffer = None;
+        }
+        Ok(grid)
+    }
+
+    /// Renders every mesh on its own, framed to fit just that mesh, e.g. for one thumbnail per
+    /// part of an assembly. Returns each image with the mesh's name, or `mesh_<index>` for
+    /// meshes without one. Apart from the framing, the usual settings apply, and textures are
+    /// shared rather than loaded again.
+    ///
+    /// Afterwards [`Self::output`] holds the render of the last mesh.
+    pub fn render_per_mesh(&mut self) -> anyhow::Result<Vec<(String, RgbImage)>> {
+        // a fixed framing or scale would stop each mesh from filling its image
+        let framing = self.settings.framing.take();
+        let world_scale = self.settings.world_scale.take();
+
+        let mut images = Vec::with_capacity(self.meshes.len());
+        let mut result = Ok(());
+        for mesh_idx in 0..self.meshes.len() {
+            self.isolated_mesh = Some(mesh_idx);
+            if let Err(err) = self.render() {
+                result = Err(err);
+                break;
+            }
+
+            images.push(self.img_buf.clone());
+        }
+
+        self.isolated_mesh = None;
+        self.settings.framing = framing;
+        self.settings.world_scale = world_scale;
+        result.map(|_| self.mesh_names().into_iter().zip(images).collect())
+    }
+
+    /// The names [`Self::render_per_mesh`] gives its images, in the same order: each mesh's
+    /// name, or `mesh_<index>` for meshes without one. Known before rendering, e.g. to check a
+    /// batch's file names up front.
+    pub fn mesh_names(&self) -> Vec<String> {
+        self.meshes
+            .iter()
+            .enumerate()
+            .map(|(mesh_idx, mesh)| if mesh.name.is_empty() { format!("mesh_{}", mesh_idx) } else { mesh.name.clone() })
+            .collect()
+    }
+
+    /// Renders the model on its own, without a background, so it can be composited over any
+    /// number of backdrops without rasterising it again (see [`RenderLayers::composite_over`]).
+    ///
+    /// The alpha of every pixel is the share of the render's passes that covered it, so edges
+    /// are only soft with more than one pass, see
+    /// [`ModelToImageBuilder::with_accumulation_samples`] and
+    /// [`ModelToImageBuilder::with_small_image_threshold`]. With a single pass alpha is either
+    /// 0 or 255, and the edges come out as aliased as the render itself.
+    ///
+    /// Annotations drawn on top of the finished image (dimension labels, the material legend,
+    /// watermarks and masks) are left out. The layers are 8-bit sRGB, like the backdrops they
+    /// go over, whatever [`ModelToImageBuilder::with_output_format`] asked for. This also
+    /// replaces [`Self::output`] and [`Self::output_any`] with the plain render, in that
+    /// output format.
+    pub fn render_layers(&mut self) -> anyhow::Result<RenderLayers> {
+        let started = Instant::now();
+        self.rasterise()?;
+        self.alpha = None;
+
+        let backdrop = self.backdrop();
+        let layers = RenderLayers::from_render(&self.img_buf, &self.coverage, &self.depth, &backdrop);
+        self.encode_output();
+        self.stats.render_time = started.elapsed();
+        Ok(layers)
+    }
+
+    /// Renders the model like [`Self::render_layers`], and also keeps the normal, texture
+    /// coordinates and material of every pixel, so [`Self::shade`] can light it again without
+    /// rasterising. Once called, later renders keep the G-buffer up to date too.
+    ///
+    /// This costs about five floats per pixel on top of the image, which is why it's opt-in.
+    pub fn render_gbuffer(&mut self) -> anyhow::Result<&RgbImage> {
+        let started = Instant::now();
+        self.gbuffer = Some(GBuffer::new(self.size.width, self.size.height));
+        self.rasterise()?;
+        self.alpha = None;
+        self.encode_output();
+        self.stats.render_time = started.elapsed();
+
+        Ok(&self.img_buf)
+    }
+
+    /// Shades the G-buffer from [`Self::render_gbuffer`] again with the directions in `lights`
+    /// (each as bright as [`ModelToImageBuilder::with_light_direction`]) and with the materials
+    /// in `overrides` painted a flat colour instead of their texture. Visibility doesn't change,
+    /// so this takes a fraction of the time of a render; the other settings still apply, and
+    /// the result replaces [`Self::output`].
+    ///
+    /// Only opaque surfaces are kept in the G-buffer, so translucent meshes, lines and overlays
+    /// drop out, and the edges aren't antialiased.
+    ///
+    /// ```no_run
+    /// # use std::collections::HashMap;
+    /// # use std::path::PathBuf;
+    /// let mut model = model_to_image::ModelToImageBuilder::new(&PathBuf::from("fish.glb")).build()?;
+    /// model.render_gbuffer()?;
+    /// for light in [[1.0, 0.0, -1.0], [-1.0, 0.0, -1.0]] {
+    ///     let image = model.shade(&[light], &HashMap::new())?;
+    ///     # let _ = image;
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn shade(&mut self, lights: &[[f32; 3]], overrides: &HashMap<usize, Colour>) -> anyhow::Result<&RgbImage> {
+        let Some(gbuffer) = self.gbuffer.take() else {
+            anyhow::bail!("There is no G-buffer to shade, call render_gbuffer first");
+        };
+
+        let started = Instant::now();
+        let lights: Vec<Vector3<f32>> = lights.iter().map(|light| Vector3::from(*light).normalize()).collect();
+        let capping = self.settings.clip_cap_colour.is_some() && !self.settings.clip_planes.is_empty();
+        let facing_debug = matches!(self.settings.render_mode, RenderMode::FacingDebug { .. });
+        let material_debug = matches!(self.settings.render_mode, RenderMode::MaterialDebug { .. });
+
+        let mut img_buf = self.backdrop();
+        let mut precise = (self.settings.output_pixels == OutputPixels::Linear16)
+            .then(|| img_buf.pixels().map(|pixel| pixel.0.map(f32::from)).collect::<Vec<_>>());
+        for (idx, pixel) in img_buf.pixels_mut().enumerate() {
+            let material_idx = gbuffer.materials[idx];
+            if material_idx == NO_MATERIAL {
+                continue;
+            }
+            let material_idx = material_idx as usize;
+
+            let normal = Vector3::from(gbuffer.normals[idx]);
+            let intensity: f32 = lights.iter().map(|light| normal.dot(light).max(0.0)).sum();
+            let front_facing = normal.dot(&VIEW_DIR) > 0.0;
+            let texture = self.textures.get(material_idx).and_then(|texture| texture.as_deref());
+
+            let shaded = self.shade_fragment(&Fragment {
+                texture,
+                uv: gbuffer.uv(idx),
+                // the ramp position isn't stored, so a ramp only shows on the original render
+                ramp_t: None,
+                // nor are the bone weights, so they show as none at all
+                bone_weight: None,
+                depth: self.depth.get(idx).copied().unwrap_or(0.5),
+                light_intensity: intensity.max(self.settings.min_intensity),
+                ambient: (self.settings.min_intensity - intensity).max(0.0),
+                // the lights handed in have no colour
+                light_tint: None,
+                front_facing,
+                material_colour: material_debug.then(|| utils::material_colour(material_idx)),
+                override_colour: overrides.get(&material_idx).copied(),
+                rim: if self.settings.render_mode.is_lit() { self.rim_light_at(&normal) } else { None },
+                emissive: self.emissive_of(material_idx),
+                cap: capping && !front_facing && !facing_debug,
+            });
+            *pixel = Rgb(shaded.map(|channel| utils::quantise(channel, false)));
+            if let Some(precise) = &mut precise {
+                precise[idx] = shaded.map(|channel| channel.clamp(0.0, 255.0));
+            }
+        }
+
+        self.img_buf = img_buf;
+        self.precise = precise;
+        self.encode_output();
+        self.gbuffer = Some(gbuffer);
+        self.stats.render_time = started.elapsed();
+        Ok(&self.img_buf)
+    }
+
+    /// Runs every render pass, averaging them when accumulating, and leaves the image, depth and
+    /// coverage in output orientation. Only fails when it runs out of time.
+    fn rasterise(&mut self) -> anyhow::Result<()> {
+        let started = self.prepare_frame();
+        self.draw_frame(started)
+    }
+
+    /// Starts the clock on the time budget and sets up the buffers the passes draw into,
+    /// returning when the frame was started.
+    pub(crate) fn prepare_frame(&mut self) -> Instant {
+        let started = Instant::now();
+        self.deadline = self.settings.time_budget.map(|budget| started + budget);
+        self.timed_out = false;
+        self.stats.time_budget_exceeded = None;
+
+        let samples = self.samples();
+        self.stats.passes = samples;
+        let pixel_count = self.size.pixel_count() as usize;
+        self.img_buf16 = None;
+        self.precise = (self.settings.output_pixels == OutputPixels::Linear16).then(|| vec![[0.0; 3]; pixel_count]);
+        self.hdr = self.settings.bloom.is_some().then(|| vec![[0.0; 3]; pixel_count]);
+        started
+    }
+
+    /// How many jittered passes a frame is drawn in, see
+    /// [`ModelToImageBuilder::with_small_image_threshold`]. Previews don't get more, they're
+    /// meant to be quick.
+    fn samples(&self) -> u32 {
+        let samples = self.settings.accumulation_samples.max(1);
+        let threshold = self.settings.small_image_threshold;
+        let small = self.size.width < threshold && self.size.height < threshold;
+        if small && self.preview.is_none() { samples.max(SMALL_IMAGE_SAMPLES) } else { samples }
+    }
+
+    /// The drawing half of [`Self::rasterise`], once [`Self::prepare_frame`] has run.
+    pub(crate) fn draw_frame(&mut self, started: Instant) -> anyhow::Result<()> {
+        let samples = self.samples();
+        let pixel_count = self.size.pixel_count() as usize;
+        if samples == 1 {
+            self.render_pass((0.0, 0.0));
+            self.coverage = self.depth.iter().map(|z| if z.is_finite() { 1.0 } else { 0.0 }).collect();
+        } else {
+            let mut accumulation = vec![[0.0_f32; 3]; pixel_count];
+            let mut hdr_accumulation = self.hdr.as_ref().map(|_| vec![[0.0_f32; 3]; pixel_count]);
+            let mut coverage = vec![0.0_f32; pixel_count];
+
+            let mut passes = 0;
+            for jitter in utils::jitter_offsets(samples, self.settings.seed) {
+                self.render_pass(jitter);
+                passes += 1;
+                match &self.precise {
+                    Some(precise) => {
+                        for (acc, pixel) in accumulation.iter_mut().zip(precise) {
+                            acc[0] += pixel[0];
+                            acc[1] += pixel[1];
+                            acc[2] += pixel[2];
+                        }
+                    }
+                    None => {
+                        for (acc, pixel) in accumulation.iter_mut().zip(self.img_buf.pixels()) {
+                            acc[0] += pixel.0[0] as f32;
+                            acc[1] += pixel.0[1] as f32;
+                            acc[2] += pixel.0[2] as f32;
+                        }
+                    }
+                }
+                if let (Some(acc), Some(hdr)) = (&mut hdr_accumulation, &self.hdr) {
+                    for (acc, pixel) in acc.iter_mut().zip(hdr) {
+                        *acc = [0, 1, 2].map(|c| acc[c] + pixel[c]);
+                    }
+                }
+                for (covered, z) in coverage.iter_mut().zip(&self.depth) {
+                    if z.is_finite() {
+                        *covered += 1.0;
+                    }
+                }
+                if self.timed_out {
+                    break;
+                }
+            }
+
+            // out of time, only the passes that were drawn count
+            self.stats.passes = passes;
+            let samples = passes as f32;
+            for (pixel, acc) in self.img_buf.pixels_mut().zip(&accumulation) {
+                *pixel = Rgb([
+                    (acc[0] / samples).round() as u8,
+                    (acc[1] / samples).round() as u8,
+                    (acc[2] / samples).round() as u8,
+                ]);
+            }
+            if let Some(precise) = &mut self.precise {
+                for (pixel, acc) in precise.iter_mut().zip(&accumulation) {
+                    *pixel = acc.map(|channel| channel / samples);
+                }
+            }
+            if let Some(acc) = hdr_accumulation {
+                self.hdr = Some(acc.into_iter().map(|acc| acc.map(|channel| channel / samples)).collect());
+            }
+            self.coverage = coverage.into_iter().map(|covered| covered / samples).collect();
+        }
+
+        self.projection = Some(self.fit_projection(&self.model_bounds(), (0.0, 0.0)));
+
+        // at the end, ensure the image is flipped. 
+        image::imageops::flip_vertical_in_place(&mut self.img_buf);
+        let width = self.size.width as usize;
+        self.depth = self.depth.chunks(width).rev().flatten().copied().collect();
+        self.coverage = self.coverage.chunks(width).rev().flatten().copied().collect();
+        if let Some(gbuffer) = &mut self.gbuffer {
+            gbuffer.flip_vertical();
+        }
+        for buffer in [&mut self.precise, &mut self.hdr].into_iter().flatten() {
+            *buffer = buffer.chunks(width).rev().flatten().copied().collect();
+        }
+        // only the rest of the image would tell, and it isn't drawn
+        if self.scissor.is_none() {
+            self.check_depth_inversion();
+        }
+
+        if self.timed_out {
+            let exceeded = TimeBudgetExceeded {
+                budget: self.settings.time_budget.unwrap_or_default(),
+                elapsed: started.elapsed(),
+            };
+            match self.settings.time_budget_policy {
+                TimeBudgetPolicy::Abort => return Err(exceeded.into()),
+                TimeBudgetPolicy::Partial => self.stats.time_budget_exceeded = Some(exceeded),
+            }
+        }
+        Ok(())
+    }
+
+    /// Warns when the surfaces that won the depth test sit mostly at the far end of the model,
+    /// which is what a model with its Z mirrored looks like: its front faces point away and are
+    /// culled, leaving the inside of the back.
+    fn check_depth_inversion(&mut self) {
+        let (sum, count) = self
+            .depth
+            .iter()
+            .filter(|z| z.is_finite())
+            .fold((0.0_f64, 0_usize), |(sum, count), &z| (sum + z as f64, count + 1));
+        if count == 0 || sum / count as f64 >= INVERTED_DEPTH_THRESHOLD {
+            return;
+        }
+        let warning = "The visible surfaces are mostly at the far side of the model, so its Z may be mirrored; \
+                       try with_depth_test(DepthTest::LessWins) or with_handedness(Handedness::Left)";
+        self.warn_once(warning.to_string());
+    }
+
+    /// Adds a warning found while rendering, unless an earlier render already gave the same
+    /// one, so rendering the same model over and over doesn't grow the list.
+    fn warn_once(&mut self, warning: String) {
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Whether the current render has run out of time, remembering it once it has.
+    fn out_of_time(&mut self) -> bool {
+        if !self.timed_out && self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            self.timed_out = true;
+        }
+        self.timed_out
+    }
+
+    /// Repaints the background in a contrasting shade if the model blends into it, see
+    /// [`ModelToImageBuilder::with_adaptive_background`].
+    fn adapt_background(&mut self) {
+        let backdrop = self.backdrop();
+        let Some(replacement) = post::contrasting_background(&self.img_buf, &self.coverage, &backdrop) else {
+            return;
+        };
+        post::replace_background(&mut self.img_buf, &self.coverage, &backdrop, replacement);
+        self.sync_precise();
+        self.warn_once(format!(
+            "The model was too close in colour to the background to stand out, so the background was changed to {:?}",
+            replacement.0
+        ));
+    }
+
+    /// Brings the unrounded colours up to date with anything drawn straight into the 8-bit
+    /// image (lines, overlays and labels), which have no more precision than that anyway.
+    fn sync_precise(&mut self) {
+        let Some(precise) = &mut self.precise else {
+            return;
+        };
+        for (precise, pixel) in precise.iter_mut().zip(self.img_buf.pixels()) {
+            // blends were rounded and opaque pixels truncated, either is left as drawn
+            let drawn = [false, true].map(|blended| precise.map(|channel| utils::quantise(channel, blended)));
+            if !drawn.contains(&pixel.0) {
+                *precise = pixel.0.map(f32::from);
+            }
+        }
+    }
+
+    /// Converts the finished render into [`RenderSettings::output_pixels`].
+    fn encode_output(&mut self) {
+        self.sync_precise();
+        if let Some(lut) = &self.settings.lut {
+            match &mut self.precise {
+                Some(precise) => {
+                    for (precise, pixel) in precise.iter_mut().zip(self.img_buf.pixels_mut()) {
+                        *precise = lut.apply(*precise);
+                        *pixel = Rgb(precise.map(|channel| channel.round() as u8));
+                    }
+                }
+                None => {
+                    for pixel in self.img_buf.pixels_mut() {
+                        *pixel = Rgb(lut.apply(pixel.0.map(f32::from)).map(|channel| channel.round() as u8));
+                    }
+                }
+            }
+        }
+        if self.settings.output_pixels == OutputPixels::Srgb8 {
+            return;
+        }
+
+        if let Some(precise) = &self.precise {
+            let linear = |channel: f32| (utils::srgb_to_linear(channel / 255.0) * 65535.0).round() as u16;
+            self.img_buf16 = Some(ImageBuffer::from_fn(self.size.width, self.size.height, |x, y| {
+                Rgb(precise[self.size.index(x, y)].map(linear))
+            }));
+        }
+        for pixel in self.img_buf.pixels_mut() {
+            *pixel = Rgb(pixel.0.map(|channel| (utils::srgb_to_linear(channel as f32 / 255.0) * 255.0).round() as u8));
+        }
+    }
+
+    /// The colour and label of every material used by a mesh, in material order.
+    fn material_legend(&self) -> Vec<(Colour, String)> {
+        let mut used: Vec<usize> = self.visible_meshes().map(|(_, mesh)| mesh.material).collect();
+        used.sort_unstable();
+        used.dedup();
+
+        used.into_iter()
+            .map(|material_idx| {
+                let name = self.material_names.get(material_idx).map_or("", String::as_str);
+                (utils::material_colour(material_idx), mesh_label(material_idx, name))
+            })
+            .collect()
+    }
+
+    /// The meshes being rendered with their index in the scene: all of them, unless
+    /// [`Self::render_per_mesh`] is rendering one on its own.
+    fn visible_meshes(&self) -> impl Iterator<Item = (usize, &MeshData)> + Clone {
+        let isolated_mesh = self.isolated_mesh;
+        self.meshes
+            .iter()
+            .enumerate()
+            .filter(move |(mesh_idx, _)| isolated_mesh.is_none_or(|isolated| isolated == *mesh_idx))
+    }
+
+    /// Bounds of the meshes being rendered.
+    fn model_bounds(&self) -> Aabb {
+        Aabb::of_meshes(self.visible_meshes().map(|(_, mesh)| mesh))
+    }
+
+    /// Extent of the model along the colour ramp's axis, so every vertex can be mapped to 0..1.
+    fn ramp_bounds(&self, bounds: &Aabb) -> Option<(f32, f32)> {
+        self.settings
+            .colour_ramp
+            .as_ref()
+            .map(|ramp| (ramp.axis.component(&bounds.min), ramp.axis.component(&bounds.max)))
+    }
+
+    /// Projects one mesh of the scene into `data`, gathering what the rasteriser needs to draw
+    /// it. The buffers in `data` are cleared and refilled, keeping their allocations.
+    fn prepare_mesh(
+        &self,
+        mesh_idx: usize,
+        projection: &Projection,
+        ramp_bounds: Option<(f32, f32)>,
+        data: &mut MeshDrawData,
+    ) {
+        let mesh = &self.meshes[mesh_idx];
+
+        data.projected.clear();
+        data.projected
+            .extend(mesh.positions.iter().map(|&v| projection.project(&Vector3::from(v))));
+        data.faces.clear();
+        data.faces
+            .extend(mesh.triangles.iter().map(|triangle| triangle.map(|idx| idx as usize)));
+        data.opacity = self.settings.opacity_for(mesh_idx, &mesh.name);
+        // a translucent mesh overlapping itself blends its own triangles back to front too.
+        // Every triangle of a mesh has the same material, so that leaves just the depth
+        if data.opacity < 1.0 && self.settings.transparent_sort != SortMode::Stable {
+            let centroid = |face: &[usize; 3]| {
+                face.iter().map(|&idx| mesh.positions.get(idx).map_or(0.0, |v| v[2])).sum::<f32>()
+            };
+            let mut keyed: Vec<(f32, [usize; 3])> = data.faces.iter().map(|face| (centroid(face), *face)).collect();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            data.faces.clear();
+            data.faces.extend(keyed.into_iter().map(|(_, face)| face));
+        }
+        data.lines.clear();
+        data.lines.extend(mesh.lines.iter().map(|line| line.map(|idx| idx as usize)));
+        data.world_coords.clear();
+        data.world_coords.extend(mesh.positions.iter().map(|&v| Vector3::from(v)));
+        data.depths.clear();
+        data.depths.extend(data.world_coords.iter().map(|v| projection.depth(v.z)));
+        data.depths_f64.clear();
+        if self.settings.depth_precision == DepthPrecision::Double {
+            data.depths_f64.extend(data.world_coords.iter().map(|v| projection.depth_f64(v.z)));
+        }
+
+        // meshes without UVs get none at all, rather than (0, 0) everywhere, so they are never
+        // painted with the texel in the corner of their texture
+        data.texture_coords.clear();
+        data.has_uvs = !mesh.uvs.is_empty();
+        if data.has_uvs {
+            data.texture_coords.extend(data.faces.iter().map(|face| {
+                let mut uvs = face.map(|vertex_index| mesh.uvs.get(vertex_index).map_or((0.0, 0.0), |&[u, v]| (u, v)));
+                utils::unwrap_uv_seam(&mut uvs);
+                uvs
+            }));
+        }
+
+        data.ramp_coords.clear();
+        if let (Some(ramp), Some((lo, hi))) = (&self.settings.colour_ramp, ramp_bounds) {
+            data.ramp_coords.extend(
+                data.world_coords
+                    .iter()
+                    .map(|v| if hi > lo { (ramp.axis.component(v) - lo) / (hi - lo) } else { 0.0 }),
+            );
+        }
+
+        data.bone_weights.clear();
+        if let RenderMode::BoneWeights { bone } = &self.settings.render_mode {
+            data.bone_weights.resize(mesh.positions.len(), 0.0);
+            if let Some(name) = selected_bone(&self.meshes, bone) {
+                for (vertex, weight) in mesh.bones.iter().filter(|b| b.name == name).flat_map(|b| &b.weights) {
+                    if let Some(total) = data.bone_weights.get_mut(*vertex as usize) {
+                        *total += weight;
+                    }
+                }
+            }
+        }
+
+        data.normals.clear();
+        if self.settings.smooth_shading && mesh.normals.len() == mesh.positions.len() {
+            data.normals.extend(mesh.normals.iter().map(|&n| Vector3::from(n)));
+        }
+
+        data.material_idx = mesh.material;
+    }
+
+    /// The visible meshes in the order they are drawn: opaque meshes first, as they fill the
+    /// z-buffer, then the translucent ones in [`RenderSettings::transparent_sort`] order so
+    /// they blend on top (the viewer looks down -z, so the smallest z is the furthest). The
+    /// sort is stable, so meshes that compare equal always blend in their original order.
+    fn draw_order(&self) -> Vec<usize> {
+        let (opaque, translucent): (Vec<_>, Vec<_>) = self
+            .visible_meshes()
+            .partition(|(mesh_idx, mesh)| self.settings.opacity_for(*mesh_idx, &mesh.name) >= 1.0);
+        // each mesh's depth is worked out once, not again for every comparison
+        let mut translucent: Vec<(usize, usize, f32)> = translucent
+            .into_iter()
+            .map(|(mesh_idx, mesh)| (mesh_idx, mesh.material, centroid_z(mesh)))
+            .collect();
+        match self.settings.transparent_sort {
+            SortMode::CentroidDepth => translucent.sort_by(|a, b| a.2.total_cmp(&b.2)),
+            SortMode::MaterialThenDepth => translucent.sort_by(|a, b| a.1.cmp(&b.1).then(a.2.total_cmp(&b.2))),
+            SortMode::Stable => {}
+        }
+
+        opaque
+            .into_iter()
+            .map(|(mesh_idx, _)| mesh_idx)
+            .chain(translucent.into_iter().map(|(mesh_idx, _, _)| mesh_idx))
+            .collect()
+    }
+
+    /// Frames `bounds` for the image being rasterised, see [`Projection::fit`].
+    fn fit_projection(&self, bounds: &Aabb, jitter: (f32, f32)) -> Projection {
+        match self.preview {
+            Some((full_size, divisor)) => {
+                Projection::fit(bounds, full_size, &self.settings, self.scale_factor, (0.0, 0.0)).downscaled(divisor, jitter)
+            }
+            None => Projection::fit(bounds, self.size, &self.settings, self.scale_factor, jitter),
+        }
     }
 
-    /// Starts the rendering, and provides a populated image buffer in the [`ModelToImage`] struct
-    pub fn render(&mut self) -> anyhow::Result<&mut Self> {
+    /// Rasterises the whole scene once into the image buffer, shifting every projected vertex
+    /// by `jitter` pixels.
+    fn render_pass(&mut self, jitter: (f32, f32)) {
         self.gen_bkg();
+        self.stats.triangles = 0;
+        self.stats.triangles_drawn = 0;
+        self.stats.triangles_culled = 0;
+        self.stats.triangles_degenerate = 0;
+        self.stats.triangles_clipped = 0;
+        self.stats.triangles_off_screen = 0;
+        self.stats.lines = 0;
+        if let Some(gbuffer) = &mut self.gbuffer {
+            gbuffer.clear();
+        }
 
-        let mut z_buffer = vec![f32::NEG_INFINITY; (self.size.width * self.size.height) as usize];
+        let bounds = self.model_bounds();
+        let projection = self.fit_projection(&bounds, jitter);
 
-        let mut min_x = f32::INFINITY;
-        let mut max_x = f32::NEG_INFINITY;
-        let mut min_y = f32::INFINITY;
-        let mut max_y = f32::NEG_INFINITY;
+        let ramp_bounds = self.ramp_bounds(&bounds);
+        let lights = self.settings.lights();
+        let draw_order = self.draw_order();
 
-        for mesh in &self.scene.meshes {
-            for vertex in &mesh.vertices {
-                min_x = min_x.min(vertex.x);
-                max_x = max_x.max(vertex.x);
-                min_y = min_y.min(vertex.y);
-                max_y = max_y.max(vertex.y);
+        let mut mesh = MeshDrawData::default();
+        let mut deferred = DeferredLines::default();
+        let z_buffer: Vec<f32> = match self.settings.depth_precision {
+            DepthPrecision::Single => {
+                self.draw_meshes(&draw_order, &projection, ramp_bounds, &lights, &mut mesh, &mut deferred)
+            }
+            // everything after the triangles only needs single precision
+            DepthPrecision::Double => {
+                let z_buffer: Vec<f64> =
+                    self.draw_meshes(&draw_order, &projection, ramp_bounds, &lights, &mut mesh, &mut deferred);
+                z_buffer.into_iter().map(|z| z as f32).collect()
             }
+        };
+
+        let colour = self.settings.line_colour.into();
+        for &[from, to] in &deferred.lines {
+            let depth_test = Some((z_buffer.as_slice(), DEPTH_EPSILON));
+            overlay::draw_line_depth(&mut self.img_buf, from, to, colour, self.settings.line_width, depth_test);
         }
+        overlay::draw_normal_ticks(&mut self.img_buf, &deferred.ticks, &z_buffer);
 
-        let model_width = max_x - min_x;
-        let model_height = max_y - min_y;
-        let margin = self.margin;
-        let scale_x = (self.size.width as f32 * (1.0 - 2.0 * margin)) / model_width;
-        let scale_y = (self.size.height as f32 * (1.0 - 2.0 * margin)) / model_height;
-        let scale = scale_x.min(scale_y);
+        let depth_test = self.settings.overlay_depth_test.then_some(z_buffer.as_slice());
+        // the skeleton is kept in world space, so it needs the same rotation and scale as the
+        // meshes got
+        let skeleton: Vec<Joint> = self
+            .skeleton
+            .iter()
+            .map(|joint| Joint { position: self.orientation * joint.position / self.scale_factor, ..*joint })
+            .collect();
+        let connectors: Vec<(Vector3<f32>, Vector3<f32>)> = self
+            .connectors
+            .iter()
+            .map(|(from, to)| (self.orientation * from / self.scale_factor, self.orientation * to / self.scale_factor))
+            .collect();
+        // the box and axes belong to the model, so its bounds are taken along its own axes,
+        // before the view turned it
+        let to_model = self.orientation.transpose();
+        let frame = overlay::ModelFrame {
+            bounds: Aabb::of_points(
+                self.visible_meshes()
+                    .flat_map(|(_, mesh)| &mesh.positions)
+                    .map(|&v| (to_model * Vector3::from(v)).into()),
+            ),
+            orientation: self.orientation,
+        };
+        for overlay in &self.settings.overlays {
+            overlay::draw_overlay(&mut self.img_buf, overlay, &frame, &projection, &skeleton, &connectors, depth_test);
+        }
 
-        let center_x = (min_x + max_x) / 2.0;
-        let center_y = (min_y + max_y) / 2.0;
-        let viewport_center_x = self.size.width as f32 / 2.0;
-        let viewport_center_y = self.size.height as f32 / 2.0;
+        self.sync_precise();
+        self.depth = z_buffer;
+    }
 
-        let mesh_draw_data: Vec<(Vec<(i32, i32)>, Vec<Vec<usize>>, Vec<nalgebra::Vector3<f32>>, Vec<Vec<(f32, f32)>>, usize)> = self
-            .scene
-            .meshes
-            .iter()
-            .map(|mesh| {
-                let projected: Vec<(i32, i32)> = mesh
-                    .vertices
-                    .iter()
-                    .map(|v| {
-                        let x = ((v.x - center_x) * scale + viewport_center_x) as i32;
-                        let y = ((v.y - center_y) * scale + viewport_center_y) as i32;
-                        (x, y)
-                    })
-                    .collect();
-                let faces: Vec<Vec<usize>> = mesh
-                    .faces
-                    .iter()
-                    .filter(|face| face.0.len() == 3)
-                    .map(|face| face.0.iter().map(|&idx| idx as usize).collect())
-                    .collect();
-                let world_coords: Vec<nalgebra::Vector3<f32>> = mesh
-                    .vertices
-                    .iter()
-                    .map(|v| nalgebra::Vector3::new(v.x, v.y, v.z))
-                    .collect();
+    /// Rasterises the triangles of the meshes in `order` into a fresh z-buffer of depths `D`,
+    /// and returns it. Their lines and normal ticks go into `deferred`.
+    fn draw_meshes<D: Depth>(
+        &mut self,
+        order: &[usize],
+        projection: &Projection,
+        ramp_bounds: Option<(f32, f32)>,
+        lights: &[(Vector3<f32>, f32, [f32; 3])],
+        mesh: &mut MeshDrawData,
+        deferred: &mut DeferredLines,
+    ) -> Vec<D> {
+        let normal_ticks = matches!(self.settings.render_mode, RenderMode::FacingDebug { normal_ticks: true });
+        let (width, height) = self.img_buf.dimensions();
+        let mut z_buffer = vec![D::FARTHEST; self.size.pixel_count() as usize];
+        for &mesh_idx in order {
+            if self.out_of_time() {
+                break;
+            }
+            self.prepare_mesh(mesh_idx, projection, ramp_bounds, mesh);
+            self.draw_mesh(mesh, lights, &mut z_buffer);
 
-                let texture_coords: Vec<Vec<(f32, f32)>> = faces
-                    .iter()
-                    .map(|face_indices| {
-                        face_indices.iter().map(|&vertex_index| {
-                            if let Some(Some(tex_coords)) = mesh.texture_coords.get(0) {
-                                if vertex_index < tex_coords.len() {
-                                    let tc = &tex_coords[vertex_index];
-                                    (tc.x, tc.y)
-                                } else {
-                                    (0.0, 0.0)
-                                }
-                            } else {
-                                (0.0, 0.0)
-                            }
-                        }).collect()
-                    })
-                    .collect();
-
-                let idx = mesh.material_index as usize; 
-
-                (projected, faces, world_coords, texture_coords, idx)
-            })
-            .collect();
+            if self.settings.line_rendering {
+                let to_screen = |idx: usize| (mesh.projected[idx].0, mesh.projected[idx].1, mesh.depths[idx]);
+                deferred.lines.extend(mesh.lines.iter().map(|&[a, b]| [to_screen(a), to_screen(b)]));
+                self.stats.lines += mesh.lines.len();
+            }
+            if normal_ticks {
+                deferred.ticks.extend(overlay::normal_ticks(mesh, width, height));
+            }
+        }
+        z_buffer
+    }
+
+    fn draw_mesh<D: Depth>(
+        &mut self,
+        mesh: &MeshDrawData,
+        lights: &[(Vector3<f32>, f32, [f32; 3])],
+        z_buffer: &mut [D],
+    ) {
+        let texture = if mesh.material_idx < self.textures.len() {
+            self.textures[mesh.material_idx].clone()
+        } else {
+            None
+        };
 
-        let light = Vector3::from(self.light_dir).normalize();
+        let projected = &mesh.projected;
+        let world_coords = &mesh.world_coords;
+        let texture_coords = &mesh.texture_coords;
+        let depths = D::of_vertices(mesh);
 
-        for (projected, faces, world_coords, texture_coords, idx) in mesh_draw_data {
-            let texture = if idx < self.textures.len() {
-                self.textures[idx].clone()
-            } else {
-                None
+        let facing_debug = matches!(self.settings.render_mode, RenderMode::FacingDebug { .. });
+        // the floor and rim light have to reach faces the light misses, as long as the viewer
+        // can see them
+        let extra_light = self.settings.min_intensity > 0.0 || self.settings.rim_light.is_some();
+        let rim_lit = self.settings.render_mode.is_lit();
+        let material_colour = matches!(self.settings.render_mode, RenderMode::MaterialDebug { .. })
+            .then(|| utils::material_colour(mesh.material_idx));
+        // with a cap, the back faces revealed by the clip planes are drawn in the cap colour, so
+        // every triangle needs rasterising instead of only the lit ones
+        let capping = self.settings.clip_cap_colour.is_some() && !self.settings.clip_planes.is_empty();
+        let coloured_lights = lights.iter().any(|&(_, _, colour)| colour != [1.0; 3]);
+        // the facing debug mode shows the winding as it is
+        let double_sided = self.is_double_sided(mesh.material_idx) && !facing_debug;
+
+        self.stats.triangles += mesh.faces.len();
+        for (face_idx, &[i0, i1, i2]) in mesh.faces.iter().enumerate() {
+            // checking the clock for every triangle would cost more than it saves
+            if face_idx % 256 == 0 && self.out_of_time() {
+                break;
+            }
+
+            let edge1 = world_coords[i2] - world_coords[i0];
+            let edge2 = world_coords[i1] - world_coords[i0];
+            // zero area triangles have no normal and cover no pixels, so just skip them
+            let Some(normal) = edge1.cross(&edge2).try_normalize(f32::EPSILON) else {
+                self.stats.triangles_degenerate += 1;
+                continue;
             };
+            // the back of a double-sided face is shaded as a face of its own, turned to the viewer
+            let normal = if double_sided && normal.dot(&VIEW_DIR) <= 0.0 { -normal } else { normal };
 
-            for (face_idx, indices) in faces.iter().enumerate() {
-                let (i0, i1, i2) = (indices[0], indices[1], indices[2]);
+            // a plane's clipped side is convex, so a triangle with every corner on it is gone
+            let corners = [i0, i1, i2].map(|idx| world_coords[idx] * self.scale_factor);
+            if self.settings.clip_planes.iter().any(|plane| corners.iter().all(|corner| plane.clips(corner))) {
+                self.stats.triangles_clipped += 1;
+                continue;
+            }
 
-                let edge1 = world_coords[i2] - world_coords[i0];
-                let edge2 = world_coords[i1] - world_coords[i0];
-                let normal = edge1.cross(&edge2).normalize();
+            let intensity: f32 = lights.iter().map(|(light, strength, _)| normal.dot(light).max(0.0) * strength).sum();
+            let front_facing = normal.dot(&VIEW_DIR) > 0.0;
+            let light_tint = (coloured_lights && intensity > 0.0).then(|| {
+                lights.iter().fold([0.0; 3], |tint, (light, strength, colour)| {
+                    let share = normal.dot(light).max(0.0) * strength / intensity;
+                    [0, 1, 2].map(|c| tint[c] + colour[c] * share)
+                })
+            });
+            let smooth = (!mesh.normals.is_empty()).then(|| {
+                let normals = [i0, i1, i2].map(|idx| {
+                    // turned to the side the face is lit from, which also covers files whose
+                    // normals point the other way to their winding
+                    let corner = mesh.normals[idx].try_normalize(f32::EPSILON).unwrap_or(normal);
+                    if corner.dot(&normal) < 0.0 { -corner } else { corner }
+                });
+                let intensities = normals.map(|corner| {
+                    lights.iter().map(|(light, strength, _)| corner.dot(light).max(0.0) * strength).sum::<f32>()
+                });
+                (normals, intensities)
+            });
+            let lit_corner = smooth.is_some_and(|(_, intensities)| intensities.iter().any(|&i| i > 0.0));
 
-                let intensity = normal.dot(&light);
+            if intensity > 0.0 || lit_corner || (extra_light && front_facing) || facing_debug || capping {
+                let pts = [
+                    (projected[i0].0, projected[i0].1, depths[i0]),
+                    (projected[i1].0, projected[i1].1, depths[i1]),
+                    (projected[i2].0, projected[i2].1, depths[i2]),
+                ];
 
-                if intensity > 0.0 {
-                    let pts = [
-                        (projected[i0].0 as f32, projected[i0].1 as f32, world_coords[i0].z),
-                        (projected[i1].0 as f32, projected[i1].1 as f32, world_coords[i1].z),
-                        (projected[i2].0 as f32, projected[i2].1 as f32, world_coords[i2].z),
-                    ];
+                let tex_coords = if mesh.has_uvs { texture_coords.get(face_idx).copied() } else { None };
 
-                    let tex_coords = if face_idx < texture_coords.len() && texture_coords[face_idx].len() == 3 {
-                        Some([
-                            texture_coords[face_idx][0],
-                            texture_coords[face_idx][1],
-                            texture_coords[face_idx][2],
-                        ])
+                let shading = TriangleShading {
+                    texture: texture.as_deref(),
+                    tex_coords,
+                    ramp_coords: if mesh.ramp_coords.is_empty() {
+                        None
                     } else {
+                        Some([mesh.ramp_coords[i0], mesh.ramp_coords[i1], mesh.ramp_coords[i2]])
+                    },
+                    bone_weights: if mesh.bone_weights.is_empty() {
                         None
-                    };
-                    
-                    self.draw_triangle(&pts, &mut z_buffer, texture.as_ref(), tex_coords, intensity);
+                    } else {
+                        Some([mesh.bone_weights[i0], mesh.bone_weights[i1], mesh.bone_weights[i2]])
+                    },
+                    world: [world_coords[i0], world_coords[i1], world_coords[i2]],
+                    normal,
+                    smooth,
+                    material_idx: mesh.material_idx,
+                    light_intensity: intensity.max(self.settings.min_intensity),
+                    ambient: (self.settings.min_intensity - intensity.max(0.0)).max(0.0),
+                    light_tint,
+                    opacity: mesh.opacity,
+                    front_facing,
+                    material_colour,
+                    rim: if rim_lit { self.rim_light_at(&normal) } else { None },
+                    cap: capping && !front_facing && !facing_debug,
+                };
+
+                match self.draw_triangle(&pts, z_buffer, &shading) {
+                    Rasterised::Drawn => self.stats.triangles_drawn += 1,
+                    Rasterised::NoArea => self.stats.triangles_degenerate += 1,
+                    Rasterised::OffScreen => self.stats.triangles_off_screen += 1,
                 }
+            } else {
+                self.stats.triangles_culled += 1;
             }
         }
-
-        // at the end, ensure the image is flipped. 
-        image::imageops::flip_vertical_in_place(&mut self.img_buf);
-        Ok(self)
     }
 
-    fn barycentric(a: (f32, f32), b: (f32, f32), c: (f32, f32), p: (f32, f32)) -> Option<(f32, f32, f32)> {
-        let s0 = (c.0 - a.0, b.0 - a.0, a.0 - p.0);
-        let s1 = (c.1 - a.1, b.1 - a.1, a.1 - p.1);
-        
-        let u = (
-            s0.1 * s1.2 - s0.2 * s1.1,
-            s0.2 * s1.0 - s0.0 * s1.2,
-            s0.0 * s1.1 - s0.1 * s1.0
-        );
-        
-        if u.2.abs() > 1e-2 {
-            let w0 = 1.0 - (u.0 + u.1) / u.2;
-            let w1 = u.1 / u.2;
-            let w2 = u.0 / u.2;
-            Some((w0, w1, w2))
-        } else {
-            None
+    /// Rasterises one triangle, unless it has no area on screen or lies outside the image.
+    fn draw_triangle<D: Depth>(
+        &mut self,
+        pts: &[(f32, f32, D); 3],
+        z_buffer: &mut [D],
+        shading: &TriangleShading,
+    ) -> Rasterised {
+        let TriangleShading {
+            texture,
+            tex_coords,
+            ramp_coords,
+            bone_weights,
+            world,
+            normal,
+            smooth,
+            material_idx,
+            light_intensity,
+            ambient,
+            light_tint,
+            opacity,
+            front_facing,
+            material_colour,
+            rim,
+            cap,
+        } = *shading;
+
+        // twice the signed area on screen, a triangle with none covers no pixels
+        let epsilon = self.settings.degenerate_epsilon;
+        let area = (pts[2].0 - pts[0].0) * (pts[1].1 - pts[0].1) - (pts[1].0 - pts[0].0) * (pts[2].1 - pts[0].1);
+        if area.abs().is_nan() || area.abs() <= epsilon {
+            return Rasterised::NoArea;
         }
-    }
 
-    fn draw_triangle(
-        &mut self,
-        pts: &[(f32, f32, f32); 3],
-        z_buffer: &mut [f32],
-        texture: Option<&DynamicImage>,
-        tex_coords: Option<[(f32, f32); 3]>,
-        light_intensity: f32,
-    ) {
         let mut bbox_min = (f32::MAX, f32::MAX);
         let mut bbox_max = (f32::NEG_INFINITY, f32::NEG_INFINITY);
         
@@ -347,64 +4167,499 @@ impl ModelToImage {
             bbox_max.1 = bbox_max.1.max(y);
         }
         
-        let min_x = (bbox_min.0.max(0.0) as i32).max(0);
-        let max_x = (bbox_max.0.min(self.size.width as f32 - 1.0) as i32).min(self.size.width as i32 - 1);
-        let min_y = (bbox_min.1.max(0.0) as i32).max(0);
-        let max_y = (bbox_max.1.min(self.size.height as f32 - 1.0) as i32).min(self.size.height as i32 - 1);
-        
+        let mut min_x = (bbox_min.0.max(0.0) as i32).max(0);
+        let mut max_x = (bbox_max.0.min(self.size.width as f32 - 1.0) as i32).min(self.size.width as i32 - 1);
+        let mut min_y = (bbox_min.1.max(0.0) as i32).max(0);
+        let mut max_y = (bbox_max.1.min(self.size.height as f32 - 1.0) as i32).min(self.size.height as i32 - 1);
+        if min_x > max_x || min_y > max_y {
+            return Rasterised::OffScreen;
+        }
+        // every pixel is worked out on its own, so only visiting some of them leaves those
+        // exactly as a full render draws them
+        if let Some(scissor) = self.scissor {
+            min_x = min_x.max(scissor.x as i32);
+            max_x = max_x.min((scissor.x + scissor.width) as i32 - 1);
+            min_y = min_y.max(scissor.y as i32);
+            max_y = max_y.min((scissor.y + scissor.height) as i32 - 1);
+        }
+
+        // the barycentric weights come from the edge functions of the triangle, with the halves
+        // that don't change along a row worked out once per row. Stepping them by adding
+        // increments would be cheaper still, but the rounding would drift from pixel to pixel
+        // and change the output, so every product is still worked out from scratch.
+        let (a, b, c) = ((pts[0].0, pts[0].1), (pts[1].0, pts[1].1), (pts[2].0, pts[2].1));
+        let (ca_x, ba_x) = (c.0 - a.0, b.0 - a.0);
+        let (ca_y, ba_y) = (c.1 - a.1, b.1 - a.1);
+        // the same sum as `area`, which was checked above, so it's never zero
+        let denominator = area;
+        let width = self.size.width as usize;
+        let clipping = !self.settings.clip_planes.is_empty();
+        let depths = pts.map(|p| p.2);
+
         for y in min_y..=max_y {
+            let ay = a.1 - y as f32;
+            let (row_u0, row_u1) = (ba_x * ay, ca_x * ay);
+            let row = y as usize * width;
+
             for x in min_x..=max_x {
-                let p = (x as f32, y as f32);
-                
-                if let Some((w0, w1, w2)) = Self::barycentric(
-                    (pts[0].0, pts[0].1),
-                    (pts[1].0, pts[1].1),
-                    (pts[2].0, pts[2].1),
-                    p
-                ) {
-                    if w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0 {
-                        let z = pts[0].2 * w0 + pts[1].2 * w1 + pts[2].2 * w2;
-                        
-                        let buffer_index = (x + y * self.size.width as i32) as usize;
-                        
-                        if z > z_buffer[buffer_index] {
-                            z_buffer[buffer_index] = z;
-                            
-                            let color = if let (Some(texture), Some(tex_coords)) = (texture, tex_coords) {
-                                let u = tex_coords[0].0 * w0 + tex_coords[1].0 * w1 + tex_coords[2].0 * w2;
-                                let v = tex_coords[0].1 * w0 + tex_coords[1].1 * w1 + tex_coords[2].1 * w2;
-                                
-                                let tex_x = ((u.fract().abs() * texture.width() as f32) as u32).min(texture.width() - 1);
-                                let tex_y = (((1.0 - v).fract().abs() * texture.height() as f32) as u32).min(texture.height() - 1);
-                                
-                                let pixel = texture.get_pixel(tex_x, tex_y);
-                                let rgb = pixel.0;
-                                
-                                let r = ((rgb[0] as f32 * light_intensity).min(255.0)) as u8;
-                                let g = ((rgb[1] as f32 * light_intensity).min(255.0)) as u8;
-                                let b = ((rgb[2] as f32 * light_intensity).min(255.0)) as u8;
-                                
-                                Rgb([r, g, b])
-                            } else {
-                                let color_value = (light_intensity * 255.0) as u8;
-                                Rgb([color_value, color_value, color_value])
-                            };
-                            
-                            self.img_buf.put_pixel(x as u32, y as u32, color);
-                        }
+                let ax = a.0 - x as f32;
+                let u0 = row_u0 - ax * ba_y;
+                let u1 = ax * ca_y - row_u1;
+                let w0 = 1.0 - (u0 + u1) / denominator;
+                let w1 = u1 / denominator;
+                let w2 = u0 / denominator;
+                if !(w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0) {
+                    continue;
+                }
+
+                let z = D::interpolate(depths, [w0, w1, w2]);
+                let buffer_index = row + x as usize;
+
+                if clipping {
+                    let position = world[0] * w0 + world[1] * w1 + world[2] * w2;
+                    if self.settings.clip_planes.iter().any(|plane| plane.clips(&(position * self.scale_factor))) {
+                        continue;
+                    }
+                }
+                // NaN depths never win
+                if z.partial_cmp(&z_buffer[buffer_index]) != Some(Ordering::Greater) {
+                    continue;
+                }
+
+                let uv = tex_coords.map(|tex_coords| (
+                    tex_coords[0].0 * w0 + tex_coords[1].0 * w1 + tex_coords[2].0 * w2,
+                    tex_coords[0].1 * w0 + tex_coords[1].1 * w1 + tex_coords[2].1 * w2,
+                ));
+
+                let (normal, light_intensity, ambient) = match smooth {
+                    Some((normals, intensities)) => {
+                        let blended = normals[0] * w0 + normals[1] * w1 + normals[2] * w2;
+                        let intensity = intensities[0] * w0 + intensities[1] * w1 + intensities[2] * w2;
+                        let min_intensity = self.settings.min_intensity;
+                        (
+                            blended.try_normalize(f32::EPSILON).unwrap_or(normal),
+                            intensity.max(min_intensity),
+                            (min_intensity - intensity.max(0.0)).max(0.0),
+                        )
+                    }
+                    None => (normal, light_intensity, ambient),
+                };
+
+                // translucent surfaces are tested against the z-buffer but never write to it
+                // (or the G-buffer), so whatever is behind them stays visible
+                if opacity >= 1.0 {
+                    z_buffer[buffer_index] = z;
+                    if let Some(gbuffer) = &mut self.gbuffer {
+                        gbuffer.write(buffer_index, &normal, uv, material_idx);
                     }
                 }
+
+                let shaded = self.shade_fragment(&Fragment {
+                    texture,
+                    uv,
+                    ramp_t: ramp_coords.map(|t| t[0] * w0 + t[1] * w1 + t[2] * w2),
+                    bone_weight: bone_weights.map(|t| t[0] * w0 + t[1] * w1 + t[2] * w2),
+                    depth: z.to_f32(),
+                    light_intensity,
+                    ambient,
+                    light_tint,
+                    front_facing,
+                    material_colour,
+                    override_colour: None,
+                    rim,
+                    emissive: self.emissive_of(material_idx),
+                    cap,
+                });
+
+                if let Some(hdr) = &mut self.hdr {
+                    let dst = hdr[buffer_index];
+                    hdr[buffer_index] = [0, 1, 2].map(|c| shaded[c].max(0.0) * opacity + dst[c] * (1.0 - opacity));
+                }
+
+                let channels: &mut [u8] = &mut self.img_buf;
+                let pixel: &mut [u8; 3] = (&mut channels[buffer_index * 3..buffer_index * 3 + 3])
+                    .try_into()
+                    .expect("three channels per pixel");
+                // the same blend without rounding, for the 16-bit output
+                if let Some(precise) = &mut self.precise {
+                    let dst = precise[buffer_index];
+                    let blended = [0, 1, 2].map(|c| shaded[c].clamp(0.0, 255.0) * opacity + dst[c] * (1.0 - opacity));
+                    precise[buffer_index] = blended;
+                    *pixel = blended.map(|channel| utils::quantise(channel, opacity < 1.0));
+                } else {
+                    let dst = if opacity < 1.0 { *pixel } else { [0; 3] };
+                    *pixel = [0, 1, 2].map(|c| {
+                        let blended = shaded[c].clamp(0.0, 255.0) * opacity + dst[c] as f32 * (1.0 - opacity);
+                        utils::quantise(blended, opacity < 1.0)
+                    });
+                }
+            }
+        }
+        Rasterised::Drawn
+    }
+
+    /// The colour of one pixel, in `0.0..=255.0` per channel but allowed to go over.
+    fn shade_fragment(&self, fragment: &Fragment) -> [f32; 3] {
+        let Fragment {
+            texture,
+            uv,
+            ramp_t,
+            bone_weight,
+            depth,
+            light_intensity,
+            ambient,
+            light_tint,
+            front_facing,
+            material_colour,
+            override_colour,
+            rim,
+            emissive,
+            cap,
+        } = *fragment;
+        let lit = !cap && self.settings.render_mode.is_lit();
+
+        // shading stays in f32 (0..255 but allowed to go over) until the very end, so adding
+        // lights together never clips a channel early and shifts the hue
+        let shaded: [f32; 3] = match (&self.settings.render_mode, uv) {
+            _ if cap => {
+                let c: [f32; 4] = self.settings.clip_cap_colour.unwrap_or_default().into();
+                [c[0] * 255.0, c[1] * 255.0, c[2] * 255.0]
+            }
+            (RenderMode::UvDebug, Some((u, v))) => [u.clamp(0.0, 1.0) * 255.0, v.clamp(0.0, 1.0) * 255.0, 0.0],
+            (RenderMode::Checker { cells }, Some((u, v))) => {
+                let cells = (*cells).max(1) as f32;
+                let parity = ((u * cells).floor() as i64 + (v * cells).floor() as i64).rem_euclid(2);
+                let value = if parity == 0 { 230.0 } else { 60.0 };
+                [value * light_intensity; 3]
+            }
+            (RenderMode::FacingDebug { .. }, _) => {
+                if front_facing {
+                    [0.0, 0.0, 255.0]
+                } else {
+                    [255.0, 0.0, 0.0]
+                }
+            }
+            (RenderMode::MaterialDebug { .. }, _) => {
+                let c: [f32; 4] = material_colour.unwrap_or_default().into();
+                [
+                    c[0] * 255.0 * light_intensity,
+                    c[1] * 255.0 * light_intensity,
+                    c[2] * 255.0 * light_intensity,
+                ]
+            }
+            (RenderMode::DepthTint { near, far }, _) => {
+                let (near, far): ([f32; 4], [f32; 4]) = ((*near).into(), (*far).into());
+                let t = depth.clamp(0.0, 1.0);
+                [0, 1, 2].map(|c| (far[c] + (near[c] - far[c]) * t) * 255.0 * light_intensity)
+            }
+            (RenderMode::BoneWeights { .. }, _) => {
+                let weight = bone_weight.unwrap_or_default().clamp(0.0, 1.0);
+                [weight * 255.0 * light_intensity, 0.0, (1.0 - weight) * 255.0 * light_intensity]
+            }
+            // missing UVs are rendered black so they stand out
+            (RenderMode::UvDebug | RenderMode::Checker { .. }, None) => [0.0; 3],
+            (RenderMode::Shaded, _) => {
+                if let Some(colour) = override_colour {
+                    let c: [f32; 4] = colour.into();
+                    [
+                        c[0] * 255.0 * light_intensity,
+                        c[1] * 255.0 * light_intensity,
+                        c[2] * 255.0 * light_intensity,
+                    ]
+                } else if let (Some(ramp), Some(t)) = (&self.settings.colour_ramp, ramp_t) {
+                    let rgb = ramp.sample(t);
+
+                    [rgb[0] * light_intensity, rgb[1] * light_intensity, rgb[2] * light_intensity]
+                } else if let (Some(texture), Some((u, v))) = (texture, uv) {
+                    // the texture repeats, so coordinates outside of 0..1 wrap around
+                    let tex_x = ((u.rem_euclid(1.0) * texture.width() as f32) as u32).min(texture.width() - 1);
+                    let rows_down = self.settings.sampled_texture_origin().rows_down(v);
+                    let tex_y = ((rows_down.rem_euclid(1.0) * texture.height() as f32) as u32).min(texture.height() - 1);
+
+                    let rgb = texture.get_pixel(tex_x, tex_y).0;
+
+                    [
+                        rgb[0] as f32 * light_intensity,
+                        rgb[1] as f32 * light_intensity,
+                        rgb[2] as f32 * light_intensity,
+                    ]
+                } else {
+                    [light_intensity * 255.0; 3]
+                }
             }
+        };
+
+        // the direct share of the light takes on the colour of the lights, and the ambient share
+        // the colour of the environment
+        let ambient_tint = self.ambient_tint.filter(|_| ambient > 0.0);
+        let shaded = if lit && light_intensity > 0.0 && (light_tint.is_some() || ambient_tint.is_some()) {
+            let direct = light_intensity - ambient;
+            let (light_tint, ambient_tint) = (light_tint.unwrap_or([1.0; 3]), ambient_tint.unwrap_or([1.0; 3]));
+            [0, 1, 2].map(|c| shaded[c] * (direct * light_tint[c] + ambient * ambient_tint[c]) / light_intensity)
+        } else {
+            shaded
+        };
+        let shaded = match rim {
+            Some(rim) if lit => [shaded[0] + rim[0], shaded[1] + rim[1], shaded[2] + rim[2]],
+            _ => shaded,
+        };
+        let shaded = if lit { [0, 1, 2].map(|c| shaded[c] + emissive[c]) } else { shaded };
+        if self.settings.tonemap && lit {
+            utils::tonemap_reinhard(shaded)
+        } else {
+            shaded
         }
     }
 
-    /// Generates a solid white-grayish background as a backdrop
+    /// The light given off by a material, nothing for materials that don't exist.
+    fn emissive_of(&self, material_idx: usize) -> [f32; 3] {
+        self.emissive.get(material_idx).copied().unwrap_or_default()
+    }
+
+    /// Whether the faces of the material are drawn from both sides, see
+    /// [`ModelToImageBuilder::with_double_sided`].
+    fn is_double_sided(&self, material_idx: usize) -> bool {
+        self.settings.double_sided || self.double_sided.get(material_idx).copied().unwrap_or_default()
+    }
+
+    /// The rim light a surface facing along `normal` picks up, in `0.0..=255.0` per channel.
+    fn rim_light_at(&self, normal: &Vector3<f32>) -> Option<[f32; 3]> {
+        self.settings.rim_light.map(|rim| {
+            let weight = rim.strength * (1.0 - normal.dot(&VIEW_DIR).abs()).powf(rim.power);
+            let colour: [f32; 4] = rim.colour.into();
+            [colour[0] * weight * 255.0, colour[1] * weight * 255.0, colour[2] * weight * 255.0]
+        })
+    }
+
+    /// Fills the image with the backdrop, ready for the model to be drawn over it
     fn gen_bkg(&mut self) {
-        for (_, _, pixel) in self.img_buf.enumerate_pixels_mut() {
-            let bkg = Colour::from((211, 211, 211));
-            // let bkg = DefinedColours::Black.colour(); // remove after
-            *pixel = Rgb(bkg.into());
+        match (&self.settings.environment, self.settings.background) {
+            (None, Background::Solid(colour)) => {
+                for pixel in self.img_buf.pixels_mut() {
+                    *pixel = colour.into();
+                }
+            }
+            _ => {
+                let backdrop = self.backdrop();
+                // the image is still upside down at this point, it's flipped after rasterising
+                self.img_buf = backdrop;
+                image::imageops::flip_vertical_in_place(&mut self.img_buf);
+            }
+        }
+
+        for buffer in [&mut self.precise, &mut self.hdr].into_iter().flatten() {
+            for (value, pixel) in buffer.iter_mut().zip(self.img_buf.pixels()) {
+                *value = pixel.0.map(f32::from);
+            }
+        }
+    }
+
+    /// What is behind the model in the output: the environment if there is one, otherwise
+    /// the background.
+    fn backdrop(&mut self) -> RgbImage {
+        let Some(environment) = &self.settings.environment else {
+            return self.settings.background.render(self.size.width, self.size.height);
+        };
+
+        let (width, height) = (self.size.width, self.size.height);
+        if self.backdrop.as_ref().is_none_or(|backdrop| backdrop.dimensions() != (width, height)) {
+            self.backdrop = Some(environment::render_backdrop(environment, width, height));
+        }
+        self.backdrop.clone().unwrap_or_default()
+    }
+
+    /// The settings this model is rendered with.
+    ///
+    /// ```no_run
+    /// # use std::path::PathBuf;
+    /// let model = model_to_image::ModelToImageBuilder::new(&PathBuf::from("fish.glb"))
+    ///     .with_size((800, 600))
+    ///     .build()?;
+    /// assert_eq!(model.settings().size, (800, 600));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn settings(&self) -> &RenderSettings {
+        &self.settings
+    }
+
+    /// The path of the model being rendered.
+    pub fn model_path(&self) -> &PathBuf {
+        &self.model_path
+    }
+
+    /// Width and height of the output image in pixels.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// How many of the model's original units one unit of the rendered scene is. This is 1.0
+    /// unless [`ModelToImageBuilder::with_normalize_scale`] rescaled the model.
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    /// The factor every material's texture was downsampled by to fit
+    /// [`ModelToImageBuilder::with_max_texture_size`], indexed by material: 2.0 for a texture
+    /// kept at half its width and height, 1.0 for one that fit and for materials without a
+    /// texture.
+    pub fn texture_downsampling(&self) -> &[f32] {
+        &self.texture_downsampling
+    }
+
+    /// The meshes whose texture was skipped because they have no texture coordinates, see
+    /// [`MissingUvs`].
+    pub fn missing_uvs(&self) -> &[MissingUvs] {
+        &self.missing_uvs
+    }
+
+    /// Non-fatal problems collected while loading and rendering the model, such as
+    /// embedded textures that failed to decode.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// A copy of this model for rendering alongside it, e.g. at other sizes on other threads,
+    /// sharing its geometry and textures instead of copying them. The instance starts with the
+    /// model's settings and empty image buffers.
+    ///
+    /// ```no_run
+    /// # use std::path::PathBuf;
+    /// let model = model_to_image::ModelToImageBuilder::new(&PathBuf::from("fish.glb")).build()?;
+    /// std::thread::scope(|scope| {
+    ///     for size in [64, 256, 1024] {
+    ///         let mut instance = model.clone_for_render().with_size((size, size))?;
+    ///         scope.spawn(move || instance.render()?.write_to(Some(&format!("fish_{}.png", size).into())));
+    ///     }
+    ///     Ok::<(), anyhow::Error>(())
+    /// })?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn clone_for_render(&self) -> RenderInstance {
+        RenderInstance::new(self)
+    }
+
+    /// The meshes as they are drawn: oriented, scaled and with the builder's changes applied.
+    /// They're shared with every [`RenderInstance`] made by [`Self::clone_for_render`] until
+    /// one changes them.
+    pub fn meshes(&self) -> &Arc<Vec<MeshData>> {
+        &self.meshes
+    }
+
+    /// The view the model is rendered from. With [`ViewPreset::Auto`], this is the one that was
+    /// picked.
+    pub fn view(&self) -> ViewPreset {
+        self.view
+    }
+
+    /// Writes what the last render left behind into `dir`, for diffing a regressed render
+    /// stage by stage instead of only by its final image:
+    ///
+    /// - `colour.png`: the output image
+    /// - `depth.png`: 16-bit depth, 1 at the back of the model up to 65535 at its front and 0
+    ///   where it doesn't cover
+    /// - `coverage.png`: how much of every pixel the model covers, 0 to 255
+    /// - `ids.png`: 16-bit material index plus one, 0 for the background, only after
+    ///   [`Self::render_gbuffer`]
+    /// - `framing.json`: the scale, centre and bounds the model was framed with
+    ///
+    /// Needs the `debug-dump` feature. The directory is created if it doesn't exist and files
+    /// already in it are overwritten.
+    #[cfg(feature = "debug-dump")]
+    pub fn debug_dump(&self, dir: impl AsRef<Path>) -> anyhow::Result<()> {
+        debug_dump::dump(self, dir.as_ref())
+    }
+
+    /// Timings and triangle counts from loading and the last [`ModelToImage::render`].
+    pub fn stats(&self) -> &RenderStats {
+        &self.stats
+    }
+
+    /// The transform from the space of the node called `name` into the model's world space (the
+    /// space the file places everything in), or `None` if there is no such node. Combine it
+    /// with [`Self::project_point`] to find where a node ends up in the image:
+    ///
+    /// ```no_run
+    /// # use std::path::PathBuf;
+    /// # let mut model = model_to_image::ModelToImageBuilder::new(&PathBuf::from("ship.glb")).build()?;
+    /// model.render()?;
+    /// if let Some(transform) = model.node_world_transform("Hardpoint_Muzzle") {
+    ///     let origin = transform.transform_point(&nalgebra::Point3::origin());
+    ///     println!("muzzle at {:?}", model.project_point(origin.into()));
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn node_world_transform(&self, name: &str) -> Option<Matrix4<f32>> {
+        self.nodes.iter().find(|(node, _)| node == name).map(|(_, transform)| *transform)
+    }
+
+    /// The pixel of the output image a point in the model's world space lands on, using the
+    /// exact projection of the last [`Self::render`]. `None` before rendering, or if the point
+    /// falls outside of the image.
+    pub fn project_point(&self, world: [f32; 3]) -> Option<(u32, u32)> {
+        let projection = self.projection?;
+        let p = self.orientation * Vector3::from(world) / self.scale_factor;
+        let (x, y) = projection.project(&p);
+        let (x, y) = (x.round(), y.round());
+        if !(x >= 0.0 && y >= 0.0 && x < self.size.width as f32 && y < self.size.height as f32) {
+            return None;
+        }
+        // the image is flipped after rasterising
+        Some((x as u32, self.size.height - 1 - y as u32))
+    }
+
+    /// The nearest and furthest z of the model, in the model's original units. The depths in
+    /// [`RenderLayers::depth`] run from 0.0 at the furthest to 1.0 at the nearest, so this maps
+    /// them back to real distances.
+    pub fn depth_range(&self) -> (f32, f32) {
+        let bounds = Aabb::of_meshes(self.meshes.iter());
+        (bounds.min.z * self.scale_factor, bounds.max.z * self.scale_factor)
+    }
+
+    /// The texture coordinates every mesh uses, in mesh order, for tracking down texturing
+    /// problems: a mesh drawn in one flat colour often has all its UVs on one point, one with
+    /// a smeared texture wraps or sits far outside 0 to 1.
+    pub fn uv_stats(&self) -> Vec<MeshUvStats> {
+        self.meshes
+            .iter()
+            .map(|mesh| MeshUvStats::of(mesh, self.textures.get(mesh.material).and_then(|texture| texture.as_deref())))
+            .collect()
+    }
+
+    /// How much of the image the model covered in the last render, counting pixels it covers in
+    /// any of the accumulation passes. All zero before rendering.
+    pub fn coverage(&self) -> CoverageStats {
+        let width = self.size.width as usize;
+        let mut stats = CoverageStats::default();
+        for (idx, _) in self.coverage.iter().enumerate().filter(|(_, coverage)| **coverage > 0.0) {
+            let (x, y) = ((idx % width) as u32, (idx / width) as u32);
+            stats.covered_pixels += 1;
+            stats.bounding_box = Some(match stats.bounding_box {
+                Some((min_x, min_y, max_x, max_y)) => (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)),
+                None => (x, y, x, y),
+            });
+        }
+        if !self.coverage.is_empty() {
+            stats.fraction = stats.covered_pixels as f32 / self.coverage.len() as f32;
+        }
+        stats
+    }
+
+    /// The outlines of the rendered model as closed polygons in output image coordinates (the
+    /// same orientation as [`Self::output`]), e.g. for hit-testing or cut paths. Holes in the
+    /// model get outlines of their own, so a torus seen face on gives two.
+    ///
+    /// The outlines are traced around the pixels the model covers and then simplified, dropping
+    /// points that are less than `tolerance` pixels off the simplified outline. Empty until
+    /// [`Self::render`] has been called.
+    pub fn silhouette_contours(&self, tolerance: f32) -> Vec<Vec<(f32, f32)>> {
+        let coverage: Vec<bool> = self.depth.iter().map(|z| z.is_finite()).collect();
+        if coverage.is_empty() {
+            return Vec::new();
         }
+        contour::trace_contours(&coverage, self.size.width, self.size.height)
+            .into_iter()
+            .map(|outline| contour::simplify_closed(&outline, tolerance))
+            .collect()
     }
 
     /// Provides the image buffer as an output for your own manipulation
@@ -413,16 +4668,370 @@ impl ModelToImage {
         &self.img_buf
     }
 
+    /// The output at the bit depth [`ModelToImageBuilder::with_output_format`] asked for: the
+    /// 16-bit image for [`OutputPixels::Linear16`], otherwise the same image as
+    /// [`Self::output`].
+    pub fn output_any(&self) -> OutputImage<'_> {
+        match &self.img_buf16 {
+            Some(img_buf16) => OutputImage::Rgb16(img_buf16),
+            None => OutputImage::Rgb8(&self.img_buf),
+        }
+    }
+
+    /// Provides the image with its alpha channel. Pixels are fully opaque unless something like
+    /// [`ModelToImageBuilder::with_mask`] made them transparent.
+    pub fn output_rgba(&self) -> RgbaImage {
+        RgbaImage::from_fn(self.size.width, self.size.height, |x, y| {
+            let rgb = self.img_buf.get_pixel(x, y).0;
+            let alpha = self.alpha.as_ref().map_or(255, |alpha| alpha.get_pixel(x, y).0[0]);
+            image::Rgba([rgb[0], rgb[1], rgb[2], alpha])
+        })
+    }
+
+    /// Whether the `write_*` methods may replace a file that already exists, e.g. to write
+    /// a first render unconditionally and refuse to clobber anything after it.
+    ///
+    /// Default: what [`ModelToImageBuilder::with_overwrite`] set, true unless changed
+    pub fn set_overwrite(&mut self, overwrite: bool) -> &mut Self {
+        self.overwrite = overwrite;
+        self
+    }
+
     /// Writes to a location as a file. By default, it is optional. If no path is provided, it is saved
-    /// as `output.png`.
-    pub fn write_to(&self, location: Option<&PathBuf>) -> anyhow::Result<()> {
-        if let Some(path) = location {
-            self.img_buf.save(path)?;
-            Ok(())
+    /// as `output.png` in the current working directory.
+    ///
+    /// Missing parent directories are created. If the output has transparency it is saved with
+    /// an alpha channel, unless the format cannot store one (like JPEG). With
+    /// [`OutputPixels::Linear16`], PNG and TIFF files get 16 bits per channel.
+    ///
+    /// Returns the path that was written, made absolute where possible so it can be logged.
+    /// Fails if the file already exists and overwriting is off, see [`Self::set_overwrite`].
+    pub fn write_to(&self, location: Option<&PathBuf>) -> anyhow::Result<PathBuf> {
+        let default_path = PathBuf::from("output.png");
+        let path = location.unwrap_or(&default_path);
+        self.prepare_output_path(path)?;
+
+        let format = image::ImageFormat::from_path(path);
+        let supports_alpha = !matches!(format, Ok(image::ImageFormat::Jpeg));
+        let supports_16_bit = matches!(format, Ok(image::ImageFormat::Png | image::ImageFormat::Tiff));
+
+        if let (Some(img_buf16), true) = (&self.img_buf16, supports_16_bit) {
+            match &self.alpha {
+                Some(alpha) => {
+                    let rgba: ImageBuffer<Rgba<u16>, Vec<u16>> = ImageBuffer::from_fn(self.size.width, self.size.height, |x, y| {
+                        let [r, g, b] = img_buf16.get_pixel(x, y).0;
+                        Rgba([r, g, b, alpha.get_pixel(x, y).0[0] as u16 * 257])
+                    });
+                    rgba.save(path)?;
+                }
+                None => img_buf16.save(path)?,
+            }
+        } else if self.alpha.is_some() && supports_alpha {
+            self.output_rgba().save(path)?;
         } else {
-            self.img_buf.save("output.png")?;
-            Ok(())
+            self.write_into(&mut path.clone(), format?)?;
+        }
+        Ok(path.canonicalize().unwrap_or_else(|_| path.clone()))
+    }
+
+    /// Writes `image`, rendered from this model by something like [`Self::render_per_mesh`], to
+    /// `path` in the format its extension names. Like [`Self::write_to`], missing parent
+    /// directories are created, an existing file is only replaced if overwriting is on, and the
+    /// path that was written is returned.
+    pub fn write_image_to(&self, image: &RgbImage, path: &Path) -> anyhow::Result<PathBuf> {
+        self.prepare_output_path(path)?;
+        image.save(path)?;
+        Ok(path.canonicalize().unwrap_or_else(|_| path.to_path_buf()))
+    }
+
+    /// Encodes the image as `format` into `sink`, e.g. a [`Vec<u8>`], a [`PathBuf`] or a
+    /// writer of your own. Only the 8-bit colour image is written; [`Self::write_to`] also
+    /// keeps the alpha channel and 16-bit output where the format can store them.
+    pub fn write_into(&self, sink: &mut dyn ImageSink, format: image::ImageFormat) -> anyhow::Result<()> {
+        sink.write(&self.img_buf, format)
+    }
+
+    /// Writes the image as a paletted PNG of at most `max_colours` colours (1 to 256), which is
+    /// often several times smaller than a truecolour one, for thumbnails served at scale.
+    ///
+    /// The colours are chosen by median cut. With `dithering`, the colours that can't be kept
+    /// are dithered (Floyd–Steinberg), which hides banding in smooth gradients but makes the
+    /// file compress worse. Images with no more colours than that are written exactly. Only the 8-bit colour image is written, without alpha. Like
+    /// [`Self::write_to`], missing parent directories are created and an existing file is only
+    /// replaced if overwriting is on. Returns the size of the palette that was written.
+    pub fn write_indexed_png_to(
+        &self,
+        path: impl AsRef<Path>,
+        max_colours: u16,
+        dithering: bool,
+    ) -> anyhow::Result<IndexedPng> {
+        let path = path.as_ref();
+        if !(1..=256).contains(&max_colours) {
+            return Err(anyhow::anyhow!(
+                "An indexed PNG has 1 to 256 colours, {} were asked for",
+                max_colours
+            ));
         }
+        self.prepare_output_path(path)?;
+
+        let quantized = palette::quantize(&self.img_buf, max_colours as usize, dithering);
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        palette::write_indexed_png(file, self.size.width, self.size.height, &quantized)
+    }
+
+    /// Renders a turntable of `frames` frames (see [`Self::turntable_frames`]) straight into an
+    /// animated PNG, each frame shown for `delay_ms` milliseconds. Unlike a GIF it keeps every
+    /// colour, so textured models don't band. Frames are encoded as they are rendered, so
+    /// only one is held in memory however many there are. The animation loops as
+    /// [`ModelToImageBuilder::with_animation_loops`] says.
+    ///
+    /// Like [`Self::write_to`], missing parent directories are created and an existing file is
+    /// only replaced if overwriting is on. Afterwards [`Self::output`] holds the last frame.
+    pub fn write_apng_to(&mut self, path: impl AsRef<Path>, frames: u32, delay_ms: u16) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        if frames == 0 {
+            return Err(anyhow::anyhow!("An animation needs at least one frame"));
+        }
+        self.prepare_output_path(path)?;
+
+        let (size, loops) = (self.size.into(), self.settings.animation_loops);
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        animation::write_apng(file, size, frames, self.turntable_frames(frames), delay_ms, loops)
+    }
+
+    /// [`Self::write_apng_to`] as an animated WebP, which is usually smaller. Every frame is
+    /// stored losslessly. Needs the `webp` feature.
+    #[cfg(feature = "webp")]
+    pub fn write_animated_webp_to(&mut self, path: impl AsRef<Path>, frames: u32, delay_ms: u32) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        if frames == 0 {
+            return Err(anyhow::anyhow!("An animation needs at least one frame"));
+        }
+        if delay_ms >= 1 << 24 {
+            return Err(anyhow::anyhow!("A WebP frame lasts at most 16777215 ms, {} were asked for", delay_ms));
+        }
+        let loops = self.settings.animation_loops;
+        let loops = u16::try_from(loops)
+            .map_err(|_| anyhow::anyhow!("A WebP animation loops at most 65535 times, {} were asked for", loops))?;
+        self.prepare_output_path(path)?;
+
+        let size = self.size.into();
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        animation::write_animated_webp(file, size, self.turntable_frames(frames), delay_ms, loops)
+    }
+
+    /// Writes every pass of the last render into one file for compositing, named `beauty` (the
+    /// output), `depth` (as in [`RenderLayers::depth`]), `normal` and `id` (each pixel's
+    /// material index plus one, 0 where there's no model) and `alpha`. Only the passes the
+    /// render produced are included: the normals and ids need [`Self::render_gbuffer`], the
+    /// alpha something that makes the output transparent, like
+    /// [`ModelToImageBuilder::with_mask`].
+    ///
+    /// Like [`Self::write_to`], missing parent directories are created and an existing file is
+    /// only replaced if overwriting is on. Fails if nothing has been rendered yet.
+    pub fn export_passes(&self, path: impl AsRef<Path>, container: PassContainer) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        if self.depth.is_empty() {
+            return Err(anyhow::anyhow!("There are no passes to export, call render first"));
+        }
+        self.prepare_output_path(path)?;
+
+        let mut passes = vec![
+            Pass {
+                name: "beauty",
+                pixels: PassPixels::Rgb8(self.img_buf.as_raw().clone()),
+            },
+            Pass {
+                name: "depth",
+                pixels: PassPixels::Depth(self.depth.clone()),
+            },
+        ];
+        if let Some(gbuffer) = &self.gbuffer {
+            passes.push(Pass {
+                name: "normal",
+                pixels: PassPixels::Normal(gbuffer.normals.clone()),
+            });
+            let ids = gbuffer.materials.iter();
+            let ids = ids.map(|&material| if material == NO_MATERIAL { 0 } else { material + 1 }).collect();
+            passes.push(Pass {
+                name: "id",
+                pixels: PassPixels::Id(ids),
+            });
+        }
+        if let Some(alpha) = &self.alpha {
+            passes.push(Pass {
+                name: "alpha",
+                pixels: PassPixels::Grey8(alpha.as_raw().clone()),
+            });
+        }
+
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        passes::write_passes(file, self.size.into(), &passes, container)
+    }
+
+    /// Fails if `path` exists and mustn't be overwritten, and creates its missing parent
+    /// directories.
+    fn prepare_output_path(&self, path: &Path) -> anyhow::Result<()> {
+        if !self.overwrite && path.exists() {
+            return Err(anyhow::anyhow!(
+                "The output path [{}] already exists and overwriting is turned off",
+                path.display()
+            ));
+        }
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        Ok(())
+    }
+}
+
+/// Drops faces that would break the renderer, recording a warning for every mesh that lost
+/// some: faces pointing at vertices that don't exist, and faces with non-finite positions.
+/// The rest of the mesh (and the scene) still renders. Returns how many faces were dropped.
+fn sanitize_meshes(meshes: &mut [MeshData], warnings: &mut Vec<String>) -> usize {
+    let mut skipped = 0;
+    for (mesh_idx, mesh) in meshes.iter_mut().enumerate() {
+        let positions = &mesh.positions;
+        let mut out_of_range = 0;
+        let mut non_finite = 0;
+
+        let mut keep = |face: &[u32]| {
+            if face.iter().any(|&idx| idx as usize >= positions.len()) {
+                out_of_range += 1;
+                return false;
+            }
+            if face.iter().any(|&idx| !positions[idx as usize].iter().all(|c| c.is_finite())) {
+                non_finite += 1;
+                return false;
+            }
+            true
+        };
+        mesh.triangles.retain(|triangle| keep(triangle));
+        mesh.lines.retain(|line| keep(line));
+
+        if out_of_range > 0 {
+            warnings.push(format!(
+                "Mesh {}: skipped {} faces referencing vertices that do not exist",
+                mesh_label(mesh_idx, &mesh.name),
+                out_of_range
+            ));
+        }
+        if non_finite > 0 {
+            warnings.push(format!(
+                "Mesh {}: skipped {} faces with non-finite vertex positions",
+                mesh_label(mesh_idx, &mesh.name),
+                non_finite
+            ));
+        }
+        skipped += out_of_range + non_finite;
+    }
+    skipped
+}
+
+/// Reads the up axis from the scene metadata assimp provides, preferring `UpAxis` over
+/// `OriginalUpAxis`. Both store the axis as 0, 1 or 2 for X, Y or Z, with the direction in a
+/// matching `*Sign` key. Falls back to Y up when there is nothing usable.
+pub(crate) fn detect_up_axis(scene: &Scene, warnings: &mut Vec<String>) -> UpAxis {
+    let Some(metadata) = &scene.metadata else {
+        return UpAxis::Y;
+    };
+    let int_value = |key: &str| {
+        metadata
+            .keys
+            .iter()
+            .position(|k| k == key)
+            .and_then(|idx| metadata.values.get(idx))
+            .and_then(|entry| match &entry.data {
+                Ok(MetadataType::Int32(value)) => Some(*value),
+                _ => None,
+            })
+    };
+
+    let Some((axis, sign)) = ["UpAxis", "OriginalUpAxis"]
+        .into_iter()
+        .find_map(|key| int_value(key).map(|axis| (axis, int_value(&format!("{}Sign", key)).unwrap_or(1))))
+    else {
+        return UpAxis::Y;
+    };
+
+    match (axis, sign < 0) {
+        (1, false) => UpAxis::Y,
+        (1, true) => UpAxis::NegY,
+        (2, false) => UpAxis::Z,
+        (2, true) => UpAxis::NegZ,
+        _ => {
+            warnings.push(format!("Unsupported up axis {} in the model metadata, keeping Y up", axis));
+            UpAxis::Y
+        }
+    }
+}
+
+/// The rotation that makes `up_axis` point along +Y, turns the side of the model `view` asks for
+/// towards the viewer and then rolls it clockwise around the viewing direction by
+/// `roll_degrees`.
+fn orientation_matrix(up_axis: UpAxis, view: ViewPreset, roll_degrees: f32) -> Matrix3<f32> {
+    let up = Matrix3::from_columns(&[
+        up_axis.to_y_up(Vector3::x()),
+        up_axis.to_y_up(Vector3::y()),
+        up_axis.to_y_up(Vector3::z()),
+    ]);
+    let roll = utils::rotation_about(&Vector3::z_axis(), -roll_degrees).into_inner();
+    roll * view.rotation() * up
+}
+
+/// Rotates every vertex and normal of the scene about the origin.
+fn rotate_scene(meshes: &mut [MeshData], rotation: &Matrix3<f32>) {
+    if *rotation == Matrix3::identity() {
+        return;
+    }
+
+    for mesh in meshes {
+        // a rotation keeps lengths and angles, so normals turn with the positions
+        for vertex in mesh.positions.iter_mut().chain(&mut mesh.normals) {
+            *vertex = (rotation * Vector3::from(*vertex)).into();
+        }
+    }
+}
+
+/// Scales the scene about the origin so its largest dimension is 1.0, returning the factor it
+/// was shrunk by. Empty or flat-as-a-point scenes are left alone.
+fn normalize_scene(meshes: &mut [MeshData]) -> f32 {
+    let largest = Aabb::of_meshes(meshes.iter()).extent().max();
+    if !largest.is_finite() || largest <= 0.0 {
+        return 1.0;
+    }
+
+    for mesh in meshes {
+        for vertex in &mut mesh.positions {
+            *vertex = vertex.map(|c| c / largest);
+        }
+    }
+    largest
+}
+
+/// The name of the bone `selector` picks out, if the model has it.
+fn selected_bone<'a>(meshes: &'a [MeshData], selector: &'a BoneSelector) -> Option<&'a str> {
+    let mut names = meshes.iter().flat_map(|mesh| &mesh.bones).map(|bone| bone.name.as_str());
+    match selector {
+        BoneSelector::Index(idx) => {
+            let mut seen: Vec<&str> = Vec::new();
+            names.find(|name| {
+                if !seen.contains(name) {
+                    seen.push(*name);
+                }
+                seen.len() == idx + 1
+            })
+        }
+        BoneSelector::Name(name) => names.find(|bone| bone == name),
+    }
+}
+
+/// Human readable name for a mesh in warnings, falling back to its index when it has no name.
+pub(crate) fn mesh_label(mesh_idx: usize, mesh_name: &str) -> String {
+    if mesh_name.is_empty() {
+        format!("#{}", mesh_idx)
+    } else {
+        format!("{} (#{})", mesh_name, mesh_idx)
     }
 }
 
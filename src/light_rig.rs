@@ -0,0 +1,138 @@
+use nalgebra::Vector3;
+
+use crate::{Colour, DefinedColours};
+
+/// A directional light, see [`LightRig`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Light {
+    /// The direction the light shines in, relative to the camera: the camera looks down -Z with
+    /// +Y up, so `[0.0, 0.0, -1.0]` shines from the camera onto the model. Needn't be unit length.
+    pub direction: [f32; 3],
+    /// How bright the light is, 1.0 being as bright as the single default light
+    pub intensity: f32,
+    /// The colour the light tints what it shines on, white by default
+    pub colour: Colour,
+}
+
+impl Light {
+    /// A white light.
+    pub fn new(direction: [f32; 3], intensity: f32) -> Self {
+        Self {
+            direction,
+            intensity,
+            colour: DefinedColours::White.colour(),
+        }
+    }
+
+    /// A light the colour of a black body at `kelvin`, see [`Colour::from_kelvin`], for warm
+    /// (2700) or cool (10000) lighting without picking RGB values by hand.
+    ///
+    /// ```
+    /// use model_to_image::{Light, LightRig};
+    /// // a warm key light and a cool rim light
+    /// let rig = LightRig::new(vec![
+    ///     Light::directional_kelvin([-1.0, -0.8, -1.0], 2700.0, 1.0),
+    ///     Light::directional_kelvin([-0.3, -1.0, 1.2], 10000.0, 0.6),
+    /// ]);
+    /// # let _ = rig;
+    /// ```
+    pub fn directional_kelvin(direction: [f32; 3], kelvin: f32, intensity: f32) -> Self {
+        Self::new(direction, intensity).with_colour(Colour::from_kelvin(kelvin))
+    }
+
+    pub fn with_colour(mut self, colour: Colour) -> Self {
+        self.colour = colour;
+        self
+    }
+}
+
+/// A set of lights placed around the camera, which replaces the single light of
+/// [`crate::ModelToImageBuilder::with_light_direction`], see
+/// [`crate::ModelToImageBuilder::with_light_rig`].
+///
+/// The lights are fixed relative to the camera, so a rig lights every view, rotation and
+/// turntable frame of a model from the same sides. Start from a preset and change what you
+/// need:
+///
+/// ```
+/// use model_to_image::{Light, LightRig};
+/// // a three point rig with a harder rim light
+/// let rig = LightRig::three_point().with_light(2, Light::new([-0.3, -1.0, 1.2], 1.0));
+/// # let _ = rig;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightRig {
+    pub lights: Vec<Light>,
+}
+
+impl LightRig {
+    /// A rig of the given lights.
+    pub fn new(lights: Vec<Light>) -> Self {
+        Self { lights }
+    }
+
+    /// The classic portrait setup, in this order: a bright key light from above and to the
+    /// right of the camera, a softer fill from the left at about eye level that keeps the key's
+    /// shadows from going black, and a rim light from behind and above that picks out the edges.
+    pub fn three_point() -> Self {
+        Self::new(vec![
+            Light::new([-1.0, -0.8, -1.0], 0.9),
+            Light::new([1.0, -0.2, -0.8], 0.35),
+            Light::new([-0.3, -1.0, 1.2], 0.6),
+        ])
+    }
+
+    /// Softer product shot lighting, in this order: a key from above right, a fill from the
+    /// left, a top light and a back light, so there are no dark sides but the shape still reads.
+    pub fn studio() -> Self {
+        Self::new(vec![
+            Light::new([-1.0, -1.0, -1.0], 0.6),
+            Light::new([1.0, -0.3, -1.0], 0.45),
+            Light::new([0.0, -1.0, -0.2], 0.35),
+            Light::new([0.0, -0.5, 1.0], 0.4),
+        ])
+    }
+
+    /// Even light with little shading, in this order: from the camera, from either side and
+    /// from above. Shows colours and textures as they are more than the shape.
+    pub fn flat() -> Self {
+        Self::new(vec![
+            Light::new([0.0, 0.0, -1.0], 0.6),
+            Light::new([1.0, 0.0, -0.5], 0.3),
+            Light::new([-1.0, 0.0, -0.5], 0.3),
+            Light::new([0.0, -1.0, -0.3], 0.2),
+        ])
+    }
+
+    /// Replaces the light at `index` (in the order the preset lists them), or adds `light` to
+    /// the end if there are only `index` lights or fewer.
+    pub fn with_light(mut self, index: usize, light: Light) -> Self {
+        match self.lights.get_mut(index) {
+            Some(existing) => *existing = light,
+            None => self.lights.push(light),
+        }
+        self
+    }
+
+    /// Checks every light has a direction and a usable intensity.
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        for (idx, light) in self.lights.iter().enumerate() {
+            let direction = Vector3::from(light.direction);
+            if !direction.iter().all(|c| c.is_finite()) || direction.norm() == 0.0 {
+                return Err(anyhow::anyhow!(
+                    "Light {} of the rig needs a direction, got [{:?}]",
+                    idx,
+                    light.direction
+                ));
+            }
+            if !light.intensity.is_finite() || light.intensity < 0.0 {
+                return Err(anyhow::anyhow!(
+                    "Light {} of the rig needs a non-negative intensity, got [{}]",
+                    idx,
+                    light.intensity
+                ));
+            }
+        }
+        Ok(())
+    }
+}
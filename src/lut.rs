@@ -0,0 +1,178 @@
+use std::cmp::Ordering;
+use std::path::Path;
+
+/// A colour lookup table applied to the finished image, see
+/// [`crate::ModelToImageBuilder::with_lut`]. Inputs and outputs are in the `0.0..=1.0` range.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Lut {
+    /// One curve per channel (red, green, blue), each sampled evenly from 0.0 to 1.0 and
+    /// interpolated in between.
+    Curves([Vec<f32>; 3]),
+    /// A `size`×`size`×`size` cube of colours with red changing fastest, then green, then blue,
+    /// as in .cube files, interpolated trilinearly.
+    Cube { size: usize, table: Vec<[f32; 3]> },
+}
+
+impl Lut {
+    /// The same curve for all three channels.
+    pub fn from_curve(curve: Vec<f32>) -> Self {
+        Self::Curves([curve.clone(), curve.clone(), curve])
+    }
+
+    /// 8-bit tables as colour pipelines usually hand them out: entry `i` of each channel is what
+    /// the value `i` becomes.
+    pub fn from_tables(red: &[u8; 256], green: &[u8; 256], blue: &[u8; 256]) -> Self {
+        let curve = |table: &[u8; 256]| table.iter().map(|&value| value as f32 / 255.0).collect();
+        Self::Curves([curve(red), curve(green), curve(blue)])
+    }
+
+    /// Parses an Adobe/Resolve .cube file, either 1D (`LUT_1D_SIZE`) or 3D (`LUT_3D_SIZE`).
+    pub fn from_cube_file(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| anyhow::anyhow!("Failed to read the LUT at [{}]: {}", path.display(), err))?;
+        Self::parse_cube(&text).map_err(|err| anyhow::anyhow!("{} in [{}]", err, path.display()))
+    }
+
+    /// Parses the text of a .cube file, see [`Self::from_cube_file`]. A `DOMAIN_MIN` or
+    /// `DOMAIN_MAX` other than 0 and 1 is folded into the table.
+    pub fn parse_cube(text: &str) -> anyhow::Result<Self> {
+        let mut size_1d = None;
+        let mut size_3d = None;
+        let mut domain = ([0.0_f32; 3], [1.0_f32; 3]);
+        let mut rows: Vec<[f32; 3]> = Vec::new();
+
+        for (line_idx, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let mut words = line.split_whitespace();
+            let Some(first) = words.next() else {
+                continue;
+            };
+            let error = |what: &str| anyhow::anyhow!("{} on line {} of the .cube file", what, line_idx + 1);
+            let triple = |words: std::str::SplitWhitespace| -> anyhow::Result<[f32; 3]> {
+                let values: Vec<f32> = words
+                    .map(|word| word.parse::<f32>().map_err(|_| error(&format!("[{}] is not a number", word))))
+                    .collect::<anyhow::Result<_>>()?;
+                <[f32; 3]>::try_from(values).map_err(|_| error("Expected three values"))
+            };
+            match first {
+                "TITLE" => {}
+                "LUT_1D_SIZE" | "LUT_3D_SIZE" => {
+                    let size = words.next().and_then(|word| word.parse::<usize>().ok());
+                    let size = Some(size.ok_or_else(|| error("Expected a size"))?);
+                    match first {
+                        "LUT_1D_SIZE" => size_1d = size,
+                        _ => size_3d = size,
+                    }
+                }
+                "DOMAIN_MIN" => domain.0 = triple(words)?,
+                "DOMAIN_MAX" => domain.1 = triple(words)?,
+                _ if first.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                    return Err(error(&format!("Unknown keyword [{}]", first)));
+                }
+                _ => rows.push(triple(line.split_whitespace())?),
+            }
+        }
+
+        if domain.0.iter().zip(&domain.1).any(|(min, max)| max.partial_cmp(min) != Some(Ordering::Greater)) {
+            return Err(anyhow::anyhow!("The .cube file's DOMAIN_MAX has to be above its DOMAIN_MIN"));
+        }
+        let lut = match (size_1d, size_3d) {
+            (Some(size), None) => {
+                if rows.len() != size {
+                    return Err(anyhow::anyhow!("The .cube file has {} rows but a LUT_1D_SIZE of {}", rows.len(), size));
+                }
+                Self::Curves([0, 1, 2].map(|c| rows.iter().map(|row| row[c]).collect()))
+            }
+            (None, Some(size)) => Self::Cube { size, table: rows },
+            _ => return Err(anyhow::anyhow!("The .cube file needs exactly one of LUT_1D_SIZE and LUT_3D_SIZE")),
+        };
+        lut.validate()?;
+        Ok(lut.with_domain(domain))
+    }
+
+    /// Resamples a valid table whose inputs run from `min` to `max` into one that runs from 0
+    /// to 1.
+    fn with_domain(self, (min, max): ([f32; 3], [f32; 3])) -> Self {
+        if min == [0.0; 3] && max == [1.0; 3] {
+            return self;
+        }
+        let domain_of = |c: usize, t: f32| ((t - min[c]) / (max[c] - min[c])).clamp(0.0, 1.0);
+        match self {
+            Self::Curves(curves) => Self::Curves([0, 1, 2].map(|c| {
+                let steps = (curves[c].len() - 1).max(1) as f32;
+                (0..curves[c].len()).map(|idx| sample_curve(&curves[c], domain_of(c, idx as f32 / steps))).collect()
+            })),
+            Self::Cube { size, table } => {
+                let steps = (size - 1) as f32;
+                let resampled = (0..table.len())
+                    .map(|idx| {
+                        let rgb = [idx % size, idx / size % size, idx / (size * size)];
+                        sample_cube(size, &table, [0, 1, 2].map(|c| domain_of(c, rgb[c] as f32 / steps)))
+                    })
+                    .collect();
+                Self::Cube { size, table: resampled }
+            }
+        }
+    }
+
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        let values: Vec<f32> = match self {
+            Self::Curves(curves) => {
+                if let Some(curve) = curves.iter().find(|curve| curve.len() < 2) {
+                    return Err(anyhow::anyhow!("A LUT curve needs at least 2 entries, got {}", curve.len()));
+                }
+                curves.iter().flatten().copied().collect()
+            }
+            Self::Cube { size, table } => {
+                if *size < 2 || size.checked_pow(3) != Some(table.len()) {
+                    return Err(anyhow::anyhow!(
+                        "A 3D LUT of size {} needs {}³ entries of at least 2³, got {}",
+                        size,
+                        size,
+                        table.len()
+                    ));
+                }
+                table.iter().flatten().copied().collect()
+            }
+        };
+        if !values.iter().all(|value| value.is_finite()) {
+            return Err(anyhow::anyhow!("The LUT has entries that aren't finite"));
+        }
+        Ok(())
+    }
+
+    /// Looks up `rgb`, in the `0.0..=255.0` range like the renderer's precise buffer.
+    pub(crate) fn apply(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let rgb = rgb.map(|channel| (channel / 255.0).clamp(0.0, 1.0));
+        let out = match self {
+            Self::Curves(curves) => [0, 1, 2].map(|c| sample_curve(&curves[c], rgb[c])),
+            Self::Cube { size, table } => sample_cube(*size, table, rgb),
+        };
+        out.map(|channel| channel.clamp(0.0, 1.0) * 255.0)
+    }
+}
+
+fn sample_curve(curve: &[f32], t: f32) -> f32 {
+    let position = t * (curve.len() - 1) as f32;
+    let idx = (position.floor() as usize).min(curve.len() - 2);
+    let amount = position - idx as f32;
+    curve[idx] + (curve[idx + 1] - curve[idx]) * amount
+}
+
+fn sample_cube(size: usize, table: &[[f32; 3]], rgb: [f32; 3]) -> [f32; 3] {
+    let positions = rgb.map(|channel| channel * (size - 1) as f32);
+    let lower = positions.map(|position| (position.floor() as usize).min(size - 2));
+    let amounts = [0, 1, 2].map(|c| positions[c] - lower[c] as f32);
+
+    let mut out = [0.0; 3];
+    for corner in 0..8 {
+        let offset = [corner & 1, (corner >> 1) & 1, (corner >> 2) & 1];
+        let weight: f32 = (0..3).map(|c| if offset[c] == 1 { amounts[c] } else { 1.0 - amounts[c] }).product();
+        let [r, g, b] = [0, 1, 2].map(|c| lower[c] + offset[c]);
+        let entry = table[r + g * size + b * size * size];
+        for (out, entry) in out.iter_mut().zip(entry) {
+            *out += entry * weight;
+        }
+    }
+    out
+}
@@ -1,26 +1,281 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-use model_to_image;
+use image::RgbImage;
+use model_to_image::{self, ModelToImage, ModelToImageBuilder, OutputNameContext, OutputNameTemplate};
+use serde_json::json;
+
+/// Where a CLI run failed, reported as the `code` of the JSON error object. These strings are
+/// part of the `--json` output format, so they must not change.
+#[derive(Debug, Clone, Copy)]
+enum ErrorCode {
+    ModelNotFound,
+    LoadFailed,
+    RenderFailed,
+    TimedOut,
+    WriteFailed,
+    NameCollision,
+}
+
+impl ErrorCode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::ModelNotFound => "model_not_found",
+            ErrorCode::LoadFailed => "load_failed",
+            ErrorCode::RenderFailed => "render_failed",
+            ErrorCode::TimedOut => "timed_out",
+            ErrorCode::WriteFailed => "write_failed",
+            ErrorCode::NameCollision => "name_collision",
+        }
+    }
+}
 
 fn main() -> anyhow::Result<()> {
-    let args = std::env::args().collect::<Vec<_>>();
-    let model_path = if args.len() >= 2 {
-        PathBuf::from(args[1].clone())
+    let mut args = std::env::args().collect::<Vec<_>>();
+    // with --json, stdout only ever holds the one JSON object and everything else goes to stderr
+    let json = args.iter().any(|arg| arg == "--json");
+    // with --per-mesh, every mesh is written to its own <stem>_<mesh name>.png
+    let per_mesh = args.iter().any(|arg| arg == "--per-mesh");
+    args.retain(|arg| arg != "--json" && arg != "--per-mesh");
+    // --timeout 10s gives up on renders that take longer than that
+    let timeout = match args.iter().position(|arg| arg == "--timeout") {
+        Some(idx) if idx + 1 < args.len() => {
+            let value = args.remove(idx + 1);
+            args.remove(idx);
+            Some(parse_duration(&value)?)
+        }
+        Some(_) => anyhow::bail!("--timeout needs a duration, like 10s or 500ms"),
+        None => None,
+    };
+    // --out-template "{stem}_{view}_{size}.png" names the images, see OutputNameTemplate
+    let template = match args.iter().position(|arg| arg == "--out-template") {
+        Some(idx) if idx + 1 < args.len() => {
+            let value = args.remove(idx + 1);
+            args.remove(idx);
+            Some(OutputNameTemplate::new(&value)?)
+        }
+        Some(_) => anyhow::bail!("--out-template needs a file name, like {{stem}}_{{view}}.png"),
+        None => None,
+    };
+
+    // several models are rendered one after another, by default each to <stem>.png
+    let model_paths: Vec<PathBuf> = if args.len() >= 2 {
+        args[1..].iter().map(PathBuf::from).collect()
     } else {
         #[cfg(debug_assertions)]
         {
-            println!("No model specified, using default");
-            println!("All args: {:?}", args)
+            eprintln!("No model specified, using default");
+            eprintln!("All args: {:?}", args)
         }
-        PathBuf::from("C:/Users/thrib/model_to_image/src/fish.glb")
+        vec![PathBuf::from("C:/Users/thrib/model_to_image/src/fish.glb")]
+    };
+    let template = match template {
+        None if model_paths.len() > 1 && !per_mesh => Some(OutputNameTemplate::new("{stem}.png")?),
+        template => template,
     };
-    let mut model = model_to_image::ModelToImageBuilder::new(PathBuf::from(
-        model_path,
-    ))
-    .with_size((800, 800))
-    .build()?;
-
-    model.render()?;
-    model.write_to(None)?;
+
+    // with a template, every image of the batch is named before anything is written, so a name
+    // clash fails the run without leaving part of the batch on disk
+    let mut planned: Vec<Option<Vec<PathBuf>>> = vec![None; model_paths.len()];
+    if let Some(template) = &template {
+        match plan_names(template, &model_paths, per_mesh, timeout) {
+            Ok(paths) => planned = paths.into_iter().map(Some).collect(),
+            Err((model_path, code, err)) => return fail(json, model_path, code, err),
+        }
+    }
+
+    let mut reports = Vec::with_capacity(model_paths.len());
+    for (model_path, paths) in model_paths.iter().zip(planned) {
+        match run(model_path, per_mesh, timeout, paths) {
+            Ok(report) => reports.push(report),
+            Err((code, err)) => return fail(json, model_path, code, err),
+        }
+    }
+
+    if json {
+        if reports.len() == 1 {
+            println!("{}", reports[0]);
+        } else {
+            println!("{}", json!({ "status": "ok", "models": reports }));
+        }
+    }
     Ok(())
 }
+
+/// Ends the run on an error with `model_path`: as the JSON error object with `--json`,
+/// otherwise as the error itself.
+fn fail(json: bool, model_path: &Path, code: ErrorCode, err: anyhow::Error) -> anyhow::Result<()> {
+    if !json {
+        return Err(err);
+    }
+    println!(
+        "{}",
+        json!({
+            "status": "error",
+            "code": code.as_str(),
+            "message": format!("{:#}", err),
+            "model": model_path.display().to_string(),
+        })
+    );
+    std::process::exit(1);
+}
+
+/// The builder every model of the run is loaded with.
+fn builder(model_path: &PathBuf, timeout: Option<Duration>) -> ModelToImageBuilder {
+    let mut builder = ModelToImageBuilder::new(model_path).with_size((800, 800));
+    if let Some(timeout) = timeout {
+        builder = builder.with_time_budget(timeout, model_to_image::TimeBudgetPolicy::Abort);
+    }
+    builder
+}
+
+fn load(model_path: &PathBuf, timeout: Option<Duration>) -> Result<ModelToImage, (ErrorCode, anyhow::Error)> {
+    if !model_path.exists() {
+        return Err((
+            ErrorCode::ModelNotFound,
+            anyhow::anyhow!("The model path [{}] does not exist", model_path.display()),
+        ));
+    }
+    builder(model_path, timeout).build().map_err(|err| (ErrorCode::LoadFailed, err))
+}
+
+/// Names every image of the batch with `template` before anything is rendered, failing if two
+/// of them would share a name. Returns the paths of each model's images, one per mesh with
+/// `per_mesh`. Per-mesh images are named after the meshes, so those models are loaded here to
+/// read their mesh names, and again when they're rendered.
+fn plan_names<'a>(
+    template: &OutputNameTemplate,
+    model_paths: &'a [PathBuf],
+    per_mesh: bool,
+    timeout: Option<Duration>,
+) -> Result<Vec<Vec<PathBuf>>, (&'a Path, ErrorCode, anyhow::Error)> {
+    let mut contexts = Vec::new();
+    // the model each context belongs to
+    let mut owners = Vec::new();
+    for (model_idx, model_path) in model_paths.iter().enumerate() {
+        let model_contexts = if per_mesh {
+            let model = load(model_path, timeout).map_err(|(code, err)| (model_path.as_path(), code, err))?;
+            let context = OutputNameContext::for_model(&model);
+            model.mesh_names().into_iter().map(|name| context.clone().with_mesh(name)).collect()
+        } else {
+            vec![OutputNameContext::for_builder(&builder(model_path, timeout))]
+        };
+        owners.extend(std::iter::repeat_n(model_idx, model_contexts.len()));
+        contexts.extend(model_contexts);
+    }
+
+    let names = template.render_all(&contexts).map_err(|collision| {
+        let model_path = model_paths[owners[collision.second]].as_path();
+        (model_path, ErrorCode::NameCollision, anyhow::Error::from(collision))
+    })?;
+    let mut paths = vec![Vec::new(); model_paths.len()];
+    for (model_idx, name) in owners.into_iter().zip(names) {
+        paths[model_idx].push(PathBuf::from(name));
+    }
+    Ok(paths)
+}
+
+/// Renders the model and builds the JSON report of what happened. `paths` are the names
+/// [`plan_names`] gave its images, when there's a template.
+fn run(
+    model_path: &PathBuf,
+    per_mesh: bool,
+    timeout: Option<Duration>,
+    paths: Option<Vec<PathBuf>>,
+) -> Result<serde_json::Value, (ErrorCode, anyhow::Error)> {
+    let started = Instant::now();
+    let mut model = load(model_path, timeout)?;
+
+    for warning in model.warnings() {
+        eprintln!("warning: {}", warning);
+    }
+
+    let outputs = if per_mesh {
+        let images = model.render_per_mesh().map_err(render_error)?;
+        let paths = paths.unwrap_or_else(|| per_mesh_paths(model_path, &images));
+        images
+            .iter()
+            .zip(&paths)
+            .map(|((_, image), path)| model.write_image_to(image, path))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map_err(|err| (ErrorCode::WriteFailed, err))?
+    } else {
+        model.render().map_err(render_error)?;
+        let path = paths.and_then(|mut paths| paths.pop());
+        vec![model.write_to(path.as_ref()).map_err(|err| (ErrorCode::WriteFailed, err))?]
+    };
+    for output in &outputs {
+        eprintln!("wrote {}", output.display());
+    }
+
+    let stats = model.stats();
+    Ok(json!({
+        "status": "ok",
+        "output": if per_mesh { None } else { Some(outputs[0].display().to_string()) },
+        "outputs": outputs.iter().map(|output| output.display().to_string()).collect::<Vec<_>>(),
+        "width": stats.width,
+        "height": stats.height,
+        "timing_ms": {
+            "load": stats.load_time.as_secs_f64() * 1000.0,
+            "render": stats.render_time.as_secs_f64() * 1000.0,
+            "total": started.elapsed().as_secs_f64() * 1000.0,
+        },
+        "triangles": {
+            "total": stats.triangles,
+            "drawn": stats.triangles_drawn,
+            "culled": stats.triangles_culled,
+            "degenerate": stats.triangles_degenerate,
+            "clipped": stats.triangles_clipped,
+            "off_screen": stats.triangles_off_screen,
+            "skipped_on_load": stats.faces_skipped,
+        },
+        "warnings": model.warnings(),
+    }))
+}
+
+/// Tells running out of time apart from other render failures.
+fn render_error(err: anyhow::Error) -> (ErrorCode, anyhow::Error) {
+    if err.is::<model_to_image::TimeBudgetExceeded>() {
+        (ErrorCode::TimedOut, err)
+    } else {
+        (ErrorCode::RenderFailed, err)
+    }
+}
+
+/// Parses a duration like `10s`, `500ms` or `2m`. A bare number is in seconds.
+fn parse_duration(value: &str) -> anyhow::Result<Duration> {
+    let split = value.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid duration [{}], expected something like 10s or 500ms", value))?;
+
+    let seconds = match unit {
+        "" | "s" => number,
+        "ms" => number / 1000.0,
+        "m" => number * 60.0,
+        _ => anyhow::bail!("Unknown duration unit [{}] in [{}], use ms, s or m", unit, value),
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|err| anyhow::anyhow!("Invalid duration [{}]: {}", value, err))
+}
+
+/// Names one image per mesh `<model stem>_<mesh name>.png`, in the current directory.
+/// Characters that don't belong in file names are replaced, and repeated mesh names get the
+/// mesh's position appended so no image overwrites another.
+fn per_mesh_paths(model_path: &Path, images: &[(String, RgbImage)]) -> Vec<PathBuf> {
+    let stem = model_path.file_stem().map_or("model".into(), |stem| stem.to_string_lossy());
+    let mut paths: Vec<PathBuf> = Vec::with_capacity(images.len());
+
+    for (mesh_idx, (name, _)) in images.iter().enumerate() {
+        let name: String = name
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        let mut path = PathBuf::from(format!("{}_{}.png", stem, name));
+        if paths.contains(&path) {
+            path = PathBuf::from(format!("{}_{}_{}.png", stem, name, mesh_idx));
+        }
+        paths.push(path);
+    }
+    paths
+}
@@ -0,0 +1,253 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use image::DynamicImage;
+use nalgebra::Matrix4;
+use russimp_ng::material::PropertyTypeInfo;
+use russimp_ng::mesh::Mesh;
+use russimp_ng::scene::Scene;
+
+use crate::scene_graph::Joint;
+use crate::{ModelToImageBuilder, TexturePolicy, UpAxis, detect_up_axis, scene_graph, texture};
+
+/// One mesh as raw arrays, for rendering geometry that doesn't come from a model file, see
+/// [`ModelToImageBuilder::from_meshes`]. Models loaded from files are converted into the same
+/// structure, so both render exactly the same way.
+///
+/// Every per vertex array other than `positions` may be left empty when the mesh doesn't have
+/// that attribute.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeshData {
+    /// Used by [`crate::MeshSelector::Name`] and in warnings, may be empty
+    pub name: String,
+    pub positions: Vec<[f32; 3]>,
+    /// Indices into `positions`, wound counter-clockwise when seen from outside the mesh.
+    /// Triangles pointing at vertices that don't exist are skipped with a warning.
+    pub triangles: Vec<[u32; 3]>,
+    /// Line segments, drawn as in [`ModelToImageBuilder::with_line_rendering`]
+    pub lines: Vec<[u32; 2]>,
+    pub normals: Vec<[f32; 3]>,
+    /// Texture coordinates, with (0, 0) at the bottom left of the texture
+    pub uvs: Vec<[f32; 2]>,
+    /// Vertex colours as RGBA, 0.0 to 1.0
+    pub colours: Vec<[f32; 4]>,
+    /// Index into the materials the mesh was given with
+    pub material: usize,
+    /// The bones that move the mesh's vertices, for [`crate::RenderMode::BoneWeights`]
+    pub bones: Vec<BoneWeights>,
+}
+
+/// How strongly one bone pulls on the vertices of a mesh.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BoneWeights {
+    /// The name of the bone, which is also the name of the node it hangs off
+    pub name: String,
+    /// Each vertex the bone moves, as its index into `positions` and the bone's weight on it
+    pub weights: Vec<(u32, f32)>,
+}
+
+/// A material for [`ModelToImageBuilder::from_meshes`].
+#[derive(Debug, Clone, Default)]
+pub struct MaterialData {
+    /// Shown in the material legend, may be empty
+    pub name: String,
+    /// The diffuse texture, sampled with the mesh's `uvs`
+    pub texture: Option<DynamicImage>,
+    /// Light the material gives off by itself, added to its shading as RGB, 0.0 to 1.0 but may
+    /// go over for lights brighter than white. Shows with [`ModelToImageBuilder::with_bloom`].
+    pub emissive: [f32; 3],
+    /// Draws the backs of the material's faces too, like foliage cards that are a single
+    /// quad, see [`ModelToImageBuilder::with_double_sided`]
+    pub double_sided: bool,
+}
+
+/// Everything [`crate::ModelToImage`] needs from a model, wherever it came from.
+#[derive(Debug)]
+pub(crate) struct SceneData {
+    pub meshes: Vec<MeshData>,
+    pub material_names: Vec<String>,
+    /// The emissive colour of every material, indexed like `material_names`
+    pub emissive: Vec<[f32; 3]>,
+    /// Whether every material is marked double-sided, indexed like `material_names`
+    pub double_sided: Vec<bool>,
+    /// The texture of every material, indexed like `material_names`
+    pub textures: Vec<Option<Arc<DynamicImage>>>,
+    /// The factor every material's texture was downsampled by, indexed like `material_names`
+    pub texture_downsampling: Vec<f32>,
+    /// Meshes with bones or morph targets, whose vertices must stay as they are
+    pub deforming: Vec<bool>,
+    /// The name and world transform of every node in the model's hierarchy, parents first
+    pub nodes: Vec<(String, Matrix4<f32>)>,
+    /// The joints of the bones, in world space like `nodes`
+    pub skeleton: Vec<Joint>,
+    /// The up axis the model declares, only looked up for
+    /// [`ModelToImageBuilder::with_auto_up_axis`]
+    pub up_axis: Option<UpAxis>,
+    pub warnings: Vec<String>,
+}
+
+impl SceneData {
+    /// Converts a scene imported by assimp, placing its meshes with the node hierarchy and
+    /// loading the textures of its materials. Only fails on textures that can't be loaded,
+    /// under [`TexturePolicy::Fail`].
+    pub fn from_scene(mut scene: Scene, builder: &ModelToImageBuilder) -> anyhow::Result<Self> {
+        let mut warnings = Vec::new();
+        scene_graph::apply_node_transforms(&mut scene, &mut warnings);
+
+        let settings = &builder.settings;
+        let up_axis = (settings.up_axis.is_none() && settings.auto_up_axis).then(|| detect_up_axis(&scene, &mut warnings));
+
+        let model_dir = builder.model_path.parent().unwrap_or(Path::new("."));
+        let cache = builder.texture_cache.clone().unwrap_or_default();
+        let mut errors = Vec::new();
+        let (textures, texture_downsampling) = texture::load_textures(
+            &scene,
+            model_dir,
+            &cache,
+            settings.max_texture_size,
+            &mut warnings,
+            &mut errors,
+        );
+        if settings.texture_policy == TexturePolicy::Fail && !errors.is_empty() {
+            return Err(anyhow::anyhow!("Failed to load the model's textures: {}", errors.join("; ")));
+        }
+        warnings.extend(errors);
+
+        let material_names = scene
+            .materials
+            .iter()
+            .map(|material| {
+                material
+                    .properties
+                    .iter()
+                    .find_map(|property| match &property.data {
+                        PropertyTypeInfo::String(name) if property.key == "?mat.name" => Some(name.clone()),
+                        _ => None,
+                    })
+                    .unwrap_or_default()
+            })
+            .collect();
+        let emissive = scene
+            .materials
+            .iter()
+            .map(|material| {
+                material
+                    .properties
+                    .iter()
+                    .find_map(|property| match &property.data {
+                        PropertyTypeInfo::FloatArray(rgb) if property.key == "$clr.emissive" && rgb.len() >= 3 => {
+                            Some([rgb[0], rgb[1], rgb[2]])
+                        }
+                        _ => None,
+                    })
+                    .unwrap_or_default()
+            })
+            .collect();
+        // glTF's doubleSided comes through as a one byte buffer, other formats as an integer
+        let double_sided = scene
+            .materials
+            .iter()
+            .map(|material| {
+                material.properties.iter().any(|property| {
+                    property.key == "$mat.twosided"
+                        && match &property.data {
+                            PropertyTypeInfo::Buffer(bytes) => bytes.iter().any(|&byte| byte != 0),
+                            PropertyTypeInfo::IntegerArray(values) => values.iter().any(|&value| value != 0),
+                            _ => false,
+                        }
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            deforming: scene
+                .meshes
+                .iter()
+                .map(|mesh| !mesh.bones.is_empty() || !mesh.anim_meshes.is_empty())
+                .collect(),
+            meshes: scene.meshes.iter().map(MeshData::from).collect(),
+            material_names,
+            emissive,
+            double_sided,
+            textures,
+            texture_downsampling,
+            nodes: scene_graph::world_transforms(&scene),
+            skeleton: scene_graph::skeleton(&scene),
+            up_axis,
+            warnings,
+        })
+    }
+
+    /// Takes meshes built by the caller, shrinking textures over the size limit.
+    pub fn from_meshes(meshes: Vec<MeshData>, materials: Vec<MaterialData>, max_texture_size: u32) -> Self {
+        let mut warnings = Vec::new();
+        let mut texture_downsampling = vec![1.0; materials.len()];
+        let textures = materials
+            .iter()
+            .enumerate()
+            .map(|(material_idx, material)| {
+                let (img, factor) = texture::fit_to_size(material.texture.clone()?, max_texture_size);
+                if factor > 1.0 {
+                    warnings.push(texture::downsampled_warning(&[material_idx], factor, max_texture_size));
+                }
+                texture_downsampling[material_idx] = factor;
+                Some(Arc::new(img))
+            })
+            .collect();
+
+        Self {
+            deforming: meshes.iter().map(|mesh| !mesh.bones.is_empty()).collect(),
+            meshes,
+            emissive: materials.iter().map(|material| material.emissive).collect(),
+            double_sided: materials.iter().map(|material| material.double_sided).collect(),
+            material_names: materials.into_iter().map(|material| material.name).collect(),
+            textures,
+            texture_downsampling,
+            nodes: Vec::new(),
+            skeleton: Vec::new(),
+            up_axis: None,
+            warnings,
+        }
+    }
+}
+
+impl From<&Mesh> for MeshData {
+    /// Keeps the first UV and colour channel; points and polygons that weren't triangulated are
+    /// dropped.
+    fn from(mesh: &Mesh) -> Self {
+        let mut triangles = Vec::new();
+        let mut lines = Vec::new();
+        for face in &mesh.faces {
+            match face.0[..] {
+                [a, b, c] => triangles.push([a, b, c]),
+                [a, b] => lines.push([a, b]),
+                _ => {}
+            }
+        }
+
+        Self {
+            name: mesh.name.clone(),
+            positions: mesh.vertices.iter().map(|v| [v.x, v.y, v.z]).collect(),
+            triangles,
+            lines,
+            normals: mesh.normals.iter().map(|n| [n.x, n.y, n.z]).collect(),
+            uvs: match mesh.texture_coords.first() {
+                Some(Some(uvs)) => uvs.iter().map(|uv| [uv.x, uv.y]).collect(),
+                _ => Vec::new(),
+            },
+            colours: match mesh.colors.first() {
+                Some(Some(colours)) => colours.iter().map(|c| [c.r, c.g, c.b, c.a]).collect(),
+                _ => Vec::new(),
+            },
+            material: mesh.material_index as usize,
+            bones: mesh
+                .bones
+                .iter()
+                .map(|bone| BoneWeights {
+                    name: bone.name.clone(),
+                    weights: bone.weights.iter().map(|weight| (weight.vertex_id, weight.weight)).collect(),
+                })
+                .collect(),
+        }
+    }
+}
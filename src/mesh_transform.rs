@@ -0,0 +1,101 @@
+use nalgebra::{Matrix3, Vector3};
+
+use crate::{MeshData, MeshSelector, utils};
+
+/// Moves, turns and resizes a mesh, see [`crate::ModelToImageBuilder::with_mesh_transform`].
+/// Vertices are scaled, then rotated, then translated, all about the model's origin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    /// In the model's units
+    pub translation: [f32; 3],
+    /// Degrees about the X, Y and Z axes, applied in that order
+    pub rotation: [f32; 3],
+    /// Per axis, 1.0 keeps the size
+    pub scale: [f32; 3],
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            translation: [0.0; 3],
+            rotation: [0.0; 3],
+            scale: [1.0; 3],
+        }
+    }
+}
+
+impl Transform {
+    /// Only moves the mesh.
+    pub fn from_translation(translation: [f32; 3]) -> Self {
+        Self {
+            translation,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_rotation(mut self, rotation: [f32; 3]) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: [f32; 3]) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        let mut values = self.translation.iter().chain(&self.rotation).chain(&self.scale);
+        if !values.all(|value| value.is_finite()) || self.scale.contains(&0.0) {
+            return Err(anyhow::anyhow!("A mesh transform needs finite values and a non-zero scale, got [{:?}]", self));
+        }
+        Ok(())
+    }
+
+    fn rotation_matrix(&self) -> Matrix3<f32> {
+        let [x, y, z] = self.rotation;
+        let rotation = utils::rotation_about(&Vector3::z_axis(), z)
+            * utils::rotation_about(&Vector3::y_axis(), y)
+            * utils::rotation_about(&Vector3::x_axis(), x);
+        rotation.into_inner()
+    }
+
+    fn apply(&self, mesh: &mut MeshData) {
+        let rotation = self.rotation_matrix();
+        let scale = Vector3::from(self.scale);
+        let translation = Vector3::from(self.translation);
+        for position in &mut mesh.positions {
+            *position = (rotation * Vector3::from(*position).component_mul(&scale) + translation).into();
+        }
+        // normals take the inverse of the scale, so they stay square to stretched faces
+        for normal in &mut mesh.normals {
+            let turned = rotation * Vector3::from(*normal).component_div(&scale);
+            *normal = turned.try_normalize(f32::EPSILON).map_or(*normal, |n| n.into());
+        }
+        // a mirroring scale turns the faces inside out, winding them back keeps them facing out
+        if self.scale.iter().filter(|&&s| s < 0.0).count() % 2 == 1 {
+            for triangle in &mut mesh.triangles {
+                triangle.swap(1, 2);
+            }
+        }
+    }
+}
+
+/// Applies the last of `overrides` that selects each mesh, see
+/// [`crate::ModelToImageBuilder::with_mesh_transform`]. Returns the selectors that matched no
+/// mesh at all.
+pub(crate) fn apply_overrides<'a>(
+    meshes: &mut [MeshData],
+    overrides: &'a [(MeshSelector, Transform)],
+) -> Vec<&'a MeshSelector> {
+    for (mesh_idx, mesh) in meshes.iter_mut().enumerate() {
+        let transform = overrides.iter().rev().find(|(selector, _)| selector.matches(mesh_idx, &mesh.name));
+        if let Some((_, transform)) = transform {
+            transform.apply(mesh);
+        }
+    }
+    overrides
+        .iter()
+        .map(|(selector, _)| selector)
+        .filter(|selector| !meshes.iter().enumerate().any(|(idx, mesh)| selector.matches(idx, &mesh.name)))
+        .collect()
+}
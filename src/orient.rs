@@ -0,0 +1,48 @@
+use nalgebra::{Matrix3, SymmetricEigen, Vector3};
+
+use crate::MeshData;
+
+/// The rotation that lines the scene's principal axes up with the view: the direction the
+/// vertices spread out the most along X, the next most along Y, and the least along Z, towards
+/// the camera. `None` when there are fewer than three vertices to go on.
+///
+/// Each axis is pointed so its largest component is positive, and equally long axes keep the
+/// order the eigen decomposition gave them, so the same model always turns the same way.
+pub(crate) fn principal_axes_rotation(meshes: &[MeshData]) -> Option<Matrix3<f32>> {
+    let points = || {
+        meshes
+            .iter()
+            .flat_map(|mesh| mesh.positions.iter())
+            .filter(|p| p.iter().all(|c| c.is_finite()))
+            .map(|p| Vector3::new(p[0] as f64, p[1] as f64, p[2] as f64))
+    };
+    let count = points().count();
+    if count < 3 {
+        return None;
+    }
+    let mean = points().sum::<Vector3<f64>>() / count as f64;
+    let covariance = points()
+        .map(|p| {
+            let offset = p - mean;
+            offset * offset.transpose()
+        })
+        .sum::<Matrix3<f64>>()
+        / count as f64;
+
+    let eigen = SymmetricEigen::new(covariance);
+    let mut order = [0, 1, 2];
+    // stable, so equal spreads keep their order
+    order.sort_by(|&a, &b| eigen.eigenvalues[b].total_cmp(&eigen.eigenvalues[a]));
+
+    let axis = |idx: usize| {
+        let v: Vector3<f64> = eigen.eigenvectors.column(idx).into();
+        let largest = v.iter().copied().max_by(|a, b| a.abs().total_cmp(&b.abs())).unwrap_or(1.0);
+        if largest < 0.0 { -v } else { v }
+    };
+    let (longest, second) = (axis(order[0]), axis(order[1]));
+    // the third is made from the others so the rotation never mirrors the model
+    let third = longest.cross(&second);
+
+    let rotation = Matrix3::from_rows(&[longest.transpose(), second.transpose(), third.transpose()]);
+    rotation.iter().all(|c| c.is_finite()).then(|| rotation.cast::<f32>())
+}
@@ -0,0 +1,270 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::{ModelToImage, ModelToImageBuilder, Size, ViewPreset};
+
+/// Every placeholder an [`OutputNameTemplate`] understands, without the braces.
+pub const OUTPUT_NAME_PLACEHOLDERS: [&str; 7] = ["stem", "view", "width", "height", "size", "mesh", "frame"];
+
+/// A file name for the images of a batch, like `{stem}_{view}_{size}.png`, with placeholders
+/// filled in from an [`OutputNameContext`] for each image:
+///
+/// - `{stem}`: the model's file name without its extension
+/// - `{view}`: the view preset, like `front` or `isometric`
+/// - `{width}`, `{height}` and `{size}`: the image size in pixels, `{size}` as `800x600`
+/// - `{mesh}`: the mesh name for per-mesh renders, empty otherwise
+/// - `{frame}`: the frame index, 0 outside of animations
+///
+/// `{{` and `}}` stand for literal braces. Unknown placeholders are refused up front, when the
+/// template is parsed, rather than ending up in file names.
+///
+/// ```
+/// use model_to_image::{OutputNameContext, OutputNameTemplate, ViewPreset};
+/// let template = OutputNameTemplate::new("{stem}_{view}_{size}.png").unwrap();
+/// let context = OutputNameContext::new("fish", ViewPreset::Top, (64, 32));
+/// assert_eq!(template.render(&context), "fish_top_64x32.png");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputNameTemplate {
+    template: String,
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Placeholder(&'static str),
+}
+
+/// What the placeholders of an [`OutputNameTemplate`] stand for in one image's name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputNameContext {
+    pub stem: String,
+    pub view: ViewPreset,
+    pub width: u32,
+    pub height: u32,
+    pub mesh: Option<String>,
+    pub frame: usize,
+}
+
+impl OutputNameContext {
+    pub fn new(stem: impl Into<String>, view: ViewPreset, size: impl Into<Size>) -> Self {
+        let size = size.into();
+        Self {
+            stem: stem.into(),
+            view,
+            width: size.width,
+            height: size.height,
+            mesh: None,
+            frame: 0,
+        }
+    }
+
+    /// The stem, view and size of `model`. Models without a file, like those from
+    /// [`crate::ModelToImageBuilder::from_meshes`], get the stem `model`.
+    pub fn for_model(model: &ModelToImage) -> Self {
+        Self::new(stem(model.model_path()), model.view(), model.size())
+    }
+
+    /// The context of the model `builder` is about to build, for naming its image before
+    /// loading it. With [`ViewPreset::Auto`] the view isn't picked until the model is built, so
+    /// `{view}` stays `auto`.
+    pub fn for_builder(builder: &ModelToImageBuilder) -> Self {
+        Self::new(stem(&builder.model_path), builder.settings.view, builder.settings.size)
+    }
+
+    pub fn with_mesh(mut self, mesh: impl Into<String>) -> Self {
+        self.mesh = Some(mesh.into());
+        self
+    }
+
+    pub fn with_frame(mut self, frame: usize) -> Self {
+        self.frame = frame;
+        self
+    }
+}
+
+impl OutputNameTemplate {
+    /// Parses `template`, failing with a [`TemplateError`] that lists every unknown
+    /// placeholder at once.
+    pub fn new(template: &str) -> Result<Self, TemplateError> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut unknown = Vec::new();
+        let mut chars = template.char_indices().peekable();
+
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                '{' if chars.next_if(|&(_, c)| c == '{').is_some() => text.push('{'),
+                '}' if chars.next_if(|&(_, c)| c == '}').is_some() => text.push('}'),
+                '{' => {
+                    let rest = &template[idx + 1..];
+                    let Some(len) = rest.find('}') else {
+                        return Err(TemplateError::UnmatchedBrace { template: template.to_string(), position: idx });
+                    };
+                    let name = &rest[..len];
+                    match OUTPUT_NAME_PLACEHOLDERS.iter().find(|&&known| known == name) {
+                        Some(&known) => {
+                            parts.push(Part::Text(std::mem::take(&mut text)));
+                            parts.push(Part::Placeholder(known));
+                        }
+                        None if !unknown.iter().any(|seen| seen == name) => unknown.push(name.to_string()),
+                        None => {}
+                    }
+                    // skip the name and its closing brace
+                    for _ in 0..name.chars().count() + 1 {
+                        chars.next();
+                    }
+                }
+                '}' => return Err(TemplateError::UnmatchedBrace { template: template.to_string(), position: idx }),
+                c => text.push(c),
+            }
+        }
+        parts.push(Part::Text(text));
+        parts.retain(|part| *part != Part::Text(String::new()));
+
+        if !unknown.is_empty() {
+            return Err(TemplateError::UnknownPlaceholders { template: template.to_string(), unknown });
+        }
+        if parts.is_empty() {
+            return Err(TemplateError::Empty);
+        }
+        Ok(Self { template: template.to_string(), parts })
+    }
+
+    /// The template as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.template
+    }
+
+    /// Whether the template holds `placeholder` (given without braces), e.g. to check that a
+    /// batch template tells its models apart with `stem`.
+    pub fn uses(&self, placeholder: &str) -> bool {
+        self.parts.iter().any(|part| matches!(part, Part::Placeholder(name) if *name == placeholder))
+    }
+
+    /// The name for the image `context` describes. Characters that don't belong in file
+    /// names are replaced with `_` in mesh names, as those come from inside the model.
+    pub fn render(&self, context: &OutputNameContext) -> String {
+        let mut name = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => name.push_str(text),
+                Part::Placeholder("stem") => name.push_str(&context.stem),
+                Part::Placeholder("view") => name.push_str(context.view.name()),
+                Part::Placeholder("width") => name.push_str(&context.width.to_string()),
+                Part::Placeholder("height") => name.push_str(&context.height.to_string()),
+                Part::Placeholder("size") => name.push_str(&format!("{}x{}", context.width, context.height)),
+                Part::Placeholder("mesh") => name.extend(
+                    context
+                        .mesh
+                        .iter()
+                        .flat_map(|mesh| mesh.chars())
+                        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' }),
+                ),
+                Part::Placeholder("frame") => name.push_str(&context.frame.to_string()),
+                Part::Placeholder(other) => unreachable!("unknown placeholder {} got past parsing", other),
+            }
+        }
+        name
+    }
+
+    /// The names for a whole batch, in order. Fails with a [`NameCollision`] if two images
+    /// would get the same name and one would overwrite the other.
+    pub fn render_all<'a>(
+        &self,
+        contexts: impl IntoIterator<Item = &'a OutputNameContext>,
+    ) -> Result<Vec<String>, NameCollision> {
+        let mut names: Vec<String> = Vec::new();
+        for context in contexts {
+            let name = self.render(context);
+            if let Some(first) = names.iter().position(|earlier| *earlier == name) {
+                return Err(NameCollision { name, first, second: names.len() });
+            }
+            names.push(name);
+        }
+        Ok(names)
+    }
+}
+
+impl FromStr for OutputNameTemplate {
+    type Err = TemplateError;
+
+    fn from_str(template: &str) -> Result<Self, TemplateError> {
+        Self::new(template)
+    }
+}
+
+impl fmt::Display for OutputNameTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.template)
+    }
+}
+
+/// An [`OutputNameTemplate`] that can't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// Placeholders that aren't in [`OUTPUT_NAME_PLACEHOLDERS`], each listed once without
+    /// braces
+    UnknownPlaceholders { template: String, unknown: Vec<String> },
+    /// A `{` without a closing `}`, or a `}` on its own, at this byte offset
+    UnmatchedBrace { template: String, position: usize },
+    /// A template that would give every image an empty name
+    Empty,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::UnknownPlaceholders { template, unknown } => write!(
+                f,
+                "The output name template [{}] has unknown placeholders {}. Use {} instead",
+                template,
+                braced(unknown.iter().map(String::as_str)),
+                braced(OUTPUT_NAME_PLACEHOLDERS.into_iter()),
+            ),
+            TemplateError::UnmatchedBrace { template, position } => write!(
+                f,
+                "The output name template [{}] has an unmatched brace at byte {}. Write {{{{ or }}}} for a \
+                 literal brace",
+                template, position
+            ),
+            TemplateError::Empty => write!(f, "The output name template is empty"),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// The model's file name without its extension, for `{stem}`.
+fn stem(model_path: &Path) -> String {
+    model_path.file_stem().map_or("model".into(), |stem| stem.to_string_lossy().into_owned())
+}
+
+/// Placeholder names as they're written in a template, e.g. `{stem}, {view}`.
+fn braced<'a>(names: impl Iterator<Item = &'a str>) -> String {
+    names.map(|name| format!("{{{}}}", name)).collect::<Vec<_>>().join(", ")
+}
+
+/// Two images of a batch would get the same name, see [`OutputNameTemplate::render_all`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameCollision {
+    pub name: String,
+    /// Positions of the two images in the batch
+    pub first: usize,
+    pub second: usize,
+}
+
+impl fmt::Display for NameCollision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Outputs {} and {} would both be named [{}], add a placeholder that tells them apart, like {{stem}} \
+             or {{mesh}}",
+            self.first, self.second, self.name
+        )
+    }
+}
+
+impl std::error::Error for NameCollision {}
@@ -0,0 +1,321 @@
+use std::time::Duration;
+
+use image::{Rgb, RgbImage};
+use nalgebra::{Matrix3, Vector3};
+
+use crate::utils::Colour;
+use crate::utils::text::{GLYPH_HEIGHT, draw_text, measure_text};
+use crate::scene_graph::Joint;
+use crate::{Aabb, DEPTH_EPSILON, DimensionLabels, MeshDrawData, Overlay, Projection, RenderStats};
+
+/// Draws a one pixel wide line between two points in image space. Parts of the line that fall
+/// outside of the image are skipped.
+pub(crate) fn draw_line(img: &mut RgbImage, from: (f32, f32), to: (f32, f32), colour: Rgb<u8>) {
+    draw_line_depth(img, (from.0, from.1, 0.0), (to.0, to.1, 0.0), colour, 1, None);
+}
+
+/// Draws a line `width` pixels thick between two points in image space, clipped to the image.
+///
+/// The third component of each point is its depth. When `depth_test` is given as the z-buffer
+/// and a tolerance, pixels of the line that are behind the model are skipped.
+pub(crate) fn draw_line_depth(
+    img: &mut RgbImage,
+    from: (f32, f32, f32),
+    to: (f32, f32, f32),
+    colour: Rgb<u8>,
+    width: u32,
+    depth_test: Option<(&[f32], f32)>,
+) {
+    let (img_width, img_height) = img.dimensions();
+    let dx = to.0 - from.0;
+    let dy = to.1 - from.1;
+    let dz = to.2 - from.2;
+    let steps = dx.abs().max(dy.abs()).ceil().max(1.0) as u32;
+    let width = width.max(1) as i64;
+    let half = (width - 1) / 2;
+
+    for step in 0..=steps {
+        let t = step as f32 / steps as f32;
+        let cx = (from.0 + dx * t).round() as i64;
+        let cy = (from.1 + dy * t).round() as i64;
+        let z = from.2 + dz * t;
+
+        for y in (cy - half)..(cy - half + width) {
+            for x in (cx - half)..(cx - half + width) {
+                if x < 0 || y < 0 || x >= img_width as i64 || y >= img_height as i64 {
+                    continue;
+                }
+                if let Some((z_buffer, epsilon)) = depth_test {
+                    if z < z_buffer[(x + y * img_width as i64) as usize] - epsilon {
+                        continue;
+                    }
+                }
+                img.put_pixel(x as u32, y as u32, colour);
+            }
+        }
+    }
+}
+
+/// The bounds of the model along its own axes, which the bounding box and axes overlays are
+/// drawn from, and the rotation that turns those axes into the view.
+pub(crate) struct ModelFrame {
+    pub bounds: Aabb,
+    pub orientation: Matrix3<f32>,
+}
+
+/// Draws one of the user requested [`Overlay`]s.
+pub(crate) fn draw_overlay(
+    img: &mut RgbImage,
+    overlay: &Overlay,
+    frame: &ModelFrame,
+    projection: &Projection,
+    skeleton: &[Joint],
+    connectors: &[(Vector3<f32>, Vector3<f32>)],
+    z_buffer: Option<&[f32]>,
+) {
+    let ModelFrame { bounds, orientation } = frame;
+    let extent = bounds.extent();
+    let depth_test = z_buffer.map(|z_buffer| (z_buffer, DEPTH_EPSILON));
+    let to_screen = |v: &Vector3<f32>| {
+        let (x, y) = projection.project(v);
+        (x, y, projection.depth(v.z))
+    };
+    let model_to_screen = |v: &Vector3<f32>| to_screen(&(orientation * v));
+
+    match *overlay {
+        Overlay::BoundingBox { colour, width_px } => {
+            let corners = bounds.corners().map(|corner| model_to_screen(&corner));
+            const EDGES: [(usize, usize); 12] = [
+                (0, 1), (1, 2), (2, 3), (3, 0),
+                (4, 5), (5, 6), (6, 7), (7, 4),
+                (0, 4), (1, 5), (2, 6), (3, 7),
+            ];
+            for (a, b) in EDGES {
+                draw_line_depth(img, corners[a], corners[b], colour.into(), width_px, depth_test);
+            }
+        }
+        Overlay::Axes { length_fraction } => {
+            let center = bounds.center();
+            let length = extent.max() * length_fraction;
+            let axes = [
+                (Vector3::x(), Rgb([255, 0, 0])),
+                (Vector3::y(), Rgb([0, 255, 0])),
+                (Vector3::z(), Rgb([0, 0, 255])),
+            ];
+            for (direction, colour) in axes {
+                let end = center + direction * length;
+                draw_line_depth(img, model_to_screen(&center), model_to_screen(&end), colour, 2, depth_test);
+            }
+        }
+        Overlay::Skeleton { colour, width_px } => {
+            let colour = colour.into();
+            for joint in skeleton {
+                if let Some(parent) = joint.parent.and_then(|parent| skeleton.get(parent)) {
+                    draw_line_depth(img, to_screen(&parent.position), to_screen(&joint.position), colour, width_px, None);
+                }
+            }
+            // a line with no length is a square, three times as wide as the bones
+            for joint in skeleton {
+                let point = to_screen(&joint.position);
+                draw_line_depth(img, point, point, colour, width_px.max(1) * 3, None);
+            }
+        }
+        Overlay::ExplodeConnectors { colour, width_px } => {
+            for (from, to) in connectors {
+                draw_line_depth(img, to_screen(from), to_screen(to), colour.into(), width_px, depth_test);
+            }
+        }
+    }
+}
+
+/// A short line out of the centre of a face, pointing the way the face faces, see
+/// [`normal_ticks`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct NormalTick {
+    centre: (f32, f32),
+    centre_depth: f32,
+    end: (f32, f32),
+}
+
+/// The ticks of every face of `mesh` whose centre lands in an image of `width` x `height`,
+/// to be drawn with [`draw_normal_ticks`] once the z-buffer is finished.
+pub(crate) fn normal_ticks(mesh: &MeshDrawData, width: u32, height: u32) -> impl Iterator<Item = NormalTick> + '_ {
+    let tick_length = (width.min(height) as f32 * 0.02).max(3.0);
+
+    mesh.faces.iter().filter_map(move |&[i0, i1, i2]| {
+        let (a, b, c) = (mesh.world_coords[i0], mesh.world_coords[i1], mesh.world_coords[i2]);
+
+        let centre = (
+            (mesh.projected[i0].0 + mesh.projected[i1].0 + mesh.projected[i2].0) / 3.0,
+            (mesh.projected[i0].1 + mesh.projected[i1].1 + mesh.projected[i2].1) / 3.0,
+        );
+        if centre.0 < 0.0 || centre.1 < 0.0 || centre.0 >= width as f32 || centre.1 >= height as f32 {
+            return None;
+        }
+
+        // the renderer's winding gives normals pointing into the model, flip it outwards
+        let outward = (b - a).cross(&(c - a)).try_normalize(f32::EPSILON)?;
+        Some(NormalTick {
+            centre,
+            centre_depth: (mesh.depths[i0] + mesh.depths[i1] + mesh.depths[i2]) / 3.0,
+            end: (centre.0 + outward.x * tick_length, centre.1 + outward.y * tick_length),
+        })
+    })
+}
+
+/// Draws the `ticks` of the faces that are visible in `z_buffer`.
+pub(crate) fn draw_normal_ticks(img: &mut RgbImage, ticks: &[NormalTick], z_buffer: &[f32]) {
+    let width = img.width() as usize;
+    for tick in ticks {
+        // faces sitting right on the visible surface should not be hidden by rounding
+        let buffer_index = tick.centre.0 as usize + tick.centre.1 as usize * width;
+        if tick.centre_depth < z_buffer[buffer_index] - DEPTH_EPSILON {
+            continue;
+        }
+        draw_line(img, tick.centre, tick.end, Rgb([255, 220, 0]));
+    }
+}
+
+/// Writes the model's dimensions along the bottom of an already flipped image, plus the scale
+/// bar if it was asked for. `scale_factor` converts the scene's units back into the model's
+/// original units.
+pub(crate) fn draw_dimension_labels(
+    img: &mut RgbImage,
+    labels: &DimensionLabels,
+    bounds: &Aabb,
+    projection: &Projection,
+    scale_factor: f32,
+) {
+    let (width, height) = img.dimensions();
+    let text_colour = Rgb([40, 40, 40]);
+    let font_scale = (width.min(height) / 256).max(1);
+    let padding = 4 * font_scale as i64;
+
+    // the labels are in the model's original units, even if the scene was normalised
+    let size = bounds.extent() * scale_factor * labels.unit_scale;
+    let text = format!(
+        "W {} × H {} × D {} {}",
+        format_length(size.x),
+        format_length(size.y),
+        format_length(size.z),
+        labels.units.suffix()
+    );
+    let text_y = height as i64 - padding - (GLYPH_HEIGHT * font_scale) as i64;
+    draw_text(img, padding, text_y, &text, text_colour, font_scale);
+
+    if !labels.scale_bar {
+        return;
+    }
+
+    // the bar is horizontal, so it's stretched along with the model's width
+    let pixels_per_unit = (projection.scale * projection.content_scale.0 / (scale_factor * labels.unit_scale)) as f64;
+    if !pixels_per_unit.is_finite() || pixels_per_unit <= 0.0 {
+        return;
+    }
+    // aim for a bar about a quarter of the image wide, rounded down to 1, 2 or 5 x 10^n units
+    let target = width as f64 * 0.25 / pixels_per_unit;
+    let power = 10f64.powf(target.log10().floor());
+    let mantissa = target / power;
+    let bar_units = power * if mantissa >= 5.0 { 5.0 } else if mantissa >= 2.0 { 2.0 } else { 1.0 };
+    let bar_length = (bar_units * pixels_per_unit).round() as i64;
+
+    let thickness = 2 * font_scale as i64;
+    let bar_right = width as i64 - padding;
+    let bar_left = bar_right - bar_length;
+    let bar_y = height as i64 - padding - thickness;
+    for y in bar_y..bar_y + thickness {
+        draw_line(img, (bar_left as f32, y as f32), (bar_right as f32, y as f32), text_colour);
+    }
+    // small end caps so the length reads clearly
+    let cap_top = (bar_y - thickness * 2) as f32;
+    for x in [bar_left, bar_right] {
+        draw_line(img, (x as f32, cap_top), (x as f32, bar_y as f32), text_colour);
+    }
+
+    let label = format!("{} {}", bar_units, labels.units.suffix());
+    let (label_width, label_height) = measure_text(&label, font_scale);
+    let label_x = bar_right - label_width as i64;
+    let label_y = bar_y - thickness * 2 - padding / 2 - label_height as i64;
+    draw_text(img, label_x, label_y, &label, text_colour, font_scale);
+}
+
+/// Writes the size of the image, how many triangles were drawn and how long the frame took into
+/// the top left corner, on a light box so it reads over any model.
+pub(crate) fn draw_debug_hud(img: &mut RgbImage, stats: &RenderStats, elapsed: Duration) {
+    let (width, height) = img.dimensions();
+    let font_scale = (width.min(height) / 256).max(1);
+    let padding = 2 * font_scale;
+    let text = format!(
+        "{}x{}\ntris {}/{}\n{:.1} ms",
+        width,
+        height,
+        stats.triangles_drawn,
+        stats.triangles,
+        elapsed.as_secs_f64() * 1000.0
+    );
+
+    let (text_width, text_height) = measure_text(&text, font_scale);
+    for y in 0..(text_height + padding * 2).min(height) {
+        for x in 0..(text_width + padding * 2).min(width) {
+            img.put_pixel(x, y, Rgb([245, 245, 245]));
+        }
+    }
+    draw_text(img, padding as i64, padding as i64, &text, Rgb([40, 40, 40]), font_scale);
+}
+
+/// Draws a strip along the bottom of the image with a colour swatch and label for every entry,
+/// left to right. Entries that don't fit in the width of the image are left off.
+pub(crate) fn draw_material_legend(img: &mut RgbImage, entries: &[(Colour, String)]) {
+    let (width, height) = img.dimensions();
+    let text_colour = Rgb([40, 40, 40]);
+    let font_scale = (width.min(height) / 256).max(1);
+    let padding = 4 * font_scale;
+    let swatch = GLYPH_HEIGHT * font_scale;
+    let strip_height = swatch + padding * 2;
+    if strip_height >= height {
+        return;
+    }
+
+    let strip_top = height - strip_height;
+    for y in strip_top..height {
+        for x in 0..width {
+            img.put_pixel(x, y, Rgb([245, 245, 245]));
+        }
+    }
+
+    let mut x = padding;
+    for (colour, label) in entries {
+        let (label_width, _) = measure_text(label, font_scale);
+        let entry_width = swatch + padding + label_width;
+        if x + entry_width > width {
+            break;
+        }
+
+        let swatch_colour: Rgb<u8> = (*colour).into();
+        for sy in strip_top + padding..strip_top + padding + swatch {
+            for sx in x..x + swatch {
+                img.put_pixel(sx, sy, swatch_colour);
+            }
+        }
+        draw_text(
+            img,
+            (x + swatch + padding) as i64,
+            (strip_top + padding) as i64,
+            label,
+            text_colour,
+            font_scale,
+        );
+        x += entry_width + padding * 3;
+    }
+}
+
+/// Formats a measurement with a sensible number of decimal places for its size.
+fn format_length(value: f32) -> String {
+    if value >= 100.0 {
+        format!("{:.0}", value)
+    } else if value >= 1.0 {
+        format!("{:.1}", value)
+    } else {
+        format!("{:.3}", value)
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::io::Write;
+
+use image::RgbImage;
+
+/// What [`crate::ModelToImage::write_indexed_png_to`] wrote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedPng {
+    /// Number of colours in the palette, no more than were asked for and fewer when the image
+    /// has fewer distinct colours
+    pub palette_size: usize,
+    /// Bits per pixel in the file: 1, 2, 4 or 8, the fewest that can index the palette
+    pub bit_depth: u8,
+}
+
+/// An image reduced to a palette, with one palette index per pixel.
+pub(crate) struct Quantized {
+    pub palette: Vec<[u8; 3]>,
+    pub indices: Vec<u8>,
+}
+
+/// A box of colours for median cut, as a range of the histogram.
+#[derive(Clone, Copy)]
+struct ColourBox {
+    start: usize,
+    end: usize,
+}
+
+/// Reduces `img` to at most `max_colours` colours (1 to 256) by median cut over its colour
+/// histogram, weighted by how many pixels have each colour. Images that already have few
+/// enough colours keep them exactly. With `dither` the rounding error of every pixel is spread
+/// to its neighbours (Floyd–Steinberg), trading flat banding for fine noise.
+pub(crate) fn quantize(img: &RgbImage, max_colours: usize, dither: bool) -> Quantized {
+    let mut counts: HashMap<[u8; 3], u32> = HashMap::new();
+    for pixel in img.pixels() {
+        *counts.entry(pixel.0).or_default() += 1;
+    }
+    let mut histogram: Vec<([u8; 3], u32)> = counts.into_iter().collect();
+    // the hash map's order differs between runs, the palette shouldn't
+    histogram.sort_unstable();
+
+    if histogram.len() <= max_colours {
+        let lookup: HashMap<[u8; 3], u8> =
+            histogram.iter().enumerate().map(|(idx, &(colour, _))| (colour, idx as u8)).collect();
+        return Quantized {
+            palette: histogram.iter().map(|&(colour, _)| colour).collect(),
+            indices: img.pixels().map(|pixel| lookup[&pixel.0]).collect(),
+        };
+    }
+
+    let palette = median_cut(&mut histogram, max_colours);
+    let indices = if dither { map_dithered(img, &palette) } else { map_nearest(img, &palette) };
+    Quantized { palette, indices }
+}
+
+fn median_cut(histogram: &mut [([u8; 3], u32)], max_colours: usize) -> Vec<[u8; 3]> {
+    let mut boxes = vec![ColourBox {
+        start: 0,
+        end: histogram.len(),
+    }];
+    while boxes.len() < max_colours {
+        // split the box spanning the widest range of any one channel
+        let widest = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, colour_box)| colour_box.end - colour_box.start > 1)
+            .map(|(idx, colour_box)| {
+                let (channel, range) = widest_channel(&histogram[colour_box.start..colour_box.end]);
+                (idx, channel, range)
+            })
+            .max_by_key(|&(_, _, range)| range);
+        let Some((idx, channel, _)) = widest else {
+            break;
+        };
+
+        let ColourBox { start, end } = boxes[idx];
+        let colours = &mut histogram[start..end];
+        colours.sort_unstable_by_key(|&(colour, _)| colour[channel]);
+        let total: u64 = colours.iter().map(|&(_, count)| count as u64).sum();
+        let mut below = 0;
+        let mut split = colours.len() / 2;
+        for (offset, &(_, count)) in colours.iter().enumerate() {
+            below += count as u64;
+            if below * 2 >= total {
+                split = offset + 1;
+                break;
+            }
+        }
+        // both halves need at least one colour
+        let split = start + split.clamp(1, colours.len() - 1);
+        boxes[idx] = ColourBox { start, end: split };
+        boxes.push(ColourBox { start: split, end });
+    }
+
+    boxes
+        .iter()
+        .map(|colour_box| {
+            let colours = &histogram[colour_box.start..colour_box.end];
+            let total: u64 = colours.iter().map(|&(_, count)| count as u64).sum();
+            [0, 1, 2].map(|c| {
+                let sum: u64 = colours.iter().map(|&(colour, count)| colour[c] as u64 * count as u64).sum();
+                ((sum + total / 2) / total) as u8
+            })
+        })
+        .collect()
+}
+
+fn widest_channel(colours: &[([u8; 3], u32)]) -> (usize, u8) {
+    (0..3)
+        .map(|c| {
+            let min = colours.iter().map(|(colour, _)| colour[c]).min().unwrap_or(0);
+            let max = colours.iter().map(|(colour, _)| colour[c]).max().unwrap_or(0);
+            (c, max - min)
+        })
+        .max_by_key(|&(_, range)| range)
+        .unwrap_or((0, 0))
+}
+
+fn nearest(palette: &[[u8; 3]], colour: [u8; 3]) -> u8 {
+    let distance = |entry: &[u8; 3]| {
+        (0..3).map(|c| (entry[c] as i32 - colour[c] as i32).pow(2)).sum::<i32>()
+    };
+    palette
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, entry)| distance(entry))
+        .map_or(0, |(idx, _)| idx as u8)
+}
+
+fn map_nearest(img: &RgbImage, palette: &[[u8; 3]]) -> Vec<u8> {
+    let mut cache: HashMap<[u8; 3], u8> = HashMap::new();
+    img.pixels()
+        .map(|pixel| *cache.entry(pixel.0).or_insert_with(|| nearest(palette, pixel.0)))
+        .collect()
+}
+
+fn map_dithered(img: &RgbImage, palette: &[[u8; 3]]) -> Vec<u8> {
+    let width = img.width() as usize;
+    let mut cache: HashMap<[u8; 3], u8> = HashMap::new();
+    let mut indices = Vec::with_capacity(img.len() / 3);
+    // errors carried into the current and the next row, one pixel of padding either side
+    let mut current = vec![[0.0_f32; 3]; width + 2];
+    let mut next = vec![[0.0_f32; 3]; width + 2];
+    for row in img.rows() {
+        for (x, pixel) in row.enumerate() {
+            let wanted = [0, 1, 2].map(|c| (pixel.0[c] as f32 + current[x + 1][c]).clamp(0.0, 255.0));
+            let rounded = wanted.map(|channel| channel.round() as u8);
+            let idx = *cache.entry(rounded).or_insert_with(|| nearest(palette, rounded));
+            indices.push(idx);
+
+            let chosen = palette[idx as usize];
+            for c in 0..3 {
+                let error = wanted[c] - chosen[c] as f32;
+                current[x + 2][c] += error * 7.0 / 16.0;
+                next[x][c] += error * 3.0 / 16.0;
+                next[x + 1][c] += error * 5.0 / 16.0;
+                next[x + 2][c] += error / 16.0;
+            }
+        }
+        std::mem::swap(&mut current, &mut next);
+        next.fill([0.0; 3]);
+    }
+    indices
+}
+
+/// The fewest bits per pixel PNG allows that can index `palette_size` colours.
+fn bit_depth(palette_size: usize) -> u8 {
+    match palette_size {
+        0..=2 => 1,
+        3..=4 => 2,
+        5..=16 => 4,
+        _ => 8,
+    }
+}
+
+/// Encodes `quantized` as an indexed PNG, packing the indices as tightly as the palette allows.
+pub(crate) fn write_indexed_png(
+    writer: impl Write,
+    width: u32,
+    height: u32,
+    quantized: &Quantized,
+) -> anyhow::Result<IndexedPng> {
+    let bits = bit_depth(quantized.palette.len());
+    let per_byte = (8 / bits) as usize;
+    let row_bytes = (width as usize).div_ceil(per_byte);
+    let mut data = vec![0_u8; row_bytes * height as usize];
+    if width > 0 {
+        for (row, indices) in data.chunks_mut(row_bytes).zip(quantized.indices.chunks(width as usize)) {
+            for (x, &idx) in indices.iter().enumerate() {
+                let shift = 8 - bits as usize * (x % per_byte + 1);
+                row[x / per_byte] |= idx << shift;
+            }
+        }
+    }
+
+    let mut encoder = png::Encoder::new(writer, width, height);
+    encoder.set_color(png::ColorType::Indexed);
+    encoder.set_depth(match bits {
+        1 => png::BitDepth::One,
+        2 => png::BitDepth::Two,
+        4 => png::BitDepth::Four,
+        _ => png::BitDepth::Eight,
+    });
+    encoder.set_palette(quantized.palette.concat());
+    encoder.set_compression(png::Compression::Best);
+    let mut png_writer = encoder.write_header()?;
+    png_writer.write_image_data(&data)?;
+    png_writer.finish()?;
+
+    Ok(IndexedPng {
+        palette_size: quantized.palette.len(),
+        bit_depth: bits,
+    })
+}
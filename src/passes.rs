@@ -0,0 +1,262 @@
+use std::io::{Cursor, Write};
+
+use image::{DynamicImage, ImageBuffer, ImageFormat, Luma, Rgb};
+
+/// The file [`crate::ModelToImage::export_passes`] packs the render passes into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PassContainer {
+    /// One uncompressed TIFF page per pass, named in its `PageName` tag. Depth and normals
+    /// keep their 32-bit float values and ids are 32-bit integers.
+    MultiTiff,
+    /// A zip with one PNG per pass, named `<pass>.png`. Depth and ids are 16-bit greyscale,
+    /// normals 8-bit colour.
+    ZipOfPngs,
+}
+
+/// The pixels of one pass, in output orientation.
+pub(crate) enum PassPixels {
+    Rgb8(Vec<u8>),
+    Grey8(Vec<u8>),
+    /// 0.0 at the back of the model to 1.0 at its front, not finite where it isn't
+    Depth(Vec<f32>),
+    /// Zero where there's no surface
+    Normal(Vec<[f32; 3]>),
+    /// Material index plus one, 0 where there's no surface
+    Id(Vec<u32>),
+}
+
+pub(crate) struct Pass {
+    pub name: &'static str,
+    pub pixels: PassPixels,
+}
+
+impl PassPixels {
+    fn to_png_image(&self, width: u32, height: u32) -> DynamicImage {
+        let idx = |x: u32, y: u32| y as usize * width as usize + x as usize;
+        match self {
+            Self::Rgb8(rgb) => DynamicImage::ImageRgb8(ImageBuffer::from_fn(width, height, |x, y| {
+                Rgb([rgb[idx(x, y) * 3], rgb[idx(x, y) * 3 + 1], rgb[idx(x, y) * 3 + 2]])
+            })),
+            Self::Grey8(grey) => DynamicImage::ImageLuma8(ImageBuffer::from_fn(width, height, |x, y| {
+                Luma([grey[idx(x, y)]])
+            })),
+            // 0 is kept for where there's no model, so the back of the model is 1
+            Self::Depth(depth) => DynamicImage::ImageLuma16(ImageBuffer::from_fn(width, height, |x, y| {
+                let z = depth[idx(x, y)];
+                Luma([if z.is_finite() { 1 + (z.clamp(0.0, 1.0) * 65534.0).round() as u16 } else { 0 }])
+            })),
+            Self::Normal(normals) => DynamicImage::ImageRgb8(ImageBuffer::from_fn(width, height, |x, y| {
+                let normal = normals[idx(x, y)];
+                if normal == [0.0; 3] {
+                    return Rgb([0, 0, 0]);
+                }
+                Rgb(normal.map(|axis| ((axis * 0.5 + 0.5).clamp(0.0, 1.0) * 255.0).round() as u8))
+            })),
+            Self::Id(ids) => DynamicImage::ImageLuma16(ImageBuffer::from_fn(width, height, |x, y| {
+                Luma([ids[idx(x, y)].min(u16::MAX as u32) as u16])
+            })),
+        }
+    }
+
+    /// Samples per pixel, bits per sample, the TIFF sample format (1 unsigned, 3 float) and
+    /// photometric interpretation (1 greyscale, 2 RGB).
+    fn tiff_layout(&self) -> (u16, u16, u16, u16) {
+        match self {
+            Self::Rgb8(_) => (3, 8, 1, 2),
+            Self::Grey8(_) => (1, 8, 1, 1),
+            Self::Depth(_) => (1, 32, 3, 1),
+            Self::Normal(_) => (3, 32, 3, 2),
+            Self::Id(_) => (1, 32, 1, 1),
+        }
+    }
+
+    fn tiff_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Rgb8(bytes) | Self::Grey8(bytes) => bytes.clone(),
+            Self::Depth(depth) => depth.iter().flat_map(|z| z.to_le_bytes()).collect(),
+            Self::Normal(normals) => normals.iter().flatten().flat_map(|axis| axis.to_le_bytes()).collect(),
+            Self::Id(ids) => ids.iter().flat_map(|id| id.to_le_bytes()).collect(),
+        }
+    }
+}
+
+pub(crate) fn write_passes(
+    writer: impl Write,
+    size: (u32, u32),
+    passes: &[Pass],
+    container: PassContainer,
+) -> anyhow::Result<()> {
+    match container {
+        PassContainer::MultiTiff => write_tiff(writer, size, passes),
+        PassContainer::ZipOfPngs => {
+            let entries = passes
+                .iter()
+                .map(|pass| {
+                    let mut png = Vec::new();
+                    pass.pixels.to_png_image(size.0, size.1).write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+                    Ok((format!("{}.png", pass.name), png))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            write_zip(writer, &entries)
+        }
+    }
+}
+
+fn too_large() -> anyhow::Error {
+    anyhow::anyhow!("The passes are too large to fit in one file")
+}
+
+/// Writes a little-endian baseline TIFF with one uncompressed strip per page. Each page's
+/// directory comes first, then the values that don't fit in it, then its pixels, so every
+/// offset is known before it's written.
+fn write_tiff(mut writer: impl Write, (width, height): (u32, u32), passes: &[Pass]) -> anyhow::Result<()> {
+    const SHORT: u16 = 3;
+    const LONG: u16 = 4;
+    const ASCII: u16 = 2;
+    const ENTRIES: u64 = 14;
+
+    writer.write_all(b"II*\0")?;
+    writer.write_all(&8_u32.to_le_bytes())?;
+    let mut offset = 8_u64;
+    for (page, pass) in passes.iter().enumerate() {
+        let (samples, bits, format, photometric) = pass.pixels.tiff_layout();
+        let data = pass.pixels.tiff_bytes();
+        let extras_start = offset + 2 + ENTRIES * 12 + 4;
+        let mut extras = Vec::new();
+        // a value is stored in the entry itself if it fits in 4 bytes, otherwise after the directory
+        let mut value = |bytes: Vec<u8>| -> anyhow::Result<[u8; 4]> {
+            if bytes.len() <= 4 {
+                let mut inline = [0; 4];
+                inline[..bytes.len()].copy_from_slice(&bytes);
+                return Ok(inline);
+            }
+            let at = u32::try_from(extras_start + extras.len() as u64).map_err(|_| too_large())?;
+            extras.extend_from_slice(&bytes);
+            extras.resize(extras.len().next_multiple_of(4), 0);
+            Ok(at.to_le_bytes())
+        };
+        let shorts = |values: &[u16]| values.iter().flat_map(|value| value.to_le_bytes()).collect::<Vec<u8>>();
+        let bits_value = value(shorts(&vec![bits; samples as usize]))?;
+        let format_value = value(shorts(&vec![format; samples as usize]))?;
+        let name = [pass.name.as_bytes(), b"\0"].concat();
+        let name_len = name.len() as u32;
+        let name_value = value(name)?;
+
+        let data_start = extras_start + extras.len() as u64;
+        let data_end = data_start + (data.len() as u64).next_multiple_of(4);
+        let next = if page + 1 < passes.len() { data_end } else { 0 };
+        let long = |value: u64| -> anyhow::Result<[u8; 4]> {
+            Ok(u32::try_from(value).map_err(|_| too_large())?.to_le_bytes())
+        };
+        let short = |value: u16| {
+            let [low, high] = value.to_le_bytes();
+            [low, high, 0, 0]
+        };
+        let page_number = shorts(&[page as u16, passes.len() as u16]);
+
+        let entries: [(u16, u16, u32, [u8; 4]); ENTRIES as usize] = [
+            // NewSubfileType: a page of a multi-page file
+            (254, LONG, 1, long(2)?),
+            (256, LONG, 1, long(width as u64)?),
+            (257, LONG, 1, long(height as u64)?),
+            (258, SHORT, samples as u32, bits_value),
+            // Compression: none
+            (259, SHORT, 1, short(1)),
+            (262, SHORT, 1, short(photometric)),
+            (273, LONG, 1, long(data_start)?),
+            (277, SHORT, 1, short(samples)),
+            (278, LONG, 1, long(height as u64)?),
+            (279, LONG, 1, long(data.len() as u64)?),
+            // PlanarConfiguration: samples interleaved
+            (284, SHORT, 1, short(1)),
+            (285, ASCII, name_len, name_value),
+            (297, SHORT, 2, page_number.try_into().unwrap_or_default()),
+            (339, SHORT, samples as u32, format_value),
+        ];
+
+        writer.write_all(&(ENTRIES as u16).to_le_bytes())?;
+        for (tag, kind, count, value) in entries {
+            writer.write_all(&tag.to_le_bytes())?;
+            writer.write_all(&kind.to_le_bytes())?;
+            writer.write_all(&count.to_le_bytes())?;
+            writer.write_all(&value)?;
+        }
+        writer.write_all(&long(next)?)?;
+        writer.write_all(&extras)?;
+        writer.write_all(&data)?;
+        writer.write_all(&vec![0; (data_end - data_start) as usize - data.len()])?;
+        offset = data_end;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes a zip of `(name, contents)` entries, stored as they are: PNGs are compressed already.
+fn write_zip(mut writer: impl Write, entries: &[(String, Vec<u8>)]) -> anyhow::Result<()> {
+    // 1980-01-01 00:00, the earliest MS-DOS date, so the same passes always give the same file
+    const DOS_TIME: u16 = 0;
+    const DOS_DATE: u16 = (1 << 5) | 1;
+
+    let mut central = Vec::new();
+    let mut offset = 0_u32;
+    for (name, contents) in entries {
+        let size = u32::try_from(contents.len()).map_err(|_| too_large())?;
+        // the fields a local header and its central directory record share, from "version needed"
+        let mut fields = Vec::new();
+        for short in [20, 0, 0, DOS_TIME, DOS_DATE] {
+            fields.extend_from_slice(&u16::to_le_bytes(short));
+        }
+        for long in [crc32(contents), size, size] {
+            fields.extend_from_slice(&long.to_le_bytes());
+        }
+        fields.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        fields.extend_from_slice(&0_u16.to_le_bytes());
+
+        writer.write_all(&0x0403_4b50_u32.to_le_bytes())?;
+        writer.write_all(&fields)?;
+        writer.write_all(name.as_bytes())?;
+        writer.write_all(contents)?;
+
+        central.extend_from_slice(&0x0201_4b50_u32.to_le_bytes());
+        // version made by, then the shared fields, then no comment, disk 0 and no attributes
+        central.extend_from_slice(&20_u16.to_le_bytes());
+        central.extend_from_slice(&fields);
+        central.extend_from_slice(&[0; 10]);
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+
+        offset = (30 + name.len() as u64 + size as u64 + offset as u64).try_into().map_err(|_| too_large())?;
+    }
+
+    let count = entries.len() as u16;
+    writer.write_all(&central)?;
+    writer.write_all(&0x0605_4b50_u32.to_le_bytes())?;
+    writer.write_all(&[0; 4])?;
+    writer.write_all(&count.to_le_bytes())?;
+    writer.write_all(&count.to_le_bytes())?;
+    writer.write_all(&(central.len() as u32).to_le_bytes())?;
+    writer.write_all(&offset.to_le_bytes())?;
+    writer.write_all(&[0; 2])?;
+    writer.flush()?;
+    Ok(())
+}
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut crc = n as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { 0xEDB8_8320 ^ (crc >> 1) } else { crc >> 1 };
+            bit += 1;
+        }
+        table[n] = crc;
+        n += 1;
+    }
+    table
+};
+
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc, &byte| CRC_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8))
+}
@@ -0,0 +1,55 @@
+use std::path::{Path, PathBuf};
+
+use crate::InvalidPath;
+
+/// Paths this long or longer need the extended-length prefix on Windows, or the C runtime
+/// assimp opens files with refuses them.
+#[cfg(windows)]
+const MAX_PATH: usize = 260;
+
+/// Turns the model path into the string assimp is handed: absolute, so errors say where the
+/// file was really looked for, and on Windows with the `\\?\` prefix when it's too long
+/// without. Fails if the file doesn't exist, or if the path can't be written as UTF-8 without
+/// changing it, since assimp would then open some other file.
+pub(crate) fn loader_path(path: &Path) -> anyhow::Result<String> {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    if !absolute.exists() {
+        return Err(anyhow::anyhow!(
+            "The model path [{}] does not exist on disk. Please ensure it exists or the path provided is correct.",
+            absolute.display()
+        ));
+    }
+    let resolved = absolute.canonicalize().unwrap_or(absolute);
+    let resolved = with_length_prefix(resolved);
+
+    match resolved.to_string_lossy() {
+        std::borrow::Cow::Borrowed(path) => Ok(path.to_string()),
+        std::borrow::Cow::Owned(_) => Err(InvalidPath {
+            path: resolved,
+            reason: "it is not valid unicode".to_string(),
+        }
+        .into()),
+    }
+}
+
+/// `canonicalize` gives verbatim (`\\?\`) paths on Windows. Assimp copes with them, but
+/// resolves relative texture paths oddly, so the prefix is only kept where it's needed.
+#[cfg(windows)]
+fn with_length_prefix(path: PathBuf) -> PathBuf {
+    let text = path.as_os_str().to_string_lossy();
+    let bare = text.strip_prefix(r"\\?\").filter(|bare| !bare.starts_with("UNC\\"));
+    match bare {
+        Some(bare) if bare.len() < MAX_PATH => PathBuf::from(bare),
+        Some(_) => path,
+        None if text.len() >= MAX_PATH => match text.strip_prefix(r"\\") {
+            Some(share) => PathBuf::from(format!(r"\\?\UNC\{}", share)),
+            None => PathBuf::from(format!(r"\\?\{}", text)),
+        },
+        None => path,
+    }
+}
+
+#[cfg(not(windows))]
+fn with_length_prefix(path: PathBuf) -> PathBuf {
+    path
+}
@@ -0,0 +1,137 @@
+use std::fmt;
+use std::time::Instant;
+
+use image::RgbImage;
+
+use crate::ModelToImage;
+
+/// [`ModelToImage::render`] taken apart into its stages, for running custom passes in between,
+/// e.g. stamping metadata into the image after it's rasterised but before the watermark goes
+/// on. Running every stage in order gives exactly what `render` does:
+///
+/// ```no_run
+/// # use std::path::PathBuf;
+/// use model_to_image::{ModelToImageBuilder, RenderPipeline};
+///
+/// let mut model = ModelToImageBuilder::new(&PathBuf::from("fish.glb")).build()?;
+/// let mut pipeline = RenderPipeline::new(&mut model);
+/// pipeline.prepare()?.rasterize()?;
+/// let frame = pipeline.framebuffer()?;
+/// for (pixel, depth) in frame.colour.pixels_mut().zip(frame.depth) {
+///     if depth.is_finite() {
+///         pixel.0[2] = 255;
+///     }
+/// }
+/// pipeline.post_process()?.finish()?;
+/// model.write_to(None)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// Stages run out of order fail with a [`StageOrderError`].
+pub struct RenderPipeline<'a> {
+    model: &'a mut ModelToImage,
+    stage: Stage,
+    started: Option<Instant>,
+}
+
+/// The last stage of a [`RenderPipeline`] that ran.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    New,
+    Prepared,
+    Rasterized,
+    PostProcessed,
+    Finished,
+}
+
+/// The image and per pixel buffers of a rasterised frame, all the right way up and indexed
+/// `x + y * width`, see [`RenderPipeline::framebuffer`].
+pub struct Framebuffer<'a> {
+    /// The rendered image, which custom stages may draw into
+    pub colour: &'a mut RgbImage,
+    /// Depth of the model, from 0.0 at its back to 1.0 at its front, negative infinity where
+    /// it doesn't cover
+    pub depth: &'a [f32],
+    /// How much of every pixel the model covers, from 0.0 to 1.0
+    pub coverage: &'a [f32],
+}
+
+/// A [`RenderPipeline`] stage was run before the one it follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageOrderError {
+    /// The stage that was asked for
+    pub requested: &'static str,
+    /// The stage the pipeline was at
+    pub current: Stage,
+}
+
+impl fmt::Display for StageOrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The {} stage can't run after the pipeline reached {:?}", self.requested, self.current)
+    }
+}
+
+impl std::error::Error for StageOrderError {}
+
+impl<'a> RenderPipeline<'a> {
+    pub fn new(model: &'a mut ModelToImage) -> Self {
+        Self { model, stage: Stage::New, started: None }
+    }
+
+    /// The last stage that ran.
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
+    /// Starts the clock on the time budget and sets up the buffers for the frame.
+    pub fn prepare(&mut self) -> anyhow::Result<&mut Self> {
+        self.advance("prepare", Stage::New, Stage::Prepared)?;
+        self.started = Some(self.model.prepare_frame());
+        Ok(self)
+    }
+
+    /// Draws the model, running every accumulation pass, and leaves the frame the right way up.
+    /// Fails when the time budget runs out under [`crate::TimeBudgetPolicy::Abort`].
+    pub fn rasterize(&mut self) -> anyhow::Result<&mut Self> {
+        self.advance("rasterize", Stage::Prepared, Stage::Rasterized)?;
+        self.model.draw_frame(self.started())?;
+        Ok(self)
+    }
+
+    /// The rasterised frame, for custom stages. Changes to the image are carried through the
+    /// rest of the pipeline.
+    pub fn framebuffer(&mut self) -> anyhow::Result<Framebuffer<'_>> {
+        if self.stage < Stage::Rasterized {
+            return Err(StageOrderError { requested: "framebuffer", current: self.stage }.into());
+        }
+        let model = &mut *self.model;
+        Ok(Framebuffer { colour: &mut model.img_buf, depth: &model.depth, coverage: &model.coverage })
+    }
+
+    /// Applies the effects, overlays and watermark set up on the builder.
+    pub fn post_process(&mut self) -> anyhow::Result<&mut Self> {
+        self.advance("post_process", Stage::Rasterized, Stage::PostProcessed)?;
+        self.model.post_process();
+        Ok(self)
+    }
+
+    /// Encodes the output and records the render time, failing under
+    /// [`crate::CoveragePolicy::Fail`] if the model covers too little of the image.
+    pub fn finish(&mut self) -> anyhow::Result<&mut Self> {
+        self.advance("finish", Stage::PostProcessed, Stage::Finished)?;
+        self.model.finish_frame(self.started())?;
+        Ok(self)
+    }
+
+    fn advance(&mut self, requested: &'static str, from: Stage, to: Stage) -> anyhow::Result<()> {
+        if self.stage != from {
+            return Err(StageOrderError { requested, current: self.stage }.into());
+        }
+        self.stage = to;
+        Ok(())
+    }
+
+    fn started(&self) -> Instant {
+        self.started.unwrap_or_else(Instant::now)
+    }
+}
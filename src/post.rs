@@ -0,0 +1,327 @@
+use image::imageops::FilterType;
+use image::{GrayImage, Luma, Rgb, RgbImage};
+
+use crate::{Bloom, Corner, DepthOfField, DropShadow, Mask, Outline, Watermark};
+
+/// Alpha blends the watermark into its corner of an already flipped image. Watermarks bigger
+/// than a quarter of the image in either direction are scaled down to fit.
+pub(crate) fn apply_watermark(img: &mut RgbImage, watermark: &Watermark) {
+    let (width, height) = img.dimensions();
+    let (max_width, max_height) = ((width / 4).max(1), (height / 4).max(1));
+
+    let mark = if watermark.image.width() > max_width || watermark.image.height() > max_height {
+        watermark.image.resize(max_width, max_height, FilterType::Triangle).to_rgba8()
+    } else {
+        watermark.image.to_rgba8()
+    };
+
+    let margin = watermark.margin_px as i64;
+    let (mark_width, mark_height) = (mark.width() as i64, mark.height() as i64);
+    let origin_x = match watermark.corner {
+        Corner::TopLeft | Corner::BottomLeft => margin,
+        Corner::TopRight | Corner::BottomRight => width as i64 - margin - mark_width,
+    };
+    let origin_y = match watermark.corner {
+        Corner::TopLeft | Corner::TopRight => margin,
+        Corner::BottomLeft | Corner::BottomRight => height as i64 - margin - mark_height,
+    };
+
+    let opacity = watermark.opacity.clamp(0.0, 1.0);
+    for (mx, my, pixel) in mark.enumerate_pixels() {
+        let x = origin_x + mx as i64;
+        let y = origin_y + my as i64;
+        if x < 0 || y < 0 || x >= width as i64 || y >= height as i64 {
+            continue;
+        }
+
+        let alpha = pixel.0[3] as f32 / 255.0 * opacity;
+        let dst = img.get_pixel_mut(x as u32, y as u32);
+        for channel in 0..3 {
+            let blended = pixel.0[channel] as f32 * alpha + dst.0[channel] as f32 * (1.0 - alpha);
+            dst.0[channel] = blended.round() as u8;
+        }
+    }
+}
+
+/// Builds the alpha channel for a [`Mask`], with one pixel of anti-aliasing along the edge.
+pub(crate) fn mask_alpha(width: u32, height: u32, mask: Mask) -> GrayImage {
+    let half = (width as f32 / 2.0, height as f32 / 2.0);
+
+    GrayImage::from_fn(width, height, |x, y| {
+        // distance from the pixel centre to the edge of the shape, negative inside
+        let p = ((x as f32 + 0.5 - half.0).abs(), (y as f32 + 0.5 - half.1).abs());
+        let distance = match mask {
+            Mask::Circle => (p.0 * p.0 + p.1 * p.1).sqrt() - half.0.min(half.1),
+            Mask::RoundedRect { radius_px } => {
+                let radius = (radius_px as f32).min(half.0).min(half.1);
+                let q = (p.0 - (half.0 - radius), p.1 - (half.1 - radius));
+                let outside = (q.0.max(0.0).powi(2) + q.1.max(0.0).powi(2)).sqrt();
+                outside + q.0.max(q.1).min(0.0) - radius
+            }
+        };
+        let coverage = (0.5 - distance).clamp(0.0, 1.0);
+        Luma([(coverage * 255.0).round() as u8])
+    })
+}
+
+/// Blurs every pixel by how far its depth is from the focal depth, as a box blur whose radius
+/// grows from nothing inside the focus range up to `max_blur_px`. `depth` is in the layout of
+/// the (flipped) image, with negative infinity where nothing was drawn.
+///
+/// Each pixel gathers the average of the box around it from a summed-area table, so the cost
+/// doesn't grow with the radius. Fractional radii blend the two nearest whole ones.
+pub(crate) fn depth_of_field(img: &mut RgbImage, depth: &[f32], dof: &DepthOfField) {
+    let (width, height) = (img.width() as usize, img.height() as usize);
+    if width == 0 || height == 0 || dof.max_blur_px <= 0.0 {
+        return;
+    }
+
+    // one extra row and column of zeros, so box sums need no special case at the edges
+    let stride = width + 1;
+    let mut table = vec![[0.0_f64; 3]; stride * (height + 1)];
+    for y in 0..height {
+        let mut row = [0.0_f64; 3];
+        for x in 0..width {
+            let pixel = img.get_pixel(x as u32, y as u32).0;
+            for c in 0..3 {
+                row[c] += pixel[c] as f64;
+                table[(y + 1) * stride + x + 1][c] = table[y * stride + x + 1][c] + row[c];
+            }
+        }
+    }
+    let box_average = |x: usize, y: usize, radius: usize| {
+        let (x0, y0) = (x.saturating_sub(radius), y.saturating_sub(radius));
+        let (x1, y1) = ((x + radius + 1).min(width), (y + radius + 1).min(height));
+        let count = ((x1 - x0) * (y1 - y0)) as f64;
+        [0, 1, 2].map(|c| {
+            (table[y1 * stride + x1][c] - table[y0 * stride + x1][c] - table[y1 * stride + x0][c]
+                + table[y0 * stride + x0][c])
+                / count
+        })
+    };
+
+    let blurred = RgbImage::from_fn(width as u32, height as u32, |x, y| {
+        let z = depth[y as usize * width + x as usize];
+        let radius = if z.is_finite() {
+            ((z - dof.focus_depth).abs() / dof.range).min(1.0) * dof.max_blur_px
+        } else if dof.blur_background {
+            dof.max_blur_px
+        } else {
+            0.0
+        };
+        if radius < 0.5 {
+            return *img.get_pixel(x, y);
+        }
+
+        let (lower, t) = (radius.floor() as usize, radius.fract() as f64);
+        let near = box_average(x as usize, y as usize, lower);
+        let far = box_average(x as usize, y as usize, lower + 1);
+        Rgb([0, 1, 2].map(|c| (near[c] * (1.0 - t) + far[c] * t).round() as u8))
+    });
+    *img = blurred;
+}
+
+/// Adds a glow around the pixels of the model brighter than the bloom threshold. `hdr` holds
+/// the unclipped colour of every pixel and `depth` says which of them are the model, both in
+/// the layout of the (flipped) image.
+///
+/// Only the part of each pixel's colour above the threshold glows, so the glow fades in as a
+/// pixel gets brighter instead of switching on. The Gaussian is split into a horizontal and a
+/// vertical pass, with nothing but darkness outside the image.
+pub(crate) fn bloom(img: &mut RgbImage, hdr: &[[f32; 3]], depth: &[f32], bloom: &Bloom) {
+    let (width, height) = (img.width() as usize, img.height() as usize);
+    if width == 0 || height == 0 || bloom.strength <= 0.0 {
+        return;
+    }
+
+    let bright: Vec<[f32; 3]> = hdr
+        .iter()
+        .zip(depth)
+        .map(|(colour, z)| {
+            let luminance = (0.2126 * colour[0] + 0.7152 * colour[1] + 0.0722 * colour[2]) / 255.0;
+            if !z.is_finite() || luminance <= bloom.threshold {
+                return [0.0; 3];
+            }
+            colour.map(|channel| channel * (luminance - bloom.threshold) / luminance)
+        })
+        .collect();
+    if bright.iter().all(|colour| *colour == [0.0; 3]) {
+        return;
+    }
+
+    // the radius covers two standard deviations, past which the weights hardly add anything
+    let sigma = (bloom.radius_px / 2.0).max(0.5);
+    let reach = bloom.radius_px.ceil() as isize;
+    let weights: Vec<f32> = (-reach..=reach)
+        .map(|offset| (-(offset * offset) as f32 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let total: f32 = weights.iter().sum();
+    let weights: Vec<f32> = weights.into_iter().map(|weight| weight / total).collect();
+
+    let blur = |source: &[[f32; 3]], step: (usize, usize)| {
+        let mut blurred = vec![[0.0_f32; 3]; source.len()];
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = [0.0_f32; 3];
+                for (tap, weight) in weights.iter().enumerate() {
+                    let offset = tap as isize - reach;
+                    let (sx, sy) = (x as isize + offset * step.0 as isize, y as isize + offset * step.1 as isize);
+                    if sx < 0 || sy < 0 || sx >= width as isize || sy >= height as isize {
+                        continue;
+                    }
+                    let colour = source[sy as usize * width + sx as usize];
+                    for c in 0..3 {
+                        sum[c] += colour[c] * weight;
+                    }
+                }
+                blurred[y * width + x] = sum;
+            }
+        }
+        blurred
+    };
+    let glow = blur(&blur(&bright, (1, 0)), (0, 1));
+
+    for (pixel, glow) in img.pixels_mut().zip(&glow) {
+        pixel.0 = [0, 1, 2].map(|c| (pixel.0[c] as f32 + glow[c] * bloom.strength).clamp(0.0, 255.0).round() as u8);
+    }
+}
+
+/// Darkens the background under the model's silhouette, shifted by the shadow's offset and
+/// softened with a box blur. `depth` says which pixels are the model, in the layout of the
+/// (flipped) image; those are left alone.
+pub(crate) fn drop_shadow(img: &mut RgbImage, depth: &[f32], shadow: &DropShadow) {
+    let (width, height) = (img.width() as i64, img.height() as i64);
+    let covered =
+        |x: i64, y: i64| x >= 0 && y >= 0 && x < width && y < height && depth[(y * width + x) as usize].is_finite();
+    let mut mask: Vec<f32> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| if covered(x - shadow.offset_px.0 as i64, y - shadow.offset_px.1 as i64) { 1.0 } else { 0.0 })
+        .collect();
+
+    let radius = shadow.blur_px.round() as i64;
+    if radius > 0 {
+        for (step_x, step_y) in [(1, 0), (0, 1)] {
+            let source = mask.clone();
+            for y in 0..height {
+                for x in 0..width {
+                    let mut sum = 0.0;
+                    for offset in -radius..=radius {
+                        let (sx, sy) = (x + offset * step_x, y + offset * step_y);
+                        if sx >= 0 && sy >= 0 && sx < width && sy < height {
+                            sum += source[(sy * width + sx) as usize];
+                        }
+                    }
+                    mask[(y * width + x) as usize] = sum / (2 * radius + 1) as f32;
+                }
+            }
+        }
+    }
+
+    for (idx, pixel) in img.pixels_mut().enumerate() {
+        if depth[idx].is_finite() || mask[idx] <= 0.0 {
+            continue;
+        }
+        let keep = 1.0 - shadow.opacity * mask[idx];
+        pixel.0 = pixel.0.map(|channel| (channel as f32 * keep).round() as u8);
+    }
+}
+
+/// Paints the background pixels within the outline's width of the model in its colour, so
+/// the line sits just outside the silhouette.
+pub(crate) fn outline(img: &mut RgbImage, depth: &[f32], outline: &Outline) {
+    let (width, height) = (img.width() as i64, img.height() as i64);
+    let reach = outline.width_px.max(1) as i64;
+    let colour: Rgb<u8> = outline.colour.into();
+
+    for y in 0..height {
+        for x in 0..width {
+            if depth[(y * width + x) as usize].is_finite() {
+                continue;
+            }
+            let near_model = (-reach..=reach).any(|dy| {
+                (-reach..=reach).any(|dx| {
+                    let (nx, ny) = (x + dx, y + dy);
+                    dx * dx + dy * dy <= reach * reach
+                        && nx >= 0
+                        && ny >= 0
+                        && nx < width
+                        && ny < height
+                        && depth[(ny * width + nx) as usize].is_finite()
+                })
+            });
+            if near_model {
+                img.put_pixel(x as u32, y as u32, colour);
+            }
+        }
+    }
+}
+
+/// Under this contrast ratio between the model and the background, the model is hard to make
+/// out. 3:1 is the WCAG minimum for large text.
+const MIN_BACKGROUND_CONTRAST: f32 = 3.0;
+
+/// A darker or lighter shade of the background for the model to stand out against, or `None`
+/// if it already does. Compares the average colours of the model and of the backdrop, the
+/// model's weighted by how much of each pixel it covers.
+pub(crate) fn contrasting_background(img: &RgbImage, coverage: &[f32], backdrop: &RgbImage) -> Option<Rgb<u8>> {
+    let mut model = [0.0; 3];
+    let mut weight = 0.0;
+    for (pixel, &covered) in img.pixels().zip(coverage) {
+        if covered > 0.0 {
+            for (sum, channel) in model.iter_mut().zip(pixel.0) {
+                *sum += channel as f32 * covered;
+            }
+            weight += covered;
+        }
+    }
+    if weight <= 0.0 {
+        return None;
+    }
+    let model = model.map(|sum| sum / weight);
+
+    let pixel_count = backdrop.pixels().len().max(1) as f32;
+    let mut background = [0.0; 3];
+    for pixel in backdrop.pixels() {
+        for (sum, channel) in background.iter_mut().zip(pixel.0) {
+            *sum += channel as f32 / pixel_count;
+        }
+    }
+
+    let model = relative_luminance(model);
+    if contrast_ratio(model, relative_luminance(background)) >= MIN_BACKGROUND_CONTRAST {
+        return None;
+    }
+    let darker = background.map(|channel| channel * 0.2);
+    let lighter = background.map(|channel| channel + (255.0 - channel) * 0.8);
+    let contrast = |colour: [f32; 3]| contrast_ratio(model, relative_luminance(colour));
+    let shade = if contrast(darker) >= contrast(lighter) { darker } else { lighter };
+    Some(Rgb(shade.map(|channel| channel.round().clamp(0.0, 255.0) as u8)))
+}
+
+/// Swaps `backdrop` for `replacement` wherever the model doesn't fully cover a pixel, keeping
+/// the model's share of the edge pixels.
+pub(crate) fn replace_background(img: &mut RgbImage, coverage: &[f32], backdrop: &RgbImage, replacement: Rgb<u8>) {
+    for ((pixel, &covered), old) in img.pixels_mut().zip(coverage).zip(backdrop.pixels()) {
+        let uncovered = 1.0 - covered.clamp(0.0, 1.0);
+        if uncovered <= 0.0 {
+            continue;
+        }
+        for ((channel, new), old) in pixel.0.iter_mut().zip(replacement.0).zip(old.0) {
+            let shift = (new as f32 - old as f32) * uncovered;
+            *channel = (*channel as f32 + shift).round().clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+/// WCAG relative luminance of an sRGB colour given as 0..255 per channel.
+fn relative_luminance(rgb: [f32; 3]) -> f32 {
+    let linear = rgb.map(|channel| {
+        let channel = channel / 255.0;
+        if channel <= 0.04045 { channel / 12.92 } else { ((channel + 0.055) / 1.055).powf(2.4) }
+    });
+    0.2126 * linear[0] + 0.7152 * linear[1] + 0.0722 * linear[2]
+}
+
+fn contrast_ratio(a: f32, b: f32) -> f32 {
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
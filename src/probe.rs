@@ -0,0 +1,68 @@
+use std::path::Path;
+
+use russimp_ng::material::DataContent;
+
+use crate::{Aabb, load_scene, scene_graph};
+
+/// Axis aligned bounding box of a model, in the model's own units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+impl Bounds {
+    /// Width, height and depth of the box.
+    pub fn size(&self) -> [f32; 3] {
+        [
+            self.max[0] - self.min[0],
+            self.max[1] - self.min[1],
+            self.max[2] - self.min[2],
+        ]
+    }
+}
+
+impl From<Aabb> for Bounds {
+    fn from(value: Aabb) -> Self {
+        Self {
+            min: value.min.into(),
+            max: value.max.into(),
+        }
+    }
+}
+
+/// Quick facts about a model, gathered by [`probe`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelProbe {
+    pub bounds: Bounds,
+    pub mesh_count: usize,
+    pub vertex_count: usize,
+    /// Number of faces as stored in the file, before any triangulation
+    pub face_count: usize,
+    /// Whether any material carries its texture inside the model file
+    pub has_embedded_textures: bool,
+}
+
+/// Loads just enough of a model to report its bounds and size, without triangulating it,
+/// decoding textures or building a [`crate::ModelToImage`]. Useful for validating uploads and
+/// for framing batches of models, see [`crate::compute_shared_framing`].
+pub fn probe(path: &Path) -> anyhow::Result<ModelProbe> {
+    let mut scene = load_scene(path, Vec::new(), &[])?;
+    // placed the same way as for rendering, so the bounds match what gets framed
+    scene_graph::apply_node_transforms(&mut scene, &mut Vec::new());
+
+    let has_embedded_textures = scene.materials.iter().any(|material| {
+        material.textures.values().any(|texture| match &texture.borrow().data {
+            DataContent::Bytes(bytes) => !bytes.is_empty(),
+            DataContent::Texel(texels) => !texels.is_empty(),
+        })
+    });
+
+    Ok(ModelProbe {
+        bounds: Aabb::of_points(scene.meshes.iter().flat_map(|mesh| mesh.vertices.iter().map(|v| [v.x, v.y, v.z]))).into(),
+        mesh_count: scene.meshes.len(),
+        vertex_count: scene.meshes.iter().map(|mesh| mesh.vertices.len()).sum(),
+        face_count: scene.meshes.iter().map(|mesh| mesh.faces.len()).sum(),
+        has_embedded_textures,
+    })
+}
@@ -0,0 +1,54 @@
+use crate::Axis;
+use crate::utils::Colour;
+
+/// A linear colour gradient laid along one axis of the model, e.g. for hypsometric tinting of
+/// terrain. Each stop is a position between 0.0 (the model's minimum along the axis) and 1.0
+/// (its maximum) paired with the colour at that point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColourRamp {
+    pub axis: Axis,
+    pub stops: Vec<(f32, Colour)>,
+}
+
+impl ColourRamp {
+    /// Checks that the ramp can actually be sampled.
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        if self.stops.is_empty() {
+            return Err(anyhow::anyhow!("The colour ramp needs at least one stop"));
+        }
+        for (position, _) in &self.stops {
+            if !position.is_finite() || !(0.0..=1.0).contains(position) {
+                return Err(anyhow::anyhow!(
+                    "The colour ramp stop at [{}] is outside of the 0.0 to 1.0 range",
+                    position
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Colour of the ramp at `t` (0.0 to 1.0), as floats in the 0 to 255 range.
+    pub(crate) fn sample(&self, t: f32) -> [f32; 3] {
+        let t = t.clamp(0.0, 1.0);
+        let to_floats = |colour: Colour| {
+            let rgb: [u8; 3] = colour.into();
+            [rgb[0] as f32, rgb[1] as f32, rgb[2] as f32]
+        };
+
+        match self.stops.iter().position(|(position, _)| *position >= t) {
+            Some(0) => to_floats(self.stops[0].1),
+            Some(idx) => {
+                let (start, from) = self.stops[idx - 1];
+                let (end, to) = self.stops[idx];
+                let amount = if end > start { (t - start) / (end - start) } else { 1.0 };
+                let (from, to) = (to_floats(from), to_floats(to));
+                [
+                    from[0] + (to[0] - from[0]) * amount,
+                    from[1] + (to[1] - from[1]) * amount,
+                    from[2] + (to[2] - from[2]) * amount,
+                ]
+            }
+            None => to_floats(self.stops[self.stops.len() - 1].1),
+        }
+    }
+}
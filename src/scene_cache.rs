@@ -0,0 +1,235 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use bincode::{Decode, Encode};
+use image::{DynamicImage, RgbImage, RgbaImage};
+use nalgebra::{Matrix4, Vector3};
+
+use crate::mesh_data::SceneData;
+use crate::scene_graph::Joint;
+use crate::{BoneWeights, MeshData, ModelToImageBuilder, UpAxis, cache_key};
+
+/// Written at the start of every entry. The version is bumped whenever the layout below
+/// changes, so entries written by another version are regenerated instead of misread.
+const MAGIC: &[u8; 8] = b"mti-scn\0";
+const FORMAT_VERSION: u32 = 3;
+
+#[derive(Encode, Decode)]
+struct CachedScene {
+    meshes: Vec<CachedMesh>,
+    material_names: Vec<String>,
+    emissive: Vec<[f32; 3]>,
+    double_sided: Vec<bool>,
+    textures: Vec<Option<CachedTexture>>,
+    texture_downsampling: Vec<f32>,
+    deforming: Vec<bool>,
+    nodes: Vec<(String, [f32; 16])>,
+    skeleton: Vec<([f32; 3], Option<u64>)>,
+    up_axis: Option<u8>,
+    warnings: Vec<String>,
+}
+
+#[derive(Encode, Decode)]
+struct CachedMesh {
+    name: String,
+    positions: Vec<[f32; 3]>,
+    triangles: Vec<[u32; 3]>,
+    lines: Vec<[u32; 2]>,
+    normals: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    colours: Vec<[f32; 4]>,
+    material: u64,
+    bones: Vec<(String, Vec<(u32, f32)>)>,
+}
+
+/// A texture as 8-bit pixels, with an alpha channel only if the original had one.
+#[derive(Encode, Decode)]
+struct CachedTexture {
+    width: u32,
+    height: u32,
+    alpha: bool,
+    pixels: Vec<u8>,
+}
+
+const UP_AXES: [UpAxis; 4] = [UpAxis::Y, UpAxis::Z, UpAxis::NegY, UpAxis::NegZ];
+
+/// See [`ModelToImageBuilder::with_scene_cache_dir`]. Returns the scene from the cache if
+/// there's a readable entry for the model, otherwise imports it with `import` and stores it for
+/// next time. The flag is whether the cache was hit.
+pub(crate) fn load_or_import(
+    dir: &Path,
+    builder: &ModelToImageBuilder,
+    import: impl FnOnce() -> anyhow::Result<SceneData>,
+) -> anyhow::Result<(SceneData, bool)> {
+    let path = entry_path(dir, builder)?;
+    if let Some(scene) = read(&path) {
+        return Ok((scene, true));
+    }
+
+    let mut scene = import()?;
+    if let Err(err) = write(&path, &scene) {
+        scene.warnings.push(format!("Could not write the scene cache entry [{}]: {}", path.display(), err));
+    }
+    Ok((scene, false))
+}
+
+fn entry_path(dir: &Path, builder: &ModelToImageBuilder) -> anyhow::Result<PathBuf> {
+    Ok(dir.join(format!("{:016x}.scene", cache_key::scene_key(builder)?)))
+}
+
+/// Reads an entry, `None` if it's missing, corrupt or from another version of the format.
+fn read(path: &Path) -> Option<SceneData> {
+    let bytes = fs::read(path).ok()?;
+    let body = bytes.strip_prefix(MAGIC.as_slice())?;
+    let (version, body) = body.split_first_chunk::<4>()?;
+    if u32::from_le_bytes(*version) != FORMAT_VERSION {
+        return None;
+    }
+    let (cached, read): (CachedScene, usize) = bincode::decode_from_slice(body, bincode::config::standard()).ok()?;
+    if read != body.len() {
+        return None;
+    }
+    from_cached(cached)
+}
+
+/// Writes an entry through a temporary file, so a build reading the same entry at the same time
+/// never sees half of one.
+fn write(path: &Path, scene: &SceneData) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend(FORMAT_VERSION.to_le_bytes());
+    bytes.extend(bincode::encode_to_vec(to_cached(scene), bincode::config::standard())?);
+
+    let temporary = path.with_extension(format!("scene.{}.tmp", std::process::id()));
+    fs::write(&temporary, bytes)?;
+    fs::rename(&temporary, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temporary);
+    })?;
+    Ok(())
+}
+
+fn to_cached(scene: &SceneData) -> CachedScene {
+    CachedScene {
+        meshes: scene
+            .meshes
+            .iter()
+            .map(|mesh| CachedMesh {
+                name: mesh.name.clone(),
+                positions: mesh.positions.clone(),
+                triangles: mesh.triangles.clone(),
+                lines: mesh.lines.clone(),
+                normals: mesh.normals.clone(),
+                uvs: mesh.uvs.clone(),
+                colours: mesh.colours.clone(),
+                material: mesh.material as u64,
+                bones: mesh.bones.iter().map(|bone| (bone.name.clone(), bone.weights.clone())).collect(),
+            })
+            .collect(),
+        material_names: scene.material_names.clone(),
+        emissive: scene.emissive.clone(),
+        double_sided: scene.double_sided.clone(),
+        textures: scene
+            .textures
+            .iter()
+            .map(|texture| {
+                let texture = texture.as_ref()?;
+                let alpha = texture.color().has_alpha();
+                Some(CachedTexture {
+                    width: texture.width(),
+                    height: texture.height(),
+                    alpha,
+                    pixels: if alpha { texture.to_rgba8().into_raw() } else { texture.to_rgb8().into_raw() },
+                })
+            })
+            .collect(),
+        texture_downsampling: scene.texture_downsampling.clone(),
+        deforming: scene.deforming.clone(),
+        nodes: scene
+            .nodes
+            .iter()
+            .map(|(name, transform)| {
+                let mut values = [0.0; 16];
+                values.copy_from_slice(transform.as_slice());
+                (name.clone(), values)
+            })
+            .collect(),
+        skeleton: scene
+            .skeleton
+            .iter()
+            .map(|joint| (joint.position.into(), joint.parent.map(|parent| parent as u64)))
+            .collect(),
+        up_axis: scene
+            .up_axis
+            .map(|up_axis| UP_AXES.iter().position(|&axis| axis == up_axis).unwrap_or(0) as u8),
+        warnings: scene.warnings.clone(),
+    }
+}
+
+/// `None` if the entry doesn't make sense, e.g. a texture with the wrong number of pixels.
+fn from_cached(cached: CachedScene) -> Option<SceneData> {
+    let textures = cached
+        .textures
+        .into_iter()
+        .map(|texture| {
+            let Some(texture) = texture else {
+                return Some(None);
+            };
+            let image = if texture.alpha {
+                DynamicImage::ImageRgba8(RgbaImage::from_raw(texture.width, texture.height, texture.pixels)?)
+            } else {
+                DynamicImage::ImageRgb8(RgbImage::from_raw(texture.width, texture.height, texture.pixels)?)
+            };
+            Some(Some(Arc::new(image)))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let up_axis = match cached.up_axis {
+        Some(idx) => Some(*UP_AXES.get(idx as usize)?),
+        None => None,
+    };
+
+    Some(SceneData {
+        meshes: cached
+            .meshes
+            .into_iter()
+            .map(|mesh| MeshData {
+                name: mesh.name,
+                positions: mesh.positions,
+                triangles: mesh.triangles,
+                lines: mesh.lines,
+                normals: mesh.normals,
+                uvs: mesh.uvs,
+                colours: mesh.colours,
+                material: mesh.material as usize,
+                bones: mesh
+                    .bones
+                    .into_iter()
+                    .map(|(name, weights)| BoneWeights { name, weights })
+                    .collect(),
+            })
+            .collect(),
+        material_names: cached.material_names,
+        emissive: cached.emissive,
+        double_sided: cached.double_sided,
+        textures,
+        texture_downsampling: cached.texture_downsampling,
+        deforming: cached.deforming,
+        nodes: cached
+            .nodes
+            .into_iter()
+            .map(|(name, values)| (name, Matrix4::from_column_slice(&values)))
+            .collect(),
+        skeleton: cached
+            .skeleton
+            .into_iter()
+            .map(|(position, parent)| Joint {
+                position: Vector3::from(position),
+                parent: parent.map(|parent| parent as usize),
+            })
+            .collect(),
+        up_axis,
+        warnings: cached.warnings,
+    })
+}
@@ -0,0 +1,182 @@
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use nalgebra::{Matrix4, Point3, Vector3};
+use russimp_ng::metadata::MetadataType;
+use russimp_ng::node::Node;
+use russimp_ng::scene::Scene;
+use russimp_ng::Matrix4x4;
+
+use crate::mesh_label;
+
+/// Converts assimp's row-major matrix into nalgebra's.
+fn to_matrix(m: &Matrix4x4) -> Matrix4<f32> {
+    Matrix4::new(
+        m.a1, m.a2, m.a3, m.a4, //
+        m.b1, m.b2, m.b3, m.b4, //
+        m.c1, m.c2, m.c3, m.c4, //
+        m.d1, m.d2, m.d3, m.d4,
+    )
+}
+
+/// How many metres one unit of the model is, from the `UnitScaleFactor` (centimetres per unit)
+/// that assimp reads from FBX files. Other formats don't declare their units, so they're taken
+/// as they are.
+pub(crate) fn unit_scale(scene: &Scene) -> Option<f32> {
+    let metadata = scene.metadata.as_ref()?;
+    let centimetres = metadata
+        .keys
+        .iter()
+        .position(|key| key == "UnitScaleFactor")
+        .and_then(|idx| metadata.values.get(idx))
+        .and_then(|entry| match &entry.data {
+            Ok(MetadataType::Float(value)) => Some(*value),
+            Ok(MetadataType::Double(value)) => Some(*value as f32),
+            _ => None,
+        })?;
+    (centimetres.is_finite() && centimetres > 0.0).then_some(centimetres / 100.0)
+}
+
+/// The transform above the root node, which converts the model into metres when it declares
+/// its units, see [`unit_scale`].
+fn root_transform(scene: &Scene) -> Matrix4<f32> {
+    unit_scale(scene).map_or_else(Matrix4::identity, Matrix4::new_scaling)
+}
+
+/// Calls `visit` with every node in the hierarchy below (and including) `node`, along with the
+/// node's transform into world space. Stops early when `visit` returns something.
+fn walk<T>(node: &Rc<Node>, parent: &Matrix4<f32>, visit: &mut impl FnMut(&Node, &Matrix4<f32>) -> Option<T>) -> Option<T> {
+    let world = parent * to_matrix(&node.transformation);
+    if let Some(found) = visit(node, &world) {
+        return Some(found);
+    }
+    node.children.borrow().iter().find_map(|child| walk(child, &world, visit))
+}
+
+/// Moves every mesh's vertices from its own space into world space, using the transforms of
+/// the node hierarchy, so models built from positioned parts render assembled. The hierarchy is
+/// kept as it is for [`world_transforms`]. Models that declare their units are converted into
+/// metres on the way.
+///
+/// A mesh can only have one set of vertices, so a mesh that several nodes place (instancing)
+/// is drawn at the first of them, with a warning.
+pub(crate) fn apply_node_transforms(scene: &mut Scene, warnings: &mut Vec<String>) {
+    let Some(root) = scene.root.clone() else {
+        return;
+    };
+
+    let root_transform = root_transform(scene);
+    let mut placements: Vec<Option<Matrix4<f32>>> = vec![None; scene.meshes.len()];
+    let mut instanced: Vec<usize> = Vec::new();
+    walk(&root, &root_transform, &mut |node, world| {
+        for &mesh_idx in &node.meshes {
+            match placements.get_mut(mesh_idx as usize) {
+                Some(slot @ None) => *slot = Some(*world),
+                Some(Some(_)) => instanced.push(mesh_idx as usize),
+                None => {}
+            }
+        }
+        None::<()>
+    });
+
+    for (mesh, placement) in scene.meshes.iter_mut().zip(placements) {
+        // meshes no node places still take the conversion into metres
+        let world = placement.unwrap_or(root_transform);
+        if world == Matrix4::identity() {
+            continue;
+        }
+        for vertex in &mut mesh.vertices {
+            let p = world.transform_point(&Point3::new(vertex.x, vertex.y, vertex.z));
+            (vertex.x, vertex.y, vertex.z) = (p.x, p.y, p.z);
+        }
+        // normals take the inverse transpose, so they stay square to faces a node stretches
+        let linear = world.fixed_view::<3, 3>(0, 0).into_owned();
+        if let Some(normal_matrix) = linear.try_inverse().map(|inverse| inverse.transpose()) {
+            for normal in &mut mesh.normals {
+                let turned = normal_matrix * Vector3::new(normal.x, normal.y, normal.z);
+                if let Some(n) = turned.try_normalize(f32::EPSILON) {
+                    (normal.x, normal.y, normal.z) = (n.x, n.y, n.z);
+                }
+            }
+        }
+        // a mirroring transform turns the faces inside out, so swap the winding back
+        if world.fixed_view::<3, 3>(0, 0).determinant() < 0.0 {
+            for face in &mut mesh.faces {
+                face.0.reverse();
+            }
+        }
+    }
+
+    instanced.sort_unstable();
+    instanced.dedup();
+    for mesh_idx in instanced {
+        warnings.push(format!(
+            "Mesh {} is placed by several nodes, only the first placement is drawn",
+            mesh_label(mesh_idx, &scene.meshes[mesh_idx].name)
+        ));
+    }
+}
+
+/// The name of every node with its transform into world space, parents before their children.
+pub(crate) fn world_transforms(scene: &Scene) -> Vec<(String, Matrix4<f32>)> {
+    let mut nodes = Vec::new();
+    if let Some(root) = &scene.root {
+        walk(root, &root_transform(scene), &mut |node, world| {
+            nodes.push((node.name.clone(), *world));
+            None::<()>
+        });
+    }
+    nodes
+}
+
+/// A joint of the model's skeleton, see [`crate::Overlay::Skeleton`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Joint {
+    pub position: Vector3<f32>,
+    /// The joint the bone ending here starts from, as an index into the same list. `None` for
+    /// the roots of the skeleton, which have no bone leading to them
+    pub parent: Option<usize>,
+}
+
+/// The skeleton of the model, parents before their children: a joint for every node a mesh's
+/// bone hangs off, and for the nodes right below them, which mark where the last bone of each
+/// chain ends.
+pub(crate) fn skeleton(scene: &Scene) -> Vec<Joint> {
+    let bones: HashSet<&str> = scene
+        .meshes
+        .iter()
+        .flat_map(|mesh| &mesh.bones)
+        .map(|bone| bone.name.as_str())
+        .collect();
+    let mut joints = Vec::new();
+    if let (Some(root), false) = (&scene.root, bones.is_empty()) {
+        add_joints(root, &root_transform(scene), None, &bones, &mut joints);
+    }
+    joints
+}
+
+/// Adds the joints at and below `node`, where `parent_bone` is the joint of the parent node if
+/// that is a bone.
+fn add_joints(
+    node: &Rc<Node>,
+    parent: &Matrix4<f32>,
+    parent_bone: Option<usize>,
+    bones: &HashSet<&str>,
+    joints: &mut Vec<Joint>,
+) {
+    let world = parent * to_matrix(&node.transformation);
+    let is_bone = bones.contains(node.name.as_str());
+    let mut joint = None;
+    if is_bone || parent_bone.is_some() {
+        joints.push(Joint {
+            position: world.transform_point(&Point3::origin()).coords,
+            parent: parent_bone,
+        });
+        joint = Some(joints.len() - 1);
+    }
+
+    let child_parent = if is_bone { joint } else { None };
+    for child in node.children.borrow().iter() {
+        add_joints(child, &world, child_parent, bones, joints);
+    }
+}
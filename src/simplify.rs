@@ -0,0 +1,182 @@
+use std::collections::{HashMap, HashSet};
+
+use nalgebra::Vector3;
+
+use crate::{Aabb, MeshData};
+
+/// How one mesh collapses onto a grid: which cluster every vertex falls into, and the faces
+/// that are left once they are remapped onto the clusters.
+struct MeshPlan {
+    cluster_of: Vec<u32>,
+    clusters: usize,
+    triangles: Vec<[u32; 3]>,
+    lines: Vec<[u32; 2]>,
+}
+
+/// Simplifies the scene down to at most `max_triangles` (or close to it) by vertex clustering:
+/// every vertex is snapped to a grid cell, the vertices in a cell are merged into their
+/// average, and faces that collapse or end up duplicated are dropped. Coarse, but fast and
+/// fine at thumbnail size.
+///
+/// Meshes flagged in `deforming` (those with bones or morph targets) are left alone, since their
+/// weights point at the original vertices. Returns the number of triangles before simplifying,
+/// or `None` if the scene was already under the limit.
+pub(crate) fn simplify_scene(meshes: &mut [MeshData], deforming: &[bool], max_triangles: usize) -> Option<usize> {
+    let before = triangle_count(meshes, |_| true);
+    if before <= max_triangles {
+        return None;
+    }
+
+    let bounds = Aabb::of_meshes(meshes.iter());
+    let extent = (bounds.max - bounds.min).max();
+    if !extent.is_finite() || extent <= 0.0 {
+        return None;
+    }
+
+    // a surface cut by an n x n x n grid keeps roughly 2n^2 triangles, so start there and
+    // shrink the cells until the result fits
+    let simplifiable = |mesh_idx: usize| !deforming.get(mesh_idx).copied().unwrap_or(false);
+    let fixed = before - triangle_count(meshes, simplifiable);
+    let budget = max_triangles.saturating_sub(fixed).max(1);
+    let mut resolution = (budget as f32 / 2.0).sqrt().max(1.0);
+    let mut plans = Vec::new();
+    for _ in 0..8 {
+        let cell = extent / resolution;
+        plans = meshes
+            .iter()
+            .enumerate()
+            .map(|(mesh_idx, mesh)| simplifiable(mesh_idx).then(|| plan(mesh, &bounds.min, cell)))
+            .collect::<Vec<_>>();
+        let kept: usize = plans.iter().flatten().map(|plan| plan.triangles.len()).sum();
+        if kept <= budget {
+            break;
+        }
+        resolution *= (budget as f32 / kept as f32).sqrt() * 0.95;
+    }
+
+    for (mesh, plan) in meshes.iter_mut().zip(plans) {
+        if let Some(plan) = plan {
+            apply(mesh, plan);
+        }
+    }
+    Some(before)
+}
+
+/// Triangles in the meshes `include` picks by index.
+fn triangle_count(meshes: &[MeshData], include: impl Fn(usize) -> bool) -> usize {
+    meshes
+        .iter()
+        .enumerate()
+        .filter(|(mesh_idx, _)| include(*mesh_idx))
+        .map(|(_, mesh)| mesh.triangles.len())
+        .sum()
+}
+
+fn plan(mesh: &MeshData, origin: &Vector3<f32>, cell: f32) -> MeshPlan {
+    // clusters are numbered in vertex order, so the result doesn't depend on hashing
+    let mut cluster_ids: HashMap<(i64, i64, i64), u32> = HashMap::new();
+    let cluster_of: Vec<u32> = mesh
+        .positions
+        .iter()
+        .map(|&[x, y, z]| {
+            let key = (
+                ((x - origin.x) / cell).floor() as i64,
+                ((y - origin.y) / cell).floor() as i64,
+                ((z - origin.z) / cell).floor() as i64,
+            );
+            let next = cluster_ids.len() as u32;
+            *cluster_ids.entry(key).or_insert(next)
+        })
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut triangles = Vec::new();
+    let mut lines = Vec::new();
+    for triangle in &mesh.triangles {
+        let remapped = triangle.map(|idx| cluster_of[idx as usize]);
+        let [a, b, c] = remapped;
+        if a == b || b == c || a == c {
+            continue;
+        }
+        let mut key = remapped;
+        key.sort_unstable();
+        if seen.insert(key) {
+            triangles.push(remapped);
+        }
+    }
+    for line in &mesh.lines {
+        let [a, b] = line.map(|idx| cluster_of[idx as usize]);
+        if a != b {
+            lines.push([a, b]);
+        }
+    }
+
+    MeshPlan {
+        cluster_of,
+        clusters: cluster_ids.len(),
+        triangles,
+        lines,
+    }
+}
+
+/// Averages `values` over every cluster.
+fn average<T: Copy>(
+    values: &[T],
+    plan: &MeshPlan,
+    zero: T,
+    add: impl Fn(T, T) -> T,
+    scale: impl Fn(T, f32) -> T,
+) -> Vec<T> {
+    let mut sums = vec![zero; plan.clusters];
+    let mut counts = vec![0u32; plan.clusters];
+    for (value, &cluster) in values.iter().zip(&plan.cluster_of) {
+        sums[cluster as usize] = add(sums[cluster as usize], *value);
+        counts[cluster as usize] += 1;
+    }
+    sums.into_iter()
+        .zip(counts)
+        .map(|(sum, count)| scale(sum, 1.0 / count.max(1) as f32))
+        .collect()
+}
+
+fn apply(mesh: &mut MeshData, plan: MeshPlan) {
+    fn add<const N: usize>(a: [f32; N], b: [f32; N]) -> [f32; N] {
+        std::array::from_fn(|i| a[i] + b[i])
+    }
+    fn scale<const N: usize>(a: [f32; N], s: f32) -> [f32; N] {
+        a.map(|c| c * s)
+    }
+
+    mesh.positions = average(&mesh.positions, &plan, [0.0; 3], add, scale);
+    // averaging UVs across a seam gives nonsense, but at thumbnail size nobody can tell
+    if !mesh.uvs.is_empty() {
+        mesh.uvs = average(&mesh.uvs, &plan, [0.0; 2], add, scale);
+    }
+    if !mesh.colours.is_empty() {
+        mesh.colours = average(&mesh.colours, &plan, [0.0; 4], add, scale);
+    }
+
+    mesh.triangles = plan.triangles;
+    mesh.lines = plan.lines;
+
+    // the old normals belong to vertices that no longer exist
+    if !mesh.normals.is_empty() {
+        mesh.normals = vertex_normals(&mesh.positions, &mesh.triangles);
+    }
+}
+
+/// Area weighted vertex normals, pointing out of faces that are wound counter-clockwise.
+pub(crate) fn vertex_normals(positions: &[[f32; 3]], triangles: &[[u32; 3]]) -> Vec<[f32; 3]> {
+    let mut normals = vec![Vector3::zeros(); positions.len()];
+    let position = |idx: u32| Vector3::from(positions[idx as usize]);
+    for &[a, b, c] in triangles {
+        let normal = (position(b) - position(a)).cross(&(position(c) - position(a)));
+        for idx in [a, b, c] {
+            normals[idx as usize] += normal;
+        }
+    }
+    normals
+        .into_iter()
+        .map(|n: Vector3<f32>| n.try_normalize(f32::EPSILON).unwrap_or_else(Vector3::zeros).into())
+        .collect()
+}
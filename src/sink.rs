@@ -0,0 +1,54 @@
+use std::io::{Cursor, Seek, Write};
+use std::path::PathBuf;
+
+use image::{ImageFormat, RgbImage};
+
+/// Somewhere a finished render can be written, encoded as `format`: a file, a buffer in
+/// memory, or anything else that implements it, like an upload to object storage. See
+/// [`crate::ModelToImage::write_into`].
+///
+/// ```no_run
+/// # use std::path::PathBuf;
+/// # let model = model_to_image::ModelToImageBuilder::new(&PathBuf::from("fish.glb")).build()?;
+/// let mut png = Vec::new();
+/// model.write_into(&mut png, image::ImageFormat::Png)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub trait ImageSink {
+    fn write(&mut self, image: &RgbImage, format: ImageFormat) -> anyhow::Result<()>;
+}
+
+/// Writes the file, creating missing parent directories. The format is the one given, whatever
+/// the file's extension says.
+impl ImageSink for PathBuf {
+    fn write(&mut self, image: &RgbImage, format: ImageFormat) -> anyhow::Result<()> {
+        if let Some(parent) = self.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        image.save_with_format(self.as_path(), format)?;
+        Ok(())
+    }
+}
+
+/// Replaces the contents of the vector with the encoded image.
+impl ImageSink for Vec<u8> {
+    fn write(&mut self, image: &RgbImage, format: ImageFormat) -> anyhow::Result<()> {
+        self.clear();
+        image.write_to(&mut Cursor::new(self), format)?;
+        Ok(())
+    }
+}
+
+/// Any writer that can seek, which some encoders (like TIFF) need, e.g. a [`std::fs::File`].
+/// Wrapped, as a blanket implementation would clash with the ones for [`PathBuf`] and
+/// [`Vec`]. The image is written at the writer's current position.
+#[derive(Debug)]
+pub struct SeekWriter<W: Write + Seek>(pub W);
+
+impl<W: Write + Seek> ImageSink for SeekWriter<W> {
+    fn write(&mut self, image: &RgbImage, format: ImageFormat) -> anyhow::Result<()> {
+        image.write_to(&mut self.0, format)?;
+        self.0.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+use image::{ImageBuffer, Pixel};
+
+/// Width and height of an image in pixels.
+///
+/// Converts from and to `(width, height)` tuples, so anywhere a size is taken either works:
+///
+/// ```
+/// use model_to_image::Size;
+/// assert_eq!(Size::from((320, 240)), Size::new(320, 240));
+/// assert_eq!(Size::square(64).aspect_ratio(), 1.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Size {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+
+impl Size {
+    pub const fn new(width: u32, height: u32) -> Self {
+        Self { width, height }
+    }
+
+    /// A size as wide as it is high.
+    pub const fn square(side: u32) -> Self {
+        Self::new(side, side)
+    }
+
+    /// A size `width` wide and as high as the aspect ratio (width over height) makes it,
+    /// rounded to the nearest pixel but at least one.
+    pub fn from_width_and_aspect_ratio(width: u32, aspect_ratio: f32) -> Self {
+        let height = (width as f32 / aspect_ratio).round();
+        let height = if height.is_finite() { height.clamp(1.0, u32::MAX as f32) as u32 } else { 1 };
+        Self::new(width, height)
+    }
+
+    pub const fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Width over height, infinite for an image with no height.
+    pub fn aspect_ratio(&self) -> f32 {
+        self.width as f32 / self.height as f32
+    }
+
+    pub const fn is_landscape(&self) -> bool {
+        self.width > self.height
+    }
+
+    pub const fn is_portrait(&self) -> bool {
+        self.height > self.width
+    }
+
+    /// Width times height, as the number of pixels can overflow a `u32`.
+    pub const fn pixel_count(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    /// The offset of pixel (`x`, `y`) in a buffer of this size laid out row by row, worked out
+    /// in `usize` so it can't wrap the way `x + y * width` in `u32` does.
+    pub(crate) const fn index(&self, x: u32, y: u32) -> usize {
+        x as usize + y as usize * self.width as usize
+    }
+
+    /// Whether both sides are at least one pixel, which every image needs to be rendered.
+    pub const fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+}
+
+impl From<(u32, u32)> for Size {
+    fn from((width, height): (u32, u32)) -> Self {
+        Self::new(width, height)
+    }
+}
+
+impl From<[u32; 2]> for Size {
+    fn from([width, height]: [u32; 2]) -> Self {
+        Self::new(width, height)
+    }
+}
+
+impl From<Size> for (u32, u32) {
+    fn from(size: Size) -> Self {
+        (size.width, size.height)
+    }
+}
+
+impl PartialEq<(u32, u32)> for Size {
+    fn eq(&self, other: &(u32, u32)) -> bool {
+        (self.width, self.height) == *other
+    }
+}
+
+/// A rectangle of pixels, `x` and `y` being its top left corner with y going down the image,
+/// see [`crate::ModelToImage::render_dirty_rect`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PixelRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl PixelRect {
+    pub const fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// The part of the rectangle inside an image of `size`, `None` if there is none.
+    pub(crate) fn clipped_to(&self, size: Size) -> Option<Self> {
+        let right = (self.x as u64 + self.width as u64).min(size.width as u64) as u32;
+        let bottom = (self.y as u64 + self.height as u64).min(size.height as u64) as u32;
+        (self.x < right && self.y < bottom).then(|| Self::new(self.x, self.y, right - self.x, bottom - self.y))
+    }
+
+    /// The same pixels in the image turned upside down, as it is while being rasterised.
+    pub(crate) const fn flipped(&self, size: Size) -> Self {
+        Self::new(self.x, size.height - self.y - self.height, self.width, self.height)
+    }
+}
+
+/// Gives images a [`Size`], so the output of [`crate::ModelToImage::output`] can be compared
+/// with the size it was asked for.
+pub trait ImageSize {
+    fn size(&self) -> Size;
+}
+
+impl<P: Pixel, C: std::ops::Deref<Target = [P::Subpixel]>> ImageSize for ImageBuffer<P, C> {
+    fn size(&self) -> Size {
+        Size::new(self.width(), self.height())
+    }
+}
+
+/// Pixels allowed by default, see [`crate::ModelToImageBuilder::with_max_pixels`]: 64
+/// megapixels, an 8192 x 8192 image.
+pub(crate) const DEFAULT_MAX_PIXELS: u64 = 8192 * 8192;
+
+/// An image was asked for with more pixels than [`crate::ModelToImageBuilder::with_max_pixels`]
+/// allows, or more than fit in memory at all. Returned (inside the [`anyhow::Error`]) by
+/// [`crate::ModelToImageBuilder::build`], and by renders that make bigger images than the
+/// model's own, like [`crate::ModelToImage::render_light_grid`].
+///
+/// ```no_run
+/// # use std::path::PathBuf;
+/// use model_to_image::{ImageTooLarge, ModelToImageBuilder};
+/// let result = ModelToImageBuilder::new(&PathBuf::from("fish.glb")).with_size((100_000, 100_000)).build();
+/// if let Err(err) = result {
+///     if let Some(too_large) = err.downcast_ref::<ImageTooLarge>() {
+///         eprintln!("asked for {} pixels", too_large.size.pixel_count());
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageTooLarge {
+    /// The size that was asked for, with the width and height saturated at `u32::MAX` if they
+    /// didn't even fit in that
+    pub size: Size,
+    pub max_pixels: u64,
+}
+
+impl ImageTooLarge {
+    /// Checks `width` by `height` pixels is no more than `max_pixels`, and that the largest
+    /// buffer kept per pixel (the 8-byte z-buffer) can be allocated.
+    pub(crate) fn check(width: u64, height: u64, max_pixels: u64) -> Result<Size, ImageTooLarge> {
+        let size = Size::new(width.min(u32::MAX as u64) as u32, height.min(u32::MAX as u64) as u32);
+        let fits = width <= u32::MAX as u64
+            && height <= u32::MAX as u64
+            && size.pixel_count() <= max_pixels
+            && size.pixel_count().checked_mul(8).is_some_and(|bytes| bytes <= isize::MAX as u64);
+        if fits { Ok(size) } else { Err(ImageTooLarge { size, max_pixels }) }
+    }
+}
+
+impl std::fmt::Display for ImageTooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "An image of {}x{} ({} pixels) is larger than the limit of {} pixels",
+            self.size.width,
+            self.size.height,
+            self.size.pixel_count(),
+            self.max_pixels
+        )
+    }
+}
+
+impl std::error::Error for ImageTooLarge {}
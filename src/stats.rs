@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use image::DynamicImage;
+
+use crate::{MeshData, TimeBudgetExceeded};
+
+/// What happened while loading and rendering a model, see [`crate::ModelToImage::stats`].
+///
+/// The triangle counts describe a single pass, so they don't grow with
+/// [`crate::ModelToImageBuilder::with_accumulation_samples`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RenderStats {
+    pub width: u32,
+    pub height: u32,
+    /// Time spent importing the model and loading its textures
+    pub load_time: Duration,
+    /// Whether the model was read from the scene cache instead of being imported, see
+    /// `ModelToImageBuilder::with_scene_cache_dir` (with the `scene-cache` feature)
+    pub scene_from_cache: bool,
+    /// Time spent in [`crate::ModelToImage::render`], zero until it has been called
+    pub render_time: Duration,
+    /// Number of passes rendered, more than one with accumulation
+    pub passes: u32,
+    /// Triangles in the scene after broken faces were dropped
+    pub triangles: usize,
+    /// Triangles that were rasterised
+    pub triangles_drawn: usize,
+    /// Triangles skipped because they face away from the light
+    pub triangles_culled: usize,
+    /// Triangles skipped because they have no area, in the model or once projected onto the
+    /// screen (see [`crate::ModelToImageBuilder::with_degenerate_epsilon`])
+    pub triangles_degenerate: usize,
+    /// Triangles skipped because [`crate::ModelToImageBuilder::with_clip_plane`] cut all of
+    /// them away
+    pub triangles_clipped: usize,
+    /// Triangles skipped because they landed entirely outside the image
+    pub triangles_off_screen: usize,
+    /// Triangles the scene had before [`crate::ModelToImageBuilder::with_max_triangles`]
+    /// simplified it, `None` if it didn't need to
+    pub triangles_before_simplifying: Option<usize>,
+    /// Vertices merged into others by [`crate::ModelToImageBuilder::with_weld`]
+    pub vertices_welded: usize,
+    /// Faces dropped on load because they referenced missing vertices or non-finite positions
+    pub faces_skipped: usize,
+    /// Line primitives drawn
+    pub lines: usize,
+    /// The largest dimension of the model, in its own units. Models far bigger than their
+    /// smallest details may need [`crate::ModelToImageBuilder::with_depth_precision`].
+    pub scene_extent: f32,
+    /// Set when the last render ran out of time under [`crate::TimeBudgetPolicy::Partial`],
+    /// so its image is incomplete
+    pub time_budget_exceeded: Option<TimeBudgetExceeded>,
+}
+
+impl RenderStats {
+    /// Explains a render with triangles where none of them were drawn, by what removed most
+    /// of them and what to check for it. `None` if anything was drawn.
+    pub(crate) fn nothing_drawn_warning(&self) -> Option<String> {
+        if self.triangles == 0 || self.triangles_drawn > 0 {
+            return None;
+        }
+        let reasons = [
+            (self.triangles_clipped, "were cut away by the clip planes; check with_clip_plane"),
+            (
+                self.triangles_off_screen,
+                "landed outside the image; check the center and extent of with_framing, or with_content_scale",
+            ),
+            (
+                self.triangles_culled,
+                "faced away from the light; check with_light_direction, or light the dark side with with_min_intensity",
+            ),
+            (
+                self.triangles_degenerate,
+                "had no area; check the model's scale, or lower with_degenerate_epsilon",
+            ),
+        ];
+        let (count, advice) = reasons.iter().max_by_key(|(count, _)| *count)?;
+        let mut breakdown = Vec::new();
+        for (count, name) in [
+            (self.triangles_clipped, "clipped"),
+            (self.triangles_off_screen, "off screen"),
+            (self.triangles_culled, "unlit"),
+            (self.triangles_degenerate, "degenerate"),
+        ] {
+            if count > 0 {
+                breakdown.push(format!("{} {}", count, name));
+            }
+        }
+        Some(format!(
+            "Nothing was drawn: {:.0}% of the {} triangles {} ({})",
+            *count as f32 / self.triangles as f32 * 100.0,
+            self.triangles,
+            advice,
+            breakdown.join(", ")
+        ))
+    }
+}
+
+/// How much of the image the model covers, see [`crate::ModelToImage::coverage`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CoverageStats {
+    /// Pixels the model covers in at least one render pass
+    pub covered_pixels: usize,
+    /// `covered_pixels` as a fraction of the whole image, 0.0 to 1.0
+    pub fraction: f32,
+    /// The smallest rectangle holding every covered pixel, as inclusive `(min_x, min_y, max_x,
+    /// max_y)` in output image coordinates. `None` when nothing is covered.
+    pub bounding_box: Option<(u32, u32, u32, u32)>,
+}
+
+/// The texture coordinates one mesh uses, see [`crate::ModelToImage::uv_stats`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MeshUvStats {
+    /// The mesh's name, may be empty
+    pub name: String,
+    /// The smallest U and V of any vertex, `None` without texture coordinates
+    pub min: Option<[f32; 2]>,
+    /// The largest U and V of any vertex, `None` without texture coordinates
+    pub max: Option<[f32; 2]>,
+    /// Whether any face stretches past the edge of the texture into the next repeat of it,
+    /// rather than staying inside one copy
+    pub wraps: bool,
+    /// Vertices without texture coordinates as a fraction of all of them, 0.0 to 1.0
+    pub missing_fraction: f32,
+    /// Width and height of the texture the mesh's material has, `None` if it has none
+    pub texture_size: Option<(u32, u32)>,
+    /// How many texels of the texture the faces cover, added up over every face, 0.0 without
+    /// a texture
+    pub texels: f32,
+}
+
+impl MeshUvStats {
+    pub(crate) fn of(mesh: &MeshData, texture: Option<&DynamicImage>) -> Self {
+        let uv_count = mesh.uvs.len().min(mesh.positions.len());
+        let uvs = &mesh.uvs[..uv_count];
+        let finite = || uvs.iter().filter(|uv| uv.iter().all(|c| c.is_finite()));
+        let min = finite().copied().reduce(|a, b| [a[0].min(b[0]), a[1].min(b[1])]);
+        let max = finite().copied().reduce(|a, b| [a[0].max(b[0]), a[1].max(b[1])]);
+
+        let face_uvs = mesh.triangles.iter().filter_map(|triangle| {
+            let [a, b, c] = triangle.map(|idx| uvs.get(idx as usize).copied());
+            Some([a?, b?, c?])
+        });
+        let mut wraps = false;
+        let mut area = 0.0;
+        for [a, b, c] in face_uvs {
+            // a face touching the edge of the texture from inside hasn't wrapped, only one that
+            // goes past it has
+            wraps |= (0..2).any(|axis| {
+                let low = a[axis].min(b[axis]).min(c[axis]);
+                let high = a[axis].max(b[axis]).max(c[axis]);
+                high > low.floor() + 1.0
+            });
+            area += ((b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1])).abs() / 2.0;
+        }
+
+        let texture_size = texture.map(|texture| (texture.width(), texture.height()));
+        let texels = texture_size.map_or(0.0, |(width, height)| area * width as f32 * height as f32);
+        let missing_fraction = match mesh.positions.len() {
+            0 => 0.0,
+            count => (count - uv_count) as f32 / count as f32,
+        };
+        Self {
+            name: mesh.name.clone(),
+            min,
+            max,
+            wraps,
+            missing_fraction,
+            texture_size,
+            texels: if texels.is_finite() { texels } else { 0.0 },
+        }
+    }
+}
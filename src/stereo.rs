@@ -0,0 +1,32 @@
+use image::{Rgb, RgbImage};
+
+/// How [`crate::ModelToImage::render_stereo`] puts the two eyes' views together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StereoMode {
+    /// The left eye's view on the left and the right eye's on the right, in an image twice as
+    /// wide, for parallel viewing or side-by-side 3D displays
+    #[default]
+    SideBySide,
+    /// One image with the red channel of the left eye's view and the green and blue of the
+    /// right's, for red/cyan glasses
+    Anaglyph,
+}
+
+impl StereoMode {
+    /// Puts together two views of the same size.
+    pub(crate) fn combine(self, left: &RgbImage, right: &RgbImage) -> RgbImage {
+        let (width, height) = left.dimensions();
+        match self {
+            Self::SideBySide => {
+                let mut pair = RgbImage::new(width * 2, height);
+                image::imageops::replace(&mut pair, left, 0, 0);
+                image::imageops::replace(&mut pair, right, width as i64, 0);
+                pair
+            }
+            Self::Anaglyph => RgbImage::from_fn(width, height, |x, y| {
+                let (left, right) = (left.get_pixel(x, y).0, right.get_pixel(x, y).0);
+                Rgb([left[0], right[1], right[2]])
+            }),
+        }
+    }
+}
@@ -0,0 +1,393 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageError, ImageFormat, ImageReader, Limits};
+use russimp_ng::material::{DataContent, TextureType};
+use russimp_ng::scene::Scene;
+
+/// What a decoded texture is stored under in a [`TextureCache`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CacheKey {
+    /// A texture file on disk, by its canonical path
+    Path(PathBuf),
+    /// A texture embedded in a model, by a hash of its encoded bytes
+    ContentHash(u64),
+}
+
+/// Decoded textures shared between any number of models, so a texture used by many models is
+/// only decoded once. Cloning the cache is cheap and every clone shares the same textures; it
+/// can also be shared across threads.
+///
+/// Textures are kept at the [`crate::ModelToImageBuilder::with_max_texture_size`] they were
+/// loaded at. A model with a smaller limit shrinks the copy it finds once and caches that too,
+/// one with a larger limit decodes the texture again if the cached copy was shrunk.
+///
+/// ```no_run
+/// # use std::path::PathBuf;
+/// use model_to_image::{ModelToImageBuilder, TextureCache};
+///
+/// let cache = TextureCache::new();
+/// for path in ["chair_a.glb", "chair_b.glb"] {
+///     let mut model = ModelToImageBuilder::new(&PathBuf::from(path))
+///         .with_texture_cache(cache.clone())
+///         .build()?;
+///     model.render()?;
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Clone, Default)]
+pub struct TextureCache {
+    entries: Arc<Mutex<HashMap<(CacheKey, u32), Fitted>>>,
+    decodes: Arc<AtomicUsize>,
+}
+
+/// A texture fitted to a size limit, and the factor it was downsampled by to fit.
+type Fitted = (Arc<DynamicImage>, f32);
+
+impl TextureCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of textures currently in the cache, counting a texture once for every size limit
+    /// it was loaded at.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// How many textures have been decoded into this cache so far. Every cache hit is a decode
+    /// that didn't have to happen.
+    pub fn decode_count(&self) -> usize {
+        self.decodes.load(Ordering::Relaxed)
+    }
+
+    /// Drops every cached texture. Models that already loaded them keep their copies.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<(CacheKey, u32), Fitted>> {
+        // a panic while holding the lock can't leave the map half updated, so carry on
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// The texture under `key` fitted to `max_size`, shrinking and caching a copy fitted to a
+    /// larger limit if that's all there is. Copies that were shrunk to fit a smaller limit
+    /// don't have the detail, so they're only used if they never had to be shrunk.
+    fn get(&self, key: &CacheKey, max_size: u32) -> Option<Fitted> {
+        let (source, source_factor) = {
+            let entries = self.lock();
+            if let Some(fitted) = entries.get(&(key.clone(), max_size)) {
+                return Some(fitted.clone());
+            }
+            entries
+                .iter()
+                .filter(|((cached_key, limit), (_, factor))| cached_key == key && (*limit > max_size || *factor <= 1.0))
+                .max_by_key(|((_, limit), _)| *limit)
+                .map(|(_, fitted)| fitted.clone())?
+        };
+
+        let (width, height) = source.dimensions();
+        let fitted = if width.max(height) <= max_size {
+            (source, source_factor)
+        } else {
+            let (img, factor) = fit_to_size((*source).clone(), max_size);
+            (Arc::new(img), source_factor * factor)
+        };
+        self.lock().insert((key.clone(), max_size), fitted.clone());
+        Some(fitted)
+    }
+
+    fn insert(&self, key: CacheKey, max_size: u32, fitted: Fitted) {
+        self.decodes.fetch_add(1, Ordering::Relaxed);
+        self.lock().insert((key, max_size), fitted);
+    }
+}
+
+impl fmt::Debug for TextureCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TextureCache")
+            .field("len", &self.len())
+            .field("decode_count", &self.decode_count())
+            .finish()
+    }
+}
+
+/// The most memory a single texture may take up while it is being decoded. Anything bigger is
+/// rejected before it can exhaust memory, whatever `max_size` is.
+const MAX_DECODE_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Shrinks `img` so neither side is larger than `max_size`, keeping its aspect ratio. Returns
+/// the factor it was shrunk by, 1.0 if it already fit.
+pub(crate) fn fit_to_size(img: DynamicImage, max_size: u32) -> (DynamicImage, f32) {
+    let (width, height) = img.dimensions();
+    let largest = width.max(height);
+    if largest <= max_size {
+        return (img, 1.0);
+    }
+
+    let factor = largest as f32 / max_size as f32;
+    let new_width = ((width as f32 / factor).round() as u32).clamp(1, max_size);
+    let new_height = ((height as f32 / factor).round() as u32).clamp(1, max_size);
+    (img.resize_exact(new_width, new_height, FilterType::Triangle), factor)
+}
+
+/// Loads the diffuse texture of every material in the scene, either embedded in the model or
+/// as a file next to it.
+///
+/// The returned vec is indexed by material index, so a material without a texture (or one that
+/// failed to load) gets a `None` in its slot. Failures are pushed to `warnings` instead of
+/// aborting the build. Textures already in `cache` are reused, and newly decoded ones are added
+/// to it.
+///
+/// Textures larger than `max_size` on either side are downsampled right after decoding, with a
+/// warning saying by how much. The second vec has the factor every material's texture was
+/// downsampled by, 1.0 where it wasn't.
+///
+/// Textures that can't be loaded (missing files, formats that can't be decoded, broken data)
+/// are left out and described in `errors`, for the caller to treat as warnings or as a failure.
+///
+/// With the `parallel` feature enabled the decoding is spread over scoped threads, which helps
+/// a lot on scenes with several large textures.
+pub(crate) fn load_textures(
+    scene: &Scene,
+    model_dir: &Path,
+    cache: &TextureCache,
+    max_size: u32,
+    warnings: &mut Vec<String>,
+    errors: &mut Vec<String>,
+) -> (Vec<Option<Arc<DynamicImage>>>, Vec<f32>) {
+    let mut textures: Vec<Option<Arc<DynamicImage>>> = vec![None; scene.materials.len()];
+    let mut downsampling = vec![1.0; scene.materials.len()];
+    // the textures still to decode, with every material waiting on each one
+    let mut pending: Vec<(CacheKey, Encoded, Vec<usize>)> = Vec::new();
+    let mut pending_by_key: HashMap<CacheKey, usize> = HashMap::new();
+
+    for (material_idx, material) in scene.materials.iter().enumerate() {
+        let Some(texture) = material.textures.get(&TextureType::Diffuse) else {
+            continue;
+        };
+
+        // the textures live behind an Rc<RefCell<..>>, which can't cross threads, so copy the
+        // raw bytes out first
+        let (key, bytes) = match &texture.borrow().data {
+            DataContent::Bytes(data) if !data.is_empty() => (CacheKey::ContentHash(hash_bytes(data)), Some(data.clone())),
+            _ => {
+                let filename = texture.borrow().filename.clone();
+                // "*0" style names refer to embedded textures assimp could not hand over
+                if filename.is_empty() || filename.starts_with('*') {
+                    continue;
+                }
+                match model_dir.join(&filename).canonicalize() {
+                    Ok(path) => (CacheKey::Path(path), None),
+                    Err(e) => {
+                        errors.push(format!(
+                            "Could not find texture file [{}] for material {}: {}",
+                            filename, material_idx, e
+                        ));
+                        continue;
+                    }
+                }
+            }
+        };
+
+        if let Some((img, factor)) = cache.get(&key, max_size) {
+            if factor > 1.0 {
+                warnings.push(downsampled_warning(&[material_idx], factor, max_size));
+            }
+            textures[material_idx] = Some(img);
+            downsampling[material_idx] = factor;
+            continue;
+        }
+        if let Some(&idx) = pending_by_key.get(&key) {
+            pending[idx].2.push(material_idx);
+            continue;
+        }
+
+        let bytes = match (bytes, &key) {
+            (Some(bytes), _) => bytes,
+            (None, CacheKey::Path(path)) => match std::fs::read(path) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    errors.push(format!(
+                        "Failed to read texture file [{}] for material {}: {}",
+                        path.display(),
+                        material_idx,
+                        e
+                    ));
+                    continue;
+                }
+            },
+            (None, CacheKey::ContentHash(_)) => continue,
+        };
+
+        let path = match &key {
+            CacheKey::Path(path) => Some(path.as_path()),
+            CacheKey::ContentHash(_) => None,
+        };
+        let format = match sniff_format(&bytes, path) {
+            Some(Sniffed::Unsupported(name)) => {
+                errors.push(format!("Unsupported texture format {} in material {}", name, material_idx));
+                continue;
+            }
+            Some(Sniffed::Image(format)) if !format.reading_enabled() => {
+                let name = format.extensions_str().first().copied().unwrap_or("?");
+                errors.push(match format {
+                    ImageFormat::Tga | ImageFormat::Tiff => format!(
+                        "Texture format {} in material {} needs the `{}` feature of model_to_image",
+                        name, material_idx, name
+                    ),
+                    _ => format!("Texture format {} in material {} is not enabled in this build", name, material_idx),
+                });
+                continue;
+            }
+            Some(Sniffed::Image(format)) => Some(format),
+            None => None,
+        };
+
+        pending_by_key.insert(key.clone(), pending.len());
+        pending.push((key, (bytes, format), vec![material_idx]));
+    }
+
+    let encoded: Vec<Encoded> = pending.iter_mut().map(|(_, encoded, _)| std::mem::take(encoded)).collect();
+    let decoded = decode_all(&encoded, max_size);
+
+    for ((key, _, material_indices), result) in pending.into_iter().zip(decoded) {
+        match result {
+            Ok((img, factor)) => {
+                if factor > 1.0 {
+                    warnings.push(downsampled_warning(&material_indices, factor, max_size));
+                }
+                let img = Arc::new(img);
+                cache.insert(key, max_size, (img.clone(), factor));
+                for material_idx in material_indices {
+                    textures[material_idx] = Some(img.clone());
+                    downsampling[material_idx] = factor;
+                }
+            }
+            Err(ImageError::Limits(_)) => {
+                for material_idx in material_indices {
+                    errors.push(format!(
+                        "Skipped the texture for material {}, it needs more than {} MiB to decode",
+                        material_idx,
+                        MAX_DECODE_BYTES / (1024 * 1024)
+                    ));
+                }
+            }
+            Err(e) => {
+                for material_idx in material_indices {
+                    errors.push(format!("Failed to load texture for material {}: {}", material_idx, e));
+                }
+            }
+        }
+    }
+
+    (textures, downsampling)
+}
+
+pub(crate) fn downsampled_warning(material_indices: &[usize], factor: f32, max_size: u32) -> String {
+    format!(
+        "Texture for materials {:?} was downsampled by a factor of {:.2} to fit the {} pixel limit",
+        material_indices, factor, max_size
+    )
+}
+
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// What the bytes of a texture say it is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Sniffed {
+    /// A format the `image` crate can read, if the feature for it is enabled
+    Image(ImageFormat),
+    /// A format nothing here can decode, like the GPU compressed ones
+    Unsupported(&'static str),
+}
+
+/// Works out a texture's format from the magic number at the start of its bytes, falling back
+/// to the file extension and then to the layout of a TGA header, which has no magic number.
+fn sniff_format(bytes: &[u8], path: Option<&Path>) -> Option<Sniffed> {
+    const MAGIC: &[(&[u8], Sniffed)] = &[
+        (b"\x89PNG", Sniffed::Image(ImageFormat::Png)),
+        (b"\xFF\xD8\xFF", Sniffed::Image(ImageFormat::Jpeg)),
+        (b"II*\0", Sniffed::Image(ImageFormat::Tiff)),
+        (b"MM\0*", Sniffed::Image(ImageFormat::Tiff)),
+        (b"GIF8", Sniffed::Image(ImageFormat::Gif)),
+        (b"qoif", Sniffed::Image(ImageFormat::Qoi)),
+        (b"#?RADIANCE", Sniffed::Image(ImageFormat::Hdr)),
+        (b"BM", Sniffed::Image(ImageFormat::Bmp)),
+        (b"\xABKTX 20\xBB", Sniffed::Unsupported("ktx2")),
+        (b"\xABKTX 11\xBB", Sniffed::Unsupported("ktx")),
+        (b"DDS ", Sniffed::Unsupported("dds")),
+        (b"8BPS", Sniffed::Unsupported("psd")),
+        (b"sB", Sniffed::Unsupported("basis")),
+    ];
+
+    if let Some((_, sniffed)) = MAGIC.iter().find(|(magic, _)| bytes.starts_with(magic)) {
+        return Some(*sniffed);
+    }
+    if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+        return Some(Sniffed::Image(ImageFormat::WebP));
+    }
+    if let Some(format) = path.and_then(|path| ImageFormat::from_path(path).ok()) {
+        return Some(Sniffed::Image(format));
+    }
+    // a colour map flag of 0 or 1 followed by one of the image types TGA defines
+    let tga_header = bytes.len() >= 18 && bytes[1] <= 1 && matches!(bytes[2], 1 | 2 | 3 | 9 | 10 | 11);
+    tga_header.then_some(Sniffed::Image(ImageFormat::Tga))
+}
+
+/// The encoded bytes of a texture, and its format if it was recognised.
+type Encoded = (Vec<u8>, Option<ImageFormat>);
+
+/// A decoded texture and the factor it was downsampled by.
+type Decoded = image::ImageResult<(DynamicImage, f32)>;
+
+fn decode_one((bytes, format): &Encoded, max_size: u32) -> Decoded {
+    let mut reader = ImageReader::new(Cursor::new(bytes));
+    match format {
+        Some(format) => reader.set_format(*format),
+        None => reader = reader.with_guessed_format()?,
+    }
+    let mut limits = Limits::default();
+    limits.max_alloc = Some(MAX_DECODE_BYTES);
+    reader.limits(limits);
+    Ok(fit_to_size(reader.decode()?, max_size))
+}
+
+#[cfg(not(feature = "parallel"))]
+fn decode_all(embedded: &[Encoded], max_size: u32) -> Vec<Decoded> {
+    embedded.iter().map(|encoded| decode_one(encoded, max_size)).collect()
+}
+
+#[cfg(feature = "parallel")]
+fn decode_all(embedded: &[Encoded], max_size: u32) -> Vec<Decoded> {
+    let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    // contiguous chunks keep the results in material order once they are joined back up
+    let chunk_size = embedded.len().div_ceil(threads).max(1);
+
+    std::thread::scope(|s| {
+        let handles: Vec<_> = embedded
+            .chunks(chunk_size)
+            .map(|chunk| s.spawn(move || chunk.iter().map(|encoded| decode_one(encoded, max_size)).collect::<Vec<_>>()))
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("texture decode thread panicked"))
+            .collect()
+    })
+}
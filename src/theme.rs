@@ -0,0 +1,102 @@
+use crate::utils::Colour;
+use crate::{Background, DropShadow, Outline, RimLight};
+
+/// A coordinated look for the render: what goes behind the model, and the outline, shadow
+/// and rim light that set it apart from it, see [`crate::ModelToImageBuilder::with_theme`].
+///
+/// Start from one of the built in themes and change what you need:
+///
+/// ```
+/// use model_to_image::{Background, Colour, Theme};
+/// let brand = Theme::dark().with_background(Background::Solid(Colour::from((18, 24, 48))));
+/// # let _ = brand;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub background: Background,
+    pub outline: Option<Outline>,
+    pub drop_shadow: Option<DropShadow>,
+    pub rim_light: Option<RimLight>,
+}
+
+impl Theme {
+    /// A pale grey gradient, with a dark outline and a soft shadow.
+    pub fn light() -> Self {
+        Self {
+            background: Background::Gradient {
+                top: Colour::from((245, 245, 245)),
+                bottom: Colour::from((211, 211, 211)),
+            },
+            outline: Some(Outline {
+                colour: Colour::from((40, 40, 40)),
+                width_px: 1,
+            }),
+            drop_shadow: Some(DropShadow {
+                opacity: 0.25,
+                offset_px: (4, 4),
+                blur_px: 4.0,
+            }),
+            rim_light: None,
+        }
+    }
+
+    /// A charcoal gradient, with a light outline, a deeper shadow and a cool rim light so dark
+    /// models don't disappear into it.
+    pub fn dark() -> Self {
+        Self {
+            background: Background::Gradient {
+                top: Colour::from((48, 48, 52)),
+                bottom: Colour::from((22, 22, 26)),
+            },
+            outline: Some(Outline {
+                colour: Colour::from((230, 230, 230)),
+                width_px: 1,
+            }),
+            drop_shadow: Some(DropShadow {
+                opacity: 0.5,
+                offset_px: (4, 4),
+                blur_px: 4.0,
+            }),
+            rim_light: Some(RimLight {
+                colour: Colour::from((180, 200, 255)),
+                strength: 0.6,
+                power: 2.0,
+            }),
+        }
+    }
+
+    pub fn with_background(mut self, background: Background) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// `None` for no outline.
+    pub fn with_outline(mut self, outline: Option<Outline>) -> Self {
+        self.outline = outline;
+        self
+    }
+
+    /// `None` for no shadow.
+    pub fn with_drop_shadow(mut self, drop_shadow: Option<DropShadow>) -> Self {
+        self.drop_shadow = drop_shadow;
+        self
+    }
+
+    /// `None` for no rim light.
+    pub fn with_rim_light(mut self, rim_light: Option<RimLight>) -> Self {
+        self.rim_light = rim_light;
+        self
+    }
+}
+
+impl Default for Theme {
+    /// The crate's own look: flat grey, without an outline, shadow or rim light.
+    fn default() -> Self {
+        Self {
+            background: Background::default(),
+            outline: None,
+            drop_shadow: None,
+            rim_light: None,
+        }
+    }
+}
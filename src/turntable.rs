@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use image::RgbImage;
+use nalgebra::Vector3;
+
+use crate::{Framing, MeshData, ModelToImage, utils};
+
+/// The frames of [`ModelToImage::turntable_frames`], rendered one at a time. Dropping it puts
+/// the model back the way it was.
+pub(crate) struct Turntable<'a> {
+    model: &'a mut ModelToImage,
+    /// Degrees the model is turned about its vertical axis in each frame
+    angles: Vec<f32>,
+    next: usize,
+    /// The meshes before turning, every frame is turned from these and they're put back after
+    original: Arc<Vec<MeshData>>,
+    /// The point the model turns around, in the middle of its bounding box
+    pivot: Vector3<f32>,
+    saved_framing: Option<Framing>,
+}
+
+impl<'a> Turntable<'a> {
+    pub fn new(model: &'a mut ModelToImage, angles: Vec<f32>) -> Self {
+        let original = Arc::clone(&model.meshes);
+        let bounds = model.model_bounds();
+        let pivot = bounds.center();
+        let saved_framing = model.settings.framing;
+
+        // framed around the circle the model sweeps, so it doesn't zoom in and out as it turns
+        let fixed_framing = model.settings.framing.is_some() || model.settings.world_scale.is_some();
+        if !fixed_framing && pivot.iter().all(|c| c.is_finite()) {
+            let radius = model
+                .visible_meshes()
+                .flat_map(|(_, mesh)| mesh.positions.iter())
+                .map(|&p| Vector3::from(p) - pivot)
+                .filter(|offset| offset.iter().all(|c| c.is_finite()))
+                .map(|offset| offset.x.hypot(offset.z))
+                .fold(0.0_f32, f32::max);
+            let scale_factor = model.scale_factor;
+            model.settings.framing = Some(Framing {
+                extent: [2.0 * radius * scale_factor, bounds.extent().y * scale_factor],
+                center: Some((pivot * scale_factor).into()),
+            });
+        }
+
+        Self { model, angles, next: 0, original, pivot, saved_framing }
+    }
+
+    fn restore(&mut self) {
+        self.model.meshes = Arc::clone(&self.original);
+    }
+}
+
+impl Iterator for Turntable<'_> {
+    type Item = anyhow::Result<RgbImage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let degrees = *self.angles.get(self.next)?;
+        self.next += 1;
+
+        let turn = utils::rotation_about(&Vector3::y_axis(), degrees);
+        let pivot = self.pivot;
+        // the first frame copies the meshes, as `original` still holds them
+        for (mesh, unturned) in Arc::make_mut(&mut self.model.meshes).iter_mut().zip(self.original.iter()) {
+            for (vertex, original) in mesh.positions.iter_mut().zip(&unturned.positions) {
+                *vertex = (pivot + turn * (Vector3::from(*original) - pivot)).into();
+            }
+        }
+        Some(self.model.render().map(|model| model.output().clone()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.angles.len() - self.next;
+        (left, Some(left))
+    }
+}
+
+impl Drop for Turntable<'_> {
+    fn drop(&mut self) {
+        self.restore();
+        self.model.settings.framing = self.saved_framing;
+    }
+}
@@ -1,9 +1,18 @@
-/// RGB format for colours
-#[derive(Clone, Copy, Debug)]
+use image::{Rgb, Rgba};
+use nalgebra::{Matrix3, Rotation3, Unit, Vector3};
+
+pub(crate) mod text;
+
+/// RGBA format for colours, with 8 bits per channel.
+///
+/// Colours made from three channels are fully opaque. Float conversions map `0.0..=1.0` onto
+/// `0..=255`, clamping anything outside of that range and rounding to the nearest value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Colour {
     r: u8,
     g: u8,
     b: u8,
+    a: u8,
 }
 
 impl Default for Colour {
@@ -13,30 +22,82 @@ impl Default for Colour {
     }
 }
 
+/// Converts a `0.0..=1.0` float channel to a byte, clamping and rounding.
+fn channel_from_f32(value: f32) -> u8 {
+    if value.is_nan() {
+        return 0;
+    }
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
 impl Colour {
-    #[allow(dead_code)]
-    #[deprecated(note = "Use the ::from() function instead")]
-    pub fn new_u8(r: u8, b: u8, g: u8) -> Colour {
-        Self { r, g, b }
+    /// Note: Consider using the ::into() function instead.
+    pub fn to_array(&self) -> [u8; 3] {
+        [self.r, self.g, self.b]
     }
 
-    #[allow(dead_code)]
-    #[deprecated(note = "Use the ::from() function instead")]
-    pub fn new_f32(r: f32, g: f32, b: f32) -> Colour {
-        let ir = (255.999 * r) as u8;
-        let ig = (255.999 * g) as u8;
-        let ib = (255.999 * b) as u8;
-        Self {
-            r: ir,
-            g: ig,
-            b: ib,
+    /// The alpha channel, 0 is fully transparent and 255 fully opaque.
+    pub fn alpha(&self) -> u8 {
+        self.a
+    }
+
+    /// The same colour with a different alpha channel.
+    pub fn with_alpha(self, alpha: u8) -> Self {
+        Self { a: alpha, ..self }
+    }
+
+    /// Linearly interpolates every channel (alpha included) from `self` at `t = 0.0` to `other`
+    /// at `t = 1.0`. `t` is clamped to that range.
+    pub fn lerp(self, other: Colour, t: f32) -> Colour {
+        let t = t.clamp(0.0, 1.0);
+        let (from, to): ([f32; 4], [f32; 4]) = (self.into(), other.into());
+        Colour::from([
+            from[0] + (to[0] - from[0]) * t,
+            from[1] + (to[1] - from[1]) * t,
+            from[2] + (to[2] - from[2]) * t,
+            from[3] + (to[3] - from[3]) * t,
+        ])
+    }
+
+    /// Multiplies the colour channels by `factor`, e.g. to apply a light intensity. Alpha is
+    /// left untouched and results are clamped to the valid range.
+    pub fn scale(self, factor: f32) -> Colour {
+        let c: [f32; 4] = self.into();
+        Colour::from([c[0] * factor, c[1] * factor, c[2] * factor, c[3]])
+    }
+
+    /// Composites `self` on top of `below` with the usual "over" operator, using the alpha of
+    /// both colours.
+    pub fn over(self, below: Colour) -> Colour {
+        let (top, bottom): ([f32; 4], [f32; 4]) = (self.into(), below.into());
+        let alpha = top[3] + bottom[3] * (1.0 - top[3]);
+        if alpha <= 0.0 {
+            return Colour::from([0.0, 0.0, 0.0, 0.0]);
         }
+        let channel = |i: usize| (top[i] * top[3] + bottom[i] * bottom[3] * (1.0 - top[3])) / alpha;
+        Colour::from([channel(0), channel(1), channel(2), alpha])
     }
 
-    #[allow(dead_code)]
-    /// Note: Consider using the ::into() function instead.
-    pub fn to_array(&self) -> [u8; 3] {
-        [self.r, self.g, self.b]
+    /// The colour of a black body glowing at `temperature` kelvin, e.g. 2700 for a warm light
+    /// bulb, 6500 for daylight or 10000 for a blue sky, using Tanner Helland's curve fit of
+    /// black body colours. The temperature is clamped to `1000.0..=40000.0`, the range the fit
+    /// covers.
+    pub fn from_kelvin(temperature: f32) -> Colour {
+        let t = temperature.clamp(1000.0, 40000.0) / 100.0;
+        let r = if t <= 66.0 { 255.0 } else { 329.698_73 * (t - 60.0).powf(-0.133_204_76) };
+        let g = if t <= 66.0 {
+            99.470_8 * t.ln() - 161.119_57
+        } else {
+            288.122_17 * (t - 60.0).powf(-0.075_514_85)
+        };
+        let b = if t >= 66.0 {
+            255.0
+        } else if t <= 19.0 {
+            0.0
+        } else {
+            138.517_73 * (t - 10.0).ln() - 305.044_8
+        };
+        Colour::from((r / 255.0, g / 255.0, b / 255.0))
     }
 }
 
@@ -46,25 +107,76 @@ impl Into<[u8; 3]> for Colour {
     }
 }
 
+impl From<Colour> for [u8; 4] {
+    fn from(value: Colour) -> Self {
+        [value.r, value.g, value.b, value.a]
+    }
+}
+
+impl From<Colour> for [f32; 4] {
+    fn from(value: Colour) -> Self {
+        [
+            value.r as f32 / 255.0,
+            value.g as f32 / 255.0,
+            value.b as f32 / 255.0,
+            value.a as f32 / 255.0,
+        ]
+    }
+}
+
+impl From<Colour> for Rgb<u8> {
+    fn from(value: Colour) -> Self {
+        Rgb([value.r, value.g, value.b])
+    }
+}
+
+impl From<Colour> for Rgba<u8> {
+    fn from(value: Colour) -> Self {
+        Rgba([value.r, value.g, value.b, value.a])
+    }
+}
+
 impl From<(u8, u8, u8)> for Colour {
     fn from(value: (u8, u8, u8)) -> Self {
-        Self { r: value.0, g: value.1, b: value.2 }
+        Self { r: value.0, g: value.1, b: value.2, a: 255 }
+    }
+}
+
+impl From<(u8, u8, u8, u8)> for Colour {
+    fn from(value: (u8, u8, u8, u8)) -> Self {
+        Self { r: value.0, g: value.1, b: value.2, a: value.3 }
     }
 }
 
 impl From<(f32, f32, f32)> for Colour {
     fn from(value: (f32, f32, f32)) -> Self {
-        let ir = (255.999 * value.0) as u8;
-        let ig = (255.999 * value.1) as u8;
-        let ib = (255.999 * value.2) as u8;
+        Colour::from([value.0, value.1, value.2, 1.0])
+    }
+}
+
+impl From<[f32; 4]> for Colour {
+    fn from(value: [f32; 4]) -> Self {
         Self {
-            r: ir,
-            g: ig,
-            b: ib,
+            r: channel_from_f32(value[0]),
+            g: channel_from_f32(value[1]),
+            b: channel_from_f32(value[2]),
+            a: channel_from_f32(value[3]),
         }
     }
 }
 
+impl From<Rgb<u8>> for Colour {
+    fn from(value: Rgb<u8>) -> Self {
+        Colour::from((value.0[0], value.0[1], value.0[2]))
+    }
+}
+
+impl From<Rgba<u8>> for Colour {
+    fn from(value: Rgba<u8>) -> Self {
+        Colour::from((value.0[0], value.0[1], value.0[2], value.0[3]))
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum DefinedColours {
     #[allow(dead_code)]
@@ -90,3 +202,155 @@ impl DefinedColours {
         }
     }
 }
+
+/// Fixes triangles whose texture coordinates wrap around the edge of the texture, like the
+/// seam of a cylinder going from U 0.98 to 0.02. Interpolating those directly would run back
+/// through the whole texture, so coordinates more than half a period away from the first
+/// corner are moved by one period towards it. Sampling repeats the texture, so the shifted
+/// coordinates still land on the right texels.
+///
+/// Only triangles with every coordinate inside `0.0..=1.0` are touched, so textures that are
+/// deliberately tiled across a triangle keep their repeats.
+pub(crate) fn unwrap_uv_seam(uvs: &mut [(f32, f32)]) {
+    let in_unit_square = |&(u, v): &(f32, f32)| (0.0..=1.0).contains(&u) && (0.0..=1.0).contains(&v);
+    if !uvs.iter().all(in_unit_square) {
+        return;
+    }
+    let Some(&(ref_u, ref_v)) = uvs.first() else {
+        return;
+    };
+
+    let unwrap = |value: f32, reference: f32| {
+        if value - reference > 0.5 {
+            value - 1.0
+        } else if reference - value > 0.5 {
+            value + 1.0
+        } else {
+            value
+        }
+    };
+    for uv in uvs.iter_mut().skip(1) {
+        *uv = (unwrap(uv.0, ref_u), unwrap(uv.1, ref_v));
+    }
+}
+
+/// Extended Reinhard tonemap on the luminance of a colour in the `0.0..=255.0` range (values
+/// over 255 allowed). Every channel is scaled by the same amount, so the hue is kept. Twice
+/// full brightness maps to full brightness.
+pub(crate) fn tonemap_reinhard(rgb: [f32; 3]) -> [f32; 3] {
+    const WHITE_SQ: f32 = 2.0 * 2.0;
+    let [r, g, b] = rgb.map(|c| c.max(0.0) / 255.0);
+    let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    if luminance <= 0.0 {
+        return [0.0; 3];
+    }
+    let mapped = luminance * (1.0 + luminance / WHITE_SQ) / (1.0 + luminance);
+    let scale = mapped / luminance * 255.0;
+    // a saturated colour can still have one channel over the top after the scaling, so bring
+    // the whole colour down rather than clipping that channel
+    let over = (r * scale).max(g * scale).max(b * scale) / 255.0;
+    let scale = if over > 1.0 { scale / over } else { scale };
+    [r * scale, g * scale, b * scale]
+}
+
+/// Quantises a shaded channel, `0.0..=255.0` but allowed to go over, to 8 bits. Opaque pixels
+/// are truncated as they always were, so existing renders don't shift up a level; blends of
+/// translucent ones are rounded.
+pub(crate) fn quantise(channel: f32, blended: bool) -> u8 {
+    let channel = channel.clamp(0.0, 255.0);
+    if blended { channel.round() as u8 } else { channel as u8 }
+}
+
+/// Undoes the sRGB transfer curve, turning a `0.0..=1.0` channel into linear light.
+pub(crate) fn srgb_to_linear(channel: f32) -> f32 {
+    let channel = channel.clamp(0.0, 1.0);
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// A distinct, stable colour for a material, stepping the hue by the golden ratio so that
+/// neighbouring indices never end up looking alike. This is the colour
+/// [`crate::RenderMode::MaterialDebug`] paints the material, fully lit.
+pub fn material_colour(material_idx: usize) -> Colour {
+    const GOLDEN_RATIO_CONJUGATE: f32 = 0.618_034;
+    let hue = (material_idx as f32 * GOLDEN_RATIO_CONJUGATE).fract() * 6.0;
+    let (saturation, value) = (0.65, 0.95);
+
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = value - chroma;
+    Colour::from((r + m, g + m, b + m))
+}
+
+/// A rotation of `degrees` about `axis`. Quarter turns come out exact: `sin` and `cos` of the
+/// angle in radians are an ulp off there, and by a different ulp with each platform's maths
+/// library, which is enough to move the edge of a triangle by a pixel between machines.
+pub(crate) fn rotation_about(axis: &Unit<Vector3<f32>>, degrees: f32) -> Rotation3<f32> {
+    let degrees = degrees.rem_euclid(360.0);
+    let (sin, cos) = match degrees {
+        0.0 => (0.0, 1.0),
+        90.0 => (1.0, 0.0),
+        180.0 => (0.0, -1.0),
+        270.0 => (-1.0, 0.0),
+        _ => degrees.to_radians().sin_cos(),
+    };
+
+    // Rodrigues' formula, with the sine and cosine from above
+    let k = axis.cross_matrix();
+    Rotation3::from_matrix_unchecked(Matrix3::identity() + k * sin + k * k * (1.0 - cos))
+}
+
+/// Radical inverse of `index` in the given `base`, the building block of a Halton sequence.
+fn radical_inverse(mut index: u32, base: u32) -> f32 {
+    let inv_base = 1.0 / base as f32;
+    let mut inv = inv_base;
+    let mut result = 0.0;
+    while index > 0 {
+        result += (index % base) as f32 * inv;
+        index /= base;
+        inv *= inv_base;
+    }
+    result
+}
+
+/// SplitMix64, used to turn a user seed into well-spread bits without pulling in an RNG.
+pub(crate) fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Produces `samples` sub-pixel offsets in the range `[-0.5, 0.5)` following a Halton (2, 3)
+/// sequence. A non-zero seed rotates the whole pattern (Cranley-Patterson rotation), which keeps
+/// its low-discrepancy properties while still being fully deterministic.
+pub(crate) fn jitter_offsets(samples: u32, seed: u64) -> Vec<(f32, f32)> {
+    let (rot_x, rot_y) = if seed == 0 {
+        (0.0, 0.0)
+    } else {
+        let bits = splitmix64(seed);
+        (
+            (bits >> 40) as f32 / (1u64 << 24) as f32,
+            ((bits >> 16) & 0xFF_FFFF) as f32 / (1u64 << 24) as f32,
+        )
+    };
+
+    (1..=samples)
+        .map(|i| {
+            let x = (radical_inverse(i, 2) + rot_x).fract();
+            let y = (radical_inverse(i, 3) + rot_y).fract();
+            (x - 0.5, y - 0.5)
+        })
+        .collect()
+}
@@ -0,0 +1,53 @@
+use crate::MeshData;
+
+/// Renumbers the vertices of every mesh that isn't deforming in the order its triangles (and
+/// then lines) first use them, see [`crate::ModelToImageBuilder::with_optimize_mesh`].
+pub(crate) fn optimize_vertex_order(meshes: &mut [MeshData], deforming: &[bool]) {
+    for (mesh_idx, mesh) in meshes.iter_mut().enumerate() {
+        if !deforming.get(mesh_idx).copied().unwrap_or(false) {
+            reorder_vertices(mesh);
+        }
+    }
+}
+
+fn reorder_vertices(mesh: &mut MeshData) {
+    let count = mesh.positions.len();
+    let mut new_index = vec![u32::MAX; count];
+    let mut order: Vec<u32> = Vec::with_capacity(count);
+    let used = mesh.triangles.iter().flatten().chain(mesh.lines.iter().flatten());
+    // vertices nothing uses go at the end, in the order they were in
+    for idx in used.copied().chain(0..count as u32) {
+        let Some(slot) = new_index.get_mut(idx as usize) else {
+            continue;
+        };
+        if *slot == u32::MAX {
+            *slot = order.len() as u32;
+            order.push(idx);
+        }
+    }
+    if order.iter().enumerate().all(|(position, &idx)| position as u32 == idx) {
+        return;
+    }
+
+    permute(&mut mesh.positions, &order);
+    permute(&mut mesh.normals, &order);
+    permute(&mut mesh.uvs, &order);
+    permute(&mut mesh.colours, &order);
+    let remap = |idx: &mut u32| {
+        if let Some(&new) = new_index.get(*idx as usize) {
+            *idx = new;
+        }
+    };
+    mesh.triangles.iter_mut().flatten().for_each(&remap);
+    mesh.lines.iter_mut().flatten().for_each(&remap);
+    for bone in &mut mesh.bones {
+        bone.weights.iter_mut().for_each(|(idx, _)| remap(idx));
+    }
+}
+
+/// Puts `values` in `order`, leaving arrays that don't have one value per vertex alone.
+fn permute<T: Copy>(values: &mut Vec<T>, order: &[u32]) {
+    if values.len() == order.len() {
+        *values = order.iter().map(|&idx| values[idx as usize]).collect();
+    }
+}
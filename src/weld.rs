@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use nalgebra::Vector3;
+
+use crate::MeshData;
+
+/// Merges the vertices of each mesh that lie within `tolerance` of each other and have the same
+/// texture coordinates and colour, averaging their normals, see
+/// [`crate::ModelToImageBuilder::with_weld`]. Vertices whose UVs differ stay split, so texture
+/// seams survive. Meshes that deform (`deforming`, indexed like `meshes`) are left alone, as
+/// are vertices with non-finite positions. Returns how many vertices were merged away.
+pub(crate) fn weld_meshes(meshes: &mut [MeshData], deforming: &[bool], tolerance: f32) -> usize {
+    meshes
+        .iter_mut()
+        .enumerate()
+        .filter(|(mesh_idx, mesh)| !deforming.get(*mesh_idx).copied().unwrap_or(false) && mesh.bones.is_empty())
+        .map(|(_, mesh)| weld_mesh(mesh, tolerance))
+        .sum()
+}
+
+fn weld_mesh(mesh: &mut MeshData, tolerance: f32) -> usize {
+    let count = mesh.positions.len();
+    // vertices are bucketed into cells a tolerance wide, so a match can only be in the same
+    // cell or a neighbouring one
+    let cell_size = if tolerance > 0.0 { tolerance } else { 1.0 };
+    let cell_of = |p: [f32; 3]| p.map(|c| (c / cell_size).floor() as i64);
+    let mut cells: HashMap<[i64; 3], Vec<u32>> = HashMap::new();
+
+    // the index every old vertex ends up at, and the old vertices kept
+    let mut remap = Vec::with_capacity(count);
+    let mut kept: Vec<usize> = Vec::new();
+    let mut normal_sums: Vec<Vector3<f32>> = Vec::new();
+    for idx in 0..count {
+        let position = mesh.positions[idx];
+        let normal = mesh.normals.get(idx).map_or_else(Vector3::zeros, |n| Vector3::from(*n));
+        if !position.iter().all(|c| c.is_finite()) {
+            remap.push(kept.len() as u32);
+            kept.push(idx);
+            normal_sums.push(normal);
+            continue;
+        }
+
+        let cell = cell_of(position);
+        let found = neighbours(cell).find_map(|neighbour| {
+            cells.get(&neighbour)?.iter().copied().find(|&candidate| {
+                let other = kept[candidate as usize];
+                same_vertex(mesh, idx, other, tolerance)
+            })
+        });
+        match found {
+            Some(target) => {
+                remap.push(target);
+                normal_sums[target as usize] += normal;
+            }
+            None => {
+                let target = kept.len() as u32;
+                remap.push(target);
+                kept.push(idx);
+                normal_sums.push(normal);
+                cells.entry(cell).or_default().push(target);
+            }
+        }
+    }
+
+    let merged = count - kept.len();
+    if merged == 0 {
+        return 0;
+    }
+
+    mesh.positions = kept.iter().map(|&idx| mesh.positions[idx]).collect();
+    if mesh.normals.len() == count {
+        // faces pointing opposite ways cancel out, those keep the first normal
+        mesh.normals = kept
+            .iter()
+            .zip(&normal_sums)
+            .map(|(&idx, sum)| sum.try_normalize(f32::EPSILON).map_or(mesh.normals[idx], |n| n.into()))
+            .collect();
+    }
+    if mesh.uvs.len() == count {
+        mesh.uvs = kept.iter().map(|&idx| mesh.uvs[idx]).collect();
+    }
+    if mesh.colours.len() == count {
+        mesh.colours = kept.iter().map(|&idx| mesh.colours[idx]).collect();
+    }
+
+    // indices past the end stay past it, for sanitize_meshes to drop with a warning
+    let map = |idx: u32| remap.get(idx as usize).copied().unwrap_or(u32::MAX);
+    for triangle in &mut mesh.triangles {
+        *triangle = triangle.map(map);
+    }
+    for line in &mut mesh.lines {
+        *line = line.map(map);
+    }
+    // triangles and lines whose corners were merged together have nothing left to draw
+    mesh.triangles.retain(|&[a, b, c]| a != b && b != c && a != c);
+    mesh.lines.retain(|&[a, b]| a != b);
+    merged
+}
+
+fn neighbours(cell: [i64; 3]) -> impl Iterator<Item = [i64; 3]> {
+    (-1..=1).flat_map(move |dx| {
+        (-1..=1).flat_map(move |dy| (-1..=1).map(move |dz| [cell[0] + dx, cell[1] + dy, cell[2] + dz]))
+    })
+}
+
+/// Whether vertices `a` and `b` are close enough and share their UVs and colour. UVs and
+/// colours have to match exactly: any difference is a seam the exporter meant.
+fn same_vertex(mesh: &MeshData, a: usize, b: usize, tolerance: f32) -> bool {
+    let distance = (Vector3::from(mesh.positions[a]) - Vector3::from(mesh.positions[b])).norm();
+    distance <= tolerance && mesh.uvs.get(a) == mesh.uvs.get(b) && mesh.colours.get(a) == mesh.colours.get(b)
+}
@@ -0,0 +1,37 @@
+mod fixtures;
+
+use image::{DynamicImage, Rgb, RgbImage};
+use model_to_image::{MaterialData, MeshData, ModelToImageBuilder};
+
+fn render(adaptive: bool) -> (RgbImage, Vec<String>) {
+    let cube = MeshData {
+        positions: fixtures::CUBE_VERTICES.to_vec(),
+        triangles: fixtures::CUBE_TRIANGLES.iter().map(|triangle| triangle.map(u32::from)).collect(),
+        uvs: vec![[0.5, 0.5]; 8],
+        ..Default::default()
+    };
+    let mid_grey = MaterialData {
+        texture: Some(DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, Rgb([128, 128, 128])))),
+        ..Default::default()
+    };
+    let mut model = ModelToImageBuilder::from_meshes(vec![cube], vec![mid_grey])
+        .with_size((64, 64))
+        .with_adaptive_background(adaptive)
+        .build()
+        .expect("build cube");
+    model.render().expect("render cube");
+    (model.output().clone(), model.warnings().to_vec())
+}
+
+#[test]
+fn a_grey_model_on_the_grey_background_gets_a_contrasting_backdrop() {
+    let (plain, _) = render(false);
+    assert_eq!(plain.get_pixel(0, 0).0, [211, 211, 211]);
+
+    let (adapted, warnings) = render(true);
+    let corner = adapted.get_pixel(0, 0).0;
+    assert!(corner[0].abs_diff(211) > 30, "the background was not changed: {:?}", corner);
+    assert!(warnings.iter().any(|warning| warning.contains("background")), "{:?}", warnings);
+    // the model itself is untouched
+    assert_eq!(adapted.get_pixel(32, 32), plain.get_pixel(32, 32));
+}
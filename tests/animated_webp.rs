@@ -0,0 +1,29 @@
+#![cfg(feature = "webp")]
+
+mod fixtures;
+
+use image::AnimationDecoder;
+use model_to_image::ModelToImageBuilder;
+
+#[test]
+fn a_turntable_webp_has_an_animation_frame_per_frame() {
+    let dir = fixtures::fixture_dir("animated_webp");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir))
+        .with_size((48, 32))
+        .with_animation_loops(2)
+        .build()
+        .expect("build cube");
+    let path = dir.join("turntable.webp");
+    model.write_animated_webp_to(&path, 6, 80).expect("write webp");
+
+    let webp = std::fs::read(&path).unwrap();
+    assert_eq!((&webp[..4], &webp[8..12]), (&b"RIFF"[..], &b"WEBP"[..]));
+    assert_eq!(u32::from_le_bytes(webp[4..8].try_into().unwrap()) as usize, webp.len() - 8);
+
+    let decoder = image::codecs::webp::WebPDecoder::new(std::io::Cursor::new(&webp)).expect("read webp");
+    let frames = decoder.into_frames().collect_frames().expect("decode frames");
+    assert_eq!(frames.len(), 6);
+    assert!(frames.iter().all(|frame| frame.buffer().dimensions() == (48, 32)));
+    // lossless, so the last frame is exactly the last render
+    assert_eq!(frames[5].buffer().pixels().next().unwrap().0[..3], model.output().get_pixel(0, 0).0);
+}
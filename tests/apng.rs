@@ -0,0 +1,68 @@
+mod fixtures;
+
+use model_to_image::ModelToImageBuilder;
+
+/// The type and data of every chunk in a PNG file.
+fn chunks(png: &[u8]) -> Vec<([u8; 4], &[u8])> {
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    let mut chunks = Vec::new();
+    let mut offset = 8;
+    while offset < png.len() {
+        let length = u32::from_be_bytes(png[offset..offset + 4].try_into().unwrap()) as usize;
+        let kind = png[offset + 4..offset + 8].try_into().unwrap();
+        chunks.push((kind, &png[offset + 8..offset + 8 + length]));
+        offset += 12 + length;
+    }
+    chunks
+}
+
+#[test]
+fn a_turntable_apng_has_a_frame_control_chunk_per_frame() {
+    let dir = fixtures::fixture_dir("apng");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir))
+        .with_size((48, 32))
+        .build()
+        .expect("build cube");
+    let path = dir.join("out/turntable.png");
+    model.write_apng_to(&path, 6, 80).expect("write apng");
+
+    let png = std::fs::read(&path).unwrap();
+    let chunks = chunks(&png);
+    let actl = chunks.iter().find(|(kind, _)| kind == b"acTL").expect("an acTL chunk").1;
+    assert_eq!(u32::from_be_bytes(actl[..4].try_into().unwrap()), 6, "frame count");
+    assert_eq!(u32::from_be_bytes(actl[4..8].try_into().unwrap()), 0, "loops forever");
+
+    let fctls: Vec<&[u8]> = chunks.iter().filter(|(kind, _)| kind == b"fcTL").map(|(_, data)| *data).collect();
+    assert_eq!(fctls.len(), 6);
+    for fctl in &fctls {
+        assert_eq!(u32::from_be_bytes(fctl[4..8].try_into().unwrap()), 48);
+        assert_eq!(u32::from_be_bytes(fctl[8..12].try_into().unwrap()), 32);
+        let delay = (u16::from_be_bytes([fctl[20], fctl[21]]), u16::from_be_bytes([fctl[22], fctl[23]]));
+        assert_eq!(delay, (80, 1000));
+    }
+    // the first frame is the default image, the others are in fdAT chunks
+    assert!(chunks.iter().any(|(kind, _)| kind == b"fdAT"));
+    assert_eq!(chunks.last().unwrap().0, *b"IEND");
+
+    // the plain PNG decoder still reads the first frame
+    assert_eq!(image::open(&path).expect("decode first frame").to_rgb8().dimensions(), (48, 32));
+}
+
+#[test]
+fn the_loop_count_is_configurable() {
+    let dir = fixtures::fixture_dir("apng_loops");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir))
+        .with_size((16, 16))
+        .with_animation_loops(3)
+        .build()
+        .expect("build cube");
+    let path = dir.join("loops.png");
+    model.write_apng_to(&path, 2, 100).expect("write apng");
+
+    let png = std::fs::read(&path).unwrap();
+    let chunks = chunks(&png);
+    let actl = chunks.iter().find(|(kind, _)| kind == b"acTL").expect("an acTL chunk").1;
+    assert_eq!(u32::from_be_bytes(actl[4..8].try_into().unwrap()), 3);
+
+    assert!(model.write_apng_to(dir.join("empty.png"), 0, 100).is_err());
+}
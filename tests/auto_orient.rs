@@ -0,0 +1,33 @@
+mod fixtures;
+
+use model_to_image::{MeshData, ModelToImageBuilder};
+
+/// A column half a unit wide, four tall and a third deep, standing up along Y.
+fn column() -> MeshData {
+    MeshData {
+        positions: fixtures::CUBE_VERTICES.iter().map(|&[x, y, z]| [x * 0.25, y * 2.0, z * 0.15]).collect(),
+        triangles: fixtures::CUBE_TRIANGLES.iter().map(|triangle| triangle.map(u32::from)).collect(),
+        ..Default::default()
+    }
+}
+
+/// Width and height of the model's silhouette in pixels.
+fn silhouette(auto_orient: bool) -> (u32, u32) {
+    let mut model = ModelToImageBuilder::from_meshes(vec![column()], Vec::new())
+        .with_size((96, 96))
+        .with_auto_orient(auto_orient)
+        .build()
+        .expect("build column");
+    model.render().expect("render column");
+    let (min_x, min_y, max_x, max_y) = model.coverage().bounding_box.expect("column drawn");
+    (max_x - min_x + 1, max_y - min_y + 1)
+}
+
+#[test]
+fn auto_orient_lays_a_tall_column_across_the_image() {
+    let (width, height) = silhouette(false);
+    assert!(height > width, "{}x{}", width, height);
+
+    let (width, height) = silhouette(true);
+    assert!(width > 3 * height, "{}x{}", width, height);
+}
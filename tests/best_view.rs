@@ -0,0 +1,44 @@
+mod fixtures;
+
+use model_to_image::{MeshData, ModelToImageBuilder, ViewPreset};
+
+/// A business card lying flat: 3.5 by 2 units across X and Z and a fiftieth of a unit thick,
+/// so the front view only sees its edge.
+fn business_card() -> MeshData {
+    MeshData {
+        positions: fixtures::CUBE_VERTICES.iter().map(|&[x, y, z]| [x * 1.75, y * 0.01, z]).collect(),
+        triangles: fixtures::CUBE_TRIANGLES.iter().map(|triangle| triangle.map(u32::from)).collect(),
+        ..Default::default()
+    }
+}
+
+/// The view the card ends up shown from and the fraction of the image it covers.
+fn render(view: ViewPreset) -> (ViewPreset, f32) {
+    let mut model = ModelToImageBuilder::from_meshes(vec![business_card()], Vec::new())
+        .with_size((96, 96))
+        .with_view(view)
+        .build()
+        .expect("build card");
+    model.render().expect("render card");
+    (model.view(), model.coverage().fraction)
+}
+
+#[test]
+fn auto_view_does_not_show_a_flat_card_edge_on() {
+    let (_, edge_on) = render(ViewPreset::Front);
+    let (view, fraction) = render(ViewPreset::Auto);
+    assert!(
+        matches!(view, ViewPreset::Top | ViewPreset::Bottom | ViewPreset::Isometric),
+        "picked {:?}",
+        view
+    );
+    assert!(fraction > 0.3, "covers {}", fraction);
+    assert!(fraction > 10.0 * edge_on, "covers {} against {} edge-on", fraction, edge_on);
+}
+
+#[test]
+fn auto_view_renders_like_the_view_it_picked() {
+    // probing the candidates leaves the model turned only to the one it picked
+    let (view, fraction) = render(ViewPreset::Auto);
+    assert_eq!(render(view), (view, fraction));
+}
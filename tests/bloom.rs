@@ -0,0 +1,55 @@
+use model_to_image::{MaterialData, MeshData, ModelToImageBuilder};
+
+/// A dark panel with two glowing stripes just in front of it, on a second material.
+fn striped_panel() -> (Vec<MeshData>, Vec<MaterialData>) {
+    let quad = |x0: f32, x1: f32, y0: f32, y1: f32, z: f32, material: usize| MeshData {
+        positions: vec![[x0, y0, z], [x1, y0, z], [x1, y1, z], [x0, y1, z]],
+        triangles: vec![[0, 1, 2], [0, 2, 3]],
+        material,
+        ..Default::default()
+    };
+    let meshes = vec![
+        quad(0.0, 10.0, 0.0, 10.0, 0.0, 0),
+        quad(2.0, 3.0, 1.0, 9.0, 0.1, 1),
+        quad(7.0, 8.0, 1.0, 9.0, 0.1, 1),
+    ];
+    let materials = vec![
+        MaterialData::default(),
+        MaterialData { name: "stripe".into(), emissive: [4.0, 3.0, 1.0], ..Default::default() },
+    ];
+    (meshes, materials)
+}
+
+fn render(bloom: bool) -> image::RgbImage {
+    let (meshes, materials) = striped_panel();
+    let mut builder = ModelToImageBuilder::from_meshes(meshes, materials)
+        .with_size((96, 64))
+        .with_min_intensity(0.2)
+        .with_light_direction([0.0, 0.0, 1.0]);
+    if bloom {
+        builder = builder.with_bloom(1.0, 4.0, 1.0);
+    }
+    let mut model = builder.build().expect("build panel");
+    model.render().expect("render panel");
+    model.output().clone()
+}
+
+#[test]
+fn bloom_puts_a_halo_around_emissive_stripes_only() {
+    let (plain, bloomed) = (render(false), render(true));
+    let brightness = |img: &image::RgbImage, x: u32| img.get_pixel(x, 32).0.iter().map(|&c| c as u32).sum::<u32>();
+
+    // the panel is lit from behind, so only the floor of the light reaches it: next to a stripe
+    // it should pick up the glow, in the middle between the stripes hardly any
+    let stripe = (0..96).find(|&x| brightness(&plain, x) > 600).expect("a stripe");
+    let beside = stripe - 2;
+    assert!(
+        brightness(&bloomed, beside) > brightness(&plain, beside) + 30,
+        "no halo beside the stripe at {}: {} vs {}",
+        beside,
+        brightness(&bloomed, beside),
+        brightness(&plain, beside)
+    );
+    // well away from the model, the background stays as it was
+    assert_eq!(bloomed.get_pixel(0, 0), plain.get_pixel(0, 0));
+}
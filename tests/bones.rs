@@ -0,0 +1,46 @@
+mod fixtures;
+
+use model_to_image::{Colour, ModelToImageBuilder, Overlay, RenderMode};
+
+#[test]
+fn bone_weights_blend_smoothly_across_the_elbow() {
+    let dir = fixtures::fixture_dir("bones");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_gltf_arm(&dir))
+        .with_size((160, 48))
+        .with_render_mode(RenderMode::BoneWeights { bone: "lower_arm".into() })
+        .build()
+        .expect("load arm");
+    model.render().expect("render arm");
+    assert!(!model.warnings().iter().any(|warning| warning.contains("bone")), "{:?}", model.warnings());
+
+    let img = model.output();
+    let row: Vec<(u32, [u8; 3])> = (0..img.width())
+        .map(|x| (x, img.get_pixel(x, img.height() / 2).0))
+        .filter(|(_, [r, g, b])| (*r, *g, *b) != (211, 211, 211))
+        .collect();
+    let (first, last) = (row.first().expect("the arm").1, row.last().expect("the arm").1);
+    assert!(first[2] > 200 && first[0] < 30, "the shoulder should be blue, got {:?}", first);
+    assert!(last[0] > 200 && last[2] < 30, "the hand should be red, got {:?}", last);
+
+    // red only ever grows towards the hand, through a ramp rather than a jump
+    for pair in row.windows(2) {
+        assert!(pair[1].1[0] + 2 >= pair[0].1[0], "red drops between {:?} and {:?}", pair[0], pair[1]);
+    }
+    let blended = row.iter().filter(|(_, [r, _, b])| *r > 40 && *b > 40).count();
+    assert!(blended > row.len() / 4, "only {} of {} pixels are blended", blended, row.len());
+}
+
+#[test]
+fn skeleton_overlay_draws_the_bones() {
+    let dir = fixtures::fixture_dir("bones_overlay");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_gltf_arm(&dir))
+        .with_size((160, 48))
+        .with_overlay(Overlay::Skeleton { colour: Colour::from((0, 255, 0)), width_px: 1 })
+        .build()
+        .expect("load arm");
+    model.render().expect("render arm");
+
+    let green = model.output().pixels().filter(|pixel| pixel.0 == [0, 255, 0]).count();
+    // two bones along most of the width of the image
+    assert!(green > 100, "only {} skeleton pixels", green);
+}
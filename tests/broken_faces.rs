@@ -0,0 +1,41 @@
+mod fixtures;
+
+use model_to_image::{MeshData, ModelToImage, ModelToImageBuilder, ViewPreset};
+
+fn cube(extra_triangles: &[[u32; 3]]) -> MeshData {
+    let mut triangles: Vec<[u32; 3]> = fixtures::CUBE_TRIANGLES.iter().map(|t| t.map(u32::from)).collect();
+    triangles.extend_from_slice(extra_triangles);
+    MeshData {
+        name: "crate".to_string(),
+        positions: fixtures::CUBE_VERTICES.to_vec(),
+        triangles,
+        ..Default::default()
+    }
+}
+
+fn render(mesh: MeshData) -> ModelToImage {
+    let mut model = ModelToImageBuilder::from_meshes(vec![mesh], Vec::new())
+        .with_size((64, 64))
+        .with_view(ViewPreset::Isometric)
+        .build()
+        .expect("build cube");
+    model.render().expect("render cube");
+    model
+}
+
+#[test]
+fn faces_past_the_end_of_the_vertices_are_skipped_with_a_warning() {
+    // two faces pointing at a vertex the mesh doesn't have
+    let broken = render(cube(&[[0, 1, 8], [99, 2, 3]]));
+    assert_eq!(broken.stats().faces_skipped, 2);
+    assert!(
+        broken.warnings().iter().any(|w| w.contains("crate (#0)") && w.contains("skipped 2 faces")),
+        "{:?}",
+        broken.warnings()
+    );
+
+    // the rest of the cube is drawn as if the broken faces weren't there
+    let clean = render(cube(&[]));
+    assert_eq!(clean.stats().faces_skipped, 0);
+    assert!(broken.output() == clean.output());
+}
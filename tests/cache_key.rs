@@ -0,0 +1,29 @@
+mod fixtures;
+
+use std::fs;
+
+use model_to_image::{ModelToImageBuilder, TimeBudgetPolicy, ViewPreset};
+
+#[test]
+fn cache_key_follows_the_model_and_the_settings() {
+    let dir = fixtures::fixture_dir("cache_key");
+    let path = fixtures::write_obj_cube(&dir);
+    let builder = || ModelToImageBuilder::new(&path).with_size((64, 64));
+    let key = builder().cache_key().expect("cache key");
+
+    assert_eq!(builder().cache_key().unwrap(), key);
+    // only decides whether rendering gives up, never what is drawn
+    let with_budget = builder().with_time_budget(std::time::Duration::from_secs(5), TimeBudgetPolicy::Abort);
+    assert_eq!(with_budget.cache_key().unwrap(), key);
+
+    assert_ne!(builder().with_size((65, 64)).cache_key().unwrap(), key);
+    assert_ne!(builder().with_margin(0.2).cache_key().unwrap(), key);
+    assert_ne!(builder().with_view(ViewPreset::Top).cache_key().unwrap(), key);
+    assert_ne!(builder().with_light_direction([0.0, -1.0, -1.0]).cache_key().unwrap(), key);
+
+    let mut bytes = fs::read(&path).unwrap();
+    let last = bytes.len() - 2;
+    bytes[last] = if bytes[last] == b'1' { b'2' } else { b'1' };
+    fs::write(&path, bytes).unwrap();
+    assert_ne!(builder().cache_key().unwrap(), key);
+}
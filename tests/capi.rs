@@ -0,0 +1,44 @@
+#![cfg(feature = "capi")]
+
+mod fixtures;
+
+use std::ffi::{CStr, CString, c_char};
+
+use model_to_image::capi::{MTI_ERROR_INVALID_OPTIONS, MTI_OK};
+
+unsafe extern "C" {
+    fn mti_render_file(
+        path: *const c_char,
+        width: u32,
+        height: u32,
+        options_json: *const c_char,
+        out_buf: *mut *mut u8,
+        out_len: *mut usize,
+    ) -> i32;
+    fn mti_last_error_message() -> *const c_char;
+    fn mti_free(ptr: *mut u8);
+}
+
+#[test]
+fn rendering_through_the_c_interface_returns_png_bytes() {
+    let dir = fixtures::fixture_dir("capi");
+    let path = CString::new(fixtures::write_obj_cube(&dir).to_str().unwrap()).unwrap();
+    let options = CString::new(r#"{"view": "isometric", "background": [0, 0, 0], "samples": 2}"#).unwrap();
+
+    let (mut buf, mut len) = (std::ptr::null_mut(), 0);
+    let code = unsafe { mti_render_file(path.as_ptr(), 48, 32, options.as_ptr(), &mut buf, &mut len) };
+    assert_eq!(code, MTI_OK);
+    assert!(unsafe { mti_last_error_message() }.is_null());
+
+    let png = unsafe { std::slice::from_raw_parts(buf, len) }.to_vec();
+    unsafe { mti_free(buf) };
+    let image = image::load_from_memory_with_format(&png, image::ImageFormat::Png).expect("decode png").to_rgb8();
+    assert_eq!(image.dimensions(), (48, 32));
+    assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0]);
+
+    let bad = CString::new(r#"{"colour_of_the_sky": "blue"}"#).unwrap();
+    let code = unsafe { mti_render_file(path.as_ptr(), 48, 32, bad.as_ptr(), &mut buf, &mut len) };
+    assert_eq!(code, MTI_ERROR_INVALID_OPTIONS);
+    let message = unsafe { CStr::from_ptr(mti_last_error_message()) }.to_str().unwrap().to_string();
+    assert!(message.contains("colour_of_the_sky"), "{}", message);
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "cli")]
+
+mod fixtures;
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use serde_json::Value;
+
+/// Runs the binary with `args` from `dir`, where it writes its images.
+fn run_cli(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_model_to_image"))
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("run the binary")
+}
+
+/// The one JSON object `--json` leaves on stdout.
+fn report(output: &Output) -> Value {
+    let stdout = String::from_utf8(output.stdout.clone()).expect("stdout is UTF-8");
+    serde_json::from_str(stdout.trim())
+        .unwrap_or_else(|err| panic!("stdout isn't one JSON object ({}): {}", err, stdout))
+}
+
+#[test]
+fn a_successful_render_reports_what_it_wrote() {
+    let dir = fixtures::fixture_dir("cli_success");
+    let model = fixtures::write_stl_cube(&dir);
+
+    let output = run_cli(&dir, &["--json", model.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let report = report(&output);
+
+    assert_eq!(report["status"], "ok");
+    let written = report["output"].as_str().expect("output path");
+    assert!(Path::new(written).exists(), "{} wasn't written", written);
+    assert_eq!(report["outputs"].as_array().map(Vec::len), Some(1));
+    assert_eq!((report["width"].as_u64(), report["height"].as_u64()), (Some(800), Some(800)));
+    assert_eq!(report["triangles"]["total"], 12);
+    assert!(report["triangles"]["drawn"].as_u64().is_some_and(|drawn| drawn > 0));
+    assert!(report["timing_ms"]["total"].as_f64().is_some_and(|ms| ms >= 0.0));
+    assert!(report["warnings"].is_array());
+}
+
+#[test]
+fn a_missing_model_is_reported_as_an_error_object() {
+    let dir = fixtures::fixture_dir("cli_missing");
+
+    let output = run_cli(&dir, &["--json", "not_there.obj"]);
+    assert_eq!(output.status.code(), Some(1));
+    let report = report(&output);
+
+    assert_eq!(report["status"], "error");
+    assert_eq!(report["code"], "model_not_found");
+    assert_eq!(report["model"], "not_there.obj");
+    assert!(report["message"].as_str().is_some_and(|message| message.contains("not_there.obj")));
+}
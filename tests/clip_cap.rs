@@ -0,0 +1,73 @@
+use std::f32::consts::{PI, TAU};
+
+use model_to_image::{Colour, MeshData, ModelToImage, ModelToImageBuilder};
+
+const SIZE: u32 = 128;
+const CAP: [u8; 3] = [255, 200, 150];
+const BACKGROUND: [u8; 3] = [211, 211, 211];
+
+/// A closed unit sphere, every triangle wound counter-clockwise seen from outside.
+fn sphere() -> MeshData {
+    let steps = 48;
+    let mut mesh = MeshData::default();
+    for j in 0..=steps {
+        for i in 0..=steps {
+            let (latitude, longitude) = (PI * (j as f32 / steps as f32 - 0.5), TAU * i as f32 / steps as f32);
+            mesh.positions.push([latitude.cos() * longitude.cos(), latitude.sin(), latitude.cos() * longitude.sin()]);
+        }
+    }
+    let side = steps + 1;
+    for j in 0..steps {
+        for i in 0..steps {
+            let corner = j * side + i;
+            for [a, b, c] in [[corner, corner + 1, corner + side + 1], [corner, corner + side + 1, corner + side]] {
+                let [pa, pb, pc] = [a, b, c].map(|idx| mesh.positions[idx as usize]);
+                let (u, v) = ([0, 1, 2].map(|k| pb[k] - pa[k]), [0, 1, 2].map(|k| pc[k] - pa[k]));
+                let normal = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+                let outwards = (0..3).map(|k| normal[k] * (pa[k] + pb[k] + pc[k])).sum::<f32>() >= 0.0;
+                mesh.triangles.push(if outwards { [a, b, c] } else { [a, c, b] });
+            }
+        }
+    }
+    mesh
+}
+
+/// The sphere with its near half, everything in front of the origin, cut away.
+fn render_hemisphere(cap: bool) -> ModelToImage {
+    let mut builder = ModelToImageBuilder::from_meshes(vec![sphere()], Vec::new())
+        .with_size((SIZE, SIZE))
+        .with_clip_plane([0.0, 0.0, 1.0], 0.0);
+    if cap {
+        builder = builder.with_clip_cap(Colour::from((CAP[0], CAP[1], CAP[2])));
+    }
+    let mut model = builder.build().expect("build sphere");
+    model.render().expect("render sphere");
+    model
+}
+
+/// Every pixel within `radius` pixels of the middle of the image.
+fn within(radius: f32) -> impl Iterator<Item = (u32, u32)> {
+    let centre = SIZE as f32 / 2.0;
+    (0..SIZE)
+        .flat_map(|y| (0..SIZE).map(move |x| (x, y)))
+        .filter(move |&(x, y)| (x as f32 + 0.5 - centre).hypot(y as f32 + 0.5 - centre) < radius)
+}
+
+#[test]
+fn the_cap_fills_the_cut_face_of_a_hemisphere() {
+    // the sphere is 0.8 of the image across, and the cut runs through its widest circle
+    let radius = SIZE as f32 * 0.4;
+
+    let capped = render_hemisphere(true);
+    for (x, y) in within(radius - 2.0) {
+        let pixel = capped.output().get_pixel(x, y).0;
+        assert!(pixel.iter().zip(CAP).all(|(&got, cap)| got.abs_diff(cap) <= 1), "({}, {}) is {:?}", x, y, pixel);
+    }
+    assert_eq!(capped.output().get_pixel(2, 2).0, BACKGROUND);
+
+    // without the cap, the inside of the far half faces away and the cut shows through
+    let open = render_hemisphere(false);
+    for (x, y) in within(radius - 2.0) {
+        assert_eq!(open.output().get_pixel(x, y).0, BACKGROUND, "({}, {})", x, y);
+    }
+}
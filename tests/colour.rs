@@ -0,0 +1,52 @@
+use image::{Rgb, Rgba};
+use model_to_image::Colour;
+
+fn channels(colour: Colour) -> [u8; 4] {
+    colour.into()
+}
+
+#[test]
+fn three_channels_are_in_order_and_opaque() {
+    let colour = Colour::from((1, 2, 3));
+    assert_eq!(channels(colour), [1, 2, 3, 255]);
+    assert_eq!(Rgb::from(colour), Rgb([1, 2, 3]));
+    assert_eq!(Rgba::from(colour.with_alpha(4)), Rgba([1, 2, 3, 4]));
+    assert_eq!(Colour::from(Rgba([5, 6, 7, 8])), Colour::from((5, 6, 7, 8)));
+    assert_eq!(Colour::from(Rgb([5, 6, 7])).alpha(), 255);
+}
+
+#[test]
+fn floats_are_clamped_and_rounded() {
+    assert_eq!(channels(Colour::from([1.5, -0.5, 0.5, 0.2])), [255, 0, 128, 51]);
+    assert_eq!(channels(Colour::from([100.4 / 255.0, 100.6 / 255.0, f32::NAN, 1.0])), [100, 101, 0, 255]);
+    assert_eq!(channels(Colour::from((0.0, 1.0, 2.0))), [0, 255, 255, 255]);
+
+    let floats: [f32; 4] = Colour::from((255, 0, 51, 255)).into();
+    assert_eq!(floats, [1.0, 0.0, 0.2, 1.0]);
+}
+
+#[test]
+fn lerp_clamps_its_factor() {
+    let (black, white) = (Colour::from((0, 0, 0, 0)), Colour::from((255, 255, 255)));
+    assert_eq!(channels(black.lerp(white, 0.5)), [128, 128, 128, 128]);
+    assert_eq!(black.lerp(white, 2.0), white);
+    assert_eq!(black.lerp(white, -1.0), black);
+}
+
+#[test]
+fn scale_clamps_and_keeps_alpha() {
+    let colour = Colour::from((200, 100, 50, 10));
+    assert_eq!(channels(colour.scale(2.0)), [255, 200, 100, 10]);
+    assert_eq!(channels(colour.scale(0.5)), [100, 50, 25, 10]);
+    assert_eq!(channels(colour.scale(-1.0)), [0, 0, 0, 10]);
+}
+
+#[test]
+fn over_blends_by_alpha() {
+    let blue = Colour::from((0, 0, 255));
+    assert_eq!(Colour::from((255, 0, 0)).over(blue), Colour::from((255, 0, 0)));
+    assert_eq!(Colour::from((255, 0, 0, 0)).over(blue), blue);
+    assert_eq!(channels(Colour::from((255, 0, 0, 128)).over(blue)), [128, 0, 127, 255]);
+    // nothing over nothing stays nothing
+    assert_eq!(channels(Colour::from((255, 0, 0, 0)).over(Colour::from((0, 0, 255, 0)))), [0, 0, 0, 0]);
+}
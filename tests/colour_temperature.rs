@@ -0,0 +1,61 @@
+use model_to_image::{Colour, DefinedColours, Light, MaterialData, MeshData, ModelToImageBuilder};
+
+#[test]
+fn known_temperatures_have_known_colours() {
+    assert_eq!(Colour::from_kelvin(6500.0).to_array(), [255, 254, 250], "daylight is near white");
+    assert_eq!(Colour::from_kelvin(6600.0), DefinedColours::White.colour());
+    assert_eq!(Colour::from_kelvin(2700.0).to_array(), [255, 167, 87], "a light bulb is warm orange");
+    assert_eq!(Colour::from_kelvin(10000.0).to_array(), [202, 218, 255], "a clear sky is bluish");
+    assert_eq!(Colour::from_kelvin(1000.0).to_array(), [255, 68, 0]);
+}
+
+#[test]
+fn temperatures_outside_the_fit_are_clamped() {
+    assert_eq!(Colour::from_kelvin(100.0), Colour::from_kelvin(1000.0));
+    assert_eq!(Colour::from_kelvin(1.0e6), Colour::from_kelvin(40000.0));
+}
+
+#[test]
+fn warmer_is_redder() {
+    let temperatures = [1500.0, 2700.0, 4000.0, 5500.0, 8000.0, 15000.0];
+    for pair in temperatures.windows(2) {
+        let (warm, cool) = (Colour::from_kelvin(pair[0]).to_array(), Colour::from_kelvin(pair[1]).to_array());
+        assert!(warm[2] <= cool[2] && warm[0] >= cool[0], "{:?} {:?}", warm, cool);
+    }
+}
+
+/// The middle pixel of a white square facing the camera, lit by `lights`.
+fn lit_by(lights: Vec<Light>) -> [u8; 3] {
+    let quad = MeshData {
+        positions: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+        triangles: vec![[0, 1, 2], [0, 2, 3]],
+        ..Default::default()
+    };
+    let mut model = ModelToImageBuilder::from_meshes(vec![quad], vec![MaterialData::default()])
+        .with_size((16, 16))
+        .with_lights(lights)
+        .build()
+        .expect("build quad");
+    model.render().expect("render quad");
+    model.output().get_pixel(8, 8).0
+}
+
+#[test]
+fn lights_tint_the_model_with_their_temperature() {
+    let white = lit_by(vec![Light::new([0.0, 0.0, -1.0], 1.0)]);
+    assert_eq!(white, [255, 255, 255]);
+    assert_eq!(lit_by(vec![Light::directional_kelvin([0.0, 0.0, -1.0], 6600.0, 1.0)]), white);
+
+    let warm = lit_by(vec![Light::directional_kelvin([0.0, 0.0, -1.0], 2700.0, 1.0)]);
+    assert_eq!(warm, [255, 167, 87]);
+    let cool = lit_by(vec![Light::directional_kelvin([0.0, 0.0, -1.0], 10000.0, 1.0)]);
+    assert_eq!(cool, [202, 218, 255]);
+}
+
+#[test]
+fn coloured_lights_mix_by_how_much_each_one_lights() {
+    let red = Light::new([0.0, 0.0, -1.0], 0.5).with_colour(DefinedColours::Red.colour());
+    let white = Light::new([0.0, 0.0, -1.0], 0.5);
+    // half the light is red and half white, so red is at full strength and the rest at half
+    assert_eq!(lit_by(vec![red, white]), [255, 128, 128]);
+}
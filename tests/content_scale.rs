@@ -0,0 +1,68 @@
+use model_to_image::{Framing, MeshData, ModelToImageBuilder};
+
+/// A disc of radius 1 facing the camera, as a fan of thin triangles.
+fn disc(segments: u32) -> MeshData {
+    let mut positions = vec![[0.0, 0.0, 0.0]];
+    for segment in 0..segments {
+        let angle = std::f32::consts::TAU * segment as f32 / segments as f32;
+        positions.push([angle.cos(), angle.sin(), 0.0]);
+    }
+    let triangles = (0..segments).map(|segment| [0, 1 + segment, 1 + (segment + 1) % segments]).collect();
+    MeshData { positions, triangles, ..Default::default() }
+}
+
+/// Width and height of the covered pixels, and the middle of them.
+fn silhouette(builder: ModelToImageBuilder) -> ((f32, f32), (f32, f32)) {
+    let mut model = builder.with_min_intensity(0.5).build().expect("build disc");
+    model.render().expect("render disc");
+    let (min_x, min_y, max_x, max_y) = model.coverage().bounding_box.expect("disc is drawn");
+    let size = ((max_x - min_x + 1) as f32, (max_y - min_y + 1) as f32);
+    let middle = ((min_x + max_x) as f32 / 2.0, (min_y + max_y) as f32 / 2.0);
+    (size, middle)
+}
+
+fn disc_builder() -> ModelToImageBuilder {
+    ModelToImageBuilder::from_meshes(vec![disc(128)], Vec::new()).with_size((128, 128))
+}
+
+#[test]
+fn half_height_turns_a_circle_into_a_two_to_one_ellipse() {
+    let ((width, height), middle) = silhouette(disc_builder().with_content_scale(1.0, 0.5));
+    let ratio = width / height;
+    assert!((ratio - 2.0).abs() < 0.1, "{}x{} is not 2:1", width, height);
+    // squashed around the middle of the image, still filling the margin across
+    assert!((middle.0 - 63.5).abs() <= 1.0 && (middle.1 - 63.5).abs() <= 1.0, "{:?}", middle);
+    assert!((width - 128.0 * 0.8).abs() <= 2.0, "{}", width);
+}
+
+#[test]
+fn content_scale_applies_after_the_margin() {
+    let ((unscaled, _), _) = silhouette(disc_builder().with_margin(0.25));
+    let ((width, height), _) = silhouette(disc_builder().with_margin(0.25).with_content_scale(0.5, 1.0));
+    assert!((width - unscaled * 0.5).abs() <= 2.0, "{} against {}", width, unscaled);
+    assert!((height - unscaled).abs() <= 1.0, "{} against {}", height, unscaled);
+}
+
+#[test]
+fn content_scale_composes_with_zoomed_and_panned_framing() {
+    let framing = Framing {
+        extent: [4.0, 4.0],
+        center: Some([0.5, 0.0, 0.0]),
+    };
+    let ((plain_width, plain_height), plain_middle) = silhouette(disc_builder().with_framing(framing));
+    let ((width, height), middle) =
+        silhouette(disc_builder().with_framing(framing).with_content_scale(1.0, 0.5));
+
+    assert!((width / height - 2.0).abs() < 0.15, "{}x{} is not 2:1", width, height);
+    assert!((width - plain_width).abs() <= 1.0 && (height - plain_height * 0.5).abs() <= 1.0);
+    // the pan is off to the side, and squashing only pulls towards the middle vertically
+    assert!(middle.0 < 60.0, "{:?}", middle);
+    assert!((middle.0 - plain_middle.0).abs() <= 1.0, "{:?} against {:?}", middle, plain_middle);
+}
+
+#[test]
+fn zero_and_negative_scales_are_rejected() {
+    for (sx, sy) in [(0.0, 1.0), (1.0, -0.5), (f32::NAN, 1.0)] {
+        assert!(disc_builder().with_content_scale(sx, sy).build().is_err(), "({}, {})", sx, sy);
+    }
+}
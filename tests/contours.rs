@@ -0,0 +1,90 @@
+use std::f32::consts::{PI, TAU};
+
+use model_to_image::{MeshData, ModelToImage, ModelToImageBuilder};
+
+const SIZE: u32 = 128;
+
+/// A closed surface from `point`, which maps `(u, v)` in `0.0..=1.0` onto it, as a grid of
+/// `steps` x `steps` squares.
+fn surface(steps: u32, point: impl Fn(f32, f32) -> [f32; 3]) -> MeshData {
+    let mut mesh = MeshData::default();
+    for j in 0..=steps {
+        for i in 0..=steps {
+            mesh.positions.push(point(i as f32 / steps as f32, j as f32 / steps as f32));
+        }
+    }
+    let side = steps + 1;
+    for j in 0..steps {
+        for i in 0..steps {
+            let corner = j * side + i;
+            mesh.triangles.push([corner, corner + 1, corner + side + 1]);
+            mesh.triangles.push([corner, corner + side + 1, corner + side]);
+        }
+    }
+    mesh
+}
+
+/// A unit sphere with its poles along the viewing direction, so its outline is the equator.
+fn sphere() -> MeshData {
+    surface(64, |u, v| {
+        let (latitude, longitude) = (PI * (v - 0.5), TAU * u);
+        [latitude.cos() * longitude.cos(), latitude.cos() * longitude.sin(), latitude.sin()]
+    })
+}
+
+/// A torus lying face on to the viewer, 1.4 across its outside and 0.6 across its hole.
+fn torus() -> MeshData {
+    surface(64, |u, v| {
+        let (around, tube) = (TAU * u, TAU * v);
+        let radius = 1.0 + 0.4 * tube.cos();
+        [radius * around.cos(), radius * around.sin(), 0.4 * tube.sin()]
+    })
+}
+
+fn render(mesh: MeshData) -> ModelToImage {
+    // double-sided, so the outline doesn't depend on the winding
+    let mut model = ModelToImageBuilder::from_meshes(vec![mesh], Vec::new())
+        .with_size((SIZE, SIZE))
+        .with_double_sided(true)
+        .with_min_intensity(0.5)
+        .build()
+        .expect("build mesh");
+    model.render().expect("render mesh");
+    model
+}
+
+/// The middle of the image, and the smallest and largest distances of `points` from it.
+fn distances_from_centre(points: &[(f32, f32)]) -> (f32, f32) {
+    let centre = SIZE as f32 / 2.0;
+    points
+        .iter()
+        .map(|(x, y)| ((x - centre).powi(2) + (y - centre).powi(2)).sqrt())
+        .fold((f32::INFINITY, 0.0), |(min, max), distance| (min.min(distance), max.max(distance)))
+}
+
+#[test]
+fn a_sphere_has_one_round_outline() {
+    let contours = render(sphere()).silhouette_contours(0.5);
+    assert_eq!(contours.len(), 1);
+
+    // 80% of the image across with the default margin
+    let radius = SIZE as f32 * 0.8 / 2.0;
+    let (nearest, furthest) = distances_from_centre(&contours[0]);
+    // half a pixel for tracing along the pixel edges, plus the simplification tolerance
+    assert!(nearest > radius - 1.5 && furthest < radius + 1.5, "{nearest} to {furthest} against {radius}");
+    assert!(contours[0].len() >= 16, "{} points", contours[0].len());
+}
+
+#[test]
+fn a_torus_face_on_has_an_outline_and_a_hole() {
+    let mut contours = render(torus()).silhouette_contours(0.5);
+    assert_eq!(contours.len(), 2);
+
+    contours.sort_by_key(|contour| std::cmp::Reverse(contour.len()));
+    let outside = SIZE as f32 * 0.8 / 2.0;
+    let hole = outside * 0.6 / 1.4;
+    for (contour, radius) in contours.iter().zip([outside, hole]) {
+        let (nearest, furthest) = distances_from_centre(contour);
+        assert!(nearest > radius - 1.5 && furthest < radius + 1.5, "{nearest} to {furthest} against {radius}");
+    }
+}
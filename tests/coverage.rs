@@ -0,0 +1,35 @@
+mod fixtures;
+
+use model_to_image::{CoveragePolicy, InsufficientCoverage, ModelToImageBuilder};
+
+#[test]
+fn a_cube_filling_the_frame_covers_nearly_all_of_it() {
+    let dir = fixtures::fixture_dir("coverage");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir))
+        .with_size((64, 64))
+        .with_margin(0.0)
+        .build()
+        .expect("load cube");
+    model.render().expect("render cube");
+
+    let coverage = model.coverage();
+    assert!(coverage.fraction > 0.95, "{:?}", coverage);
+    assert_eq!(coverage.covered_pixels as f32 / (64.0 * 64.0), coverage.fraction);
+    let (min_x, min_y, max_x, max_y) = coverage.bounding_box.expect("covered");
+    assert!(min_x <= 1 && min_y <= 1 && max_x >= 62 && max_y >= 62, "{:?}", coverage.bounding_box);
+}
+
+#[test]
+fn an_empty_scene_covers_nothing_and_fails_a_minimum() {
+    let mut model = ModelToImageBuilder::from_meshes(Vec::new(), Vec::new())
+        .with_size((32, 32))
+        .with_min_coverage(0.05, CoveragePolicy::Fail)
+        .build()
+        .expect("build empty scene");
+
+    let err = model.render().err().expect("coverage below the minimum");
+    let insufficient = err.downcast_ref::<InsufficientCoverage>().expect("coverage error");
+    assert_eq!(insufficient.coverage.covered_pixels, 0);
+    assert_eq!(insufficient.coverage.fraction, 0.0);
+    assert_eq!(model.coverage().bounding_box, None);
+}
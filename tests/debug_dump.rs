@@ -0,0 +1,27 @@
+#![cfg(feature = "debug-dump")]
+
+mod fixtures;
+
+use model_to_image::ModelToImageBuilder;
+
+#[test]
+fn debug_dump_writes_every_buffer_at_the_render_size() {
+    let dir = fixtures::fixture_dir("debug_dump");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir))
+        .with_size((80, 48))
+        .build()
+        .expect("load cube");
+    model.render_gbuffer().expect("render cube");
+
+    let dump = dir.join("dump");
+    model.debug_dump(&dump).expect("dump buffers");
+
+    for name in ["colour.png", "depth.png", "coverage.png", "ids.png"] {
+        let dimensions = image::image_dimensions(dump.join(name)).expect(name);
+        assert_eq!(dimensions, (80, 48), "{} has the wrong size", name);
+    }
+    let framing = std::fs::read_to_string(dump.join("framing.json")).expect("framing.json");
+    for key in ["\"scale\"", "\"centre\"", "\"bounds\""] {
+        assert!(framing.contains(key), "framing.json has no {}: {}", key, framing);
+    }
+}
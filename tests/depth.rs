@@ -0,0 +1,103 @@
+mod fixtures;
+
+use image::{DynamicImage, Rgb, RgbImage};
+use model_to_image::{MaterialData, MeshData, ModelToImageBuilder, RenderMode, ViewPreset};
+
+#[test]
+fn depth_is_normalised_across_the_model() {
+    let dir = fixtures::fixture_dir("depth_is_normalised");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_stl_cube(&dir))
+        .with_size((64, 64))
+        .build()
+        .expect("load cube");
+
+    assert_eq!(model.depth_range(), (-1.0, 1.0));
+
+    let layers = model.render_layers().expect("render cube");
+    let covered: Vec<f32> = layers.depth.pixels().map(|p| p.0[0]).filter(|z| z.is_finite()).collect();
+    assert!(!covered.is_empty());
+    // only the front face of the cube is visible, which is the nearest point of the model
+    assert!(covered.iter().all(|&z| (z - 1.0).abs() < 1e-4), "front face depths: {:?}", &covered[..4]);
+}
+
+const BACKGROUND: [u8; 3] = [211, 211, 211];
+
+/// A red panel a little in front of a larger blue one, `gap` apart in depth.
+fn panels(gap: f32) -> (Vec<MeshData>, Vec<MaterialData>) {
+    let quad = |(x0, x1): (f32, f32), z: f32, material: usize| MeshData {
+        positions: vec![[x0, x0, z], [x1, x0, z], [x1, x1, z], [x0, x1, z]],
+        triangles: vec![[0, 1, 2], [0, 2, 3]],
+        uvs: vec![[0.5, 0.5]; 4],
+        material,
+        ..Default::default()
+    };
+    let flat = |colour: [u8; 3]| MaterialData {
+        texture: Some(DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, Rgb(colour)))),
+        ..Default::default()
+    };
+    (
+        vec![quad((0.25, 0.75), gap / 2.0, 0), quad((0.0, 1.0), -gap / 2.0, 1)],
+        vec![flat([255, 0, 0]), flat([0, 0, 255])],
+    )
+}
+
+fn render_panels(gap: f32, reversed: bool) -> RgbImage {
+    let (mut meshes, materials) = panels(gap);
+    if reversed {
+        meshes.reverse();
+    }
+    let mut model = ModelToImageBuilder::from_meshes(meshes, materials)
+        .with_size((64, 64))
+        .build()
+        .expect("build panels");
+    model.render().expect("render panels");
+    model.output().clone()
+}
+
+#[test]
+fn the_nearer_panel_wins_however_deep_the_model_is() {
+    let golden = render_panels(1.0, false);
+    let [r, g, b] = golden.get_pixel(32, 32).0;
+    assert!(r > 128 && g < 64 && b < 64, "centre: {:?}", [r, g, b]);
+    assert!(golden.pixels().any(|p| p.0[2] > 128 && p.0[0] < 64), "the far panel shows around the near one");
+
+    // normalising depth maps any range onto [0, 1], so neither the spread in depth nor the
+    // order the meshes are drawn in may change a single pixel
+    for gap in [1.0e-3, 1.0, 1.0e3] {
+        for reversed in [false, true] {
+            assert!(render_panels(gap, reversed) == golden, "gap {gap}, reversed {reversed}");
+        }
+    }
+}
+
+#[test]
+fn the_cube_fixtures_show_only_their_front_faces() {
+    let dir = fixtures::fixture_dir("depth_front_faces");
+    let cubes = [
+        fixtures::write_stl_cube(&dir),
+        fixtures::write_obj_cube(&dir),
+        fixtures::write_gltf_cube(&dir),
+    ];
+    for path in &cubes {
+        for view in [ViewPreset::Front, ViewPreset::Back, ViewPreset::Isometric] {
+            let mut model = ModelToImageBuilder::new(path)
+                .with_size((64, 64))
+                .with_view(view)
+                .with_render_mode(RenderMode::FacingDebug { normal_ticks: false })
+                .build()
+                .expect("load cube");
+            model.render().expect("render cube");
+
+            // the back faces are drawn too in this mode, in red, so any red pixel is a back face
+            // that won the depth test
+            let covered: Vec<[u8; 3]> = model.output().pixels().map(|p| p.0).filter(|&p| p != BACKGROUND).collect();
+            assert!(!covered.is_empty(), "{} from {:?} is empty", path.display(), view);
+            assert!(
+                covered.iter().all(|&p| p == [0, 0, 255]),
+                "{} from {:?} shows a back face",
+                path.display(),
+                view
+            );
+        }
+    }
+}
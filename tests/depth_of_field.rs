@@ -0,0 +1,39 @@
+use model_to_image::{MeshData, ModelToImageBuilder};
+
+/// Four upright bars, left to right, each one nearer the viewer than the last.
+fn staggered_bars() -> MeshData {
+    let mut bars = MeshData { name: "bars".into(), ..Default::default() };
+    for i in 0..4 {
+        let (x, z) = (i as f32 * 4.0, i as f32);
+        let first = bars.positions.len() as u32;
+        bars.positions.extend([[x, 0.0, z], [x + 2.0, 0.0, z], [x + 2.0, 6.0, z], [x, 6.0, z]]);
+        bars.triangles.extend([[first, first + 1, first + 2], [first, first + 2, first + 3]]);
+    }
+    bars
+}
+
+fn render(dof: bool) -> image::RgbImage {
+    let mut builder = ModelToImageBuilder::from_meshes(vec![staggered_bars()], Vec::new()).with_size((160, 64));
+    if dof {
+        builder = builder.with_depth_of_field(1.0, 1.0, 4.0);
+    }
+    let mut model = builder.build().expect("build bars");
+    model.render().expect("render bars");
+    model.output().clone()
+}
+
+#[test]
+fn depth_of_field_keeps_the_focus_sharp_and_blurs_the_far_end() {
+    let (sharp, blurred) = (render(false), render(true));
+    let changed_in = |columns: std::ops::Range<u32>| {
+        columns
+            .flat_map(|x| (0..64).map(move |y| (x, y)))
+            .filter(|&(x, y)| sharp.get_pixel(x, y) != blurred.get_pixel(x, y))
+            .count()
+    };
+
+    // the nearest bar is at the focal depth, the furthest a whole range behind it
+    let (far, near) = (changed_in(0..53), changed_in(125..160));
+    assert_eq!(near, 0, "the bar in focus was blurred");
+    assert!(far > 50, "only {} pixels of the far bar were blurred", far);
+}
@@ -0,0 +1,51 @@
+use image::{DynamicImage, Rgb, RgbImage};
+use model_to_image::{DepthPrecision, MaterialData, MeshData, ModelToImageBuilder};
+
+/// A unit square wall, a railing panel 1 mm in front of it, and a backdrop 1000 km behind
+/// both, so the depth of the scene is a billion times the gap between wall and railing: too
+/// little for single precision to tell them apart.
+fn deep_scene() -> (Vec<MeshData>, Vec<MaterialData>) {
+    let quad = |(x0, x1): (f32, f32), z: f32, material: usize| MeshData {
+        positions: vec![[x0, x0, z], [x1, x0, z], [x1, x1, z], [x0, x1, z]],
+        triangles: vec![[0, 1, 2], [0, 2, 3]],
+        uvs: vec![[0.5, 0.5]; 4],
+        material,
+        ..Default::default()
+    };
+    let flat = |colour: [u8; 3]| MaterialData {
+        texture: Some(DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, Rgb(colour)))),
+        ..Default::default()
+    };
+    (
+        vec![quad((0.0, 1.0), -1.0e6, 0), quad((0.0, 1.0), 0.0, 0), quad((0.25, 0.75), 0.001, 1)],
+        vec![flat([0, 0, 255]), flat([255, 0, 0])],
+    )
+}
+
+/// How many pixels in the middle of the image, which is all railing, show something else.
+fn speckles(precision: DepthPrecision) -> usize {
+    let (meshes, materials) = deep_scene();
+    let mut model = ModelToImageBuilder::from_meshes(meshes, materials)
+        .with_size((64, 64))
+        .with_depth_precision(precision)
+        .build()
+        .expect("build scene");
+    model.render().expect("render scene");
+    assert!(model.stats().scene_extent >= 1.0e6);
+
+    let img = model.output();
+    (24..40)
+        .flat_map(|y| (24..40).map(move |x| (x, y)))
+        .filter(|&(x, y)| img.get_pixel(x, y).0 != [255, 0, 0])
+        .count()
+}
+
+#[test]
+fn single_precision_depth_loses_millimetre_details_in_a_deep_scene() {
+    assert!(speckles(DepthPrecision::Single) > 0);
+}
+
+#[test]
+fn double_precision_depth_keeps_millimetre_details_in_a_deep_scene() {
+    assert_eq!(speckles(DepthPrecision::Double), 0);
+}
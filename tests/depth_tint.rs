@@ -0,0 +1,50 @@
+use model_to_image::{Colour, MeshData, ModelToImageBuilder, RenderMode};
+
+/// A UV sphere of radius 1 around the origin, wound counter-clockwise from outside.
+fn sphere(rings: u32, segments: u32) -> MeshData {
+    let mut positions = Vec::new();
+    for ring in 0..=rings {
+        let theta = std::f32::consts::PI * ring as f32 / rings as f32;
+        for segment in 0..=segments {
+            let phi = std::f32::consts::TAU * segment as f32 / segments as f32;
+            positions.push([theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin()]);
+        }
+    }
+    let mut triangles = Vec::new();
+    for ring in 0..rings {
+        for segment in 0..segments {
+            let a = ring * (segments + 1) + segment;
+            let b = a + segments + 1;
+            triangles.push([a, a + 1, b]);
+            triangles.push([a + 1, b + 1, b]);
+        }
+    }
+    MeshData { positions, triangles, ..Default::default() }
+}
+
+#[test]
+fn depth_tint_fades_a_sphere_from_near_to_far_colour() {
+    let mut model = ModelToImageBuilder::from_meshes(vec![sphere(48, 96)], Vec::new())
+        .with_size((96, 96))
+        .with_margin(0.0)
+        .with_render_mode(RenderMode::DepthTint {
+            near: Colour::from((255, 0, 0)),
+            far: Colour::from((0, 0, 255)),
+        })
+        // flat lighting, so only the depth shows
+        .with_min_intensity(1.0)
+        .build()
+        .expect("build sphere");
+    model.render().expect("render sphere");
+    let img = model.output();
+
+    // the share of red along a radius, from the middle out to near the rim
+    let red_share = |x: u32| {
+        let [r, _, b] = img.get_pixel(x, 48).0;
+        r as f32 / (r as f32 + b as f32).max(1.0)
+    };
+    let shares: Vec<f32> = (48..=92).step_by(4).map(red_share).collect();
+    assert!(shares[0] > 0.9, "the middle is not the near colour: {:?}", shares);
+    assert!(*shares.last().unwrap() < 0.8, "the rim is not tinted towards far: {:?}", shares);
+    assert!(shares.windows(2).all(|pair| pair[1] <= pair[0] + 0.02), "not a smooth fade: {:?}", shares);
+}
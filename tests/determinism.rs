@@ -0,0 +1,57 @@
+mod fixtures;
+
+use model_to_image::{Colour, MatchTolerance, ModelToImageBuilder, ViewPreset, assert_images_match};
+
+#[test]
+fn renders_are_byte_identical_across_runs() {
+    let dir = fixtures::fixture_dir("determinism");
+    let path = fixtures::write_obj_cube(&dir);
+
+    // every build loads the model from scratch, with its own texture cache
+    let render = || {
+        let mut model = ModelToImageBuilder::new(&path)
+            .with_size((96, 96))
+            .with_view(ViewPreset::Isometric)
+            .with_camera_roll(90.0)
+            .with_accumulation_samples(4)
+            .with_seed(7)
+            .with_rim_light(Colour::from((255, 200, 150)), 0.5, 2.0)
+            .build()
+            .expect("load cube");
+        model.render().expect("render cube");
+        model.output().clone()
+    };
+
+    let first = render();
+    let second = render();
+    assert_eq!(first.as_raw(), second.as_raw());
+    assert_images_match(&second, &first, MatchTolerance::EXACT);
+}
+
+#[test]
+fn eight_samples_smooth_the_silhouette_the_same_way_every_run() {
+    let dir = fixtures::fixture_dir("determinism_edges");
+    let path = fixtures::write_stl_cube(&dir);
+
+    let render = || {
+        let mut model = ModelToImageBuilder::new(&path)
+            .with_size((96, 96))
+            .with_view(ViewPreset::Isometric)
+            .with_accumulation_samples(8)
+            .build()
+            .expect("load cube");
+        model.render_layers().expect("render cube").colour
+    };
+
+    let first = render();
+    assert_eq!(first.as_raw(), render().as_raw());
+
+    // a column a quarter of the way in crosses the slanted top edge of the silhouette: from
+    // uncovered, through pixels the edge only partly covers, to fully covered
+    let column: Vec<u8> = (0..96).map(|y| first.get_pixel(24, y).0[3]).collect();
+    let edge = column.iter().position(|&alpha| alpha > 0).expect("the column crosses the cube");
+    let inside = column.iter().position(|&alpha| alpha == 255).expect("the column reaches inside the cube");
+    assert!(edge < inside, "no soft edge in {:?}", column);
+    assert!(column[edge..inside].iter().all(|&alpha| alpha > 0 && alpha < 255));
+    assert!(inside - edge <= 3, "the edge is {} pixels wide", inside - edge);
+}
@@ -0,0 +1,55 @@
+mod fixtures;
+
+use image::RgbImage;
+use model_to_image::{GLYPH_HEIGHT, ModelToImageBuilder, Units};
+
+const SIZE: u32 = 512;
+const TEXT: [u8; 3] = [40, 40, 40];
+const BACKGROUND: [u8; 3] = [211, 211, 211];
+
+fn render(labels: bool) -> RgbImage {
+    let dir = fixtures::fixture_dir(&format!("dimension_labels_{}", labels));
+    let mut builder = ModelToImageBuilder::new(&fixtures::write_stl_cube(&dir)).with_size((SIZE, SIZE));
+    if labels {
+        builder = builder.with_dimension_labels(Units::Centimetres, 100.0, true);
+    }
+    let mut model = builder.build().expect("load cube");
+    model.render().expect("render cube");
+    model.output().clone()
+}
+
+#[test]
+fn the_labels_and_scale_bar_sit_clear_of_the_model() {
+    let (plain, labelled) = (render(false), render(true));
+
+    let drawn: Vec<(u32, u32)> = labelled
+        .enumerate_pixels()
+        .filter(|(x, y, pixel)| pixel != &plain.get_pixel(*x, *y))
+        .map(|(x, y, _)| (x, y))
+        .collect();
+    assert!(!drawn.is_empty(), "no labels were drawn");
+    for &(x, y) in &drawn {
+        assert_eq!(labelled.get_pixel(x, y).0, TEXT, "({}, {}) isn't text", x, y);
+        // only ever over the background, never over the model
+        assert_eq!(plain.get_pixel(x, y).0, BACKGROUND, "({}, {}) is drawn over the model", x, y);
+    }
+
+    // a readable size at 512 pixels: glyphs drawn at twice their size, inside the frame with
+    // room to spare
+    let (min_y, max_y) = (drawn.iter().map(|p| p.1).min().unwrap(), drawn.iter().map(|p| p.1).max().unwrap());
+    let (min_x, max_x) = (drawn.iter().map(|p| p.0).min().unwrap(), drawn.iter().map(|p| p.0).max().unwrap());
+    assert!(min_x > 0 && max_x < SIZE - 1 && max_y < SIZE - 1, "labels touch the edge of the image");
+    assert!(max_y - min_y + 1 >= GLYPH_HEIGHT * 2, "labels are {} pixels high", max_y - min_y + 1);
+
+    // the sizes along the bottom left, the bar along the bottom right
+    let left = drawn.iter().filter(|p| p.0 < SIZE / 2).count();
+    assert!(left > 100, "{} pixels of sizes", left);
+    let longest_run = (min_y..=max_y)
+        .map(|y| {
+            let row: Vec<bool> = (SIZE / 2..SIZE).map(|x| labelled.get_pixel(x, y).0 == TEXT).collect();
+            row.split(|&text| !text).map(<[bool]>::len).max().unwrap_or_default()
+        })
+        .max()
+        .unwrap_or_default();
+    assert!(longest_run >= SIZE as usize / 16, "the scale bar is {} pixels long", longest_run);
+}
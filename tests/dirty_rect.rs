@@ -0,0 +1,78 @@
+mod fixtures;
+
+use std::path::Path;
+
+use image::Rgb;
+use model_to_image::{Colour, ModelToImage, ModelToImageBuilder, PixelRect, RenderPipeline, ViewPreset};
+
+fn build(path: &Path, configure: impl FnOnce(ModelToImageBuilder) -> ModelToImageBuilder) -> ModelToImage {
+    let builder = ModelToImageBuilder::new(path).with_size((96, 80)).with_view(ViewPreset::Isometric);
+    configure(builder).build().expect("build cube")
+}
+
+/// Renders the model in full and then paints `rects` over the finished image.
+fn render_and_corrupt(model: &mut ModelToImage, rects: &[PixelRect]) {
+    let mut pipeline = RenderPipeline::new(model);
+    pipeline.prepare().unwrap().rasterize().unwrap().post_process().unwrap().finish().unwrap();
+    let frame = pipeline.framebuffer().unwrap();
+    for rect in rects {
+        for y in rect.y..rect.y + rect.height {
+            for x in rect.x..rect.x + rect.width {
+                frame.colour.put_pixel(x, y, Rgb([255, 0, 255]));
+            }
+        }
+    }
+}
+
+#[test]
+fn redrawing_a_corrupted_rect_restores_the_full_render() {
+    let dir = fixtures::fixture_dir("dirty_rect");
+    let path = fixtures::write_obj_cube(&dir);
+    let mut full = build(&path, |builder| builder);
+    full.render().expect("render cube");
+
+    // across the edge of the cube, and then one reaching past the image
+    for rect in [PixelRect::new(30, 20, 25, 30), PixelRect::new(70, 60, 100, 100)] {
+        let mut model = build(&path, |builder| builder);
+        render_and_corrupt(&mut model, &[rect]);
+        assert_ne!(model.output(), full.output());
+        model.render_dirty_rect(rect).expect("redraw the rect");
+        assert_eq!(model.output(), full.output(), "{:?}", rect);
+        assert_eq!(model.coverage(), full.coverage());
+    }
+}
+
+#[test]
+fn pixels_outside_the_rect_are_left_alone() {
+    let dir = fixtures::fixture_dir("dirty_rect_outside");
+    let path = fixtures::write_obj_cube(&dir);
+    let (dirty, untouched) = (PixelRect::new(40, 30, 16, 16), PixelRect::new(0, 0, 8, 8));
+    let mut model = build(&path, |builder| builder.with_accumulation_samples(4));
+    render_and_corrupt(&mut model, &[dirty, untouched]);
+    model.render_dirty_rect(dirty).expect("redraw the rect");
+
+    assert_eq!(model.output().get_pixel(3, 3), &Rgb([255, 0, 255]));
+    assert_ne!(model.output().get_pixel(47, 37), &Rgb([255, 0, 255]));
+}
+
+#[test]
+fn effects_that_spread_fall_back_to_a_full_render() {
+    let dir = fixtures::fixture_dir("dirty_rect_outline");
+    let path = fixtures::write_obj_cube(&dir);
+    let outlined = |builder: ModelToImageBuilder| builder.with_outline(Colour::from((0, 0, 0)), 2);
+    let mut full = build(&path, outlined);
+    full.render().expect("render cube");
+
+    let mut model = build(&path, outlined);
+    let (dirty, elsewhere) = (PixelRect::new(30, 20, 25, 30), PixelRect::new(0, 0, 8, 8));
+    render_and_corrupt(&mut model, &[dirty, elsewhere]);
+    model.render_dirty_rect(dirty).expect("redraw the rect");
+    assert_eq!(model.output(), full.output());
+}
+
+#[test]
+fn a_full_render_is_needed_first() {
+    let dir = fixtures::fixture_dir("dirty_rect_first");
+    let mut model = build(&fixtures::write_obj_cube(&dir), |builder| builder);
+    assert!(model.render_dirty_rect(PixelRect::new(0, 0, 8, 8)).is_err());
+}
@@ -0,0 +1,51 @@
+use image::{DynamicImage, GrayImage, Luma};
+use model_to_image::{Axis, MeshData, ModelToImageBuilder};
+
+/// A flat 2 x 2 grid of `cells` x `cells` squares in the XY plane, with UVs across it.
+fn flat_grid(cells: u32) -> MeshData {
+    let side = cells + 1;
+    let mut grid = MeshData::default();
+    for y in 0..side {
+        for x in 0..side {
+            let (u, v) = (x as f32 / cells as f32, y as f32 / cells as f32);
+            grid.positions.push([u * 2.0, v * 2.0, 0.0]);
+            grid.uvs.push([u, v]);
+        }
+    }
+    for y in 0..cells {
+        for x in 0..cells {
+            let corner = y * side + x;
+            grid.triangles.push([corner, corner + 1, corner + side + 1]);
+            grid.triangles.push([corner, corner + side + 1, corner + side]);
+        }
+    }
+    grid
+}
+
+/// White in the middle, falling off to black at the edges like the top of a sphere.
+fn dome_heightmap() -> DynamicImage {
+    DynamicImage::ImageLuma8(GrayImage::from_fn(64, 64, |x, y| {
+        let (dx, dy) = ((x as f32 - 31.5) / 32.0, (y as f32 - 31.5) / 32.0);
+        Luma([((1.0 - dx * dx - dy * dy).max(0.0).sqrt() * 255.0) as u8])
+    }))
+}
+
+#[test]
+fn a_flat_grid_displaced_by_a_radial_heightmap_renders_as_a_dome() {
+    let mut model = ModelToImageBuilder::from_meshes(vec![flat_grid(32)], Vec::new())
+        .with_size((96, 96))
+        .with_displacement(dome_heightmap(), 0.8, Axis::Z)
+        .build()
+        .expect("build grid");
+    model.render().expect("render grid");
+
+    // framing and depth include the raised middle
+    let (low, high) = model.depth_range();
+    assert!((high - low - 0.8).abs() < 0.05, "depth range {:?}", (low, high));
+
+    // lit head on, the top of the dome faces the light and its slopes turn away from it
+    let img = model.output();
+    let brightness = |x: u32, y: u32| img.get_pixel(x, y).0[0];
+    let (top, slope) = (brightness(48, 48), brightness(48 + 36, 48));
+    assert!(top > slope + 40, "top {} vs slope {}", top, slope);
+}
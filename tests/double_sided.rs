@@ -0,0 +1,73 @@
+mod fixtures;
+
+use std::path::Path;
+
+use model_to_image::{MaterialData, MeshData, ModelToImage, ModelToImageBuilder, ViewPreset};
+
+/// The model rendered from `view`, lit from the camera so only faces turned to it are lit.
+fn render(
+    path: &Path,
+    view: ViewPreset,
+    configure: impl FnOnce(ModelToImageBuilder) -> ModelToImageBuilder,
+) -> ModelToImage {
+    let builder = ModelToImageBuilder::new(&path.to_path_buf())
+        .with_size((96, 48))
+        .with_view(view)
+        .with_light_direction([0.0, 0.0, -1.0]);
+    let mut model = configure(builder).build().expect("build foliage");
+    model.render().expect("render foliage");
+    model
+}
+
+#[test]
+fn a_double_sided_card_shows_from_both_sides() {
+    let dir = fixtures::fixture_dir("double_sided_marked");
+    let path = fixtures::write_glb_foliage(&dir, true);
+
+    let front = render(&path, ViewPreset::Front, |builder| builder);
+    let back = render(&path, ViewPreset::Back, |builder| builder);
+    let (front_pixels, back_pixels) = (front.coverage().covered_pixels, back.coverage().covered_pixels);
+    assert!(back_pixels as f32 >= front_pixels as f32 * 0.95, "{} against {}", back_pixels, front_pixels);
+
+    // the cube is still culled where it faces away
+    assert!(back.stats().triangles_culled > 0);
+    assert_eq!(back.stats().triangles_culled, front.stats().triangles_culled);
+}
+
+#[test]
+fn an_unmarked_card_is_culled_from_behind() {
+    let dir = fixtures::fixture_dir("double_sided_unmarked");
+    let path = fixtures::write_glb_foliage(&dir, false);
+
+    let front = render(&path, ViewPreset::Front, |builder| builder);
+    let back = render(&path, ViewPreset::Back, |builder| builder);
+    let (front_pixels, back_pixels) = (front.coverage().covered_pixels, back.coverage().covered_pixels);
+    assert!((back_pixels as f32) < front_pixels as f32 * 0.75, "{} against {}", back_pixels, front_pixels);
+    assert_eq!(back.stats().triangles_culled, front.stats().triangles_culled + 2);
+
+    // unless every material is drawn from both sides
+    let everything = render(&path, ViewPreset::Back, |builder| builder.with_double_sided(true));
+    assert!(everything.coverage().covered_pixels as f32 >= front_pixels as f32 * 0.95);
+}
+
+#[test]
+fn double_sided_materials_can_be_given_directly() {
+    // a square facing away from the camera
+    let quad = MeshData {
+        positions: vec![[0.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 1.0, 0.0], [1.0, 0.0, 0.0]],
+        triangles: vec![[0, 1, 2], [0, 2, 3]],
+        ..Default::default()
+    };
+    let drawn = |double_sided: bool| {
+        let material = MaterialData { double_sided, ..Default::default() };
+        let mut model = ModelToImageBuilder::from_meshes(vec![quad.clone()], vec![material])
+            .with_size((16, 16))
+            .with_light_direction([0.0, 0.0, -1.0])
+            .build()
+            .expect("build quad");
+        model.render().expect("render quad");
+        model.stats().triangles_drawn
+    };
+    assert_eq!(drawn(false), 0);
+    assert_eq!(drawn(true), 2);
+}
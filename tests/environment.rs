@@ -0,0 +1,62 @@
+mod fixtures;
+
+use image::{DynamicImage, Rgb, RgbImage};
+use model_to_image::ModelToImageBuilder;
+
+#[test]
+fn environment_replaces_the_grey_background() {
+    // a crude sunset: orange sky over a purple ground
+    let (sky, ground) = (Rgb([250, 140, 40]), Rgb([70, 30, 90]));
+    let panorama = RgbImage::from_fn(64, 32, |_, y| if y < 16 { sky } else { ground });
+
+    let dir = fixtures::fixture_dir("environment_background");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_stl_cube(&dir))
+        .with_size((64, 64))
+        .with_environment(DynamicImage::ImageRgb8(panorama))
+        .build()
+        .expect("load cube");
+    model.render().expect("render cube");
+
+    let output = model.output();
+    assert_eq!(*output.get_pixel(0, 0), sky);
+    assert_eq!(*output.get_pixel(0, 63), ground);
+    // the cube is still drawn in front of it
+    assert_ne!(*output.get_pixel(32, 32), sky);
+    assert_ne!(*output.get_pixel(32, 32), ground);
+}
+
+#[test]
+fn environment_options_can_come_before_the_environment() {
+    // red to the left of straight ahead and blue to the right, so turning it shows
+    let panorama = RgbImage::from_fn(64, 32, |x, _| if x < 32 { Rgb([200, 40, 40]) } else { Rgb([40, 40, 200]) });
+    let panorama = DynamicImage::ImageRgb8(panorama);
+    let dir = fixtures::fixture_dir("environment_option_order");
+    let path = fixtures::write_stl_cube(&dir);
+    let render = |builder: ModelToImageBuilder| {
+        let mut model = builder.with_size((64, 64)).with_min_intensity(0.3).build().expect("load cube");
+        model.render().expect("render cube");
+        model.output().clone()
+    };
+
+    let after = render(
+        ModelToImageBuilder::new(&path)
+            .with_environment(panorama.clone())
+            .with_environment_rotation(60.0, 10.0)
+            .with_environment_ambient(true),
+    );
+    let before = render(
+        ModelToImageBuilder::new(&path)
+            .with_environment_rotation(60.0, 10.0)
+            .with_environment_ambient(true)
+            .with_environment(panorama.clone()),
+    );
+    let plain = render(ModelToImageBuilder::new(&path).with_environment(panorama.clone()));
+    assert_eq!(before, after);
+    assert_ne!(before, plain, "the options took effect");
+
+    let key = |builder: ModelToImageBuilder| builder.cache_key().expect("cache key");
+    assert_eq!(
+        key(ModelToImageBuilder::new(&path).with_environment_rotation(60.0, 10.0).with_environment(panorama.clone())),
+        key(ModelToImageBuilder::new(&path).with_environment(panorama).with_environment_rotation(60.0, 10.0)),
+    );
+}
@@ -0,0 +1,44 @@
+use image::{DynamicImage, Rgb, RgbImage};
+use model_to_image::{Colour, MaterialData, MeshData, ModelToImageBuilder, Overlay};
+
+/// Three red panels side by side, touching, as the parts of an assembly.
+fn assembly() -> (Vec<MeshData>, Vec<MaterialData>) {
+    let part = |x0: f32| MeshData {
+        positions: vec![[x0, 0.0, 0.0], [x0 + 1.0, 0.0, 0.0], [x0 + 1.0, 1.0, 0.0], [x0, 1.0, 0.0]],
+        triangles: vec![[0, 1, 2], [0, 2, 3]],
+        uvs: vec![[0.5, 0.5]; 4],
+        ..Default::default()
+    };
+    let red = MaterialData {
+        texture: Some(DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, Rgb([255, 0, 0])))),
+        ..Default::default()
+    };
+    (vec![part(-1.5), part(-0.5), part(0.5)], vec![red])
+}
+
+fn render(factor: f32) -> RgbImage {
+    let (meshes, materials) = assembly();
+    let mut model = ModelToImageBuilder::from_meshes(meshes, materials)
+        .with_size((128, 64))
+        .with_explode(factor)
+        .with_overlay(Overlay::ExplodeConnectors { colour: Colour::from((0, 0, 255)), width_px: 1 })
+        .build()
+        .expect("build assembly");
+    model.render().expect("render assembly");
+    model.output().clone()
+}
+
+/// The number of separate runs of red pixels along the middle row of the image.
+fn parts_in_middle_row(img: &RgbImage) -> usize {
+    let y = img.height() / 2 + 4;
+    let red: Vec<bool> = (0..img.width()).map(|x| img.get_pixel(x, y).0 == [255, 0, 0]).collect();
+    red.iter().zip(std::iter::once(&false).chain(&red)).filter(|&(&now, &before)| now && !before).count()
+}
+
+#[test]
+fn exploding_an_assembly_opens_gaps_between_its_parts() {
+    assert_eq!(parts_in_middle_row(&render(0.0)), 1, "the parts should touch without exploding");
+    let exploded = render(1.5);
+    assert_eq!(parts_in_middle_row(&exploded), 3, "the exploded parts should be apart");
+    assert!(exploded.pixels().any(|pixel| pixel.0 == [0, 0, 255]), "no connector lines were drawn");
+}
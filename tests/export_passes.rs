@@ -0,0 +1,106 @@
+mod fixtures;
+
+use image::GenericImageView;
+use model_to_image::{Mask, ModelToImage, ModelToImageBuilder, PassContainer};
+
+fn cube(dir: &std::path::Path) -> ModelToImage {
+    ModelToImageBuilder::new(&fixtures::write_obj_cube(dir))
+        .with_size((40, 24))
+        .with_mask(Mask::Circle)
+        .build()
+        .expect("build cube")
+}
+
+/// The name and contents of every entry in a zip, read from the local headers.
+fn zip_entries(zip: &[u8]) -> Vec<(String, &[u8])> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    while zip[offset..offset + 4] == 0x0403_4b50_u32.to_le_bytes() {
+        let u16_at = |at: usize| u16::from_le_bytes([zip[offset + at], zip[offset + at + 1]]) as usize;
+        let size = u32::from_le_bytes(zip[offset + 18..offset + 22].try_into().unwrap()) as usize;
+        assert_eq!(u16_at(8), 0, "stored without compression");
+        let (name_len, extra_len) = (u16_at(26), u16_at(28));
+        let name = String::from_utf8(zip[offset + 30..offset + 30 + name_len].to_vec()).unwrap();
+        let start = offset + 30 + name_len + extra_len;
+        entries.push((name, &zip[start..start + size]));
+        offset = start + size;
+    }
+    assert_eq!(zip[offset..offset + 4], 0x0201_4b50_u32.to_le_bytes(), "central directory after the entries");
+    entries
+}
+
+/// The `PageName` of every page in a little-endian TIFF.
+fn tiff_page_names(tiff: &[u8]) -> Vec<String> {
+    let u16_at = |at: usize| u16::from_le_bytes([tiff[at], tiff[at + 1]]) as usize;
+    let u32_at = |at: usize| u32::from_le_bytes(tiff[at..at + 4].try_into().unwrap()) as usize;
+    assert_eq!(&tiff[..4], b"II*\0");
+    let mut names = Vec::new();
+    let mut ifd = u32_at(4);
+    while ifd != 0 {
+        let count = u16_at(ifd);
+        for entry in (0..count).map(|idx| ifd + 2 + idx * 12) {
+            if u16_at(entry) == 285 {
+                let (len, at) = (u32_at(entry + 4), u32_at(entry + 8));
+                names.push(String::from_utf8(tiff[at..at + len - 1].to_vec()).unwrap());
+            }
+        }
+        ifd = u32_at(ifd + 2 + count * 12);
+    }
+    names
+}
+
+#[test]
+fn a_zip_holds_the_passes_that_were_rendered() {
+    let dir = fixtures::fixture_dir("export_passes_zip");
+    let mut model = cube(&dir);
+    model.render().expect("render cube");
+    let path = dir.join("out/passes.zip");
+    model.export_passes(&path, PassContainer::ZipOfPngs).expect("export passes");
+
+    let zip = std::fs::read(&path).unwrap();
+    let entries = zip_entries(&zip);
+    let names: Vec<&str> = entries.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["beauty.png", "depth.png", "alpha.png"]);
+    for (name, png) in &entries {
+        let image = image::load_from_memory(png).unwrap_or_else(|err| panic!("decode {}: {}", name, err));
+        assert_eq!(image.dimensions(), (40, 24), "{}", name);
+    }
+
+    let beauty = image::load_from_memory(entries[0].1).unwrap().to_rgb8();
+    assert_eq!(&beauty, model.output());
+    let depth = image::load_from_memory(entries[1].1).unwrap();
+    assert!(matches!(depth, image::DynamicImage::ImageLuma16(_)), "16-bit depth");
+}
+
+#[test]
+fn the_gbuffer_adds_normal_and_id_passes() {
+    let dir = fixtures::fixture_dir("export_passes_gbuffer");
+    let mut model = cube(&dir);
+    model.render_gbuffer().expect("render cube");
+    let path = dir.join("passes.zip");
+    model.export_passes(&path, PassContainer::ZipOfPngs).expect("export passes");
+
+    let zip = std::fs::read(&path).unwrap();
+    let names: Vec<String> = zip_entries(&zip).into_iter().map(|(name, _)| name).collect();
+    assert_eq!(names, ["beauty.png", "depth.png", "normal.png", "id.png"]);
+}
+
+#[test]
+fn a_multi_page_tiff_names_every_page() {
+    let dir = fixtures::fixture_dir("export_passes_tiff");
+    let mut model = cube(&dir);
+    model.render().expect("render cube");
+    let path = dir.join("passes.tiff");
+    model.export_passes(&path, PassContainer::MultiTiff).expect("export passes");
+
+    let tiff = std::fs::read(&path).unwrap();
+    assert_eq!(tiff_page_names(&tiff), ["beauty", "depth", "alpha"]);
+}
+
+#[test]
+fn exporting_before_rendering_fails() {
+    let dir = fixtures::fixture_dir("export_passes_unrendered");
+    let model = cube(&dir);
+    let err = model.export_passes(dir.join("passes.zip"), PassContainer::ZipOfPngs).unwrap_err();
+    assert!(err.to_string().contains("call render first"), "{}", err);
+}
@@ -0,0 +1,74 @@
+mod fixtures;
+
+use std::collections::HashSet;
+
+use model_to_image::{MeshData, ModelToImageBuilder, RenderMode, ViewPreset};
+
+const BLUE: [u8; 3] = [0, 0, 255];
+const RED: [u8; 3] = [255, 0, 0];
+const BACKGROUND: [u8; 3] = [211, 211, 211];
+
+/// The cube with the two triangles of each face in `flipped` wound the wrong way round.
+fn cube_with_flipped_faces(flipped: &[usize]) -> MeshData {
+    let triangles = fixtures::CUBE_TRIANGLES
+        .iter()
+        .enumerate()
+        .map(|(triangle_idx, &[a, b, c])| if flipped.contains(&(triangle_idx / 2)) { [a, c, b] } else { [a, b, c] })
+        .map(|triangle| triangle.map(u32::from))
+        .collect();
+    MeshData { positions: fixtures::CUBE_VERTICES.to_vec(), triangles, ..Default::default() }
+}
+
+/// The pixels drawn red and the pixels drawn blue, from the corner the three front faces show.
+fn facing(flipped: &[usize]) -> (HashSet<(u32, u32)>, HashSet<(u32, u32)>) {
+    let mut model = ModelToImageBuilder::from_meshes(vec![cube_with_flipped_faces(flipped)], Vec::new())
+        .with_size((96, 96))
+        .with_view(ViewPreset::Isometric)
+        .with_render_mode(RenderMode::FacingDebug { normal_ticks: false })
+        .build()
+        .expect("build cube");
+    model.render().expect("render cube");
+
+    let (mut red, mut blue) = (HashSet::new(), HashSet::new());
+    for (x, y, pixel) in model.output().enumerate_pixels() {
+        match pixel.0 {
+            RED => red.insert((x, y)),
+            BLUE => blue.insert((x, y)),
+            other => {
+                assert_eq!(other, BACKGROUND, "({}, {}) is neither face colour", x, y);
+                false
+            }
+        };
+    }
+    (red, blue)
+}
+
+#[test]
+fn exactly_the_flipped_faces_show_red() {
+    let (red, blue) = facing(&[]);
+    assert!(red.is_empty());
+    let covered = blue.len();
+
+    // flipping one face at a time finds the three the view shows, each a third of the cube
+    let mut visible = Vec::new();
+    for face in 0..6 {
+        let (red, _) = facing(&[face]);
+        if !red.is_empty() {
+            let share = red.len() as f32 / covered as f32;
+            assert!((share - 1.0 / 3.0).abs() < 0.05, "face {} covers {} of the cube", face, share);
+            visible.push((face, red));
+        }
+    }
+    assert_eq!(visible.len(), 3);
+
+    let (first, second) = (&visible[0], &visible[1]);
+    let (red, blue) = facing(&[first.0, second.0]);
+    let expected: HashSet<(u32, u32)> = first.1.union(&second.1).copied().collect();
+    // the pixels along the edges the faces share can go to either face
+    let misplaced = red.symmetric_difference(&expected).count();
+    assert!(misplaced < covered / 50, "{} pixels differ from the two faces", misplaced);
+    assert!(red.len() + blue.len() >= covered - covered / 50);
+    // the face left alone is still blue
+    let third = &visible[2].1;
+    assert!(third.iter().filter(|pixel| blue.contains(pixel)).count() > third.len() * 9 / 10);
+}
@@ -0,0 +1,516 @@
+//! Tiny models written out at test time, so the tests don't depend on binary files in the
+//! repository.
+
+// each test binary only uses some of the fixtures
+#![allow(dead_code)]
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Corners of a 2x2x2 cube centred on the origin.
+pub const CUBE_VERTICES: [[f32; 3]; 8] = [
+    [-1.0, -1.0, -1.0],
+    [1.0, -1.0, -1.0],
+    [1.0, 1.0, -1.0],
+    [-1.0, 1.0, -1.0],
+    [-1.0, -1.0, 1.0],
+    [1.0, -1.0, 1.0],
+    [1.0, 1.0, 1.0],
+    [-1.0, 1.0, 1.0],
+];
+
+/// Triangles of the cube, wound counter-clockwise seen from outside.
+pub const CUBE_TRIANGLES: [[u16; 3]; 12] = [
+    [4, 5, 6],
+    [4, 6, 7],
+    [0, 2, 1],
+    [0, 3, 2],
+    [1, 2, 6],
+    [1, 6, 5],
+    [0, 4, 7],
+    [0, 7, 3],
+    [3, 7, 6],
+    [3, 6, 2],
+    [0, 1, 5],
+    [0, 5, 4],
+];
+
+/// A fresh directory for one test's fixtures.
+pub fn fixture_dir(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("fixtures").join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("create fixture directory");
+    dir
+}
+
+fn face_normal(triangle: &[u16; 3]) -> [f32; 3] {
+    let [a, b, c] = triangle.map(|idx| CUBE_VERTICES[idx as usize]);
+    let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+    let n = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+    let length = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+    [n[0] / length, n[1] / length, n[2] / length]
+}
+
+/// An ASCII STL cube.
+pub fn write_stl_cube(dir: &Path) -> PathBuf {
+    let mut stl = String::from("solid cube\n");
+    for triangle in &CUBE_TRIANGLES {
+        let n = face_normal(triangle);
+        writeln!(stl, "  facet normal {} {} {}\n    outer loop", n[0], n[1], n[2]).unwrap();
+        for &idx in triangle {
+            let v = CUBE_VERTICES[idx as usize];
+            writeln!(stl, "      vertex {} {} {}", v[0], v[1], v[2]).unwrap();
+        }
+        stl.push_str("    endloop\n  endfacet\n");
+    }
+    stl.push_str("endsolid cube\n");
+
+    let path = dir.join("cube.stl");
+    fs::write(&path, stl).expect("write stl fixture");
+    path
+}
+
+/// An OBJ cube with texture coordinates and a red material in a separate MTL file.
+pub fn write_obj_cube(dir: &Path) -> PathBuf {
+    fs::write(dir.join("cube.mtl"), "newmtl red\nKd 0.9 0.1 0.1\n").expect("write mtl fixture");
+    write_obj(dir)
+}
+
+/// The OBJ cube, with its material textured by `texture` saved as `file_name` next to it.
+pub fn write_obj_cube_with_texture(dir: &Path, file_name: &str, texture: &[u8]) -> PathBuf {
+    fs::write(dir.join(file_name), texture).expect("write texture fixture");
+    fs::write(dir.join("cube.mtl"), format!("newmtl red\nKd 0.9 0.1 0.1\nmap_Kd {}\n", file_name))
+        .expect("write mtl fixture");
+    write_obj(dir)
+}
+
+/// The cube scaled by `scale`, as an OBJ file without materials, e.g. for the same model saved
+/// in different units.
+pub fn write_scaled_cube(dir: &Path, scale: f32) -> PathBuf {
+    let mut obj = String::new();
+    for v in &CUBE_VERTICES {
+        writeln!(obj, "v {} {} {}", v[0] * scale, v[1] * scale, v[2] * scale).unwrap();
+    }
+    for triangle in &CUBE_TRIANGLES {
+        let [a, b, c] = triangle.map(|idx| idx + 1);
+        writeln!(obj, "f {a} {b} {c}").unwrap();
+    }
+
+    let path = dir.join("scaled_cube.obj");
+    fs::write(&path, obj).expect("write obj fixture");
+    path
+}
+
+/// A tower four times as tall as it is wide, standing along Z the way Z-up exporters write one.
+pub fn write_obj_z_up_tower(dir: &Path) -> PathBuf {
+    let mut obj = String::new();
+    for v in &CUBE_VERTICES {
+        writeln!(obj, "v {} {} {}", v[0] * 0.5, v[1] * 0.5, v[2] * 2.0).unwrap();
+    }
+    for triangle in &CUBE_TRIANGLES {
+        let [a, b, c] = triangle.map(|idx| idx + 1);
+        writeln!(obj, "f {a} {b} {c}").unwrap();
+    }
+
+    let path = dir.join("tower.obj");
+    fs::write(&path, obj).expect("write obj fixture");
+    path
+}
+
+/// An uncompressed 24-bit TGA filled with one colour.
+pub fn tga_bytes(width: u16, height: u16, rgb: [u8; 3]) -> Vec<u8> {
+    let mut tga = vec![0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    tga.extend(width.to_le_bytes());
+    tga.extend(height.to_le_bytes());
+    tga.extend([24, 0]);
+    for _ in 0..width as usize * height as usize {
+        tga.extend([rgb[2], rgb[1], rgb[0]]);
+    }
+    tga
+}
+
+/// The OBJ cube with two extra faces that each use a vertex twice, so they have no area.
+pub fn write_obj_cube_with_degenerates(dir: &Path) -> PathBuf {
+    let path = write_obj(dir);
+    let mut obj = fs::read_to_string(&path).expect("read obj fixture");
+    obj.push_str("f 1/1 1/1 2/2\nf 3/3 4/4 4/4\n");
+    let path = dir.join("cube_with_degenerates.obj");
+    fs::write(&path, obj).expect("write obj fixture");
+    path
+}
+
+/// The start of a KTX2 file, enough for its format to be recognised.
+pub fn ktx2_bytes() -> Vec<u8> {
+    let mut ktx2 = b"\xABKTX 20\xBB\r\n\x1A\n".to_vec();
+    ktx2.resize(80, 0);
+    ktx2
+}
+
+fn write_obj(dir: &Path) -> PathBuf {
+    let mut obj = String::from("mtllib cube.mtl\n");
+    for v in &CUBE_VERTICES {
+        writeln!(obj, "v {} {} {}", v[0], v[1], v[2]).unwrap();
+    }
+    for v in &CUBE_VERTICES {
+        writeln!(obj, "vt {} {}", (v[0] + 1.0) / 2.0, (v[1] + 1.0) / 2.0).unwrap();
+    }
+    obj.push_str("usemtl red\n");
+    for triangle in &CUBE_TRIANGLES {
+        // OBJ indices start at 1
+        let [a, b, c] = triangle.map(|idx| idx + 1);
+        writeln!(obj, "f {a}/{a} {b}/{b} {c}/{c}").unwrap();
+    }
+
+    let path = dir.join("cube.obj");
+    fs::write(&path, obj).expect("write obj fixture");
+    path
+}
+
+/// A glTF cube with its vertex and index data in an external `.bin` buffer.
+pub fn write_gltf_cube(dir: &Path) -> PathBuf {
+    write_gltf(dir, "cube", r#"{ "mesh": 0 }"#, &[], false)
+}
+
+/// A glTF cube with an extra empty node called `name` at `translation`, e.g. for an attachment
+/// point.
+pub fn write_gltf_cube_with_marker(dir: &Path, name: &str, translation: [f32; 3]) -> PathBuf {
+    let marker = format!(
+        r#"{{ "name": "{}", "translation": [{}, {}, {}] }}"#,
+        name, translation[0], translation[1], translation[2]
+    );
+    write_gltf(dir, "cube_with_marker", r#"{ "mesh": 0 }"#, &[marker], false)
+}
+
+/// An ASCII FBX cube with `centimetres` per unit, the `UnitScaleFactor` FBX stores its units
+/// in, and its corners at `half_size` units from the middle.
+pub fn write_fbx_cube(dir: &Path, centimetres: f32, half_size: f32) -> PathBuf {
+    let properties = format!(
+        r#"		P: "UnitScaleFactor", "double", "Number", "",{centimetres}
+		P: "OriginalUnitScaleFactor", "double", "Number", "",{centimetres}"#
+    );
+    write_fbx(dir, "cube", [half_size; 3], &properties)
+}
+
+/// The tower of [`write_obj_z_up_tower`] in metres as an FBX file, with its `UpAxis` setting
+/// saying Z is up.
+pub fn write_fbx_z_up_tower(dir: &Path) -> PathBuf {
+    let properties = r#"		P: "UpAxis", "int", "Integer", "",2
+		P: "UpAxisSign", "int", "Integer", "",1
+		P: "UnitScaleFactor", "double", "Number", "",100"#;
+    write_fbx(dir, "tower", [0.5, 0.5, 2.0], properties)
+}
+
+/// Writes the cube, stretched by `scale` along each axis, as `<stem>.fbx` with `properties`
+/// (`P:` lines) in its global settings.
+fn write_fbx(dir: &Path, stem: &str, scale: [f32; 3], properties: &str) -> PathBuf {
+    let vertices: Vec<String> =
+        CUBE_VERTICES.iter().flat_map(|v| [0, 1, 2].map(|c| (v[c] * scale[c]).to_string())).collect();
+    // the last corner of every polygon is stored as -(index + 1)
+    let indices: Vec<String> = CUBE_TRIANGLES
+        .iter()
+        .flat_map(|&[a, b, c]| [a as i32, b as i32, -(c as i32) - 1])
+        .map(|idx| idx.to_string())
+        .collect();
+    let fbx = format!(
+        r#"; FBX 7.4.0 project file
+FBXHeaderExtension:  {{
+	FBXHeaderVersion: 1003
+	FBXVersion: 7400
+}}
+GlobalSettings:  {{
+	Version: 1000
+	Properties70:  {{
+{properties}
+	}}
+}}
+Objects:  {{
+	Geometry: 1000, "Geometry::Cube", "Mesh" {{
+		Vertices: *{vertex_count} {{
+			a: {vertices}
+		}}
+		PolygonVertexIndex: *{index_count} {{
+			a: {indices}
+		}}
+		GeometryVersion: 124
+	}}
+	Model: 2000, "Model::Cube", "Mesh" {{
+		Version: 232
+	}}
+}}
+Connections:  {{
+	C: "OO",1000,2000
+	C: "OO",2000,0
+}}
+"#,
+        vertex_count = vertices.len(),
+        vertices = vertices.join(","),
+        index_count = indices.len(),
+        indices = indices.join(","),
+    );
+
+    let path = dir.join(format!("{}.fbx", stem));
+    fs::write(&path, fbx).expect("write fbx fixture");
+    path
+}
+
+/// A flat, binary PLY grid of `cells` x `cells` squares (two triangles each) spanning -1..1 on
+/// x and y, bumped a little in z so it isn't all one plane. Big enough grids stand in for
+/// photogrammetry scans.
+pub fn write_ply_grid(dir: &Path, cells: u32) -> PathBuf {
+    let side = cells + 1;
+    let header = format!(
+        "ply\nformat binary_little_endian 1.0\nelement vertex {}\nproperty float x\nproperty float y\nproperty float z\nelement face {}\nproperty list uchar uint vertex_indices\nend_header\n",
+        side * side,
+        cells * cells * 2
+    );
+
+    let mut ply = header.into_bytes();
+    for y in 0..side {
+        for x in 0..side {
+            let (fx, fy) = (x as f32 / cells as f32 * 2.0 - 1.0, y as f32 / cells as f32 * 2.0 - 1.0);
+            let z = 0.1 * (fx * 7.0).sin() * (fy * 5.0).cos();
+            for component in [fx, fy, z] {
+                ply.extend_from_slice(&component.to_le_bytes());
+            }
+        }
+    }
+    for y in 0..cells {
+        for x in 0..cells {
+            let corner = y * side + x;
+            for triangle in [[corner, corner + 1, corner + side + 1], [corner, corner + side + 1, corner + side]] {
+                ply.push(3);
+                for idx in triangle {
+                    ply.extend_from_slice(&idx.to_le_bytes());
+                }
+            }
+        }
+    }
+
+    let path = dir.join(format!("grid_{}.ply", cells));
+    fs::write(&path, ply).expect("write ply fixture");
+    path
+}
+
+/// A rigged glTF arm: a flat strip four units long along +X, with an upper arm bone from the
+/// shoulder at the origin to the elbow at x = 2, and a lower arm bone from there to the hand.
+/// The weights blend from one bone to the other across the middle of the strip, between
+/// x = 1 and x = 3.
+pub fn write_gltf_arm(dir: &Path) -> PathBuf {
+    let mut buffer = Vec::new();
+    let mut views = Vec::new();
+    let mut view = |buffer: &mut Vec<u8>, bytes: Vec<u8>| {
+        views.push(format!(
+            r#"{{ "buffer": 0, "byteOffset": {}, "byteLength": {} }}"#,
+            buffer.len(),
+            bytes.len()
+        ));
+        buffer.extend(bytes);
+    };
+
+    // two rows of five vertices, at x = 0 to 4
+    let columns = 0..5_u16;
+    let positions: Vec<[f32; 3]> = [0.0, 0.5]
+        .iter()
+        .flat_map(|&y| columns.clone().map(move |x| [x as f32, y, 0.0]))
+        .collect();
+    view(&mut buffer, positions.iter().flatten().flat_map(|c| c.to_le_bytes()).collect());
+    let indices: Vec<u16> = (0..4).flat_map(|x| [x, x + 1, x + 6, x, x + 6, x + 5]).collect();
+    view(&mut buffer, indices.iter().flat_map(|idx| idx.to_le_bytes()).collect());
+    view(&mut buffer, positions.iter().flat_map(|_| [0_u8, 1, 0, 0]).collect());
+    let upper = |x: f32| (1.0 - (x - 1.0) / 2.0).clamp(0.0, 1.0);
+    let weights: Vec<f32> = positions.iter().flat_map(|p| [upper(p[0]), 1.0 - upper(p[0]), 0.0, 0.0]).collect();
+    view(&mut buffer, weights.iter().flat_map(|w| w.to_le_bytes()).collect());
+    // the inverse bind matrices, column major: the upper arm at the origin, the lower at x = 2
+    let mut lower = [1.0_f32, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+    let identity = lower;
+    lower[12] = -2.0;
+    view(&mut buffer, identity.iter().chain(&lower).flat_map(|c| c.to_le_bytes()).collect());
+    fs::write(dir.join("arm.bin"), &buffer).expect("write gltf buffer fixture");
+
+    let gltf = format!(
+        r#"{{
+  "asset": {{ "version": "2.0" }},
+  "scene": 0,
+  "scenes": [{{ "nodes": [0, 1] }}],
+  "nodes": [
+    {{ "name": "arm", "mesh": 0, "skin": 0 }},
+    {{ "name": "upper_arm", "children": [2] }},
+    {{ "name": "lower_arm", "translation": [2, 0, 0], "children": [3] }},
+    {{ "name": "hand", "translation": [2, 0, 0] }}
+  ],
+  "skins": [{{ "joints": [1, 2], "inverseBindMatrices": 4 }}],
+  "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0, "JOINTS_0": 2, "WEIGHTS_0": 3 }}, "indices": 1 }}] }}],
+  "buffers": [{{ "uri": "arm.bin", "byteLength": {total} }}],
+  "bufferViews": [{views}],
+  "accessors": [
+    {{ "bufferView": 0, "componentType": 5126, "count": 10, "type": "VEC3", "min": [0, 0, 0], "max": [4, 0.5, 0] }},
+    {{ "bufferView": 1, "componentType": 5123, "count": {index_count}, "type": "SCALAR" }},
+    {{ "bufferView": 2, "componentType": 5121, "count": 10, "type": "VEC4" }},
+    {{ "bufferView": 3, "componentType": 5126, "count": 10, "type": "VEC4" }},
+    {{ "bufferView": 4, "componentType": 5126, "count": 2, "type": "MAT4" }}
+  ]
+}}
+"#,
+        total = buffer.len(),
+        views = views.join(", "),
+        index_count = indices.len(),
+    );
+
+    let path = dir.join("arm.gltf");
+    fs::write(&path, gltf).expect("write gltf fixture");
+    path
+}
+
+/// A binary glTF of the cube (material `bark`) with a foliage card beside it: a single quad
+/// from x = 2 to 4 facing +Z, with the material `leaf`, marked `doubleSided` if `double_sided`.
+pub fn write_glb_foliage(dir: &Path, double_sided: bool) -> PathBuf {
+    const CARD_VERTICES: [[f32; 3]; 4] = [[2.0, -1.0, 0.0], [4.0, -1.0, 0.0], [4.0, 1.0, 0.0], [2.0, 1.0, 0.0]];
+    const CARD_TRIANGLES: [[u16; 3]; 2] = [[0, 1, 2], [0, 2, 3]];
+
+    let mut buffer = Vec::new();
+    let mut views = Vec::new();
+    let mut view = |buffer: &mut Vec<u8>, bytes: Vec<u8>| {
+        views.push(format!(
+            r#"{{ "buffer": 0, "byteOffset": {}, "byteLength": {} }}"#,
+            buffer.len(),
+            bytes.len()
+        ));
+        buffer.extend(bytes);
+    };
+    // every view is a multiple of four bytes long, so the floats stay aligned
+    view(&mut buffer, CUBE_VERTICES.iter().flatten().flat_map(|c| c.to_le_bytes()).collect());
+    view(&mut buffer, CUBE_TRIANGLES.iter().flatten().flat_map(|idx| idx.to_le_bytes()).collect());
+    view(&mut buffer, CARD_VERTICES.iter().flatten().flat_map(|c| c.to_le_bytes()).collect());
+    view(&mut buffer, CARD_TRIANGLES.iter().flatten().flat_map(|idx| idx.to_le_bytes()).collect());
+
+    let mut json = format!(
+        r#"{{
+  "asset": {{ "version": "2.0" }},
+  "scene": 0,
+  "scenes": [{{ "nodes": [0, 1] }}],
+  "nodes": [{{ "name": "trunk", "mesh": 0 }}, {{ "name": "leaves", "mesh": 1 }}],
+  "materials": [{{ "name": "bark" }}, {{ "name": "leaf", "doubleSided": {double_sided} }}],
+  "meshes": [
+    {{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1, "material": 0 }}] }},
+    {{ "primitives": [{{ "attributes": {{ "POSITION": 2 }}, "indices": 3, "material": 1 }}] }}
+  ],
+  "buffers": [{{ "byteLength": {total} }}],
+  "bufferViews": [{views}],
+  "accessors": [
+    {{ "bufferView": 0, "componentType": 5126, "count": 8, "type": "VEC3", "min": [-1, -1, -1], "max": [1, 1, 1] }},
+    {{ "bufferView": 1, "componentType": 5123, "count": 36, "type": "SCALAR" }},
+    {{ "bufferView": 2, "componentType": 5126, "count": 4, "type": "VEC3", "min": [2, -1, 0], "max": [4, 1, 0] }},
+    {{ "bufferView": 3, "componentType": 5123, "count": 6, "type": "SCALAR" }}
+  ]
+}}"#,
+        total = buffer.len(),
+        views = views.join(", "),
+    );
+    // both chunks are padded to four bytes, the JSON with spaces
+    while json.len() % 4 != 0 {
+        json.push(' ');
+    }
+    buffer.resize(buffer.len().next_multiple_of(4), 0);
+
+    let mut glb = Vec::new();
+    glb.extend_from_slice(b"glTF");
+    glb.extend_from_slice(&2_u32.to_le_bytes());
+    glb.extend_from_slice(&((12 + 8 + json.len() + 8 + buffer.len()) as u32).to_le_bytes());
+    glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    glb.extend_from_slice(b"JSON");
+    glb.extend_from_slice(json.as_bytes());
+    glb.extend_from_slice(&(buffer.len() as u32).to_le_bytes());
+    glb.extend_from_slice(b"BIN\0");
+    glb.extend_from_slice(&buffer);
+
+    let path = dir.join("foliage.glb");
+    fs::write(&path, glb).expect("write glb fixture");
+    path
+}
+
+/// A glTF cube with normals out of its corners, its node given `node_fields`, e.g. a scale.
+pub fn write_gltf_cube_with_normals(dir: &Path, node_fields: &str) -> PathBuf {
+    write_gltf(dir, "cube_with_normals", &format!(r#"{{ "mesh": 0, {} }}"#, node_fields), &[], true)
+}
+
+/// Writes the glTF cube as `<stem>.gltf`, placed by the node `mesh_node`, with `extra_nodes`
+/// (JSON objects) next to the cube's node in the scene, and with vertex normals if `normals`.
+fn write_gltf(dir: &Path, stem: &str, mesh_node: &str, extra_nodes: &[String], normals: bool) -> PathBuf {
+    let mut buffer = Vec::new();
+    for v in &CUBE_VERTICES {
+        for component in v {
+            buffer.extend_from_slice(&component.to_le_bytes());
+        }
+    }
+    let positions_length = buffer.len();
+    if normals {
+        for v in &CUBE_VERTICES {
+            for component in v {
+                buffer.extend_from_slice(&(component / 3f32.sqrt()).to_le_bytes());
+            }
+        }
+    }
+    let vertices_length = buffer.len();
+    for triangle in &CUBE_TRIANGLES {
+        for idx in triangle {
+            buffer.extend_from_slice(&idx.to_le_bytes());
+        }
+    }
+    let indices_length = buffer.len() - vertices_length;
+    fs::write(dir.join(format!("{}.bin", stem)), &buffer).expect("write gltf buffer fixture");
+
+    let mut nodes = vec![mesh_node.to_string()];
+    nodes.extend_from_slice(extra_nodes);
+    let scene_nodes: Vec<String> = (0..nodes.len()).map(|idx| idx.to_string()).collect();
+
+    // the normals, when there are any, come last so the other indices stay put
+    let (normal_attribute, normal_view, normal_accessor) = if normals {
+        (
+            r#", "NORMAL": 2"#.to_string(),
+            format!(
+                r#",
+    {{ "buffer": 0, "byteOffset": {}, "byteLength": {}, "target": 34962 }}"#,
+                positions_length,
+                vertices_length - positions_length
+            ),
+            format!(
+                r#",
+    {{ "bufferView": 2, "componentType": 5126, "count": {}, "type": "VEC3" }}"#,
+                CUBE_VERTICES.len()
+            ),
+        )
+    } else {
+        Default::default()
+    };
+
+    let gltf = format!(
+        r#"{{
+  "asset": {{ "version": "2.0" }},
+  "scene": 0,
+  "scenes": [{{ "nodes": [{scene_nodes}] }}],
+  "nodes": [{nodes}],
+  "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0{normal_attribute} }}, "indices": 1 }}] }}],
+  "buffers": [{{ "uri": "{stem}.bin", "byteLength": {total} }}],
+  "bufferViews": [
+    {{ "buffer": 0, "byteOffset": 0, "byteLength": {positions_length}, "target": 34962 }},
+    {{ "buffer": 0, "byteOffset": {vertices_length}, "byteLength": {indices_length}, "target": 34963 }}{normal_view}
+  ],
+  "accessors": [
+    {{ "bufferView": 0, "componentType": 5126, "count": {vertex_count}, "type": "VEC3", "min": [-1, -1, -1], "max": [1, 1, 1] }},
+    {{ "bufferView": 1, "componentType": 5123, "count": {index_count}, "type": "SCALAR" }}{normal_accessor}
+  ]
+}}
+"#,
+        scene_nodes = scene_nodes.join(", "),
+        nodes = nodes.join(", "),
+        total = buffer.len(),
+        vertex_count = CUBE_VERTICES.len(),
+        index_count = CUBE_TRIANGLES.len() * 3,
+    );
+
+    let path = dir.join(format!("{}.gltf", stem));
+    fs::write(&path, gltf).expect("write gltf fixture");
+    path
+}
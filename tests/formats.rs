@@ -0,0 +1,63 @@
+mod fixtures;
+
+use std::path::{Path, PathBuf};
+
+use model_to_image::ModelToImageBuilder;
+
+/// Renders `path` small and checks that the model actually ended up in the image.
+fn assert_renders(path: &Path) {
+    assert!(
+        model_to_image::is_supported(path),
+        "{} is not in supported_extensions()",
+        path.display()
+    );
+
+    let mut model = ModelToImageBuilder::new(&path.to_path_buf())
+        .with_size((64, 64))
+        .build()
+        .unwrap_or_else(|err| panic!("failed to load {}: {:#}", path.display(), err));
+    model
+        .render()
+        .unwrap_or_else(|err| panic!("failed to render {}: {:#}", path.display(), err));
+
+    let stats = model.stats();
+    assert_eq!(stats.triangles, 12, "{} lost triangles on import", path.display());
+    assert!(stats.triangles_drawn > 0, "{} drew no triangles", path.display());
+
+    // the cube faces the viewer and fills most of the frame, so plenty of pixels must differ
+    // from the background in the corner
+    let output = model.output();
+    let background = *output.get_pixel(0, 0);
+    let covered = output.pixels().filter(|pixel| **pixel != background).count();
+    assert!(
+        covered > (64 * 64) / 4,
+        "{} only covered {} pixels",
+        path.display(),
+        covered
+    );
+}
+
+#[test]
+fn format_matrix() {
+    let cases: [(&str, fn(&Path) -> PathBuf); 3] = [
+        ("stl", fixtures::write_stl_cube),
+        ("obj", fixtures::write_obj_cube),
+        ("gltf", fixtures::write_gltf_cube),
+    ];
+
+    for (name, write) in cases {
+        let dir = fixtures::fixture_dir(&format!("format_matrix_{}", name));
+        assert_renders(&write(&dir));
+    }
+}
+
+#[test]
+fn supported_extensions_cover_common_formats() {
+    let extensions = model_to_image::supported_extensions();
+    for extension in ["fbx", "obj", "stl", "gltf", "glb", "dae", "ply"] {
+        assert!(extensions.contains(&extension), "missing {}", extension);
+    }
+    assert!(model_to_image::is_supported(Path::new("Model.GLB")));
+    assert!(!model_to_image::is_supported(Path::new("notes.txt")));
+    assert!(!model_to_image::is_supported(Path::new("glb")));
+}
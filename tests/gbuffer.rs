@@ -0,0 +1,31 @@
+mod fixtures;
+
+use std::collections::HashMap;
+
+use model_to_image::{DefinedColours, ModelToImageBuilder};
+
+#[test]
+fn shade_relights_without_rasterising() {
+    let dir = fixtures::fixture_dir("shade_relights");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_stl_cube(&dir))
+        .with_size((64, 64))
+        .build()
+        .expect("load cube");
+
+    let rendered = model.render_gbuffer().expect("render cube").clone();
+
+    // shading with the default light straight on gives back the render
+    let head_on = model.shade(&[[0.0, 0.0, -1.0]], &HashMap::new()).expect("shade").clone();
+    assert_eq!(head_on, rendered);
+
+    let grazing = model.shade(&[[1.0, 0.0, -1.0]], &HashMap::new()).expect("shade").clone();
+    assert_ne!(grazing, head_on, "moving the light didn't change the shading");
+
+    // the cube faces the viewer, so the middle is lit by less than the head on light
+    assert!(grazing.get_pixel(32, 32).0[0] < head_on.get_pixel(32, 32).0[0]);
+
+    let red = HashMap::from([(0, DefinedColours::Red.colour())]);
+    let recoloured = model.shade(&[[0.0, 0.0, -1.0]], &red).expect("shade");
+    let [r, g, b] = recoloured.get_pixel(32, 32).0;
+    assert!(r > g && r > b, "material override wasn't applied: {:?}", [r, g, b]);
+}
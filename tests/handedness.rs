@@ -0,0 +1,82 @@
+mod fixtures;
+
+use image::{DynamicImage, Rgb, RgbImage};
+use model_to_image::{DepthTest, Handedness, MaterialData, MeshData, ModelToImageBuilder};
+
+/// A red panel half a unit in front of a larger blue one, as exported by a tool with Z pointing
+/// forward: mirrored in Z, with the winding left as it was.
+fn z_forward_panels() -> (Vec<MeshData>, Vec<MaterialData>) {
+    let quad = |(x0, x1): (f32, f32), z: f32, material: usize| MeshData {
+        positions: vec![[x0, x0, -z], [x1, x0, -z], [x1, x1, -z], [x0, x1, -z]],
+        triangles: vec![[0, 1, 2], [0, 2, 3]],
+        uvs: vec![[0.5, 0.5]; 4],
+        material,
+        ..Default::default()
+    };
+    let flat = |colour: [u8; 3]| MaterialData {
+        texture: Some(DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, Rgb(colour)))),
+        ..Default::default()
+    };
+    (
+        vec![quad((0.25, 0.75), 0.5, 0), quad((0.0, 1.0), -0.5, 1)],
+        vec![flat([255, 0, 0]), flat([0, 0, 255])],
+    )
+}
+
+fn centre_colour(configure: impl FnOnce(ModelToImageBuilder) -> ModelToImageBuilder) -> [u8; 3] {
+    let (meshes, materials) = z_forward_panels();
+    let builder = ModelToImageBuilder::from_meshes(meshes, materials).with_size((64, 64));
+    let mut model = configure(builder).build().expect("build panels");
+    model.render().expect("render panels");
+    model.output().get_pixel(32, 32).0
+}
+
+fn is_red([r, g, b]: [u8; 3]) -> bool {
+    r > 128 && g < 64 && b < 64
+}
+
+fn is_blue([r, g, b]: [u8; 3]) -> bool {
+    b > 128 && r < 64 && g < 64
+}
+
+#[test]
+fn a_z_forward_export_shows_its_far_side_by_default() {
+    let colour = centre_colour(|builder| builder);
+    assert!(is_blue(colour), "{:?}", colour);
+}
+
+#[test]
+fn less_wins_shows_the_front_of_a_z_forward_export() {
+    let colour = centre_colour(|builder| builder.with_depth_test(DepthTest::LessWins));
+    assert!(is_red(colour), "{:?}", colour);
+}
+
+#[test]
+fn left_handedness_mirrors_a_z_forward_export_back() {
+    let colour = centre_colour(|builder| builder.with_handedness(Handedness::Left));
+    assert!(is_red(colour), "{:?}", colour);
+}
+
+fn mirrored_cube_warnings(handedness: Handedness) -> Vec<String> {
+    let cube = MeshData {
+        positions: fixtures::CUBE_VERTICES.iter().map(|&[x, y, z]| [x, y, -z]).collect(),
+        triangles: fixtures::CUBE_TRIANGLES.iter().map(|triangle| triangle.map(u32::from)).collect(),
+        ..Default::default()
+    };
+    let mut model = ModelToImageBuilder::from_meshes(vec![cube], Vec::new())
+        .with_size((64, 64))
+        .with_handedness(handedness)
+        .build()
+        .expect("build cube");
+    model.render().expect("render cube");
+    model.warnings().to_vec()
+}
+
+#[test]
+fn an_inside_out_render_is_warned_about() {
+    let warnings = mirrored_cube_warnings(Handedness::Right);
+    assert!(warnings.iter().any(|warning| warning.contains("mirrored")), "{:?}", warnings);
+
+    let warnings = mirrored_cube_warnings(Handedness::Left);
+    assert!(!warnings.iter().any(|warning| warning.contains("mirrored")), "{:?}", warnings);
+}
@@ -0,0 +1,48 @@
+mod fixtures;
+
+use model_to_image::{ModelToImageBuilder, ViewPreset};
+
+fn mean_luminance(img: &image::RgbImage) -> f32 {
+    let covered: Vec<f32> = img
+        .pixels()
+        .filter(|pixel| pixel.0 != [211, 211, 211])
+        .map(|pixel| pixel.0[0] as f32 * 0.299 + pixel.0[1] as f32 * 0.587 + pixel.0[2] as f32 * 0.114)
+        .collect();
+    covered.iter().sum::<f32>() / covered.len().max(1) as f32
+}
+
+#[test]
+fn a_headlight_lights_every_view_about_the_same() {
+    let dir = fixtures::fixture_dir("headlight");
+    let path = fixtures::write_obj_cube(&dir);
+
+    // a turn around the cube and over it, each view square onto a face
+    let views = [
+        ViewPreset::Front,
+        ViewPreset::Right,
+        ViewPreset::Back,
+        ViewPreset::Left,
+        ViewPreset::Top,
+        ViewPreset::Bottom,
+    ];
+    let means: Vec<f32> = views
+        .iter()
+        .map(|&view| {
+            let mut model = ModelToImageBuilder::new(&path)
+                .with_size((64, 64))
+                .with_view(view)
+                // ignored with the headlight on
+                .with_light_direction([1.0, 0.0, 0.0])
+                .with_headlight(true)
+                .build()
+                .expect("load cube");
+            model.render().expect("render cube");
+            mean_luminance(model.output())
+        })
+        .collect();
+
+    let mean = means.iter().sum::<f32>() / means.len() as f32;
+    let variance = means.iter().map(|m| (m - mean).powi(2)).sum::<f32>() / means.len() as f32;
+    assert!(mean > 100.0, "the headlight left the cube dark: {:?}", means);
+    assert!(variance.sqrt() < 0.15 * mean, "views differ too much in brightness: {:?}", means);
+}
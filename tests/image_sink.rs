@@ -0,0 +1,39 @@
+mod fixtures;
+
+use image::{ImageFormat, RgbImage};
+use model_to_image::{ImageSink, ModelToImageBuilder};
+
+/// Counts what it's given instead of storing it.
+#[derive(Default)]
+struct CountingSink {
+    images: usize,
+    last_size: Option<(u32, u32)>,
+}
+
+impl ImageSink for CountingSink {
+    fn write(&mut self, image: &RgbImage, _format: ImageFormat) -> anyhow::Result<()> {
+        self.images += 1;
+        self.last_size = Some(image.dimensions());
+        Ok(())
+    }
+}
+
+#[test]
+fn a_custom_sink_receives_exactly_one_image_of_the_render_size() {
+    let dir = fixtures::fixture_dir("image_sink");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir))
+        .with_size((48, 32))
+        .build()
+        .expect("load cube");
+    model.render().expect("render cube");
+
+    let mut sink = CountingSink::default();
+    model.write_into(&mut sink, ImageFormat::Png).expect("write into sink");
+    assert_eq!(sink.images, 1);
+    assert_eq!(sink.last_size, Some((48, 32)));
+
+    let mut png = Vec::new();
+    model.write_into(&mut png, ImageFormat::Png).expect("write into buffer");
+    let decoded = image::load_from_memory_with_format(&png, ImageFormat::Png).expect("decode png");
+    assert_eq!(decoded.to_rgb8(), *model.output());
+}
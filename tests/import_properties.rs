@@ -0,0 +1,20 @@
+mod fixtures;
+
+use model_to_image::{ModelToImageBuilder, PropertyValue};
+
+fn triangles(builder: ModelToImageBuilder) -> usize {
+    let mut model = builder.with_size((32, 32)).build().expect("load cube");
+    model.render().expect("render cube");
+    model.stats().triangles
+}
+
+#[test]
+fn the_remove_degenerates_property_drops_faces_without_area() {
+    let dir = fixtures::fixture_dir("import_properties");
+    let path = fixtures::write_obj_cube_with_degenerates(&dir);
+
+    let kept = triangles(ModelToImageBuilder::new(&path));
+    let removed = triangles(ModelToImageBuilder::new(&path).with_import_property("PP_FD_REMOVE", PropertyValue::Bool(true)));
+    assert_eq!(kept, fixtures::CUBE_TRIANGLES.len() + 2);
+    assert_eq!(removed, fixtures::CUBE_TRIANGLES.len());
+}
@@ -0,0 +1,96 @@
+mod fixtures;
+
+use std::path::Path;
+
+use model_to_image::{Axis, Colour, ModelToImage, ModelToImageBuilder, psnr};
+
+/// The cube shaded with a colour ramp and antialiased, so the render has far more colours than
+/// fit in a palette.
+fn render_colourful(dir: &Path) -> ModelToImage {
+    let path = fixtures::write_obj_cube(dir);
+    let mut model = ModelToImageBuilder::new(&path)
+        .with_size((128, 128))
+        .with_accumulation_samples(4)
+        .with_colour_ramp(Axis::X, vec![(0.0, Colour::from((200, 40, 40))), (1.0, Colour::from((40, 80, 220)))])
+        .build()
+        .expect("load cube");
+    model.render().expect("render cube");
+    model
+}
+
+#[test]
+fn indexed_png_is_smaller_and_close_to_the_truecolour_render() {
+    let dir = fixtures::fixture_dir("indexed_png");
+    let model = render_colourful(&dir);
+
+    let truecolour = dir.join("truecolour.png");
+    model.write_to(Some(&truecolour)).expect("write truecolour png");
+    let indexed = dir.join("indexed.png");
+    let written = model.write_indexed_png_to(&indexed, 64, false).expect("write indexed png");
+    assert!(written.palette_size <= 64 && written.palette_size > 16, "{:?}", written);
+    assert_eq!(written.bit_depth, 8);
+
+    let truecolour_bytes = std::fs::metadata(&truecolour).unwrap().len();
+    let indexed_bytes = std::fs::metadata(&indexed).unwrap().len();
+    assert!(
+        indexed_bytes < truecolour_bytes,
+        "indexed {} bytes, truecolour {} bytes",
+        indexed_bytes,
+        truecolour_bytes
+    );
+
+    let decoded = image::open(&indexed).expect("decode indexed png").to_rgb8();
+    let quality = psnr(model.output(), &decoded).unwrap();
+    assert!(quality >= 30.0, "PSNR {:.1} dB", quality);
+}
+
+#[test]
+fn dithered_indexed_png_still_resembles_the_render() {
+    let dir = fixtures::fixture_dir("indexed_png_dithered");
+    let model = render_colourful(&dir);
+
+    let indexed = dir.join("dithered.png");
+    let written = model.write_indexed_png_to(&indexed, 16, true).expect("write dithered png");
+    assert!(written.palette_size <= 16, "{:?}", written);
+    assert_eq!(written.bit_depth, 4);
+
+    let decoded = image::open(&indexed).expect("decode dithered png").to_rgb8();
+    let quality = psnr(model.output(), &decoded).unwrap();
+    assert!(quality >= 20.0, "PSNR {:.1} dB", quality);
+}
+
+#[test]
+fn images_with_few_colours_keep_them_exactly() {
+    let dir = fixtures::fixture_dir("indexed_png_exact");
+    let path = fixtures::write_obj_cube(&dir);
+    let mut model = ModelToImageBuilder::new(&path).with_size((64, 64)).build().expect("load cube");
+    model.render().expect("render cube");
+
+    let indexed = dir.join("exact.png");
+    let written = model.write_indexed_png_to(&indexed, 256, false).expect("write indexed png");
+    let decoded = image::open(&indexed).expect("decode indexed png").to_rgb8();
+    assert!(written.palette_size < 256, "{:?}", written);
+    assert_eq!(&decoded, model.output());
+}
+
+#[test]
+fn palette_sizes_outside_a_png_palette_are_rejected() {
+    let dir = fixtures::fixture_dir("indexed_png_limits");
+    let model = render_colourful(&dir);
+    assert!(model.write_indexed_png_to(dir.join("none.png"), 0, false).is_err());
+    assert!(model.write_indexed_png_to(dir.join("many.png"), 257, false).is_err());
+    assert!(!dir.join("none.png").exists());
+}
+
+#[test]
+fn dithering_is_chosen_per_write() {
+    let dir = fixtures::fixture_dir("indexed_png_dithering_key");
+    let model = render_colourful(&dir);
+    model.write_indexed_png_to(dir.join("plain.png"), 16, false).expect("write plain png");
+    model.write_indexed_png_to(dir.join("dithered.png"), 16, true).expect("write dithered png");
+    assert_ne!(
+        std::fs::read(dir.join("plain.png")).unwrap(),
+        std::fs::read(dir.join("dithered.png")).unwrap(),
+        "dithering changes the pixels"
+    );
+}
@@ -0,0 +1,63 @@
+mod fixtures;
+
+use model_to_image::{ImageSize, ImageTooLarge, ModelToImageBuilder, Size};
+
+#[test]
+fn very_wide_and_very_tall_images_render() {
+    let dir = fixtures::fixture_dir("large_images_strips");
+    let path = fixtures::write_obj_cube(&dir);
+    for size in [(70_000, 10), (10, 70_000)] {
+        let mut model = ModelToImageBuilder::new(&path).with_size(size).build().expect("build strip");
+        model.render().expect("render strip");
+        assert_eq!(model.output().size(), size);
+        let coverage = model.coverage();
+        assert!(coverage.covered_pixels > 0, "{:?} drew nothing", size);
+        let (min_x, min_y, max_x, max_y) = coverage.bounding_box.unwrap();
+        assert!(max_x < size.0 && max_y < size.1 && min_x <= max_x && min_y <= max_y);
+    }
+}
+
+#[test]
+fn images_over_the_pixel_limit_fail_to_build() {
+    let dir = fixtures::fixture_dir("large_images_limit");
+    let path = fixtures::write_obj_cube(&dir);
+
+    let err = ModelToImageBuilder::new(&path).with_size((10_000, 10_000)).build().unwrap_err();
+    let too_large = err.downcast_ref::<ImageTooLarge>().expect("an ImageTooLarge error");
+    assert_eq!(too_large.size, Size::new(10_000, 10_000));
+    assert_eq!(too_large.max_pixels, 8192 * 8192);
+
+    let limited = || ModelToImageBuilder::new(&path).with_max_pixels(1_000);
+    assert!(limited().with_size((40, 25)).build().is_ok());
+    let err = limited().with_size((40, 26)).build().unwrap_err();
+    assert!(err.downcast_ref::<ImageTooLarge>().is_some(), "{}", err);
+}
+
+#[test]
+fn sizes_that_cannot_be_allocated_fail_even_without_a_limit() {
+    let dir = fixtures::fixture_dir("large_images_unlimited");
+    let path = fixtures::write_obj_cube(&dir);
+    let err = ModelToImageBuilder::new(&path)
+        .with_size((u32::MAX, u32::MAX))
+        .with_max_pixels(u64::MAX)
+        .build()
+        .unwrap_err();
+    assert!(err.downcast_ref::<ImageTooLarge>().is_some(), "{}", err);
+}
+
+#[test]
+fn light_grids_over_the_pixel_limit_fail() {
+    let dir = fixtures::fixture_dir("large_images_grid");
+    let path = fixtures::write_obj_cube(&dir);
+    let mut model = ModelToImageBuilder::new(&path)
+        .with_size((64, 64))
+        .with_max_pixels(64 * 64 * 2)
+        .build()
+        .expect("build cube");
+    let candidates = [[0.0, 0.0, -1.0], [1.0, 0.0, -1.0], [-1.0, 0.0, -1.0], [0.0, 1.0, -1.0]];
+
+    let err = model.render_light_grid(&candidates, 2).unwrap_err();
+    assert!(err.downcast_ref::<ImageTooLarge>().is_some(), "{}", err);
+    let strip = model.render_light_grid(&candidates[..2], 2).expect("a grid within the limit");
+    assert_eq!(strip.size(), (128, 64));
+}
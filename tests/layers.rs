@@ -0,0 +1,43 @@
+mod fixtures;
+
+use image::{Rgb, RgbImage};
+use model_to_image::{ModelToImageBuilder, RenderLayers, ViewPreset};
+
+fn render_layers(samples: u32) -> RenderLayers {
+    let dir = fixtures::fixture_dir(&format!("layers_{}", samples));
+    let mut model = ModelToImageBuilder::new(&fixtures::write_stl_cube(&dir))
+        .with_size((64, 64))
+        .with_view(ViewPreset::Isometric)
+        .with_accumulation_samples(samples)
+        .build()
+        .expect("load cube");
+    model.render_layers().expect("render cube")
+}
+
+#[test]
+fn only_the_background_showing_through_changes_between_backdrops() {
+    let layers = render_layers(8);
+    let over_white = layers.composite_over(&RgbImage::from_pixel(64, 64, Rgb([255, 255, 255])));
+    let over_black = layers.composite_over(&RgbImage::from_pixel(64, 64, Rgb([0, 0, 0])));
+
+    let mut soft_edges = 0;
+    for (x, y, pixel) in layers.colour.enumerate_pixels() {
+        let (white, black) = (over_white.get_pixel(x, y), over_black.get_pixel(x, y));
+        match pixel.0[3] {
+            255 => assert_eq!(white, black, "({}, {}) is fully covered", x, y),
+            0 => assert_eq!((white.0, black.0), ([255; 3], [0; 3]), "({}, {}) is background", x, y),
+            _ => {
+                soft_edges += 1;
+                assert_ne!(white, black, "({}, {}) is partly background", x, y);
+            }
+        }
+    }
+    assert!(soft_edges > 0, "accumulated edges are partly covered");
+}
+
+#[test]
+fn a_single_pass_has_hard_edges() {
+    let layers = render_layers(1);
+    assert!(layers.colour.pixels().all(|pixel| matches!(pixel.0[3], 0 | 255)));
+    assert!(layers.colour.pixels().any(|pixel| pixel.0[3] == 255));
+}
@@ -0,0 +1,27 @@
+mod fixtures;
+
+use image::GenericImageView;
+use model_to_image::{ModelToImageBuilder, ViewPreset};
+
+#[test]
+fn light_grid_tiles_one_render_per_direction() {
+    let dir = fixtures::fixture_dir("light_grid");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir))
+        .with_size((48, 40))
+        .with_view(ViewPreset::Isometric)
+        .build()
+        .expect("load cube");
+
+    let candidates = [[1.0, 0.0, -1.0], [-1.0, 0.0, -1.0], [0.0, 1.0, -1.0], [0.0, -1.0, -1.0]];
+    let grid = model.render_light_grid(&candidates, 2).expect("render light grid");
+    assert_eq!(grid.dimensions(), (96, 80));
+
+    let quadrants: Vec<Vec<u8>> = (0..4)
+        .map(|idx| grid.view((idx % 2) * 48, (idx / 2) * 40, 48, 40).to_image().into_raw())
+        .collect();
+    for a in 0..4 {
+        for b in a + 1..4 {
+            assert_ne!(quadrants[a], quadrants[b], "tiles {} and {} are the same", a, b);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+mod fixtures;
+
+use std::path::Path;
+
+use model_to_image::{MAX_LIGHT_INTENSITY, ModelToImage, ModelToImageBuilder};
+
+/// Shines on the front of the cube at an angle whose cosine is 0.4.
+const SLANTED: [f32; 3] = [0.916_515_1, 0.0, -0.4];
+
+fn render(path: &Path, configure: impl FnOnce(ModelToImageBuilder) -> ModelToImageBuilder) -> ModelToImage {
+    let mut model = configure(ModelToImageBuilder::new(path).with_size((32, 32))).build().expect("build cube");
+    model.render().expect("render cube");
+    model
+}
+
+fn centre(model: &ModelToImage) -> u8 {
+    model.output().get_pixel(16, 16).0[0]
+}
+
+fn intensity_warnings(model: &ModelToImage) -> Vec<&String> {
+    model.warnings().iter().filter(|warning| warning.contains("bright") || warning.contains("clamped")).collect()
+}
+
+#[test]
+fn the_length_of_the_light_direction_is_its_intensity() {
+    let dir = fixtures::fixture_dir("light_intensity_length");
+    let path = fixtures::write_obj_cube(&dir);
+    for (magnitude, expected) in [(0.5, 51), (1.0, 102), (2.0, 204)] {
+        let model = render(&path, |builder| builder.with_light_direction(SLANTED.map(|c| c * magnitude)));
+        assert!(centre(&model).abs_diff(expected) <= 1, "{} lit the face {}", magnitude, centre(&model));
+        let warned = !intensity_warnings(&model).is_empty();
+        assert_eq!(warned, magnitude != 1.0, "{}: {:?}", magnitude, model.warnings());
+    }
+}
+
+#[test]
+fn an_explicit_intensity_matches_without_warnings() {
+    let dir = fixtures::fixture_dir("light_intensity_explicit");
+    let path = fixtures::write_obj_cube(&dir);
+    for (intensity, expected) in [(0.5, 51), (1.0, 102), (2.0, 204)] {
+        let model = render(&path, |builder| builder.with_light_direction(SLANTED).with_light_intensity(intensity));
+        assert!(centre(&model).abs_diff(expected) <= 1, "{} lit the face {}", intensity, centre(&model));
+        assert!(intensity_warnings(&model).is_empty(), "{:?}", model.warnings());
+    }
+
+    let headlight = render(&path, |builder| builder.with_headlight(true).with_light_intensity(0.5));
+    assert!(centre(&headlight).abs_diff(128) <= 1, "{}", centre(&headlight));
+}
+
+#[test]
+fn out_of_range_intensities_are_clamped_or_rejected() {
+    let dir = fixtures::fixture_dir("light_intensity_range");
+    let path = fixtures::write_obj_cube(&dir);
+    let model = render(&path, |builder| builder.with_light_intensity(MAX_LIGHT_INTENSITY * 10.0));
+    assert!(model.warnings().iter().any(|warning| warning.contains("clamped")), "{:?}", model.warnings());
+    assert_eq!(centre(&model), 255);
+
+    let invalid = [
+        ModelToImageBuilder::new(&path).with_light_intensity(-1.0),
+        ModelToImageBuilder::new(&path).with_light_intensity(f32::NAN),
+        ModelToImageBuilder::new(&path).with_light_direction([0.0, 0.0, 0.0]),
+        ModelToImageBuilder::new(&path).with_light_direction([0.0, f32::INFINITY, -1.0]),
+    ];
+    for builder in invalid {
+        assert!(builder.build().is_err());
+    }
+}
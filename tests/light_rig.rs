@@ -0,0 +1,110 @@
+use std::path::PathBuf;
+
+use image::RgbImage;
+use model_to_image::{Light, LightRig, MeshData, ModelToImageBuilder, psnr};
+
+const BACKGROUND: [u8; 3] = [211, 211, 211];
+
+/// A UV sphere of radius 1 around the origin, wound counter-clockwise from outside.
+fn sphere(rings: u32, segments: u32) -> MeshData {
+    let mut positions = Vec::new();
+    for ring in 0..=rings {
+        let theta = std::f32::consts::PI * ring as f32 / rings as f32;
+        for segment in 0..=segments {
+            let phi = std::f32::consts::TAU * segment as f32 / segments as f32;
+            positions.push([theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin()]);
+        }
+    }
+    let mut triangles = Vec::new();
+    for ring in 0..rings {
+        for segment in 0..segments {
+            let a = ring * (segments + 1) + segment;
+            let b = a + segments + 1;
+            triangles.push([a, a + 1, b]);
+            triangles.push([a + 1, b + 1, b]);
+        }
+    }
+    MeshData { positions, triangles, ..Default::default() }
+}
+
+fn sphere_builder() -> ModelToImageBuilder {
+    ModelToImageBuilder::from_meshes(vec![sphere(32, 64)], Vec::new()).with_size((96, 96)).with_margin(0.05)
+}
+
+fn render(builder: ModelToImageBuilder) -> RgbImage {
+    let mut model = builder.build().expect("build model");
+    model.render().expect("render model");
+    model.output().clone()
+}
+
+fn luminance(pixel: [u8; 3]) -> f32 {
+    pixel[0] as f32 * 0.299 + pixel[1] as f32 * 0.587 + pixel[2] as f32 * 0.114
+}
+
+/// Mean luminance of the covered pixels in the columns `columns`.
+fn mean_luminance(img: &RgbImage, columns: std::ops::Range<u32>) -> f32 {
+    let covered: Vec<f32> = columns
+        .flat_map(|x| (0..img.height()).map(move |y| (x, y)))
+        .map(|(x, y)| img.get_pixel(x, y).0)
+        .filter(|&pixel| pixel != BACKGROUND)
+        .map(luminance)
+        .collect();
+    covered.iter().sum::<f32>() / covered.len().max(1) as f32
+}
+
+#[test]
+fn three_point_lights_the_key_side_brighter_than_the_fill_side() {
+    let img = render(sphere_builder().with_light_rig(LightRig::three_point()));
+    let fill = mean_luminance(&img, 8..40);
+    let key = mean_luminance(&img, 56..88);
+    assert!(key > fill * 1.3, "key side {:.1}, fill side {:.1}", key, fill);
+    // the fill keeps the side away from the key from going black
+    assert!(fill > 20.0, "fill side {:.1}", fill);
+}
+
+#[test]
+fn the_rim_light_picks_out_the_top_edge() {
+    let with_rim = render(sphere_builder().with_light_rig(LightRig::three_point()));
+    let without_rim = render(
+        sphere_builder()
+            .with_light_rig(LightRig::three_point())
+            .with_rig_light(2, Light::new([-0.3, -1.0, 1.2], 0.0)),
+    );
+
+    // just inside the silhouette at the top of the sphere, which faces up and away
+    let top = (0..48).find(|&y| with_rim.get_pixel(48, y).0 != BACKGROUND).expect("sphere is drawn") + 1;
+    let rim = luminance(with_rim.get_pixel(48, top).0);
+    let unlit = luminance(without_rim.get_pixel(48, top).0);
+    assert!(rim > unlit + 20.0, "rim {:.1} against {:.1} without", rim, unlit);
+}
+
+#[test]
+fn the_rig_stays_with_the_camera_as_the_model_turns() {
+    let mut model = sphere_builder().with_light_rig(LightRig::three_point()).build().expect("build sphere");
+    for frame in model.turntable_frames(4) {
+        let frame = frame.expect("render frame");
+        let fill = mean_luminance(&frame, 8..40);
+        let key = mean_luminance(&frame, 56..88);
+        assert!(key > fill * 1.3, "key side {:.1}, fill side {:.1}", key, fill);
+    }
+}
+
+#[test]
+fn presets_look_different_from_the_single_light() {
+    let fish = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/src/fish.glb"));
+    let builder = || ModelToImageBuilder::new(&fish).with_size((128, 128));
+    let single = render(builder());
+    for rig in [LightRig::three_point(), LightRig::studio(), LightRig::flat()] {
+        let lit = render(builder().with_light_rig(rig.clone()));
+        let similarity = psnr(&single, &lit).unwrap();
+        assert!(similarity < 30.0, "{:?} looks like the single light, PSNR {:.1} dB", rig, similarity);
+    }
+}
+
+#[test]
+fn lights_without_a_direction_are_rejected() {
+    let broken = LightRig::three_point().with_light(1, Light::new([0.0, 0.0, 0.0], 0.5));
+    assert!(sphere_builder().with_light_rig(broken).build().is_err());
+    let negative = LightRig::flat().with_light(0, Light::new([0.0, 0.0, -1.0], -1.0));
+    assert!(sphere_builder().with_light_rig(negative).build().is_err());
+}
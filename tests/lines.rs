@@ -0,0 +1,42 @@
+use model_to_image::{Colour, MeshData, ModelToImageBuilder};
+
+const RED: [u8; 3] = [255, 0, 0];
+
+/// Red pixels in the top and the bottom half of a quad at z = 0, with a line in front of it
+/// across its top half and one behind it across its bottom half. The lines come first, so
+/// they're only depth tested once the quad is drawn.
+fn red_pixels(line_rendering: bool) -> (usize, usize) {
+    let lines = MeshData {
+        positions: vec![[-0.8, 0.5, 0.5], [0.8, 0.5, 0.5], [-0.8, -0.5, -0.5], [0.8, -0.5, -0.5]],
+        lines: vec![[0, 1], [2, 3]],
+        ..Default::default()
+    };
+    let quad = MeshData {
+        positions: vec![[-1.0, -1.0, 0.0], [1.0, -1.0, 0.0], [1.0, 1.0, 0.0], [-1.0, 1.0, 0.0]],
+        triangles: vec![[0, 1, 2], [0, 2, 3]],
+        ..Default::default()
+    };
+    let mut model = ModelToImageBuilder::from_meshes(vec![lines, quad], Vec::new())
+        .with_size((64, 64))
+        .with_line_rendering(line_rendering)
+        .with_line_style(Colour::from((255, 0, 0)), 1)
+        .build()
+        .expect("build lines");
+    model.render().expect("render lines");
+    assert_eq!(model.stats().lines, if line_rendering { 2 } else { 0 });
+
+    let output = model.output();
+    let count = |rows: std::ops::Range<u32>| {
+        rows.flat_map(|y| (0..64).map(move |x| (x, y))).filter(|&(x, y)| output.get_pixel(x, y).0 == RED).count()
+    };
+    (count(0..32), count(32..64))
+}
+
+#[test]
+fn lines_are_depth_tested_against_the_finished_model() {
+    let (top, bottom) = red_pixels(true);
+    assert!(top > 30, "the line in front shows, {} pixels", top);
+    assert_eq!(bottom, 0, "the line behind is hidden");
+
+    assert_eq!(red_pixels(false), (0, 0));
+}
@@ -0,0 +1,81 @@
+mod fixtures;
+
+use std::path::Path;
+
+use model_to_image::{Lut, ModelToImageBuilder, OutputPixels};
+
+fn render(path: &Path, lut: Option<Lut>) -> image::RgbImage {
+    let mut builder = ModelToImageBuilder::new(path).with_size((64, 64));
+    if let Some(lut) = lut {
+        builder = builder.with_lut(lut);
+    }
+    let mut model = builder.build().expect("build cube");
+    model.render().expect("render cube");
+    model.output().clone()
+}
+
+fn assert_inverted(plain: &image::RgbImage, inverted: &image::RgbImage) {
+    for (plain, inverted) in plain.pixels().zip(inverted.pixels()) {
+        for (p, i) in plain.0.iter().zip(inverted.0) {
+            assert!((255 - *p as i32 - i as i32).abs() <= 1, "{:?} inverted to {:?}", plain, inverted);
+        }
+    }
+}
+
+#[test]
+fn inverting_curves_invert_every_channel() {
+    let dir = fixtures::fixture_dir("lut_curves");
+    let path = fixtures::write_obj_cube(&dir);
+    let plain = render(&path, None);
+
+    assert_inverted(&plain, &render(&path, Some(Lut::from_curve(vec![1.0, 0.0]))));
+    let table: [u8; 256] = std::array::from_fn(|idx| 255 - idx as u8);
+    assert_inverted(&plain, &render(&path, Some(Lut::from_tables(&table, &table, &table))));
+}
+
+#[test]
+fn an_inverting_cube_file_inverts_every_channel() {
+    let dir = fixtures::fixture_dir("lut_cube");
+    let path = fixtures::write_obj_cube(&dir);
+    let mut text = String::from("TITLE \"invert\"\n# red changes fastest\nLUT_3D_SIZE 2\n");
+    for idx in 0..8 {
+        text += &format!("{} {} {}\n", 1 - (idx & 1), 1 - (idx >> 1 & 1), 1 - (idx >> 2 & 1));
+    }
+    let cube_path = dir.join("invert.cube");
+    std::fs::write(&cube_path, text).unwrap();
+
+    let lut = Lut::from_cube_file(&cube_path).expect("parse .cube");
+    assert_inverted(&render(&path, None), &render(&path, Some(lut)));
+}
+
+#[test]
+fn the_lut_applies_before_the_output_format() {
+    let dir = fixtures::fixture_dir("lut_linear");
+    let path = fixtures::write_obj_cube(&dir);
+    let mut model = ModelToImageBuilder::new(&path)
+        .with_size((16, 16))
+        .with_output_format(OutputPixels::Linear16)
+        .with_lut(Lut::from_curve(vec![0.0, 0.0]))
+        .build()
+        .expect("build cube");
+    model.render().expect("render cube");
+    assert!(model.output().pixels().all(|pixel| pixel.0 == [0, 0, 0]));
+}
+
+#[test]
+fn invalid_luts_fail() {
+    let dir = fixtures::fixture_dir("lut_invalid");
+    let path = fixtures::write_obj_cube(&dir);
+    let invalid = [
+        Lut::from_curve(vec![0.5]),
+        Lut::Cube { size: 3, table: vec![[0.0; 3]; 8] },
+        Lut::from_curve(vec![0.0, f32::NAN]),
+    ];
+    for lut in invalid {
+        assert!(ModelToImageBuilder::new(&path).with_lut(lut.clone()).build().is_err(), "{:?}", lut);
+    }
+
+    assert!(Lut::parse_cube("LUT_3D_SIZE 2\n0 0 0\n").is_err());
+    assert!(Lut::parse_cube("LUT_1D_SIZE 2\n0 0\n1 1 1\n").is_err());
+    assert!(Lut::parse_cube("0 0 0\n1 1 1\n").is_err());
+}
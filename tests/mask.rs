@@ -0,0 +1,41 @@
+mod fixtures;
+
+use image::RgbaImage;
+use model_to_image::{Mask, ModelToImageBuilder};
+
+fn masked(dir_name: &str, mask: Mask) -> RgbaImage {
+    let dir = fixtures::fixture_dir(dir_name);
+    let mut model = ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir))
+        .with_size((64, 64))
+        .with_mask(mask)
+        .build()
+        .expect("load cube");
+    model.render().expect("render cube");
+
+    let path = model.write_to(Some(&dir.join("masked.png"))).expect("write png");
+    image::open(path).expect("read png back").to_rgba8()
+}
+
+#[test]
+fn a_circle_mask_saves_transparent_corners_and_a_smooth_edge() {
+    let image = masked("mask_circle", Mask::Circle);
+    for (x, y) in [(0, 0), (63, 0), (0, 63), (63, 63)] {
+        assert_eq!(image.get_pixel(x, y).0[3], 0, "corner {x}, {y}");
+    }
+    assert_eq!(image.get_pixel(32, 32).0[3], 255);
+    assert!(
+        image.pixels().any(|p| p.0[3] > 0 && p.0[3] < 255),
+        "the edge of the circle is anti-aliased"
+    );
+}
+
+#[test]
+fn a_rounded_rectangle_only_cuts_the_corners() {
+    let image = masked("mask_rounded_rect", Mask::RoundedRect { radius_px: 12 });
+    assert_eq!(image.get_pixel(0, 0).0[3], 0);
+    assert_eq!(image.get_pixel(63, 63).0[3], 0);
+    // the middle of every edge is well away from the rounded corners
+    for (x, y) in [(32, 0), (0, 32), (63, 32), (32, 63)] {
+        assert_eq!(image.get_pixel(x, y).0[3], 255, "edge at {x}, {y}");
+    }
+}
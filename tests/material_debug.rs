@@ -0,0 +1,37 @@
+use image::Rgb;
+use model_to_image::{MaterialData, MeshData, ModelToImageBuilder, RenderMode, material_colour};
+
+/// A square facing the camera from `x` to `x + 1`, on `material`.
+fn square(x: f32, material: usize) -> MeshData {
+    MeshData {
+        positions: vec![[x, 0.0, 0.0], [x + 1.0, 0.0, 0.0], [x + 1.0, 1.0, 0.0], [x, 1.0, 0.0]],
+        triangles: vec![[0, 1, 2], [0, 2, 3]],
+        material,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn two_materials_show_in_their_own_colours() {
+    let materials = vec![
+        MaterialData { name: "paint".into(), ..Default::default() },
+        MaterialData { name: "chrome".into(), ..Default::default() },
+    ];
+    let mut model = ModelToImageBuilder::from_meshes(vec![square(0.0, 0), square(1.0, 1)], materials)
+        .with_size((64, 32))
+        .with_render_mode(RenderMode::MaterialDebug { legend: false })
+        .build()
+        .expect("build squares");
+    model.render().expect("render squares");
+
+    let (paint, chrome) = (Rgb::from(material_colour(0)), Rgb::from(material_colour(1)));
+    assert_ne!(paint, chrome);
+    let close = |pixel: &Rgb<u8>, expected: Rgb<u8>| pixel.0.iter().zip(expected.0).all(|(&a, b)| a.abs_diff(b) <= 1);
+    // each square lit straight on, so every pixel of it is its material's colour
+    for y in 10..22 {
+        for x in 10..28 {
+            assert!(close(model.output().get_pixel(x, y), paint), "({}, {}) isn't paint", x, y);
+            assert!(close(model.output().get_pixel(x + 26, y), chrome), "({}, {}) isn't chrome", x + 26, y);
+        }
+    }
+}
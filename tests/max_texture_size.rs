@@ -0,0 +1,87 @@
+mod fixtures;
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use image::{DynamicImage, ImageFormat, Rgb, RgbImage};
+use model_to_image::{MaterialData, MeshData, ModelToImage, ModelToImageBuilder, TextureCache};
+
+/// The OBJ cube textured with an 8192x8192 PNG.
+fn write_huge_textured_cube(dir: &Path) -> PathBuf {
+    let mut png = Cursor::new(Vec::new());
+    RgbImage::from_pixel(8192, 8192, Rgb([30, 120, 200]))
+        .write_to(&mut png, ImageFormat::Png)
+        .expect("encode texture");
+    fixtures::write_obj_cube_with_texture(dir, "huge.png", png.get_ref())
+}
+
+fn texture_size(model: &ModelToImage) -> Option<(u32, u32)> {
+    model.uv_stats()[0].texture_size
+}
+
+#[test]
+fn huge_textures_are_kept_at_the_limit() {
+    let dir = fixtures::fixture_dir("max_texture_size_file");
+    let path = write_huge_textured_cube(&dir);
+
+    let model = ModelToImageBuilder::new(&path)
+        .with_max_texture_size(1024)
+        .build()
+        .expect("build cube");
+    assert_eq!(texture_size(&model), Some((1024, 1024)));
+    assert_eq!(model.texture_downsampling(), [8.0]);
+    assert!(model.warnings().iter().any(|warning| warning.contains("factor of 8.00")), "{:?}", model.warnings());
+
+    let mesh = MeshData {
+        positions: fixtures::CUBE_VERTICES.to_vec(),
+        triangles: fixtures::CUBE_TRIANGLES.iter().map(|triangle| triangle.map(u32::from)).collect(),
+        ..Default::default()
+    };
+    let material = MaterialData {
+        texture: Some(DynamicImage::ImageRgb8(RgbImage::new(8192, 4096))),
+        ..Default::default()
+    };
+    let model = ModelToImageBuilder::from_meshes(vec![mesh], vec![material])
+        .with_max_texture_size(1024)
+        .build()
+        .expect("build meshes");
+    assert_eq!(texture_size(&model), Some((1024, 512)));
+    assert_eq!(model.texture_downsampling(), [8.0]);
+}
+
+#[test]
+fn shrunk_textures_are_cached_per_limit() {
+    let dir = fixtures::fixture_dir("max_texture_size_cache");
+    let path = write_huge_textured_cube(&dir);
+    let cache = TextureCache::new();
+    let build = |max_size: u32| {
+        ModelToImageBuilder::new(&path)
+            .with_texture_cache(cache.clone())
+            .with_max_texture_size(max_size)
+            .build()
+            .expect("build cube")
+    };
+
+    let first = build(1024);
+    assert_eq!((cache.decode_count(), cache.len()), (1, 1));
+
+    // the same limit again is a plain cache hit
+    let again = build(1024);
+    assert_eq!((cache.decode_count(), cache.len()), (1, 1));
+    assert_eq!(texture_size(&again), texture_size(&first));
+    assert_eq!(again.texture_downsampling(), [8.0]);
+
+    // a smaller one shrinks the cached copy once, without decoding
+    let smaller = build(512);
+    assert_eq!(texture_size(&smaller), Some((512, 512)));
+    assert_eq!(smaller.texture_downsampling(), [16.0]);
+    assert_eq!((cache.decode_count(), cache.len()), (1, 2));
+    build(512);
+    assert_eq!((cache.decode_count(), cache.len()), (1, 2));
+
+    // a larger one needs the detail the cached copies lost
+    let larger = build(2048);
+    assert_eq!(texture_size(&larger), Some((2048, 2048)));
+    assert_eq!(larger.texture_downsampling(), [4.0]);
+    assert_eq!((cache.decode_count(), cache.len()), (2, 3));
+}
@@ -0,0 +1,30 @@
+mod fixtures;
+
+use model_to_image::{MatchTolerance, MeshData, ModelToImageBuilder, assert_images_match};
+
+#[test]
+fn meshes_from_raw_arrays_render_like_the_same_model_from_a_file() {
+    let cube = MeshData {
+        name: "cube".into(),
+        positions: fixtures::CUBE_VERTICES.to_vec(),
+        triangles: fixtures::CUBE_TRIANGLES.iter().map(|triangle| triangle.map(u32::from)).collect(),
+        ..Default::default()
+    };
+    let mut model = ModelToImageBuilder::from_meshes(vec![cube], Vec::new())
+        .with_size((64, 64))
+        .with_light_direction([0.48, -0.64, -0.6])
+        .build()
+        .expect("build from meshes");
+    model.render().expect("render cube");
+    assert!(model.warnings().is_empty(), "{:?}", model.warnings());
+
+    let dir = fixtures::fixture_dir("mesh_data");
+    let mut from_file = ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir))
+        .with_size((64, 64))
+        .with_light_direction([0.48, -0.64, -0.6])
+        .build()
+        .expect("load cube");
+    from_file.render().expect("render cube");
+
+    assert_images_match(model.output(), from_file.output(), MatchTolerance::default());
+}
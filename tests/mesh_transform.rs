@@ -0,0 +1,95 @@
+mod fixtures;
+
+use model_to_image::{MeshData, ModelToImage, ModelToImageBuilder, Transform};
+
+/// The fixture cube stretched to fill the box from `min` to `max`.
+fn block(name: &str, min: [f32; 3], max: [f32; 3]) -> MeshData {
+    MeshData {
+        name: name.to_string(),
+        positions: fixtures::CUBE_VERTICES
+            .iter()
+            .map(|v| [0, 1, 2].map(|c| min[c] + (v[c] + 1.0) / 2.0 * (max[c] - min[c])))
+            .collect(),
+        triangles: fixtures::CUBE_TRIANGLES.iter().map(|triangle| triangle.map(u32::from)).collect(),
+        ..Default::default()
+    }
+}
+
+/// A wide table with a prop floating a unit above it.
+fn table_and_prop() -> Vec<MeshData> {
+    vec![
+        block("table", [-2.0, -1.0, -1.0], [2.0, 0.0, 1.0]),
+        block("prop", [-0.5, 1.0, -0.5], [0.5, 2.0, 0.5]),
+    ]
+}
+
+fn render(builder: ModelToImageBuilder) -> ModelToImage {
+    let mut model = builder.with_size((128, 128)).build().expect("build scene");
+    model.render().expect("render scene");
+    model
+}
+
+/// Background pixels down the middle column between the top and bottom of the model.
+fn gap_pixels(model: &ModelToImage) -> usize {
+    let img = model.output();
+    let covered: Vec<bool> = (0..img.height()).map(|y| img.get_pixel(64, y).0 != [211, 211, 211]).collect();
+    let top = covered.iter().position(|&c| c).expect("model is drawn");
+    let bottom = covered.iter().rposition(|&c| c).expect("model is drawn");
+    covered[top..=bottom].iter().filter(|&&c| !c).count()
+}
+
+fn covered_height(model: &ModelToImage) -> f32 {
+    let (_, min_y, _, max_y) = model.coverage().bounding_box.expect("model is drawn");
+    (max_y - min_y + 1) as f32
+}
+
+#[test]
+fn moving_the_prop_down_closes_the_gap_and_reframes() {
+    let floating = render(ModelToImageBuilder::from_meshes(table_and_prop(), Vec::new()));
+    assert!(gap_pixels(&floating) > 5);
+
+    let placed = render(
+        ModelToImageBuilder::from_meshes(table_and_prop(), Vec::new())
+            .with_mesh_transform("prop", Transform::from_translation([0.0, -1.0, 0.0])),
+    );
+    assert_eq!(gap_pixels(&placed), 0);
+    assert!(!placed.warnings().iter().any(|warning| warning.contains("transform")), "{:?}", placed.warnings());
+
+    // the table's width sets the scale either way, so the scene is two thirds as tall
+    let ratio = covered_height(&placed) / covered_height(&floating);
+    assert!((ratio - 2.0 / 3.0).abs() < 0.03, "height ratio {}", ratio);
+}
+
+#[test]
+fn the_last_matching_transform_wins() {
+    let placed = render(
+        ModelToImageBuilder::from_meshes(table_and_prop(), Vec::new())
+            .with_mesh_transform("prop", Transform::from_translation([0.0, 5.0, 0.0]))
+            .with_mesh_transform(1, Transform::from_translation([0.0, -1.0, 0.0])),
+    );
+    assert_eq!(gap_pixels(&placed), 0);
+}
+
+#[test]
+fn scaling_and_rotating_keep_faces_lit() {
+    let flipped = render(
+        ModelToImageBuilder::from_meshes(table_and_prop(), Vec::new()).with_mesh_transform(
+            "prop",
+            Transform::from_translation([0.0, -0.5, 0.0]).with_rotation([0.0, 90.0, 0.0]).with_scale([-1.0, 0.5, 1.0]),
+        ),
+    );
+    assert_eq!(gap_pixels(&flipped), 0);
+}
+
+#[test]
+fn transforms_that_match_nothing_are_warned_about() {
+    let model = render(
+        ModelToImageBuilder::from_meshes(table_and_prop(), Vec::new())
+            .with_mesh_transform("lamp", Transform::from_translation([1.0, 0.0, 0.0])),
+    );
+    assert!(model.warnings().iter().any(|warning| warning.contains("lamp")), "{:?}", model.warnings());
+
+    let broken = ModelToImageBuilder::from_meshes(table_and_prop(), Vec::new())
+        .with_mesh_transform(0, Transform::default().with_scale([1.0, 0.0, 1.0]));
+    assert!(broken.build().is_err());
+}
@@ -0,0 +1,40 @@
+use image::{Rgb, RgbImage};
+use model_to_image::{psnr, ssim};
+
+/// A diagonal gradient with some structure in it for SSIM to compare.
+fn gradient() -> RgbImage {
+    RgbImage::from_fn(32, 24, |x, y| Rgb([(x * 8) as u8, (y * 10) as u8, ((x + y) * 4) as u8]))
+}
+
+#[test]
+fn identical_images_score_perfectly() {
+    let img = gradient();
+    assert_eq!(psnr(&img, &img).unwrap(), f64::INFINITY);
+    assert!((ssim(&img, &img).unwrap() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn an_inverted_image_scores_low() {
+    let img = gradient();
+    let mut inverted = img.clone();
+    image::imageops::invert(&mut inverted);
+
+    assert!(psnr(&img, &inverted).unwrap() < 10.0);
+    assert!(ssim(&img, &inverted).unwrap() < 0.2);
+}
+
+#[test]
+fn one_level_everywhere_is_48_db() {
+    let img = RgbImage::from_pixel(16, 16, Rgb([100, 100, 100]));
+    let brighter = RgbImage::from_pixel(16, 16, Rgb([101, 101, 101]));
+    // 10 * log10(255^2 / 1)
+    assert!((psnr(&img, &brighter).unwrap() - 48.130_803_6).abs() < 1e-6);
+    assert!(ssim(&img, &brighter).unwrap() > 0.99);
+}
+
+#[test]
+fn images_of_different_sizes_are_an_error() {
+    let err = psnr(&RgbImage::new(4, 4), &RgbImage::new(4, 5)).unwrap_err();
+    assert!(err.to_string().contains("different sizes"), "{}", err);
+    assert!(ssim(&RgbImage::new(4, 4), &RgbImage::new(5, 4)).is_err());
+}
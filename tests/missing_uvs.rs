@@ -0,0 +1,75 @@
+use image::{DynamicImage, Rgb, RgbImage};
+use model_to_image::{MaterialData, MeshData, MissingUvs, ModelToImageBuilder, RenderMode};
+
+fn quad(name: &str, uvs: bool) -> MeshData {
+    MeshData {
+        name: name.to_string(),
+        positions: vec![[-1.0, -1.0, 0.0], [1.0, -1.0, 0.0], [1.0, 1.0, 0.0], [-1.0, 1.0, 0.0]],
+        triangles: vec![[0, 1, 2], [0, 2, 3]],
+        uvs: if uvs { vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]] } else { Vec::new() },
+        ..Default::default()
+    }
+}
+
+/// Red, apart from a green texel in every corner, which is what a mesh with every UV at
+/// (0, 0) would be painted with.
+fn texture() -> DynamicImage {
+    DynamicImage::ImageRgb8(RgbImage::from_fn(8, 8, |x, y| {
+        if (x == 0 || x == 7) && (y == 0 || y == 7) { Rgb([0, 255, 0]) } else { Rgb([255, 0, 0]) }
+    }))
+}
+
+fn render(mesh: MeshData, texture: Option<DynamicImage>) -> model_to_image::ModelToImage {
+    let material = MaterialData { texture, ..Default::default() };
+    let mut model = ModelToImageBuilder::from_meshes(vec![mesh], vec![material])
+        .with_size((32, 32))
+        .build()
+        .expect("build quad");
+    model.render().expect("render quad");
+    model
+}
+
+#[test]
+fn a_textured_mesh_without_uvs_is_shaded_grey() {
+    let model = render(quad("plate", false), Some(texture()));
+    let centre = model.output().get_pixel(16, 16).0;
+    assert!(centre[0] == centre[1] && centre[1] == centre[2], "not grey: {:?}", centre);
+    // exactly as if it had no texture at all
+    assert_eq!(model.output(), render(quad("plate", false), None).output());
+
+    let missing = MissingUvs { mesh_idx: 0, name: "plate".to_string() };
+    assert_eq!(model.missing_uvs(), [missing.clone()]);
+    assert!(model.warnings().contains(&missing.to_string()), "{:?}", model.warnings());
+
+    // with UVs the texture shows and nothing is missing
+    let model = render(quad("plate", true), Some(texture()));
+    assert_eq!(model.output().get_pixel(16, 16).0, [255, 0, 0]);
+    assert!(model.missing_uvs().is_empty());
+}
+
+#[test]
+fn the_uv_debug_view_shows_a_mesh_without_uvs_black_and_warns() {
+    let render_uvs = |mesh: MeshData| {
+        let mut model = ModelToImageBuilder::from_meshes(vec![mesh], Vec::new())
+            .with_size((32, 32))
+            .with_render_mode(RenderMode::UvDebug)
+            .build()
+            .expect("build quad");
+        model.render().expect("render quad");
+        model
+    };
+
+    let model = render_uvs(quad("plate", false));
+    assert_eq!(model.output().get_pixel(16, 16).0, [0, 0, 0]);
+    assert!(
+        model.warnings().contains(&"Meshes without texture coordinates: plate (#0)".to_string()),
+        "{:?}",
+        model.warnings()
+    );
+
+    // with UVs the middle of the quad is half way along U and V
+    let model = render_uvs(quad("plate", true));
+    let [u, v, _] = model.output().get_pixel(16, 16).0;
+    assert!(u.abs_diff(128) < 16 && v.abs_diff(128) < 16, "{:?}", (u, v));
+    assert!(!model.warnings().iter().any(|warning| warning.contains("without texture coordinates")));
+}
@@ -0,0 +1,58 @@
+mod fixtures;
+
+use std::path::Path;
+
+use model_to_image::{MatchTolerance, ModelToImage, ModelToImageBuilder, ViewPreset, assert_images_match, probe};
+
+fn build(path: &Path) -> ModelToImage {
+    let mut model = ModelToImageBuilder::new(path)
+        .with_size((64, 64))
+        .with_view(ViewPreset::Isometric)
+        .with_normalize_scale(true)
+        .build()
+        .expect("load cube");
+    model.render().expect("render cube");
+    model
+}
+
+#[test]
+fn millimetres_and_metres_render_alike_when_normalised() {
+    // the same 2 metre cube, saved in metres and in millimetres
+    let metres = build(&fixtures::write_scaled_cube(&fixtures::fixture_dir("normalize_scale_m"), 1.0));
+    let millimetres = build(&fixtures::write_scaled_cube(&fixtures::fixture_dir("normalize_scale_mm"), 1000.0));
+
+    // each remembers the units it came in
+    let ratio = millimetres.scale_factor() / metres.scale_factor();
+    assert!((ratio - 1000.0).abs() < 0.01, "{}", ratio);
+    let tolerance = MatchTolerance {
+        max_channel_difference: 1,
+        max_differing_fraction: 0.01,
+    };
+    assert_images_match(millimetres.output(), metres.output(), tolerance);
+}
+
+#[test]
+fn fbx_files_are_converted_into_metres() {
+    // the same 2 metre cube, saved in centimetres and in metres
+    let centimetres = fixtures::write_fbx_cube(&fixtures::fixture_dir("normalize_scale_fbx_cm"), 1.0, 100.0);
+    let metres = fixtures::write_fbx_cube(&fixtures::fixture_dir("normalize_scale_fbx_m"), 100.0, 1.0);
+
+    for path in [&centimetres, &metres] {
+        let size = probe(path).expect("probe cube").bounds.size();
+        assert!(size.iter().all(|&side| (side - 2.0).abs() < 1e-4), "{}: {:?}", path.display(), size);
+    }
+
+    // at a fixed scale, in pixels per metre, they come out the same size
+    let render = |path: &Path| {
+        let mut model = ModelToImageBuilder::new(path)
+            .with_size((64, 64))
+            .with_world_scale(16.0)
+            .build()
+            .expect("load cube");
+        model.render().expect("render cube");
+        model.coverage().bounding_box
+    };
+    let bounding_box = render(&centimetres);
+    assert!(bounding_box.is_some());
+    assert_eq!(render(&metres), bounding_box);
+}
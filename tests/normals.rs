@@ -0,0 +1,44 @@
+mod fixtures;
+
+use model_to_image::{MeshData, ModelToImage, ModelToImageBuilder, ViewPreset};
+use nalgebra::Vector3;
+
+/// Checks the corner normals of a cube stretched by `stretch` still point straight out of
+/// each corner's faces: along the corner's position divided by the stretch squared.
+fn assert_corner_normals(model: &ModelToImage, stretch: [f32; 3]) {
+    let mesh = &model.meshes()[0];
+    assert_eq!(mesh.normals.len(), mesh.positions.len());
+    let stretch = Vector3::from(stretch);
+    for (position, normal) in mesh.positions.iter().zip(&mesh.normals) {
+        let expected = Vector3::from(*position).component_div(&stretch.component_mul(&stretch)).normalize();
+        let normal = Vector3::from(*normal);
+        assert!((normal - expected).norm() < 1e-4, "normal {:?} at {:?}", normal, position);
+    }
+}
+
+#[test]
+fn node_transforms_keep_normals_square_to_the_faces() {
+    let dir = fixtures::fixture_dir("normals_node_transform");
+    // transforming the normals like the positions would tip them towards the long side
+    let path = fixtures::write_gltf_cube_with_normals(&dir, r#""scale": [4, 1, 1]"#);
+    let model = ModelToImageBuilder::new(&path).build().expect("load cube");
+    assert_corner_normals(&model, [4.0, 1.0, 1.0]);
+}
+
+#[test]
+fn views_turn_the_normals_with_the_model() {
+    let cube = MeshData {
+        positions: fixtures::CUBE_VERTICES.to_vec(),
+        triangles: fixtures::CUBE_TRIANGLES.iter().map(|triangle| triangle.map(u32::from)).collect(),
+        normals: fixtures::CUBE_VERTICES.iter().map(|v| v.map(|c| c / 3f32.sqrt())).collect(),
+        ..Default::default()
+    };
+    for view in [ViewPreset::Right, ViewPreset::Top, ViewPreset::Isometric] {
+        let model = ModelToImageBuilder::from_meshes(vec![cube.clone()], Vec::new())
+            .with_view(view)
+            .with_camera_roll(30.0)
+            .build()
+            .expect("build cube");
+        assert_corner_normals(&model, [1.0; 3]);
+    }
+}
@@ -0,0 +1,69 @@
+use model_to_image::{Framing, MaterialData, MeshData, ModelToImageBuilder};
+
+/// A unit square facing the camera, lit by the default light.
+fn quad() -> MeshData {
+    MeshData {
+        positions: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+        triangles: vec![[0, 1, 2], [0, 2, 3]],
+        ..Default::default()
+    }
+}
+
+/// The warning explaining an empty render, if there was one.
+fn nothing_drawn(mesh: MeshData, configure: impl FnOnce(ModelToImageBuilder) -> ModelToImageBuilder) -> Option<String> {
+    let builder = ModelToImageBuilder::from_meshes(vec![mesh], vec![MaterialData::default()]).with_size((32, 32));
+    let mut model = configure(builder).build().expect("build quad");
+    model.render().expect("render quad");
+    model.warnings().iter().find(|warning| warning.starts_with("Nothing was drawn")).cloned()
+}
+
+#[test]
+fn a_drawn_model_has_no_warning() {
+    assert_eq!(nothing_drawn(quad(), |builder| builder), None);
+}
+
+#[test]
+fn clip_planes_cutting_everything_are_blamed() {
+    let warning = nothing_drawn(quad(), |builder| builder.with_clip_plane([0.0, 0.0, 1.0], -5.0)).expect("a warning");
+    assert!(warning.contains("100% of the 2 triangles were cut away by the clip planes"), "{}", warning);
+    assert!(warning.contains("with_clip_plane"), "{}", warning);
+}
+
+#[test]
+fn a_framing_centred_elsewhere_is_blamed() {
+    let framing = Framing {
+        extent: [1.0, 1.0],
+        center: Some([100.0, 0.0, 0.0]),
+    };
+    let warning = nothing_drawn(quad(), |builder| builder.with_framing(framing)).expect("a warning");
+    assert!(warning.contains("landed outside the image"), "{}", warning);
+    assert!(warning.contains("(2 off screen)"), "{}", warning);
+}
+
+#[test]
+fn a_light_from_behind_is_blamed() {
+    let warning = nothing_drawn(quad(), |builder| builder.with_light_direction([0.0, 0.0, 1.0])).expect("a warning");
+    assert!(warning.contains("faced away from the light"), "{}", warning);
+}
+
+#[test]
+fn faces_without_area_are_blamed() {
+    let flat = MeshData {
+        positions: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+        triangles: vec![[0, 1, 2], [2, 1, 0]],
+        ..Default::default()
+    };
+    let warning = nothing_drawn(flat, |builder| builder).expect("a warning");
+    assert!(warning.contains("had no area"), "{}", warning);
+}
+
+#[test]
+fn every_reason_is_counted() {
+    // the plane cuts away the triangle above the diagonal, the light misses the other one
+    let warning = nothing_drawn(quad(), |builder| {
+        builder.with_clip_plane([-1.0, 1.0, 0.0], -0.01).with_light_direction([0.0, 0.0, 1.0])
+    })
+    .expect("a warning");
+    assert!(warning.contains("50% of the 2 triangles"), "{}", warning);
+    assert!(warning.contains("(1 clipped, 1 unlit)"), "{}", warning);
+}
@@ -0,0 +1,66 @@
+mod fixtures;
+
+use model_to_image::{MeshData, ModelToImageBuilder, RenderMode, ViewPreset};
+
+/// A bumpy `cells` x `cells` grid with its vertices stored in a scrambled order, as some
+/// exporters leave them, with UVs, vertex colours and a line along one edge.
+fn scrambled_grid(cells: u32) -> MeshData {
+    let side = cells + 1;
+    let count = side * side;
+    // a step coprime with the count visits every slot once
+    let step = (2..count).find(|step| gcd(*step, count) == 1 && *step > count / 3).unwrap();
+    let slot = |idx: u32| (idx as u64 * step as u64 % count as u64) as u32;
+
+    let mut mesh = MeshData {
+        positions: vec![[0.0; 3]; count as usize],
+        uvs: vec![[0.0; 2]; count as usize],
+        colours: vec![[0.0; 4]; count as usize],
+        ..Default::default()
+    };
+    for y in 0..side {
+        for x in 0..side {
+            let (u, v) = (x as f32 / cells as f32, y as f32 / cells as f32);
+            let (fx, fy) = (u * 2.0 - 1.0, v * 2.0 - 1.0);
+            let at = slot(y * side + x) as usize;
+            mesh.positions[at] = [fx, fy, 0.2 * (fx * 5.0).sin() * (fy * 4.0).cos()];
+            mesh.uvs[at] = [u, v];
+            mesh.colours[at] = [u, v, 1.0 - u, 1.0];
+        }
+    }
+    for y in 0..cells {
+        for x in 0..cells {
+            let corner = y * side + x;
+            mesh.triangles.push([corner, corner + 1, corner + side + 1].map(slot));
+            mesh.triangles.push([corner, corner + side + 1, corner + side].map(slot));
+        }
+    }
+    mesh.lines = (0..cells).map(|x| [x, x + 1].map(slot)).collect();
+    mesh
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+fn render(builder: ModelToImageBuilder, optimize: bool) -> image::RgbImage {
+    let mut model = builder.with_size((96, 96)).with_optimize_mesh(optimize).build().expect("build mesh");
+    model.render().expect("render mesh");
+    model.output().clone()
+}
+
+#[test]
+fn optimizing_the_vertex_order_leaves_the_image_unchanged() {
+    let grid = || ModelToImageBuilder::from_meshes(vec![scrambled_grid(40)], Vec::new()).with_view(ViewPreset::Isometric);
+    assert_eq!(render(grid(), true), render(grid(), false));
+
+    let checker = || grid().with_render_mode(RenderMode::Checker { cells: 8 });
+    assert_eq!(render(checker(), true), render(checker(), false));
+}
+
+#[test]
+fn models_from_files_are_unchanged_too() {
+    let dir = fixtures::fixture_dir("optimize_mesh_cube");
+    let path = fixtures::write_obj_cube(&dir);
+    let cube = || ModelToImageBuilder::new(&path).with_view(ViewPreset::Isometric);
+    assert_eq!(render(cube(), true), render(cube(), false));
+}
@@ -0,0 +1,64 @@
+mod fixtures;
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use model_to_image::{Axis, Colour, ModelToImageBuilder, OutputImage, OutputPixels};
+
+/// Renders the cube with a dark gradient across it, which is where 8-bit linear output bands
+/// the worst.
+fn render_gradient(path: &Path, output_pixels: OutputPixels) -> model_to_image::ModelToImage {
+    let mut model = ModelToImageBuilder::new(&path.to_path_buf())
+        .with_size((256, 256))
+        .with_colour_ramp(Axis::X, vec![(0.0, Colour::from((0, 0, 0))), (1.0, Colour::from((40, 40, 40)))])
+        .with_output_format(output_pixels)
+        .build()
+        .expect("load cube");
+    model.render().expect("render cube");
+    model
+}
+
+#[test]
+fn linear16_gradients_do_not_band() {
+    let dir = fixtures::fixture_dir("linear16_gradients");
+    let path = fixtures::write_stl_cube(&dir);
+
+    let linear8 = render_gradient(&path, OutputPixels::Linear8);
+    let OutputImage::Rgb8(linear8) = linear8.output_any() else {
+        panic!("Linear8 should give an 8-bit image");
+    };
+    let levels8: BTreeSet<u8> = (40..216).map(|x| linear8.get_pixel(x, 128).0[0]).collect();
+
+    let linear16 = render_gradient(&path, OutputPixels::Linear16);
+    let OutputImage::Rgb16(image16) = linear16.output_any() else {
+        panic!("Linear16 should give a 16-bit image");
+    };
+    let levels16: BTreeSet<u16> = (40..216).map(|x| image16.get_pixel(x, 128).0[0]).collect();
+
+    assert!(levels8.len() <= 10, "8-bit linear unexpectedly smooth: {:?}", levels8);
+    assert!(levels16.len() >= 100, "16-bit output banded into {} levels", levels16.len());
+
+    let written = linear16.write_to(Some(&dir.join("gradient.png"))).expect("write png");
+    let reopened = image::open(written).expect("open png");
+    assert!(matches!(reopened, image::DynamicImage::ImageRgb16(_)), "{:?}", reopened.color());
+}
+
+#[test]
+fn layers_and_gbuffer_renders_are_encoded_too() {
+    let dir = fixtures::fixture_dir("output_format_layers");
+    let path = fixtures::write_stl_cube(&dir);
+
+    for output_pixels in [OutputPixels::Linear8, OutputPixels::Linear16] {
+        let rendered = render_gradient(&path, output_pixels);
+
+        let mut layered = render_gradient(&path, output_pixels);
+        layered.render_layers().expect("render layers");
+        assert_eq!(layered.output(), rendered.output(), "{:?}", output_pixels);
+        assert_eq!(layered.output_any(), rendered.output_any(), "{:?}", output_pixels);
+
+        let mut gbuffered = render_gradient(&path, output_pixels);
+        gbuffered.render_gbuffer().expect("render gbuffer");
+        assert_eq!(gbuffered.output(), rendered.output(), "{:?}", output_pixels);
+        assert_eq!(gbuffered.output_any(), rendered.output_any(), "{:?}", output_pixels);
+    }
+}
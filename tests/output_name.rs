@@ -0,0 +1,114 @@
+mod fixtures;
+
+use std::path::PathBuf;
+
+use model_to_image::{
+    ModelToImageBuilder, NameCollision, OutputNameContext, OutputNameTemplate, TemplateError, ViewPreset,
+};
+
+fn context(stem: &str) -> OutputNameContext {
+    OutputNameContext::new(stem, ViewPreset::Isometric, (800, 600))
+}
+
+#[test]
+fn every_placeholder_is_expanded() {
+    let template = OutputNameTemplate::new("{stem}/{view}_{width}_{height}_{size}_{mesh}_{frame}.png").unwrap();
+    let context = context("fish").with_mesh("fin").with_frame(7);
+    assert_eq!(template.render(&context), "fish/isometric_800_600_800x600_fin_7.png");
+    assert!(template.uses("mesh"));
+    assert!(!template.uses("nonsense"));
+}
+
+#[test]
+fn placeholders_without_a_value_are_left_empty_or_zero() {
+    let template = OutputNameTemplate::new("{stem}_{mesh}_{frame}.png").unwrap();
+    assert_eq!(template.render(&context("fish")), "fish__0.png");
+}
+
+#[test]
+fn mesh_names_are_made_safe_for_file_names() {
+    let template: OutputNameTemplate = "{stem}_{mesh}.png".parse().unwrap();
+    let context = context("fish").with_mesh("left fin/2.001");
+    assert_eq!(template.render(&context), "fish_left_fin_2_001.png");
+}
+
+#[test]
+fn doubled_braces_are_literal() {
+    let template = OutputNameTemplate::new("{{{stem}}}.png").unwrap();
+    assert_eq!(template.render(&context("fish")), "{fish}.png");
+    assert_eq!(template.to_string(), "{{{stem}}}.png");
+}
+
+#[test]
+fn unknown_placeholders_are_all_listed() {
+    let err = OutputNameTemplate::new("{stem}_{veiw}_{sise}_{veiw}.png").unwrap_err();
+    assert_eq!(
+        err,
+        TemplateError::UnknownPlaceholders {
+            template: "{stem}_{veiw}_{sise}_{veiw}.png".to_string(),
+            unknown: vec!["veiw".to_string(), "sise".to_string()],
+        }
+    );
+    let message = err.to_string();
+    assert!(message.contains("{veiw}, {sise}"), "{}", message);
+    assert!(message.contains("{stem}, {view}, {width}"), "{}", message);
+}
+
+#[test]
+fn unmatched_braces_and_empty_templates_are_refused() {
+    assert!(matches!(
+        OutputNameTemplate::new("{stem.png"),
+        Err(TemplateError::UnmatchedBrace { position: 0, .. })
+    ));
+    assert!(matches!(
+        OutputNameTemplate::new("stem}.png"),
+        Err(TemplateError::UnmatchedBrace { position: 4, .. })
+    ));
+    assert_eq!(OutputNameTemplate::new(""), Err(TemplateError::Empty));
+}
+
+#[test]
+fn two_models_with_the_same_name_collide() {
+    let template = OutputNameTemplate::new("{view}_{size}.png").unwrap();
+    let contexts = [context("fish"), context("cube")];
+    let err = template.render_all(&contexts).unwrap_err();
+    assert_eq!(
+        err,
+        NameCollision {
+            name: "isometric_800x600.png".to_string(),
+            first: 0,
+            second: 1,
+        }
+    );
+    assert!(err.to_string().contains("{stem}"), "{}", err);
+
+    let template = OutputNameTemplate::new("{stem}_{view}_{size}.png").unwrap();
+    assert_eq!(
+        template.render_all(&contexts).unwrap(),
+        ["fish_isometric_800x600.png", "cube_isometric_800x600.png"]
+    );
+}
+
+#[test]
+fn the_context_of_a_model_has_its_stem_view_and_size() {
+    let dir = fixtures::fixture_dir("output_name_model");
+    let model = ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir))
+        .with_size((48, 32))
+        .with_view(ViewPreset::Top)
+        .build()
+        .expect("build cube");
+
+    let template = OutputNameTemplate::new("{stem}_{view}_{size}.png").unwrap();
+    assert_eq!(template.render(&OutputNameContext::for_model(&model)), "cube_top_48x32.png");
+}
+
+#[test]
+fn a_builder_names_its_model_before_it_is_built() {
+    // the file doesn't have to exist yet
+    let builder = ModelToImageBuilder::new(&PathBuf::from("not_loaded/cube.obj"))
+        .with_size((48, 32))
+        .with_view(ViewPreset::Top);
+
+    let template = OutputNameTemplate::new("{stem}_{view}_{size}.png").unwrap();
+    assert_eq!(template.render(&OutputNameContext::for_builder(&builder)), "cube_top_48x32.png");
+}
@@ -0,0 +1,64 @@
+mod fixtures;
+
+use model_to_image::{Colour, MeshData, ModelToImage, ModelToImageBuilder, Overlay, ViewPreset};
+
+const BOX: [u8; 3] = [255, 0, 255];
+
+/// A box 4 wide, 2 high and 1 deep, so none of its edges line up from the isometric corner.
+fn slab() -> MeshData {
+    MeshData {
+        positions: fixtures::CUBE_VERTICES.iter().map(|v| [v[0] * 2.0, v[1], v[2] * 0.5]).collect(),
+        triangles: fixtures::CUBE_TRIANGLES.iter().map(|t| t.map(u32::from)).collect(),
+        ..Default::default()
+    }
+}
+
+fn render_with_overlays() -> ModelToImage {
+    let mut model = ModelToImageBuilder::from_meshes(vec![slab()], Vec::new())
+        .with_size((128, 128))
+        .with_view(ViewPreset::Isometric)
+        .with_overlay(Overlay::BoundingBox { colour: Colour::from((BOX[0], BOX[1], BOX[2])), width_px: 1 })
+        .with_overlay(Overlay::Axes { length_fraction: 0.5 })
+        .build()
+        .expect("build slab");
+    model.render().expect("render slab");
+    model
+}
+
+/// Whether `colour` is drawn on or right next to where `point` lands in the image.
+fn drawn_at(model: &ModelToImage, point: [f32; 3], colour: [u8; 3]) -> bool {
+    let (x, y) = model.project_point(point).expect("point inside the image");
+    (x.saturating_sub(1)..=x + 1)
+        .flat_map(|x| (y.saturating_sub(1)..=y + 1).map(move |y| (x, y)))
+        .any(|(x, y)| model.output().get_pixel(x, y).0 == colour)
+}
+
+#[test]
+fn all_twelve_box_edges_are_drawn() {
+    let model = render_with_overlays();
+    let corner = |idx: usize| {
+        let [x, y, z] = fixtures::CUBE_VERTICES[idx];
+        [x * 2.0, y, z * 0.5]
+    };
+    let edges = [(0, 1), (1, 2), (2, 3), (3, 0), (4, 5), (5, 6), (6, 7), (7, 4), (0, 4), (1, 5), (2, 6), (3, 7)];
+    for (a, b) in edges {
+        let (a, b) = (corner(a), corner(b));
+        // a quarter and three quarters of the way along, clear of the corners
+        for t in [0.25, 0.75] {
+            let point = [0, 1, 2].map(|c| a[c] + (b[c] - a[c]) * t);
+            assert!(drawn_at(&model, point, BOX), "no box edge at {:?}", point);
+        }
+    }
+}
+
+#[test]
+fn the_axes_point_along_x_y_and_z_in_red_green_and_blue() {
+    let model = render_with_overlays();
+    // the axes are half of the longest side long, from the middle of the box
+    let axes = [([1.0, 0.0, 0.0], [255, 0, 0]), ([0.0, 1.0, 0.0], [0, 255, 0]), ([0.0, 0.0, 1.0], [0, 0, 255])];
+    for (point, colour) in axes {
+        assert!(drawn_at(&model, point, colour), "no {:?} axis at {:?}", colour, point);
+    }
+    // each axis only runs one way from the middle
+    assert!(!drawn_at(&model, [-1.0, 0.0, 0.0], [255, 0, 0]));
+}
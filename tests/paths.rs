@@ -0,0 +1,77 @@
+mod fixtures;
+
+use model_to_image::ModelToImageBuilder;
+
+#[test]
+fn models_load_from_paths_with_non_ascii_characters() {
+    let dir = fixtures::fixture_dir("paths").join("modèles 模型 ✓");
+    std::fs::create_dir_all(&dir).expect("create unicode directory");
+    let path = fixtures::write_obj_cube(&dir);
+
+    let mut model = ModelToImageBuilder::new(&path).with_size((32, 32)).build().expect("load cube");
+    model.render().expect("render cube");
+    assert!(model.stats().triangles_drawn > 0);
+}
+
+#[test]
+fn missing_models_are_reported_with_their_absolute_path() {
+    let relative = std::path::PathBuf::from("no_such_dir/missing.obj");
+    let err = ModelToImageBuilder::new(&relative).build().expect_err("missing model");
+    let absolute = std::env::current_dir().expect("current dir").join(&relative);
+    assert!(err.to_string().contains(&absolute.display().to_string()), "{}", err);
+}
+
+#[cfg(windows)]
+#[test]
+fn models_load_from_paths_longer_than_max_path() {
+    let mut dir = fixtures::fixture_dir("long_paths");
+    while dir.as_os_str().len() < 300 {
+        dir.push("a_fairly_long_directory_name");
+    }
+    std::fs::create_dir_all(&dir).expect("create long directory");
+    let path = fixtures::write_obj_cube(&dir);
+
+    let mut model = ModelToImageBuilder::new(&path).with_size((32, 32)).build().expect("load cube");
+    model.render().expect("render cube");
+    assert!(model.stats().triangles_drawn > 0);
+}
+
+#[test]
+fn writing_creates_missing_directories_and_returns_the_path() {
+    let dir = fixtures::fixture_dir("paths_nested_output");
+    let builder = ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir)).with_size((16, 16));
+    let mut model = builder.build().expect("load cube");
+    model.render().expect("render cube");
+
+    let target = dir.join("out").join("thumbs").join("a.png");
+    let written = model.write_to(Some(&target)).expect("write into missing directories");
+    assert!(target.is_file());
+    assert_eq!(written, target.canonicalize().expect("canonical path"));
+}
+
+#[test]
+fn existing_files_are_only_replaced_with_overwriting_on() {
+    let dir = fixtures::fixture_dir("paths_no_overwrite");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir))
+        .with_size((16, 16))
+        .with_overwrite(false)
+        .build()
+        .expect("load cube");
+    model.render().expect("render cube");
+
+    let target = dir.join("a.png");
+    std::fs::write(&target, b"keep me").expect("write existing file");
+    let err = model.write_to(Some(&target)).expect_err("existing file");
+    assert!(err.to_string().contains("already exists"), "{}", err);
+    assert_eq!(std::fs::read(&target).expect("read existing file"), b"keep me");
+
+    model.set_overwrite(true).write_to(Some(&target)).expect("overwrite");
+    assert_ne!(std::fs::read(&target).expect("read new file"), b"keep me");
+}
+
+#[test]
+fn overwriting_does_not_change_the_cache_key() {
+    let path = fixtures::write_obj_cube(&fixtures::fixture_dir("paths_overwrite_key"));
+    let key = |overwrite: bool| ModelToImageBuilder::new(&path).with_overwrite(overwrite).cache_key().expect("key");
+    assert_eq!(key(true), key(false));
+}
@@ -0,0 +1,99 @@
+mod fixtures;
+
+use image::RgbImage;
+use model_to_image::{MeshData, ModelToImageBuilder};
+
+const BACKGROUND: [u8; 3] = [211, 211, 211];
+
+/// A cube at the origin and, far off to its side, a wide unnamed panel four times as wide as
+/// it is high, so that the two would be small specks in a render of both.
+fn cube_and_panel() -> Vec<MeshData> {
+    let cube = MeshData {
+        name: "cube".to_string(),
+        positions: fixtures::CUBE_VERTICES.to_vec(),
+        triangles: fixtures::CUBE_TRIANGLES.iter().map(|t| t.map(u32::from)).collect(),
+        ..Default::default()
+    };
+    let panel = MeshData {
+        positions: vec![[20.0, 0.0, 0.0], [24.0, 0.0, 0.0], [24.0, 1.0, 0.0], [20.0, 1.0, 0.0]],
+        triangles: vec![[0, 1, 2], [0, 2, 3]],
+        ..Default::default()
+    };
+    vec![cube, panel]
+}
+
+/// The width and height of the box around the pixels the model covers.
+fn covered_size(image: &RgbImage) -> (u32, u32) {
+    let covered: Vec<(u32, u32)> =
+        image.enumerate_pixels().filter(|(_, _, p)| p.0 != BACKGROUND).map(|(x, y, _)| (x, y)).collect();
+    assert!(!covered.is_empty(), "nothing was drawn");
+    let width = covered.iter().map(|c| c.0).max().unwrap() - covered.iter().map(|c| c.0).min().unwrap() + 1;
+    let height = covered.iter().map(|c| c.1).max().unwrap() - covered.iter().map(|c| c.1).min().unwrap() + 1;
+    (width, height)
+}
+
+#[test]
+fn each_mesh_is_framed_on_its_own() {
+    let mut model = ModelToImageBuilder::from_meshes(cube_and_panel(), Vec::new())
+        .with_size((64, 64))
+        .build()
+        .expect("build meshes");
+    let images = model.render_per_mesh().expect("render meshes");
+
+    let names: Vec<&str> = images.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["cube", "mesh_1"]);
+
+    // framed together, the cube would be a few pixels across; on its own it fills the image
+    let (cube_width, cube_height) = covered_size(&images[0].1);
+    assert!(cube_width > 40 && cube_width.abs_diff(cube_height) <= 1, "cube covers {cube_width}x{cube_height}");
+
+    let (panel_width, panel_height) = covered_size(&images[1].1);
+    assert!(panel_width > 40, "panel covers {panel_width}x{panel_height}");
+    assert!(panel_width.abs_diff(4 * panel_height) <= 4, "panel covers {panel_width}x{panel_height}");
+    assert!(images[0].1 != images[1].1);
+}
+
+#[test]
+fn the_whole_model_is_framed_again_afterwards() {
+    let mut model = ModelToImageBuilder::from_meshes(cube_and_panel(), Vec::new())
+        .with_size((64, 64))
+        .build()
+        .expect("build meshes");
+    model.render().expect("render model");
+    let whole = model.output().clone();
+
+    model.render_per_mesh().expect("render meshes");
+    model.render().expect("render model again");
+    assert!(model.output() == &whole);
+}
+
+#[test]
+fn mesh_names_are_known_before_rendering() {
+    let mut model = ModelToImageBuilder::from_meshes(cube_and_panel(), Vec::new())
+        .with_size((32, 32))
+        .build()
+        .expect("build meshes");
+    let names = model.mesh_names();
+    assert_eq!(names, ["cube", "mesh_1"]);
+
+    let images = model.render_per_mesh().expect("render meshes");
+    assert!(images.iter().map(|(name, _)| name).eq(&names));
+}
+
+#[test]
+fn per_mesh_images_are_written_through_the_overwrite_check() {
+    let dir = fixtures::fixture_dir("per_mesh_overwrite");
+    let mut model = ModelToImageBuilder::from_meshes(cube_and_panel(), Vec::new())
+        .with_size((32, 32))
+        .with_overwrite(false)
+        .build()
+        .expect("build meshes");
+    let images = model.render_per_mesh().expect("render meshes");
+
+    let path = dir.join("parts").join("cube.png");
+    let written = model.write_image_to(&images[0].1, &path).expect("write cube");
+    assert_eq!(image::open(&written).expect("read cube back").to_rgb8(), images[0].1);
+    // a second image under the same name is refused rather than replacing the first
+    assert!(model.write_image_to(&images[1].1, &path).is_err());
+    assert_eq!(image::open(&written).expect("read cube back").to_rgb8(), images[0].1);
+}
@@ -0,0 +1,43 @@
+mod fixtures;
+
+use model_to_image::{ModelToImageBuilder, RenderPipeline, Stage, StageOrderError};
+
+fn cube(name: &str) -> model_to_image::ModelToImage {
+    let dir = fixtures::fixture_dir(name);
+    ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir))
+        .with_size((64, 64))
+        .with_light_direction([0.3, -0.4, -1.0])
+        .with_outline(model_to_image::Colour::from((20, 20, 20)), 1)
+        .build()
+        .expect("load cube")
+}
+
+#[test]
+fn running_the_stages_by_hand_matches_render() {
+    let mut one_shot = cube("pipeline_one_shot");
+    one_shot.render().expect("render cube");
+
+    let mut staged = cube("pipeline_staged");
+    let mut pipeline = RenderPipeline::new(&mut staged);
+    pipeline.prepare().expect("prepare").rasterize().expect("rasterize");
+    let covered = pipeline.framebuffer().expect("framebuffer").coverage.iter().filter(|c| **c > 0.0).count();
+    assert!(covered > 0);
+    pipeline.post_process().expect("post process").finish().expect("finish");
+    assert_eq!(pipeline.stage(), Stage::Finished);
+
+    assert_eq!(staged.output(), one_shot.output());
+}
+
+#[test]
+fn custom_stages_carry_through_and_stages_keep_their_order() {
+    let mut model = cube("pipeline_custom");
+    let mut pipeline = RenderPipeline::new(&mut model);
+    let err = pipeline.rasterize().err().expect("rasterize before prepare");
+    assert!(err.downcast_ref::<StageOrderError>().is_some(), "{}", err);
+
+    pipeline.prepare().expect("prepare").rasterize().expect("rasterize");
+    pipeline.framebuffer().expect("framebuffer").colour.put_pixel(0, 0, image::Rgb([255, 0, 255]));
+    pipeline.post_process().expect("post process").finish().expect("finish");
+
+    assert_eq!(model.output().get_pixel(0, 0).0, [255, 0, 255]);
+}
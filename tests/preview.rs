@@ -0,0 +1,38 @@
+mod fixtures;
+
+use image::{Rgb, RgbImage};
+use model_to_image::{ModelToImageBuilder, ViewPreset, psnr, ssim};
+
+#[test]
+fn preview_is_framed_like_the_full_render() {
+    let dir = fixtures::fixture_dir("preview");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir))
+        .with_size((128, 96))
+        .with_view(ViewPreset::Isometric)
+        .build()
+        .expect("load cube");
+
+    let preview = model.render_preview(4).expect("render preview");
+    assert_eq!(preview.dimensions(), (32, 24));
+    model.render().expect("render cube");
+    // every preview pixel stands in for a 4x4 block of the full image
+    let full = model.output();
+    let downscaled = RgbImage::from_fn(32, 24, |x, y| {
+        let mut sum = [0u32; 3];
+        for (dx, dy) in (0..4).flat_map(|dx| (0..4).map(move |dy| (dx, dy))) {
+            let pixel = full.get_pixel(x * 4 + dx, y * 4 + dy);
+            for c in 0..3 {
+                sum[c] += pixel.0[c] as u32;
+            }
+        }
+        Rgb(sum.map(|channel| ((channel + 8) / 16) as u8))
+    });
+
+    // only the pixels along the silhouette and the edges between faces may differ, where the
+    // preview samples one point and the downscaled image averages a block. A quarter of the
+    // pixels off by 4 levels would still be over 40 dB, and the shapes have to line up
+    let psnr = psnr(&preview, &downscaled).expect("same size");
+    let ssim = ssim(&preview, &downscaled).expect("same size");
+    assert!(psnr >= 40.0, "PSNR {:.1} dB", psnr);
+    assert!(ssim >= 0.95, "SSIM {:.3}", ssim);
+}
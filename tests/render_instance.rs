@@ -0,0 +1,91 @@
+mod fixtures;
+
+use std::path::Path;
+use std::sync::Arc;
+
+use model_to_image::{ModelToImage, ModelToImageBuilder, ViewPreset};
+
+const SIZES: [(u32, u32); 4] = [(24, 24), (48, 32), (64, 96), (100, 40)];
+
+fn cube(path: &Path, size: (u32, u32)) -> ModelToImage {
+    ModelToImageBuilder::new(&path.to_path_buf())
+        .with_size(size)
+        .with_light_direction([0.48, -0.64, -0.6])
+        .build()
+        .expect("build cube")
+}
+
+#[test]
+fn instances_render_four_sizes_on_four_threads() {
+    let dir = fixtures::fixture_dir("render_instance_threads");
+    let path = fixtures::write_obj_cube(&dir);
+    let model = cube(&path, (32, 32));
+    assert_eq!(Arc::strong_count(model.meshes()), 1);
+
+    let outputs: Vec<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> = SIZES
+            .iter()
+            .map(|&size| {
+                let mut instance = model.clone_for_render().with_size(size).expect("resize instance");
+                scope.spawn(move || instance.render().expect("render instance").output().clone())
+            })
+            .collect();
+        // every instance holds the one copy of the geometry while it renders
+        assert!(Arc::strong_count(model.meshes()) > 1);
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+    assert_eq!(Arc::strong_count(model.meshes()), 1, "the instances let go when dropped");
+
+    for (output, size) in outputs.iter().zip(SIZES) {
+        let mut expected = cube(&path, size);
+        assert_eq!(output, expected.render().expect("render cube").output(), "{:?}", size);
+    }
+}
+
+#[test]
+fn instances_share_the_geometry_until_one_changes_it() {
+    let dir = fixtures::fixture_dir("render_instance_sharing");
+    let model = cube(&fixtures::write_obj_cube(&dir), (32, 32));
+
+    let mut front = model.clone_for_render().with_view(ViewPreset::Front);
+    let resized = model.clone_for_render().with_size((16, 16)).expect("resize instance");
+    assert_eq!(Arc::strong_count(model.meshes()), 3);
+    assert!(Arc::ptr_eq(front.meshes(), model.meshes()));
+
+    // a turntable turns its own copy and hands the shared one back at the end
+    front.render_turntable(3).expect("render turntable");
+    assert!(Arc::ptr_eq(front.meshes(), model.meshes()));
+
+    let top = model.clone_for_render().with_view(ViewPreset::Top);
+    assert!(!Arc::ptr_eq(top.meshes(), model.meshes()));
+    assert_eq!(top.view(), ViewPreset::Top);
+    drop(resized);
+    assert_eq!(Arc::strong_count(model.meshes()), 2);
+}
+
+#[test]
+fn an_instance_renders_with_its_own_light() {
+    let dir = fixtures::fixture_dir("render_instance_light");
+    let path = fixtures::write_obj_cube(&dir);
+    let model = cube(&path, (32, 32));
+
+    let mut instance = model.clone_for_render().with_light_direction([-0.48, -0.64, -0.6]).expect("relight");
+    let expected = ModelToImageBuilder::new(&path)
+        .with_size((32, 32))
+        .with_light_direction([-0.48, -0.64, -0.6])
+        .build()
+        .expect("build cube")
+        .render()
+        .expect("render cube")
+        .output()
+        .clone();
+    assert_eq!(instance.render().expect("render instance").output(), &expected);
+    assert!(instance.clone_for_render().with_light_direction([0.0; 3]).is_err());
+}
+
+#[test]
+fn an_instance_rejects_an_empty_size() {
+    let dir = fixtures::fixture_dir("render_instance_empty");
+    let model = cube(&fixtures::write_obj_cube(&dir), (32, 32));
+    assert!(model.clone_for_render().with_size((0, 10)).is_err());
+}
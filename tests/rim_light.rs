@@ -0,0 +1,80 @@
+use image::{DynamicImage, Rgb, RgbImage};
+use model_to_image::{Colour, MaterialData, MeshData, ModelToImageBuilder};
+
+const SIZE: u32 = 96;
+const BACKGROUND: [u8; 3] = [211, 211, 211];
+
+/// A black UV sphere of radius 1 around the origin, wound counter-clockwise from outside.
+fn black_sphere(rings: u32, segments: u32) -> (MeshData, MaterialData) {
+    let mut positions = Vec::new();
+    for ring in 0..=rings {
+        let theta = std::f32::consts::PI * ring as f32 / rings as f32;
+        for segment in 0..=segments {
+            let phi = std::f32::consts::TAU * segment as f32 / segments as f32;
+            positions.push([theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin()]);
+        }
+    }
+    let mut triangles = Vec::new();
+    for ring in 0..rings {
+        for segment in 0..segments {
+            let a = ring * (segments + 1) + segment;
+            let b = a + segments + 1;
+            triangles.push([a, a + 1, b]);
+            triangles.push([a + 1, b + 1, b]);
+        }
+    }
+    let uvs = vec![[0.5, 0.5]; positions.len()];
+    let black = MaterialData {
+        texture: Some(DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, Rgb([0, 0, 0])))),
+        ..Default::default()
+    };
+    (MeshData { positions, triangles, uvs, ..Default::default() }, black)
+}
+
+fn render(rim: bool) -> RgbImage {
+    let (sphere, black) = black_sphere(32, 64);
+    let mut builder = ModelToImageBuilder::from_meshes(vec![sphere], vec![black]).with_size((SIZE, SIZE));
+    if rim {
+        builder = builder.with_rim_light(Colour::from((255, 255, 255)), 2.0, 2.0);
+    }
+    let mut model = builder.build().expect("build sphere");
+    model.render().expect("render sphere");
+    model.output().clone()
+}
+
+fn luminance(pixel: [u8; 3]) -> f32 {
+    pixel[0] as f32 * 0.299 + pixel[1] as f32 * 0.587 + pixel[2] as f32 * 0.114
+}
+
+/// The first pixel of `pixels` the sphere covers.
+fn first_covered(img: &RgbImage, mut pixels: impl Iterator<Item = (u32, u32)>) -> (u32, u32) {
+    pixels.find(|&(x, y)| img.get_pixel(x, y).0 != BACKGROUND).expect("sphere is drawn")
+}
+
+/// The outermost pixels of the sphere along the middle row and column, on all four sides.
+fn silhouette(img: &RgbImage) -> [(u32, u32); 4] {
+    let middle = SIZE / 2;
+    [
+        first_covered(img, (0..SIZE).map(|x| (x, middle))),
+        first_covered(img, (0..SIZE).rev().map(|x| (x, middle))),
+        first_covered(img, (0..SIZE).map(|y| (middle, y))),
+        first_covered(img, (0..SIZE).rev().map(|y| (middle, y))),
+    ]
+}
+
+#[test]
+fn a_white_rim_light_outlines_a_black_model() {
+    let (plain, rimmed) = (render(false), render(true));
+    let edge = silhouette(&rimmed);
+    assert_eq!(edge, silhouette(&plain), "the rim light changed the sphere's outline");
+
+    for (x, y) in edge {
+        assert!(luminance(plain.get_pixel(x, y).0) < 8.0, "({}, {}) isn't black without the rim", x, y);
+        let lit = luminance(rimmed.get_pixel(x, y).0);
+        assert!(lit > 128.0, "({}, {}) on the silhouette is only {:.1} bright", x, y, lit);
+    }
+
+    // the middle faces the camera, where the rim light adds nothing
+    let centre = rimmed.get_pixel(SIZE / 2, SIZE / 2).0;
+    assert!(centre.iter().all(|&c| c <= 2), "the middle of the sphere is {:?}", centre);
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "scene-cache")]
+
+mod fixtures;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use image::RgbImage;
+use model_to_image::ModelToImageBuilder;
+
+fn render(model_path: &PathBuf, cache_dir: &Path) -> (RgbImage, bool) {
+    let mut model = ModelToImageBuilder::new(model_path)
+        .with_size((96, 96))
+        .with_scene_cache_dir(cache_dir)
+        .build()
+        .expect("build cube");
+    model.render().expect("render cube");
+    (model.output().clone(), model.stats().scene_from_cache)
+}
+
+fn entries(cache_dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(cache_dir).expect("read cache dir").map(|entry| entry.unwrap().path()).collect()
+}
+
+#[test]
+fn a_second_build_reads_the_scene_from_the_cache() {
+    let dir = fixtures::fixture_dir("scene_cache");
+    let model_path = fixtures::write_obj_cube(&dir);
+    let cache_dir = dir.join("cache");
+
+    let (imported, from_cache) = render(&model_path, &cache_dir);
+    assert!(!from_cache);
+    assert_eq!(entries(&cache_dir).len(), 1);
+
+    let (cached, from_cache) = render(&model_path, &cache_dir);
+    assert!(from_cache);
+    assert_eq!(cached, imported);
+}
+
+#[test]
+fn a_changed_model_is_not_read_from_the_cache() {
+    let dir = fixtures::fixture_dir("scene_cache_changed");
+    let model_path = fixtures::write_obj_cube(&dir);
+    let cache_dir = dir.join("cache");
+    render(&model_path, &cache_dir);
+
+    let obj = fs::read_to_string(&model_path).unwrap().replace("v 1", "v 2");
+    fs::write(&model_path, obj).unwrap();
+    let (_, from_cache) = render(&model_path, &cache_dir);
+    assert!(!from_cache);
+    assert_eq!(entries(&cache_dir).len(), 2);
+}
+
+#[test]
+fn corrupt_entries_are_imported_again_and_replaced() {
+    let dir = fixtures::fixture_dir("scene_cache_corrupt");
+    let model_path = fixtures::write_obj_cube(&dir);
+    let cache_dir = dir.join("cache");
+    let (imported, _) = render(&model_path, &cache_dir);
+    let [entry] = &entries(&cache_dir)[..] else {
+        panic!("expected one cache entry");
+    };
+    let valid = fs::read(entry).unwrap();
+
+    let corruptions = [
+        Vec::new(),
+        b"not a scene".to_vec(),
+        valid[..valid.len() / 2].to_vec(),
+        [&valid[..8], &[99, 0, 0, 0], &valid[12..]].concat(),
+    ];
+    for corrupt in corruptions {
+        fs::write(entry, &corrupt).unwrap();
+        let (rendered, from_cache) = render(&model_path, &cache_dir);
+        assert!(!from_cache);
+        assert_eq!(rendered, imported);
+        assert_eq!(fs::read(entry).unwrap(), valid);
+
+        let (_, from_cache) = render(&model_path, &cache_dir);
+        assert!(from_cache);
+    }
+}
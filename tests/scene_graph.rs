@@ -0,0 +1,24 @@
+mod fixtures;
+
+use model_to_image::ModelToImageBuilder;
+use nalgebra::Point3;
+
+#[test]
+fn node_projects_onto_its_place_in_the_render() {
+    let dir = fixtures::fixture_dir("node_projects");
+    // the marker sits on the top right front corner of the cube
+    let path = fixtures::write_gltf_cube_with_marker(&dir, "Hardpoint_Muzzle", [1.0, 1.0, 1.0]);
+    let mut model = ModelToImageBuilder::new(&path).with_size((64, 64)).build().expect("load cube");
+
+    let transform = model.node_world_transform("Hardpoint_Muzzle").expect("marker node");
+    let marker = transform.transform_point(&Point3::origin());
+    assert!(model.project_point(marker.into()).is_none(), "nothing is projected before rendering");
+
+    model.render().expect("render cube");
+    let (x, y) = model.project_point(marker.into()).expect("marker inside the image");
+    // the cube fills the image apart from a 10% margin, so the corner is near the top right
+    assert!((52..=60).contains(&x), "x = {}", x);
+    assert!((3..=11).contains(&y), "y = {}", y);
+
+    assert!(model.node_world_transform("Missing").is_none());
+}
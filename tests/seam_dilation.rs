@@ -0,0 +1,94 @@
+use image::{DynamicImage, Rgba, RgbaImage};
+use model_to_image::{MaterialData, MeshData, ModelToImageBuilder};
+
+const ATLAS_SIZE: u32 = 64;
+const RED: [u8; 4] = [255, 0, 0, 255];
+const GREEN: [u8; 4] = [0, 255, 0, 255];
+
+/// Where each half of the square lands in the atlas: `offset + scale * position`. The two
+/// islands are apart in the atlas, so their edges are UV seams.
+const ISLANDS: [(f32, f32); 2] = [(0.05, 0.41), (0.55, 0.41)];
+
+/// A square facing the camera, split along its diagonal into two triangles with their own
+/// island of the atlas each.
+fn square() -> MeshData {
+    let halves = [[[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]], [[1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]];
+    let mut mesh = MeshData::default();
+    for (half, (offset, scale)) in halves.iter().zip(ISLANDS) {
+        let first = mesh.positions.len() as u32;
+        for &[x, y] in half {
+            mesh.positions.push([x, y, 0.0]);
+            mesh.uvs.push([offset + scale * x, offset + scale * y]);
+        }
+        mesh.triangles.push([first, first + 1, first + 2]);
+    }
+    mesh
+}
+
+/// The atlas of [`square`]: a red and a green island with hard borders, on `background`.
+fn atlas(background: [u8; 4]) -> RgbaImage {
+    RgbaImage::from_fn(ATLAS_SIZE, ATLAS_SIZE, |x, y| {
+        // texel centres, with v counted up from the bottom row
+        let u = (x as f32 + 0.5) / ATLAS_SIZE as f32;
+        let v = 1.0 - (y as f32 + 0.5) / ATLAS_SIZE as f32;
+        let (low, high) = (ISLANDS[0], ISLANDS[1]);
+        if u >= low.0 && v >= low.0 && u + v <= 2.0 * low.0 + low.1 {
+            Rgba(RED)
+        } else if u <= high.0 + high.1 && v <= high.0 + high.1 && u + v >= 2.0 * high.0 + high.1 {
+            Rgba(GREEN)
+        } else {
+            Rgba(background)
+        }
+    })
+}
+
+/// How many pixels of a 256² render of the square come out near black, and whether both
+/// islands show.
+fn dark_pixels(texture: DynamicImage, dilation: u32) -> usize {
+    let material = MaterialData { texture: Some(texture), ..Default::default() };
+    let mut model = ModelToImageBuilder::from_meshes(vec![square()], vec![material])
+        .with_size((256, 256))
+        .with_light_direction([0.0, 0.0, -1.0])
+        .with_seam_dilation(dilation)
+        .build()
+        .expect("build square");
+    model.render().expect("render square");
+
+    let pixels: Vec<[u8; 3]> = model.output().pixels().map(|pixel| pixel.0).collect();
+    assert!(pixels.contains(&[255, 0, 0]) && pixels.contains(&[0, 255, 0]), "both islands show");
+    pixels.iter().filter(|pixel| pixel.iter().all(|&channel| channel < 60)).count()
+}
+
+#[test]
+fn dilation_hides_the_seams_of_an_opaque_atlas() {
+    let texture = || DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(atlas([0, 0, 0, 255])).to_rgb8());
+    assert!(dark_pixels(texture(), 0) > 0, "the seams show without dilation");
+    assert_eq!(dark_pixels(texture(), 4), 0);
+}
+
+#[test]
+fn dilation_fills_transparent_texels_around_the_islands() {
+    let texture = || DynamicImage::ImageRgba8(atlas([0, 0, 0, 0]));
+    assert!(dark_pixels(texture(), 0) > 0, "the seams show without dilation");
+    assert_eq!(dark_pixels(texture(), 4), 0);
+}
+
+#[test]
+fn textures_the_uvs_cover_entirely_are_left_alone() {
+    let mut mesh = square();
+    // one island spanning the whole texture
+    for (uv, position) in mesh.uvs.iter_mut().zip(&mesh.positions) {
+        *uv = [position[0], position[1]];
+    }
+    let texture = DynamicImage::ImageRgba8(atlas([0, 0, 0, 255]));
+    let render = |dilation: u32| {
+        let material = MaterialData { texture: Some(texture.clone()), ..Default::default() };
+        let mut model = ModelToImageBuilder::from_meshes(vec![mesh.clone()], vec![material])
+            .with_size((64, 64))
+            .with_seam_dilation(dilation)
+            .build()
+            .expect("build square");
+        model.render().expect("render square").output().clone()
+    };
+    assert_eq!(render(4), render(0));
+}
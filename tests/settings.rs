@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use model_to_image::{ModelToImageBuilder, RenderSettings};
+
+#[test]
+fn shared_defaults_can_be_overridden_field_by_field() {
+    let defaults = RenderSettings { margin: 0.2, ..Default::default() };
+    let builder = ModelToImageBuilder::new(&PathBuf::from("model.obj"))
+        .with_settings(defaults.clone())
+        .with_size((64, 32));
+    assert_eq!(builder.settings.size, (64, 32));
+    assert_eq!(builder.settings.margin, 0.2);
+    assert_eq!(RenderSettings { size: (64, 32), ..defaults }, builder.settings);
+}
+
+#[test]
+#[allow(deprecated)]
+fn the_old_fields_are_still_reachable() {
+    let mut builder = ModelToImageBuilder::new(&PathBuf::from("model.obj"))
+        .with_size((64, 32))
+        .with_light_direction([1.0, 0.0, 0.0])
+        .with_margin(0.05);
+    assert_eq!(builder.size(), (64, 32));
+    assert_eq!(builder.light_dir(), [1.0, 0.0, 0.0]);
+    assert_eq!(builder.margin(), 0.05);
+
+    *builder.size_mut() = (16, 16);
+    *builder.light_dir_mut() = [0.0, -1.0, 0.0];
+    *builder.margin_mut() = 0.0;
+    assert_eq!(builder.settings.size, (16, 16));
+    assert_eq!(builder.settings.light_dir, [0.0, -1.0, 0.0]);
+    assert_eq!(builder.settings.margin, 0.0);
+}
@@ -0,0 +1,42 @@
+mod fixtures;
+
+use std::path::Path;
+
+use model_to_image::{Framing, ModelToImageBuilder, compute_shared_framing};
+
+/// Width and height of the pixels the model covers.
+fn silhouette(path: &Path, framing: Option<Framing>) -> (u32, u32) {
+    let mut builder = ModelToImageBuilder::new(path).with_size((128, 128));
+    if let Some(framing) = framing {
+        builder = builder.with_framing(framing);
+    }
+    let mut model = builder.build().expect("load cube");
+    model.render().expect("render cube");
+    let (min_x, min_y, max_x, max_y) = model.coverage().bounding_box.expect("cube is drawn");
+    (max_x - min_x + 1, max_y - min_y + 1)
+}
+
+#[test]
+fn a_shared_framing_keeps_the_smaller_model_smaller() {
+    let big = fixtures::write_scaled_cube(&fixtures::fixture_dir("shared_framing_big"), 1.0);
+    let small = fixtures::write_scaled_cube(&fixtures::fixture_dir("shared_framing_small"), 0.5);
+
+    let framing = compute_shared_framing(&[big.clone(), small.clone()]).expect("probe cubes");
+    assert_eq!(framing.extent, [2.0, 2.0]);
+    assert_eq!(framing.center, None);
+
+    let (big_width, big_height) = silhouette(&big, Some(framing));
+    let (small_width, small_height) = silhouette(&small, Some(framing));
+    // half the size, in proportion
+    assert!(small_width.abs_diff(big_width / 2) <= 1, "{small_width} against {big_width}");
+    assert!(small_height.abs_diff(big_height / 2) <= 1, "{small_height} against {big_height}");
+
+    // framed on their own, both fill the image
+    let (own_width, _) = silhouette(&small, None);
+    assert!(own_width.abs_diff(silhouette(&big, None).0) <= 1, "{own_width} against {big_width}");
+}
+
+#[test]
+fn no_models_is_an_error() {
+    assert!(compute_shared_framing(&[]).is_err());
+}
@@ -0,0 +1,88 @@
+use model_to_image::{MeshData, ModelToImage, ModelToImageBuilder, ViewPreset};
+
+/// A gently bumpy `cells` x `cells` grid facing +Z, with UVs running along X and Y and every
+/// normal pointing the wrong way, along +X.
+fn grid(cells: u32) -> MeshData {
+    let side = cells + 1;
+    let mut mesh = MeshData::default();
+    for y in 0..side {
+        for x in 0..side {
+            let (u, v) = (x as f32 / cells as f32, y as f32 / cells as f32);
+            let (fx, fy) = (u * 2.0 - 1.0, v * 2.0 - 1.0);
+            mesh.positions.push([fx, fy, 0.05 * (fx * 5.0).sin() * (fy * 4.0).cos()]);
+            mesh.uvs.push([u, v]);
+            mesh.normals.push([1.0, 0.0, 0.0]);
+        }
+    }
+    for y in 0..cells {
+        for x in 0..cells {
+            let corner = y * side + x;
+            mesh.triangles.push([corner, corner + 1, corner + side + 1]);
+            mesh.triangles.push([corner, corner + side + 1, corner + side]);
+        }
+    }
+    mesh
+}
+
+fn build(max_triangles: usize) -> ModelToImage {
+    ModelToImageBuilder::from_meshes(vec![grid(100)], Vec::new())
+        .with_size((128, 128))
+        .with_view(ViewPreset::Front)
+        .with_max_triangles(max_triangles)
+        .build()
+        .expect("build grid")
+}
+
+#[test]
+fn the_scene_is_simplified_to_the_budget() {
+    let model = build(2_000);
+    let mesh = &model.meshes()[0];
+    assert!(mesh.triangles.len() <= 2_000, "{} triangles", mesh.triangles.len());
+    // coarse, but not collapsed to nothing
+    assert!(mesh.triangles.len() > 500, "{} triangles", mesh.triangles.len());
+    assert!(mesh.positions.len() < grid(100).positions.len());
+    assert_eq!(model.stats().triangles_before_simplifying, Some(20_000));
+}
+
+#[test]
+fn scenes_under_the_budget_are_left_alone() {
+    let model = build(20_000);
+    assert_eq!(model.meshes()[0].triangles.len(), 20_000);
+    assert_eq!(model.stats().triangles_before_simplifying, None);
+}
+
+#[test]
+fn normals_are_recomputed_from_the_simplified_faces() {
+    let model = build(2_000);
+    let mesh = &model.meshes()[0];
+    assert_eq!(mesh.normals.len(), mesh.positions.len());
+    for normal in &mesh.normals {
+        let length = normal.iter().map(|c| c * c).sum::<f32>().sqrt();
+        assert!((length - 1.0).abs() < 1e-3, "normal {:?}", normal);
+        // the grid is only slightly bumpy, so every normal points out of its front, whatever
+        // the mesh came with
+        assert!(normal[2] > 0.7, "normal {:?}", normal);
+    }
+}
+
+#[test]
+fn uvs_are_carried_through() {
+    let model = build(2_000);
+    let mesh = &model.meshes()[0];
+    assert_eq!(mesh.uvs.len(), mesh.positions.len());
+
+    // the UVs run straight along X and Y, and a merged vertex averages both, so they still do
+    let range = |values: Vec<f32>| {
+        let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        (min, max - min)
+    };
+    for axis in 0..2 {
+        let (position_min, position_extent) = range(mesh.positions.iter().map(|p| p[axis]).collect());
+        let (uv_min, uv_extent) = range(mesh.uvs.iter().map(|uv| uv[axis]).collect());
+        for (position, uv) in mesh.positions.iter().zip(&mesh.uvs) {
+            let along = (position[axis] - position_min) / position_extent;
+            assert!(((uv[axis] - uv_min) / uv_extent - along).abs() < 1e-3, "{:?} at {:?}", uv, position);
+        }
+    }
+}
@@ -0,0 +1,33 @@
+mod fixtures;
+
+use model_to_image::{ImageSize, ModelToImageBuilder, Size};
+
+#[test]
+fn sizes_convert_to_and_from_tuples() {
+    let size = Size::new(320, 180);
+    assert_eq!(Size::from((320, 180)), size);
+    assert_eq!(Size::from([320, 180]), size);
+    assert_eq!(<(u32, u32)>::from(size), (320, 180));
+    assert_eq!(size, (320, 180));
+    assert_eq!((size.width(), size.height()), (320, 180));
+
+    assert!(size.is_landscape() && !size.is_portrait());
+    assert!((size.aspect_ratio() - 16.0 / 9.0).abs() < 1e-6);
+    assert_eq!(Size::from_width_and_aspect_ratio(320, 16.0 / 9.0), size);
+    assert_eq!(Size::square(8), (8, 8));
+    assert_eq!(Size::new(70_000, 70_000).pixel_count(), 4_900_000_000);
+}
+
+#[test]
+fn small_sizes_are_kept_and_empty_ones_are_rejected() {
+    let dir = fixtures::fixture_dir("size");
+    let path = fixtures::write_obj_cube(&dir);
+
+    let mut icon = ModelToImageBuilder::new(&path).with_size(Size::square(8)).build().expect("load cube");
+    icon.render().expect("render icon");
+    assert_eq!(icon.size(), Size::square(8));
+    assert_eq!(icon.output().size(), icon.size());
+
+    let err = ModelToImageBuilder::new(&path).with_size((0, 16)).build().err().expect("empty size");
+    assert!(err.to_string().contains("at least one pixel"), "{}", err);
+}
@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+
+use image::{Rgb, RgbImage};
+use model_to_image::{ModelToImage, ModelToImageBuilder, SMALL_IMAGE_SAMPLES, psnr};
+
+fn fish(configure: impl FnOnce(ModelToImageBuilder) -> ModelToImageBuilder) -> ModelToImage {
+    let path = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/src/fish.glb"));
+    let mut model = configure(ModelToImageBuilder::new(&path)).build().expect("build fish");
+    model.render().expect("render fish");
+    model
+}
+
+/// Averages every `factor`×`factor` block of `image` into one pixel.
+fn box_downsample(image: &RgbImage, factor: u32) -> RgbImage {
+    RgbImage::from_fn(image.width() / factor, image.height() / factor, |x, y| {
+        let mut sum = [0_u32; 3];
+        for dy in 0..factor {
+            for dx in 0..factor {
+                let pixel = image.get_pixel(x * factor + dx, y * factor + dy).0;
+                for (sum, channel) in sum.iter_mut().zip(pixel) {
+                    *sum += channel as u32;
+                }
+            }
+        }
+        Rgb(sum.map(|channel| (channel as f32 / (factor * factor) as f32).round() as u8))
+    })
+}
+
+#[test]
+fn a_tiny_fish_is_smoothed_like_a_larger_one_scaled_down() {
+    let smoothed = fish(|builder| builder.with_size((16, 16)).with_small_image_threshold(32));
+    let aliased = fish(|builder| builder.with_size((16, 16)));
+    let reference = box_downsample(fish(|builder| builder.with_size((64, 64))).output(), 4);
+
+    assert_eq!(smoothed.stats().passes, SMALL_IMAGE_SAMPLES);
+    assert_eq!(aliased.stats().passes, 1);
+    assert!(smoothed.coverage().fraction > 0.05, "{:?}", smoothed.coverage());
+    let smoothed_psnr = psnr(smoothed.output(), &reference).unwrap();
+    let aliased_psnr = psnr(aliased.output(), &reference).unwrap();
+    assert!(smoothed_psnr > aliased_psnr, "{} <= {}", smoothed_psnr, aliased_psnr);
+}
+
+#[test]
+fn more_explicit_samples_are_kept() {
+    let model = fish(|builder| {
+        builder.with_size((16, 16)).with_small_image_threshold(32).with_accumulation_samples(32)
+    });
+    assert_eq!(model.stats().passes, 32);
+}
+
+#[test]
+fn images_at_the_threshold_are_left_alone() {
+    let model = fish(|builder| builder.with_size((32, 16)).with_small_image_threshold(32));
+    assert_eq!(model.stats().passes, 1);
+}
+
+#[test]
+fn tiny_images_render_without_panicking() {
+    for size in [(1, 1), (2, 3), (16, 1), (1, 16)] {
+        let model = fish(|builder| builder.with_size(size).with_small_image_threshold(32));
+        assert_eq!(model.output().dimensions(), size);
+    }
+}
+
+#[test]
+fn a_margin_leaving_no_room_is_clamped_with_a_warning() {
+    for margin in [0.5, 0.75] {
+        let model = fish(|builder| builder.with_size((16, 16)).with_margin(margin));
+        assert!(
+            model.warnings().iter().any(|warning| warning.contains("leaves less than a pixel")),
+            "{:?}",
+            model.warnings()
+        );
+        // drawn a pixel across, not mirrored to fill the image
+        assert!(model.coverage().covered_pixels <= 4, "{:?}", model.coverage());
+    }
+
+    let model = fish(|builder| builder.with_size((16, 16)));
+    assert!(!model.warnings().iter().any(|warning| warning.contains("leaves less than a pixel")));
+}
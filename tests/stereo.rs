@@ -0,0 +1,58 @@
+mod fixtures;
+
+use image::GenericImageView;
+use model_to_image::{ModelToImage, ModelToImageBuilder, StereoMode, ViewPreset};
+
+fn build(path: &std::path::Path) -> ModelToImage {
+    ModelToImageBuilder::new(path)
+        .with_size((64, 64))
+        .with_view(ViewPreset::Isometric)
+        .build()
+        .expect("build cube")
+}
+
+fn covered(img: &image::RgbImage) -> usize {
+    img.pixels().filter(|pixel| pixel.0 != [211, 211, 211]).count()
+}
+
+#[test]
+fn side_by_side_views_differ_but_cover_about_as_much() {
+    let dir = fixtures::fixture_dir("stereo_side_by_side");
+    let mut model = build(&fixtures::write_obj_cube(&dir));
+    let pair = model.render_stereo(6.0, StereoMode::SideBySide).expect("render pair");
+    assert_eq!(pair.dimensions(), (128, 64));
+
+    let (left, right) = (pair.view(0, 0, 64, 64).to_image(), pair.view(64, 0, 64, 64).to_image());
+    assert_ne!(left, right);
+    let (left_area, right_area) = (covered(&left) as f32, covered(&right) as f32);
+    assert!(left_area > 0.0);
+    assert!((left_area - right_area).abs() / left_area < 0.05, "{} against {}", left_area, right_area);
+
+    // the model is put back, so a plain render is unchanged
+    model.render().expect("render after the pair");
+    let mut fresh = build(&fixtures::write_obj_cube(&dir));
+    fresh.render().expect("render fresh");
+    assert_eq!(model.output(), fresh.output());
+}
+
+#[test]
+fn anaglyphs_take_red_from_the_left_eye_and_the_rest_from_the_right() {
+    let dir = fixtures::fixture_dir("stereo_anaglyph");
+    let path = fixtures::write_obj_cube(&dir);
+    let pair = build(&path).render_stereo(6.0, StereoMode::SideBySide).expect("render pair");
+    let anaglyph = build(&path).render_stereo(6.0, StereoMode::Anaglyph).expect("render anaglyph");
+    assert_eq!(anaglyph.dimensions(), (64, 64));
+    for (x, y, pixel) in anaglyph.enumerate_pixels() {
+        let (left, right) = (pair.get_pixel(x, y).0, pair.get_pixel(x + 64, y).0);
+        assert_eq!(pixel.0, [left[0], right[1], right[2]], "at ({}, {})", x, y);
+    }
+}
+
+#[test]
+fn eye_separations_out_of_range_are_rejected() {
+    let dir = fixtures::fixture_dir("stereo_invalid");
+    let mut model = build(&fixtures::write_obj_cube(&dir));
+    for separation in [-1.0, 120.0, f32::NAN] {
+        assert!(model.render_stereo(separation, StereoMode::Anaglyph).is_err(), "{}", separation);
+    }
+}
@@ -0,0 +1,52 @@
+//! Slow tests on very large models, run with `cargo test --release -- --ignored`.
+
+mod fixtures;
+
+use model_to_image::ModelToImageBuilder;
+
+/// Reads a `kB` field such as `VmRSS` out of `/proc/self/status`, in bytes.
+#[cfg(target_os = "linux")]
+fn proc_status_bytes(field: &str) -> u64 {
+    let status = std::fs::read_to_string("/proc/self/status").expect("read /proc/self/status");
+    let line = status
+        .lines()
+        .find(|line| line.starts_with(field))
+        .unwrap_or_else(|| panic!("no {} in /proc/self/status", field));
+    let kb: u64 = line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|kb| kb.parse().ok())
+        .expect("parse /proc/self/status");
+    kb * 1024
+}
+
+#[test]
+#[ignore = "writes and renders a 5M triangle model, takes a while"]
+#[cfg(target_os = "linux")]
+fn five_million_triangles_render_within_twice_the_scene() {
+    // 1600 x 1600 cells, two triangles each
+    let dir = fixtures::fixture_dir("stress_5m");
+    let path = fixtures::write_ply_grid(&dir, 1600);
+
+    let before_load = proc_status_bytes("VmRSS:");
+    let mut model = ModelToImageBuilder::new(&path)
+        .with_size((512, 512))
+        .build()
+        .expect("load grid");
+    let loaded = proc_status_bytes("VmRSS:");
+    let scene = loaded.saturating_sub(before_load);
+
+    // reset the peak so it only covers the render, the importer's own peak doesn't count
+    std::fs::write("/proc/self/clear_refs", "5").expect("reset peak RSS");
+    model.render().expect("render grid");
+    let peak = proc_status_bytes("VmHWM:");
+
+    assert!(model.stats().triangles >= 5_000_000, "only {} triangles", model.stats().triangles);
+    let rendering = peak.saturating_sub(before_load);
+    assert!(
+        rendering <= scene * 2,
+        "peak while rendering was {} MiB for a {} MiB scene",
+        rendering / (1024 * 1024),
+        scene / (1024 * 1024)
+    );
+}
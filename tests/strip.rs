@@ -0,0 +1,49 @@
+mod fixtures;
+
+use model_to_image::ModelToImageBuilder;
+
+/// How many pixels of the middle row of each slice the model covers.
+fn silhouette_widths(strip: &image::RgbImage, slice_px: u32) -> Vec<u32> {
+    let y = strip.height() / 2;
+    (0..strip.width() / slice_px)
+        .map(|slice| {
+            let columns = slice * slice_px..(slice + 1) * slice_px;
+            columns.filter(|&x| strip.get_pixel(x, y).0 != [211, 211, 211]).count() as u32
+        })
+        .collect()
+}
+
+#[test]
+fn a_cube_strip_repeats_every_quarter_turn() {
+    let dir = fixtures::fixture_dir("strip_cube");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir))
+        .with_size((32, 32))
+        .build()
+        .expect("build cube");
+    let strip = model.render_strip(360, 48, 32).expect("render strip");
+    assert_eq!(strip.dimensions(), (360 * 48, 32));
+
+    let widths = silhouette_widths(&strip, 48);
+    let (narrowest, widest) = (*widths.iter().min().unwrap(), *widths.iter().max().unwrap());
+    // face on the cube is a square, corner on it is √2 wider
+    assert!(widest as f32 / narrowest as f32 > 1.3, "{} to {}", narrowest, widest);
+    for slice in 0..270 {
+        let (now, later) = (widths[slice] as i32, widths[slice + 90] as i32);
+        assert!((now - later).abs() <= 1, "slice {} is {} wide but slice {} is {}", slice, now, slice + 90, later);
+        assert!((now - widths[slice + 1] as i32).abs() <= 2, "jump after slice {}", slice);
+    }
+    assert!(widths[0] <= narrowest + 1 && widths[45] + 1 >= widest, "{:?}", &widths[..90]);
+
+    // the model's own image is the size it was built with
+    model.render().expect("render after the strip");
+    assert_eq!(model.output().dimensions(), (32, 32));
+}
+
+#[test]
+fn empty_strips_are_rejected() {
+    let dir = fixtures::fixture_dir("strip_empty");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir)).build().expect("build cube");
+    assert!(model.render_strip(0, 8, 8).is_err());
+    assert!(model.render_strip(8, 0, 8).is_err());
+    assert!(model.render_strip(8, 8, 0).is_err());
+}
@@ -0,0 +1,116 @@
+mod fixtures;
+
+use image::{Rgb, RgbImage};
+use model_to_image::{DefinedColours, GLYPH_HEIGHT, ModelToImageBuilder, draw_text, measure_text};
+
+/// "Hi" at scale 1, one character apart.
+const HI: [&str; 7] = [
+    "#...#...#..",
+    "#...#......",
+    "#...#..##..",
+    "#####...#..",
+    "#...#...#..",
+    "#...#...#..",
+    "#...#..###.",
+];
+
+/// The image as rows of `#` for lit pixels and `.` for black ones.
+fn bitmap(img: &RgbImage) -> Vec<String> {
+    (0..img.height())
+        .map(|y| (0..img.width()).map(|x| if img.get_pixel(x, y).0 == [0, 0, 0] { '.' } else { '#' }).collect())
+        .collect()
+}
+
+/// `rows` with every pixel made `scale` pixels wide and tall.
+fn scaled(rows: &[&str], scale: usize) -> Vec<String> {
+    rows.iter()
+        .flat_map(|row| {
+            let wide: String = row.chars().flat_map(|ch| std::iter::repeat_n(ch, scale)).collect();
+            std::iter::repeat_n(wide, scale)
+        })
+        .collect()
+}
+
+#[test]
+fn text_matches_the_golden_bitmap() {
+    assert_eq!(measure_text("Hi", 1), (11, 7));
+    let mut img = RgbImage::new(13, 9);
+    draw_text(&mut img, 1, 1, "Hi", DefinedColours::White.colour(), 1);
+
+    let mut expected = vec![".".repeat(13)];
+    expected.extend(HI.iter().map(|row| format!(".{}.", row)));
+    expected.push(".".repeat(13));
+    assert_eq!(bitmap(&img), expected);
+}
+
+#[test]
+fn every_font_pixel_is_a_square_of_the_scale() {
+    assert_eq!(measure_text("Hi", 3), (33, 21));
+    let mut img = RgbImage::new(33, 21);
+    draw_text(&mut img, 0, 0, "Hi", Rgb([255, 0, 0]), 3);
+    assert_eq!(bitmap(&img), scaled(&HI, 3));
+    assert!(img.pixels().all(|pixel| pixel.0 == [0, 0, 0] || pixel.0 == [255, 0, 0]));
+}
+
+#[test]
+fn text_past_the_edges_is_clipped() {
+    let mut img = RgbImage::new(11, 7);
+    draw_text(&mut img, -3, -2, "Hi", Rgb([255, 255, 255]), 1);
+    let expected: Vec<String> = HI[2..]
+        .iter()
+        .map(|row| format!("{}...", &row[3..]))
+        .chain(std::iter::repeat_n(".".repeat(11), 2))
+        .collect();
+    assert_eq!(bitmap(&img), expected);
+
+    // entirely outside, in every direction
+    for (x, y) in [(-100, 0), (100, 0), (0, -100), (0, 100), (i64::MIN / 2, i64::MAX / 2)] {
+        let mut img = RgbImage::new(11, 7);
+        draw_text(&mut img, x, y, "Hi", Rgb([255, 255, 255]), 1);
+        assert_eq!(bitmap(&img), vec![".".repeat(11); 7]);
+    }
+}
+
+#[test]
+fn line_breaks_start_a_new_line() {
+    let line_height = GLYPH_HEIGHT + 2;
+    assert_eq!(measure_text("Hi\nH", 1), (11, line_height + GLYPH_HEIGHT));
+    assert_eq!(measure_text("", 2), (0, GLYPH_HEIGHT * 2));
+
+    let mut img = RgbImage::new(11, 16);
+    draw_text(&mut img, 0, 0, "Hi\nH", Rgb([255, 255, 255]), 1);
+    let rows = bitmap(&img);
+    assert_eq!(rows[..7], HI.map(String::from));
+    assert_eq!(rows[7..9], vec![".".repeat(11); 2]);
+    let second: Vec<String> = HI.iter().map(|row| format!("{}......", &row[..5])).collect();
+    assert_eq!(rows[9..], second);
+}
+
+#[test]
+fn the_debug_hud_is_drawn_in_the_top_left_corner() {
+    let dir = fixtures::fixture_dir("text_debug_hud");
+    let path = fixtures::write_obj_cube(&dir);
+    let render = |debug_hud: bool| {
+        let mut model = ModelToImageBuilder::new(&path)
+            .with_size((128, 96))
+            .with_debug_hud(debug_hud)
+            .build()
+            .expect("build cube");
+        model.render().expect("render cube");
+        model.output().clone()
+    };
+    let (plain, hud) = (render(false), render(true));
+
+    let (text_width, _) = measure_text("128x96", 1);
+    let corner = |img: &RgbImage| (0..text_width).map(|x| img.get_pixel(x, 3).0).collect::<Vec<_>>();
+    assert_ne!(corner(&hud), corner(&plain));
+    assert!(corner(&hud).contains(&[40, 40, 40]), "the hud has text");
+    assert_eq!(hud.get_pixel(0, 0).0, [245, 245, 245]);
+
+    // the rest of the image is left alone
+    for (x, y, pixel) in plain.enumerate_pixels() {
+        if x >= 64 || y >= 48 {
+            assert_eq!(hud.get_pixel(x, y), pixel, "({}, {})", x, y);
+        }
+    }
+}
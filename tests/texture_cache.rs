@@ -0,0 +1,44 @@
+mod fixtures;
+
+use model_to_image::{ModelToImageBuilder, TextureCache};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn two_models_sharing_a_texture_decode_it_once() {
+    assert_send_sync::<TextureCache>();
+
+    let dir = fixtures::fixture_dir("texture_cache_shared");
+    let first = fixtures::write_obj_cube_with_texture(&dir, "atlas.tga", &fixtures::tga_bytes(4, 4, [0, 200, 0]));
+    // a second model using the same material library, and so the same texture file
+    let second = dir.join("other.obj");
+    std::fs::copy(&first, &second).expect("copy obj fixture");
+
+    let cache = TextureCache::new();
+    let build = |path: &std::path::Path| {
+        ModelToImageBuilder::new(path)
+            .with_texture_cache(cache.clone())
+            .build()
+            .expect("build cube")
+    };
+
+    build(&first);
+    assert_eq!((cache.decode_count(), cache.len()), (1, 1));
+    // from another thread too, as the batch CLI's jobs do
+    let second_cache = cache.clone();
+    std::thread::spawn(move || {
+        ModelToImageBuilder::new(&second)
+            .with_texture_cache(second_cache)
+            .build()
+            .expect("build cube");
+    })
+    .join()
+    .unwrap();
+    assert_eq!((cache.decode_count(), cache.len()), (1, 1));
+
+    // once cleared, the next build decodes the texture again
+    cache.clear();
+    assert!(cache.is_empty());
+    build(&first);
+    assert_eq!(cache.decode_count(), 2);
+}
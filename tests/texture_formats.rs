@@ -0,0 +1,41 @@
+mod fixtures;
+
+use model_to_image::{ModelToImageBuilder, TexturePolicy};
+
+#[test]
+fn tga_textures_load_with_the_tga_feature() {
+    let dir = fixtures::fixture_dir("texture_formats_tga");
+    let path = fixtures::write_obj_cube_with_texture(&dir, "green.tga", &fixtures::tga_bytes(4, 4, [20, 200, 40]));
+    let model = ModelToImageBuilder::new(&path).build().expect("load cube");
+
+    if cfg!(feature = "tga") {
+        assert!(model.warnings().is_empty(), "{:?}", model.warnings());
+    } else {
+        assert!(
+            model.warnings().iter().any(|warning| warning.contains("needs the `tga` feature")),
+            "{:?}",
+            model.warnings()
+        );
+    }
+}
+
+#[test]
+fn ktx2_textures_are_named_in_the_warning() {
+    let dir = fixtures::fixture_dir("texture_formats_ktx2");
+    let path = fixtures::write_obj_cube_with_texture(&dir, "basecolor.ktx2", &fixtures::ktx2_bytes());
+
+    let mut model = ModelToImageBuilder::new(&path).build().expect("load cube");
+    assert!(
+        model.warnings().iter().any(|warning| warning.starts_with("Unsupported texture format ktx2 in material ")),
+        "{:?}",
+        model.warnings()
+    );
+    model.render().expect("render untextured");
+
+    let err = ModelToImageBuilder::new(&path)
+        .with_texture_policy(TexturePolicy::Fail)
+        .build()
+        .err()
+        .expect("unsupported texture fails the build");
+    assert!(err.to_string().contains("ktx2"), "{}", err);
+}
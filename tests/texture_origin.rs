@@ -0,0 +1,79 @@
+use image::{DynamicImage, Rgb, RgbImage};
+use model_to_image::{MaterialData, MeshData, ModelToImageBuilder, TextureOrigin};
+
+/// An "F", top row first, so it reads differently upside down and mirrored.
+const GLYPH: [&str; 4] = ["####", "#...", "###.", "#..."];
+
+/// The glyph in black on white, one texel per character, stored top row first as images are.
+fn glyph_texture() -> DynamicImage {
+    DynamicImage::ImageRgb8(RgbImage::from_fn(4, 4, |x, y| {
+        if GLYPH[y as usize].as_bytes()[x as usize] == b'#' { Rgb([0, 0, 0]) } else { Rgb([255, 255, 255]) }
+    }))
+}
+
+/// A unit square facing the camera with the glyph stretched over it, (0, 0) at its bottom left.
+fn glyph_quad() -> (Vec<MeshData>, Vec<MaterialData>) {
+    let mesh = MeshData {
+        positions: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+        triangles: vec![[0, 1, 2], [0, 2, 3]],
+        uvs: vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]],
+        ..Default::default()
+    };
+    let material = MaterialData {
+        texture: Some(glyph_texture()),
+        ..Default::default()
+    };
+    (vec![mesh], vec![material])
+}
+
+/// Reads the glyph back off the render: the centre of each of the 4×4 cells the quad covers.
+fn read_glyph(configure: impl FnOnce(ModelToImageBuilder) -> ModelToImageBuilder) -> Vec<String> {
+    let (meshes, materials) = glyph_quad();
+    let builder = ModelToImageBuilder::from_meshes(meshes, materials).with_size((96, 96));
+    let mut model = configure(builder).build().expect("build glyph quad");
+    model.render().expect("render glyph quad");
+    let image = model.output();
+
+    let background = Rgb([211, 211, 211]);
+    let covered: Vec<(u32, u32)> =
+        image.enumerate_pixels().filter(|(_, _, pixel)| **pixel != background).map(|(x, y, _)| (x, y)).collect();
+    let (min_x, max_x) = (covered.iter().map(|p| p.0).min().unwrap(), covered.iter().map(|p| p.0).max().unwrap());
+    let (min_y, max_y) = (covered.iter().map(|p| p.1).min().unwrap(), covered.iter().map(|p| p.1).max().unwrap());
+    let cell = |min: u32, max: u32, idx: u32| min + ((max - min + 1) * (2 * idx + 1)) / 8;
+
+    (0..4)
+        .map(|row| {
+            (0..4)
+                .map(|col| {
+                    let pixel = image.get_pixel(cell(min_x, max_x, col), cell(min_y, max_y, row));
+                    if pixel.0[0] < 128 { '#' } else { '.' }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn a_bottom_left_origin_reads_the_glyph_upright() {
+    assert_eq!(read_glyph(|builder| builder), GLYPH);
+    assert_eq!(read_glyph(|builder| builder.with_texture_origin(TextureOrigin::BottomLeft)), GLYPH);
+}
+
+#[test]
+fn a_top_left_origin_turns_the_glyph_upside_down() {
+    let upside_down: Vec<&str> = GLYPH.iter().rev().copied().collect();
+    assert_eq!(read_glyph(|builder| builder.with_texture_origin(TextureOrigin::TopLeft)), upside_down);
+}
+
+#[test]
+fn flipping_on_import_draws_the_same_as_flipping_while_sampling() {
+    let sampled = read_glyph(|builder| builder.with_texture_origin(TextureOrigin::TopLeft));
+    let imported =
+        read_glyph(|builder| builder.with_texture_origin(TextureOrigin::TopLeft).with_flip_uvs_on_import(true));
+    assert_eq!(imported, sampled);
+}
+
+#[test]
+fn flipping_on_import_does_nothing_for_a_bottom_left_origin() {
+    assert_eq!(read_glyph(|builder| builder.with_flip_uvs_on_import(true)), GLYPH);
+}
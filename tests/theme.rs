@@ -0,0 +1,36 @@
+mod fixtures;
+
+use image::{Rgb, RgbImage};
+use model_to_image::{Colour, ModelToImageBuilder, Theme};
+
+fn render_cube(name: &str, configure: impl FnOnce(ModelToImageBuilder) -> ModelToImageBuilder) -> RgbImage {
+    let dir = fixtures::fixture_dir(name);
+    let builder = ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir)).with_size((96, 96));
+    let mut model = configure(builder).build().expect("load cube");
+    model.render().expect("render cube");
+    model.output().clone()
+}
+
+fn count(img: &RgbImage, colour: [u8; 3]) -> usize {
+    img.pixels().filter(|pixel| **pixel == Rgb(colour)).count()
+}
+
+#[test]
+fn dark_theme_gives_a_dark_background_with_a_light_outline() {
+    let img = render_cube("theme_dark", |builder| builder.with_theme(Theme::dark()));
+
+    for corner in [img.get_pixel(0, 0), img.get_pixel(95, 95)] {
+        assert!(corner.0.iter().all(|&channel| channel < 60), "background is not dark: {:?}", corner);
+    }
+    assert!(count(&img, [230, 230, 230]) > 50, "no light outline around the cube");
+}
+
+#[test]
+fn later_calls_override_single_parts_of_the_theme() {
+    let img = render_cube("theme_override", |builder| {
+        builder.with_theme(Theme::dark()).with_outline(Colour::from((255, 0, 0)), 2)
+    });
+
+    assert!(count(&img, [255, 0, 0]) > 50, "no red outline around the cube");
+    assert!(img.get_pixel(0, 0).0.iter().all(|&channel| channel < 60), "the dark background was lost");
+}
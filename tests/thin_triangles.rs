@@ -0,0 +1,52 @@
+use model_to_image::{MaterialData, MeshData, ModelToImage, ModelToImageBuilder};
+
+/// Slivers in the disc of [`disc`], few enough to render quickly.
+const SLIVERS: u32 = 4000;
+
+/// A disc of radius `radius` facing the camera, made of a fan of [`SLIVERS`] slivers around
+/// its centre, plus one triangle with its corners in a line.
+fn disc(radius: f32) -> MeshData {
+    let mut mesh = MeshData { positions: vec![[0.0, 0.0, 0.0]], ..Default::default() };
+    for idx in 0..SLIVERS {
+        let angle = idx as f32 / SLIVERS as f32 * std::f32::consts::TAU;
+        mesh.positions.push([radius * angle.cos(), radius * angle.sin(), 0.0]);
+        mesh.triangles.push([0, idx + 1, (idx + 1) % SLIVERS + 1]);
+    }
+    // no area at all
+    mesh.triangles.push([0, 1, 1]);
+    mesh
+}
+
+/// The disc at one pixel per unit, so a disc of radius 2 has slivers a few thousandths of a
+/// square pixel in area.
+fn render(configure: impl FnOnce(ModelToImageBuilder) -> ModelToImageBuilder) -> ModelToImage {
+    let builder = ModelToImageBuilder::from_meshes(vec![disc(2.0)], vec![MaterialData::default()])
+        .with_size((16, 16))
+        .with_world_scale(1.0)
+        .with_light_direction([0.0, 0.0, -1.0]);
+    let mut model = configure(builder).build().expect("build disc");
+    model.render().expect("render disc");
+    model
+}
+
+#[test]
+fn slivers_far_under_a_pixel_are_drawn() {
+    let model = render(|builder| builder);
+    assert_eq!(model.stats().triangles_drawn, SLIVERS as usize);
+    // the disc covers at least a 3x3 block of pixel centres wherever it lands
+    assert!(model.coverage().covered_pixels >= 9, "{:?}", model.coverage());
+    assert!(model.output().pixels().any(|pixel| pixel.0 == [255, 255, 255]), "the slivers are lit");
+}
+
+#[test]
+fn triangles_without_area_are_counted_not_dropped_silently() {
+    let model = render(|builder| builder);
+    assert_eq!(model.stats().triangles_degenerate, 1);
+
+    // the old threshold skips every sliver, and says so
+    let model = render(|builder| builder.with_degenerate_epsilon(1e-2));
+    assert_eq!(model.stats().triangles_drawn, 0);
+    assert_eq!(model.stats().triangles_degenerate, SLIVERS as usize + 1);
+    assert_eq!(model.coverage().covered_pixels, 0);
+    assert!(model.warnings().iter().any(|warning| warning.contains("had no area")), "{:?}", model.warnings());
+}
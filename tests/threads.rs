@@ -0,0 +1,67 @@
+mod fixtures;
+
+use std::sync::Mutex;
+
+use model_to_image::{ModelToImage, ModelToImageBuilder};
+
+fn assert_send<T: Send>() {}
+fn assert_sync<T: Sync>() {}
+
+#[test]
+fn the_model_and_its_builder_can_cross_threads() {
+    assert_send::<ModelToImage>();
+    assert_sync::<ModelToImage>();
+    assert_send::<ModelToImageBuilder>();
+    assert_sync::<ModelToImageBuilder>();
+}
+
+fn cube(name: &str) -> ModelToImage {
+    let dir = fixtures::fixture_dir(name);
+    ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir))
+        .with_size((48, 32))
+        .with_light_direction([0.48, -0.64, -0.6])
+        .build()
+        .expect("build cube")
+}
+
+#[test]
+fn a_model_behind_a_mutex_renders_from_two_threads_in_turn() {
+    let mut expected = cube("threads_mutex_expected");
+    let expected = expected.render().expect("render cube").output().clone();
+
+    let model = Mutex::new(cube("threads_mutex"));
+    std::thread::scope(|scope| {
+        for _ in 0..2 {
+            scope.spawn(|| {
+                let mut model = model.lock().unwrap();
+                assert_eq!(model.render().expect("render cube").output(), &expected);
+            });
+        }
+    });
+}
+
+#[test]
+fn clones_render_in_parallel() {
+    let mut model = cube("threads_clones");
+    let expected = model.render().expect("render cube").output().clone();
+
+    let outputs: Vec<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let mut clone = model.clone();
+                scope.spawn(move || clone.render().expect("render clone").output().clone())
+            })
+            .collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+    for output in &outputs {
+        assert_eq!(output, &expected);
+    }
+}
+
+#[test]
+fn a_model_built_on_another_thread_renders_here() {
+    let mut model = std::thread::spawn(|| cube("threads_built_elsewhere")).join().unwrap();
+    model.render().expect("render cube");
+    assert!(model.stats().triangles_drawn > 0);
+}
@@ -0,0 +1,63 @@
+mod fixtures;
+
+use std::time::{Duration, Instant};
+
+use model_to_image::{ModelToImageBuilder, TimeBudgetExceeded, TimeBudgetPolicy};
+
+#[test]
+fn tiny_time_budget_aborts_promptly() {
+    let dir = fixtures::fixture_dir("tiny_time_budget");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_ply_grid(&dir, 400))
+        .with_size((512, 512))
+        .with_accumulation_samples(8)
+        .with_time_budget(Duration::from_nanos(1), TimeBudgetPolicy::Abort)
+        .build()
+        .expect("load grid");
+
+    let started = Instant::now();
+    let err = model.render().expect_err("a 1ns budget can't be met");
+    let elapsed = started.elapsed();
+
+    let exceeded = err
+        .downcast_ref::<TimeBudgetExceeded>()
+        .unwrap_or_else(|| panic!("expected TimeBudgetExceeded, got {:#}", err));
+    assert_eq!(exceeded.budget, Duration::from_nanos(1));
+
+    // it gave up on the first check instead of drawing the 320k triangles eight times over
+    let stats = model.stats();
+    assert_eq!(stats.passes, 1);
+    assert!(stats.triangles_drawn < 1000, "drew {} triangles", stats.triangles_drawn);
+    assert!(elapsed < Duration::from_secs(5), "took {:?} to give up", elapsed);
+}
+
+#[test]
+fn partial_renders_report_running_out_of_time_in_their_stats() {
+    let dir = fixtures::fixture_dir("partial_time_budget");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_ply_grid(&dir, 400))
+        .with_size((256, 256))
+        .with_time_budget(Duration::from_nanos(1), TimeBudgetPolicy::Partial)
+        .build()
+        .expect("load grid");
+
+    let mut warnings = Vec::new();
+    for _ in 0..3 {
+        model.render().expect("a partial render still succeeds");
+        let exceeded = model.stats().time_budget_exceeded.expect("ran out of time");
+        assert_eq!(exceeded.budget, Duration::from_nanos(1));
+        warnings.push(model.warnings().len());
+    }
+    // every render says so on its own, rather than piling up warnings on the model
+    assert!(warnings.iter().all(|&count| count == warnings[0]), "{:?}", model.warnings());
+}
+
+#[test]
+fn renders_within_their_budget_report_nothing() {
+    let dir = fixtures::fixture_dir("ample_time_budget");
+    let mut model = ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir))
+        .with_size((32, 32))
+        .with_time_budget(Duration::from_secs(60), TimeBudgetPolicy::Partial)
+        .build()
+        .expect("load cube");
+    model.render().expect("render cube");
+    assert_eq!(model.stats().time_budget_exceeded, None);
+}
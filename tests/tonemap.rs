@@ -0,0 +1,49 @@
+use image::{DynamicImage, Rgb, RgbImage};
+use model_to_image::{Light, MaterialData, MeshData, ModelToImageBuilder};
+
+/// A saturated red, which per-channel clipping washes out towards pink.
+const RED: [u8; 3] = [220, 30, 30];
+
+/// The centre pixel of a red textured quad facing the camera, lit by two lights as bright as
+/// the default one shining straight at it.
+fn render_red_quad(tonemap: bool) -> [u8; 3] {
+    let quad = MeshData {
+        positions: vec![[-1.0, -1.0, 0.0], [1.0, -1.0, 0.0], [1.0, 1.0, 0.0], [-1.0, 1.0, 0.0]],
+        triangles: vec![[0, 1, 2], [0, 2, 3]],
+        uvs: vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]],
+        ..Default::default()
+    };
+    let material = MaterialData {
+        texture: Some(DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 4, Rgb(RED)))),
+        ..Default::default()
+    };
+    let mut model = ModelToImageBuilder::from_meshes(vec![quad], vec![material])
+        .with_size((32, 32))
+        .with_lights(vec![Light::new([0.0, 0.0, -1.0], 1.0), Light::new([0.0, 0.0, -1.0], 1.0)])
+        .with_tonemap(tonemap)
+        .build()
+        .expect("build quad");
+    model.render().expect("render quad");
+    model.output().get_pixel(16, 16).0
+}
+
+/// Green and blue as fractions of red, which stay the same as long as the hue does.
+fn ratios(rgb: [u8; 3]) -> [f32; 2] {
+    [rgb[1] as f32 / rgb[0] as f32, rgb[2] as f32 / rgb[0] as f32]
+}
+
+#[test]
+fn tonemapping_two_bright_lights_keeps_the_hue() {
+    let expected = ratios(RED);
+
+    let tonemapped = render_red_quad(true);
+    for (ratio, expected) in ratios(tonemapped).into_iter().zip(expected) {
+        assert!((ratio - expected).abs() < 0.015, "{:?} is not the hue of {:?}", tonemapped, RED);
+    }
+    assert!(tonemapped[0] > RED[0], "twice the light is brighter: {:?}", tonemapped);
+
+    // clipping each channel on its own keeps adding green and blue after red is full
+    let clipped = render_red_quad(false);
+    assert_eq!(clipped[0], 255);
+    assert!(ratios(clipped)[0] > expected[0] + 0.05, "{:?}", clipped);
+}
@@ -0,0 +1,93 @@
+mod fixtures;
+
+use image::{DynamicImage, Rgb, RgbImage};
+use model_to_image::{MaterialData, MeshData, ModelToImageBuilder, RenderLayers};
+
+const SIZE: u32 = 64;
+
+/// A pixel on the plane, clear of the cube.
+const BESIDE_THE_CUBE: (u32, u32) = (10, 10);
+
+/// A checkered plane facing the camera behind where the cube goes, twice as wide as the cube.
+fn textured_plane() -> (MeshData, MaterialData) {
+    let plane = MeshData {
+        name: "plane".to_string(),
+        positions: vec![[-2.0, -2.0, -2.0], [2.0, -2.0, -2.0], [2.0, 2.0, -2.0], [-2.0, 2.0, -2.0]],
+        triangles: vec![[0, 1, 2], [0, 2, 3]],
+        uvs: vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]],
+        ..Default::default()
+    };
+    let checker =
+        RgbImage::from_fn(4, 4, |x, y| if (x + y) % 2 == 0 { Rgb([40, 160, 40]) } else { Rgb([160, 40, 160]) });
+    let material = MaterialData { texture: Some(DynamicImage::ImageRgb8(checker)), ..Default::default() };
+    (plane, material)
+}
+
+/// The plane, and in front of it the cube at `opacity` (no cube at all for `None`), lit white.
+fn render(opacity: Option<f32>) -> (RgbImage, RenderLayers) {
+    let (plane, texture) = textured_plane();
+    let mut meshes = vec![plane];
+    if opacity.is_some() {
+        meshes.push(MeshData {
+            name: "cube".to_string(),
+            positions: fixtures::CUBE_VERTICES.to_vec(),
+            triangles: fixtures::CUBE_TRIANGLES.iter().map(|t| t.map(u32::from)).collect(),
+            material: 1,
+            ..Default::default()
+        });
+    }
+    let mut builder = ModelToImageBuilder::from_meshes(meshes, vec![texture, MaterialData::default()])
+        .with_size((SIZE, SIZE));
+    if let Some(opacity) = opacity {
+        builder = builder.with_mesh_opacity("cube", opacity);
+    }
+    let mut model = builder.build().expect("build scene");
+    let layers = model.render_layers().expect("render scene");
+    (model.output().clone(), layers)
+}
+
+/// Pixels well inside the cube's front face, which covers the middle 40% of the image.
+fn inside_the_cube() -> impl Iterator<Item = (u32, u32)> {
+    let (start, end) = (SIZE / 2 - 10, SIZE / 2 + 10);
+    (start..end).flat_map(move |y| (start..end).map(move |x| (x, y)))
+}
+
+#[test]
+fn the_plane_shows_through_a_cube_at_forty_percent() {
+    let (plane, _) = render(None);
+    let (blended, _) = render(Some(0.4));
+
+    // the cube's front face is lit white, and laid over the texture at 40/60
+    for (x, y) in inside_the_cube() {
+        let (behind, got) = (plane.get_pixel(x, y).0, blended.get_pixel(x, y).0);
+        for c in 0..3 {
+            let expected = 0.4 * 255.0 + 0.6 * behind[c] as f32;
+            assert!((got[c] as f32 - expected).abs() <= 2.0, "({}, {}) is {:?} over {:?}", x, y, got, behind);
+        }
+    }
+    // the checker still shows: its two colours stay apart under the cube
+    let under_cube: Vec<[u8; 3]> = inside_the_cube().map(|(x, y)| blended.get_pixel(x, y).0).collect();
+    assert!(under_cube.iter().any(|pixel| pixel[1] > pixel[0] + 40));
+    assert!(under_cube.iter().any(|pixel| pixel[0] > pixel[1] + 40));
+
+    // the plane around the cube is untouched
+    let (x, y) = BESIDE_THE_CUBE;
+    assert_eq!(blended.get_pixel(x, y), plane.get_pixel(x, y));
+}
+
+#[test]
+fn a_translucent_cube_leaves_the_depth_of_the_plane_behind_it() {
+    let (_, translucent) = render(Some(0.4));
+    let (x, y) = BESIDE_THE_CUBE;
+    let plane_depth = translucent.depth.get_pixel(x, y).0[0];
+    assert!(plane_depth.is_finite());
+    for (x, y) in inside_the_cube() {
+        let depth = translucent.depth.get_pixel(x, y).0[0];
+        assert!((depth - plane_depth).abs() < 1.0e-4, "({}, {}) is at {}, the plane at {}", x, y, depth, plane_depth);
+    }
+
+    // an opaque cube does write its depth, nearer than the plane
+    let (_, opaque) = render(Some(1.0));
+    let centre = opaque.depth.get_pixel(SIZE / 2, SIZE / 2).0[0];
+    assert!(centre > opaque.depth.get_pixel(x, y).0[0]);
+}
@@ -0,0 +1,53 @@
+use image::{DynamicImage, Rgb, RgbImage};
+use model_to_image::{MaterialData, MeshData, ModelToImageBuilder, SortMode};
+
+const BLUE: [f32; 3] = [0.0, 0.0, 255.0];
+const RED: [f32; 3] = [255.0, 0.0, 0.0];
+const BACKGROUND: [f32; 3] = [211.0; 3];
+
+/// Two half transparent quads covering the same square: a blue one in front, first in the
+/// model and on the first material, and a red one behind it.
+fn overlapping_quads(sort: SortMode) -> RgbImage {
+    let quad = |z: f32, material: usize| MeshData {
+        positions: vec![[0.0, 0.0, z], [4.0, 0.0, z], [4.0, 4.0, z], [0.0, 4.0, z]],
+        triangles: vec![[0, 1, 2], [0, 2, 3]],
+        uvs: vec![[0.5, 0.5]; 4],
+        material,
+        ..Default::default()
+    };
+    let flat = |colour: [f32; 3]| MaterialData {
+        texture: Some(DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, Rgb(colour.map(|c| c as u8))))),
+        ..Default::default()
+    };
+
+    let mut model = ModelToImageBuilder::from_meshes(vec![quad(1.0, 0), quad(0.0, 1)], vec![flat(BLUE), flat(RED)])
+        .with_size((32, 32))
+        .with_mesh_opacity(0, 0.5)
+        .with_mesh_opacity(1, 0.5)
+        .with_transparent_sort(sort)
+        .build()
+        .expect("build quads");
+    model.render().expect("render quads");
+    model.output().clone()
+}
+
+/// Blends `first` and then `second` over the background at half opacity, rounding to 8 bits
+/// after each blend like the renderer.
+fn expected(first: [f32; 3], second: [f32; 3]) -> [u8; 3] {
+    let blend = |src: [f32; 3], dst: [f32; 3]| [0, 1, 2].map(|c| (src[c] * 0.5 + dst[c] * 0.5).round());
+    blend(second, blend(first, BACKGROUND)).map(|c| c as u8)
+}
+
+#[test]
+fn each_sort_mode_blends_the_quads_in_its_own_order() {
+    for (sort, colour) in [
+        (SortMode::CentroidDepth, expected(RED, BLUE)),
+        (SortMode::MaterialThenDepth, expected(BLUE, RED)),
+        (SortMode::Stable, expected(BLUE, RED)),
+    ] {
+        let centre = overlapping_quads(sort).get_pixel(16, 16).0;
+        for c in 0..3 {
+            assert!(centre[c].abs_diff(colour[c]) <= 1, "{:?}: got {:?}, expected {:?}", sort, centre, colour);
+        }
+    }
+}
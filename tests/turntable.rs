@@ -0,0 +1,28 @@
+mod fixtures;
+
+use model_to_image::{ModelToImageBuilder, ViewPreset};
+
+#[test]
+fn turntable_frames_are_lazy_and_leave_the_model_as_it_was() {
+    let dir = fixtures::fixture_dir("turntable");
+    let build = || {
+        ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir))
+            .with_size((64, 48))
+            .with_view(ViewPreset::Isometric)
+            .build()
+            .expect("load cube")
+    };
+
+    let mut model = build();
+    let frames: Vec<_> = model.turntable_frames(12).take(3).map(|frame| frame.expect("render frame")).collect();
+    assert_eq!(frames.len(), 3);
+    assert!(frames.iter().all(|frame| frame.dimensions() == (64, 48)));
+    assert_ne!(frames[0], frames[1], "the model did not turn");
+
+    model.render().expect("render after the turntable");
+    let mut fresh = build();
+    fresh.render().expect("render fresh");
+    assert_eq!(model.output(), fresh.output());
+
+    assert_eq!(model.render_turntable(4).expect("render turntable").len(), 4);
+}
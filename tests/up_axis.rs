@@ -0,0 +1,50 @@
+mod fixtures;
+
+use std::path::Path;
+
+use model_to_image::{ModelToImageBuilder, UpAxis};
+
+/// The width and height in pixels of `model` from the front, after `configure`.
+fn silhouette(model: &Path, configure: impl FnOnce(ModelToImageBuilder) -> ModelToImageBuilder) -> (u32, u32) {
+    let mut model = configure(ModelToImageBuilder::new(&model.to_path_buf()).with_size((128, 128)))
+        .build()
+        .expect("load tower");
+    model.render().expect("render tower");
+    let (min_x, min_y, max_x, max_y) = model.coverage().bounding_box.expect("tower drawn");
+    (max_x - min_x + 1, max_y - min_y + 1)
+}
+
+/// Whether the tower stands four times as tall as it is wide.
+fn upright((width, height): (u32, u32)) -> bool {
+    height > width * 3
+}
+
+/// Whether the tower is seen end on, as a square.
+fn end_on((width, height): (u32, u32)) -> bool {
+    width.abs_diff(height) <= 1
+}
+
+#[test]
+fn a_z_up_obj_stands_upright_with_z_as_the_up_axis() {
+    let dir = fixtures::fixture_dir("up_axis_obj");
+    let tower = fixtures::write_obj_z_up_tower(&dir);
+
+    // OBJ files don't say which way is up, so the tower lies pointing at the camera
+    let as_is = silhouette(&tower, |builder| builder.with_auto_up_axis(true));
+    assert!(end_on(as_is), "a Z-up OBJ was turned by itself: {:?}", as_is);
+
+    let turned = silhouette(&tower, |builder| builder.with_up_axis(UpAxis::Z));
+    assert!(upright(turned), "the tower isn't standing up: {:?}", turned);
+}
+
+#[test]
+fn an_explicit_up_axis_wins_over_the_one_in_the_file() {
+    let dir = fixtures::fixture_dir("up_axis_fbx");
+    let tower = fixtures::write_fbx_z_up_tower(&dir);
+
+    let detected = silhouette(&tower, |builder| builder.with_auto_up_axis(true));
+    assert!(upright(detected), "the file's Z up wasn't used: {:?}", detected);
+
+    let overridden = silhouette(&tower, |builder| builder.with_auto_up_axis(true).with_up_axis(UpAxis::Y));
+    assert!(end_on(overridden), "the file's Z up beat the explicit Y up: {:?}", overridden);
+}
@@ -0,0 +1,48 @@
+use image::{DynamicImage, Rgb, RgbImage};
+use model_to_image::{MaterialData, MeshData, ModelToImageBuilder};
+
+const RED: [u8; 3] = [255, 0, 0];
+const GREEN: [u8; 3] = [0, 255, 0];
+const BLUE: [u8; 3] = [0, 0, 255];
+
+/// A quad facing the camera whose U runs from 0.9 on its left edge, across the seam of the
+/// texture, to 0.1 on its right, like the faces either side of a cylinder's seam.
+fn quad_across_the_seam() -> MeshData {
+    MeshData {
+        positions: vec![[-1.0, -1.0, 0.0], [1.0, -1.0, 0.0], [1.0, 1.0, 0.0], [-1.0, 1.0, 0.0]],
+        triangles: vec![[0, 1, 2], [0, 2, 3]],
+        uvs: vec![[0.9, 0.5], [0.1, 0.5], [0.1, 0.5], [0.9, 0.5]],
+        ..Default::default()
+    }
+}
+
+/// Red at the start of U, blue at the end and green in between, which only shows if
+/// interpolation runs back through the whole texture.
+fn banded_texture() -> MaterialData {
+    let texture = RgbImage::from_fn(10, 1, |x, _| match x {
+        0..2 => Rgb(RED),
+        8.. => Rgb(BLUE),
+        _ => Rgb(GREEN),
+    });
+    MaterialData {
+        texture: Some(DynamicImage::ImageRgb8(texture)),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn a_triangle_across_the_seam_samples_only_the_edges_of_the_texture() {
+    let mut model = ModelToImageBuilder::from_meshes(vec![quad_across_the_seam()], vec![banded_texture()])
+        .with_size((64, 64))
+        .build()
+        .expect("build quad");
+    model.render().expect("render quad");
+
+    let mostly = |colour: [u8; 3]| {
+        let channel = colour.iter().position(|&c| c == 255).unwrap();
+        model.output().pixels().filter(|p| (0..3).all(|c| (p.0[c] > 128) == (c == channel))).count()
+    };
+    assert_eq!(mostly(GREEN), 0, "interpolation ran back through the texture");
+    // the blue end of the texture on one side, wrapping round to the red start on the other
+    assert!(mostly(RED) > 100 && mostly(BLUE) > 100, "{} red, {} blue", mostly(RED), mostly(BLUE));
+}
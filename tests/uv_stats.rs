@@ -0,0 +1,74 @@
+use image::DynamicImage;
+use model_to_image::{MaterialData, MeshData, ModelToImage, ModelToImageBuilder};
+
+/// A unit square split into two triangles, with `uvs` at its corners counter-clockwise from
+/// the bottom left.
+fn quad(name: &str, offset: f32, uvs: &[[f32; 2]], material: usize) -> MeshData {
+    MeshData {
+        name: name.to_string(),
+        positions: vec![[offset, 0.0, 0.0], [offset + 1.0, 0.0, 0.0], [offset + 1.0, 1.0, 0.0], [offset, 1.0, 0.0]],
+        triangles: vec![[0, 1, 2], [0, 2, 3]],
+        uvs: uvs.to_vec(),
+        material,
+        ..Default::default()
+    }
+}
+
+fn scene(min_texels: f32) -> ModelToImage {
+    let meshes = vec![
+        quad("in_range", 0.0, &[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]], 0),
+        quad("wrapping", 1.0, &[[0.5, -0.25], [1.5, -0.25], [1.5, 0.75], [0.5, 0.75]], 0),
+        quad("missing", 2.0, &[], 0),
+        quad("partial", 3.0, &[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]], 0),
+        quad("atlas_corner", 4.0, &[[0.0, 0.0], [0.01, 0.0], [0.01, 0.01], [0.0, 0.01]], 0),
+        quad("untextured", 5.0, &[[0.0, 0.0], [0.01, 0.0], [0.01, 0.01], [0.0, 0.01]], 1),
+    ];
+    let materials = vec![
+        MaterialData {
+            name: "atlas".into(),
+            texture: Some(DynamicImage::new_rgb8(64, 32)),
+            ..Default::default()
+        },
+        MaterialData::default(),
+    ];
+    ModelToImageBuilder::from_meshes(meshes, materials)
+        .with_size((64, 64))
+        .with_min_texels(min_texels)
+        .build()
+        .expect("build quads")
+}
+
+#[test]
+fn uv_ranges_wrapping_and_missing_uvs_are_reported_per_mesh() {
+    let stats = scene(0.0).uv_stats();
+    let names: Vec<&str> = stats.iter().map(|mesh| mesh.name.as_str()).collect();
+    assert_eq!(names, ["in_range", "wrapping", "missing", "partial", "atlas_corner", "untextured"]);
+
+    assert_eq!((stats[0].min, stats[0].max), (Some([0.0, 0.0]), Some([1.0, 1.0])));
+    assert!(!stats[0].wraps);
+    assert_eq!(stats[0].texture_size, Some((64, 32)));
+    assert!((stats[0].texels - 64.0 * 32.0).abs() < 1.0e-3, "{}", stats[0].texels);
+
+    assert_eq!((stats[1].min, stats[1].max), (Some([0.5, -0.25]), Some([1.5, 0.75])));
+    assert!(stats[1].wraps);
+
+    assert_eq!((stats[2].min, stats[2].missing_fraction), (None, 1.0));
+    assert_eq!(stats[2].texels, 0.0);
+    assert_eq!(stats[3].missing_fraction, 0.25);
+    assert_eq!(stats[0].missing_fraction, 0.0);
+
+    assert_eq!(stats[5].texture_size, None);
+    assert_eq!(stats[5].texels, 0.0);
+}
+
+#[test]
+fn meshes_covering_too_few_texels_are_warned_about() {
+    let texel_warnings = |model: &ModelToImage| -> Vec<String> {
+        model.warnings().iter().filter(|warning| warning.contains("resolution too low")).cloned().collect()
+    };
+    let warned = texel_warnings(&scene(16.0));
+    assert_eq!(warned.len(), 1, "{:?}", warned);
+    assert!(warned[0].contains("atlas_corner") && warned[0].contains("64x32"), "{}", warned[0]);
+
+    assert!(texel_warnings(&scene(0.0)).is_empty());
+}
@@ -0,0 +1,51 @@
+mod fixtures;
+
+use image::{DynamicImage, Rgba, RgbaImage};
+use model_to_image::{Corner, ModelToImageBuilder};
+
+fn logo(size: u32) -> DynamicImage {
+    DynamicImage::ImageRgba8(RgbaImage::from_pixel(size, size, Rgba([200, 0, 0, 255])))
+}
+
+fn render(dir_name: &str, watermark: Option<(DynamicImage, Corner)>) -> image::RgbImage {
+    let dir = fixtures::fixture_dir(dir_name);
+    let mut builder = ModelToImageBuilder::new(&fixtures::write_obj_cube(&dir)).with_size((64, 64));
+    if let Some((image, corner)) = watermark {
+        builder = builder.with_watermark(image, corner, 1.0, 2);
+    }
+    let mut model = builder.build().expect("load cube");
+    model.render().expect("render cube");
+    model.output().clone()
+}
+
+/// The pixels that differ between `a` and `b`.
+fn differences(a: &image::RgbImage, b: &image::RgbImage) -> Vec<(u32, u32)> {
+    a.enumerate_pixels().filter(|(x, y, p)| b.get_pixel(*x, *y) != *p).map(|(x, y, _)| (x, y)).collect()
+}
+
+#[test]
+fn the_watermark_only_touches_its_corner() {
+    let plain = render("watermark_plain", None);
+    let marked = render("watermark_bottom_right", Some((logo(8), Corner::BottomRight)));
+
+    let changed = differences(&plain, &marked);
+    assert!(!changed.is_empty());
+    // bottom right, as the image is seen, after the flip
+    assert!(changed.iter().all(|&(x, y)| (54..62).contains(&x) && (54..62).contains(&y)), "{:?}", changed);
+    assert_eq!(marked.get_pixel(57, 57).0, [200, 0, 0]);
+    // nothing changes in the opposite corner
+    assert!(changed.iter().all(|&(x, y)| x >= 32 && y >= 32));
+}
+
+#[test]
+fn large_watermarks_are_scaled_down_to_a_quarter() {
+    let plain = render("watermark_large_plain", None);
+    let marked = render("watermark_large", Some((logo(64), Corner::TopLeft)));
+
+    let changed = differences(&plain, &marked);
+    let right = changed.iter().map(|c| c.0).max().expect("a changed pixel");
+    let bottom = changed.iter().map(|c| c.1).max().expect("a changed pixel");
+    // a 16 pixel square, 2 pixels in from the top left
+    assert!(right < 2 + 16 && bottom < 2 + 16, "reaches {right}, {bottom}");
+    assert_eq!(marked.get_pixel(8, 8).0, [200, 0, 0]);
+}
@@ -0,0 +1,96 @@
+mod fixtures;
+
+use model_to_image::{MeshData, ModelToImageBuilder};
+
+/// The cube as some exporters write it: every triangle with three vertices of its own, each
+/// carrying the triangle's normal, and optionally every face mapped to its own UV square.
+fn per_face_cube(uv_islands: bool) -> MeshData {
+    let mut mesh = MeshData::default();
+    for (triangle_idx, triangle) in fixtures::CUBE_TRIANGLES.iter().enumerate() {
+        let [a, b, c] = triangle.map(|idx| fixtures::CUBE_VERTICES[idx as usize]);
+        let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+        let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+        let n = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+        let length = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+
+        let first = mesh.positions.len() as u32;
+        for corner in [a, b, c] {
+            // a hair apart, as floating point exports often are
+            mesh.positions.push(corner.map(|c| c + 1.0e-6 * triangle_idx as f32));
+            mesh.normals.push(n.map(|c| c / length));
+            if uv_islands {
+                // the face's own square of the texture, from where the corner is on the face
+                let face = (triangle_idx / 2) as f32;
+                let (s, t) = match n.iter().position(|c| c.abs() > 0.0) {
+                    Some(0) => (corner[1], corner[2]),
+                    Some(1) => (corner[0], corner[2]),
+                    _ => (corner[0], corner[1]),
+                };
+                mesh.uvs.push([(face + (s + 1.0) / 2.0) / 6.0, (t + 1.0) / 2.0]);
+            }
+        }
+        mesh.triangles.push([first, first + 1, first + 2]);
+    }
+    mesh
+}
+
+/// Renders the mesh with smooth shading, so whether its faces share their corners shows.
+fn render(mesh: MeshData, weld: Option<f32>) -> model_to_image::ModelToImage {
+    let mut builder =
+        ModelToImageBuilder::from_meshes(vec![mesh], Vec::new()).with_size((64, 64)).with_smooth_shading(true);
+    if let Some(tolerance) = weld {
+        builder = builder.with_weld(tolerance);
+    }
+    let mut model = builder.build().expect("build cube");
+    model.render().expect("render cube");
+    model
+}
+
+#[test]
+fn welding_joins_a_per_face_cube_back_into_eight_vertices() {
+    let welded = render(per_face_cube(false), Some(1.0e-4));
+    assert_eq!(welded.stats().vertices_welded, 36 - 8);
+    assert_eq!(welded.stats().triangles, 12);
+
+    let unwelded = render(per_face_cube(false), None);
+    assert_eq!(unwelded.stats().vertices_welded, 0);
+    // the welded corners average the normals of the faces around them, so the light blends
+    // across each face instead of filling it evenly
+    assert_ne!(welded.output(), unwelded.output());
+    assert_eq!(welded.coverage().bounding_box, unwelded.coverage().bounding_box);
+}
+
+#[test]
+fn faces_that_share_no_corners_stay_flat_under_smooth_shading() {
+    let unwelded = render(per_face_cube(false), None);
+    let mut flat = ModelToImageBuilder::from_meshes(vec![per_face_cube(false)], Vec::new())
+        .with_size((64, 64))
+        .build()
+        .expect("build cube");
+    flat.render().expect("render cube");
+    // blending three equal corners can round a hair differently from the face's own light
+    let differences = unwelded.output().pixels().zip(flat.output().pixels()).map(|(a, b)| {
+        a.0.iter().zip(b.0).map(|(&a, b)| a.abs_diff(b)).max().unwrap_or_default()
+    });
+    assert!(differences.max().unwrap_or_default() <= 1);
+}
+
+#[test]
+fn vertices_further_apart_than_the_tolerance_stay_split() {
+    let welded = render(per_face_cube(false), Some(0.0));
+    assert_eq!(welded.stats().vertices_welded, 0);
+}
+
+#[test]
+fn uv_seams_are_kept() {
+    // the two triangles of each face share two corners with the same UVs, but the faces
+    // don't share any
+    let welded = render(per_face_cube(true), Some(1.0e-4));
+    assert_eq!(welded.stats().vertices_welded, 36 - 6 * 4);
+}
+
+#[test]
+fn negative_tolerances_are_rejected() {
+    let builder = ModelToImageBuilder::from_meshes(vec![per_face_cube(false)], Vec::new()).with_weld(-1.0);
+    assert!(builder.build().is_err());
+}
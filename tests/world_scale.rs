@@ -0,0 +1,35 @@
+mod fixtures;
+
+use model_to_image::{MeshData, ModelToImageBuilder};
+
+/// The cube scaled to `width` units across, centred on the origin.
+fn cube(width: f32) -> MeshData {
+    MeshData {
+        positions: fixtures::CUBE_VERTICES.iter().map(|v| v.map(|c| c * width / 2.0)).collect(),
+        triangles: fixtures::CUBE_TRIANGLES.iter().map(|t| t.map(u32::from)).collect(),
+        ..Default::default()
+    }
+}
+
+/// The width and height in pixels of the cube `width` units across at 32 pixels a unit.
+fn silhouette(width: f32) -> (u32, u32) {
+    // an odd size puts the middle of the image, and so the cube's edges, half way between
+    // pixel centres, so no edge lands exactly on a row or column of them
+    let mut model = ModelToImageBuilder::from_meshes(vec![cube(width)], Vec::new())
+        .with_size((129, 129))
+        .with_world_scale(32.0)
+        .build()
+        .expect("build cube");
+    model.render().expect("render cube");
+    assert_eq!(model.output().dimensions(), (129, 129), "the image keeps its size");
+
+    let (min_x, min_y, max_x, max_y) = model.coverage().bounding_box.expect("cube drawn");
+    (max_x - min_x + 1, max_y - min_y + 1)
+}
+
+#[test]
+fn a_box_twice_as_wide_is_exactly_twice_as_many_pixels_wide() {
+    let (small, large) = (silhouette(1.0), silhouette(2.0));
+    assert_eq!(small, (32, 32));
+    assert_eq!(large, (small.0 * 2, small.1 * 2));
+}